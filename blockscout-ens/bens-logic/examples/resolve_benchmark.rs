@@ -177,6 +177,7 @@ async fn main() -> Result<(), anyhow::Error> {
         .batch_resolve_address_names(BatchResolveAddressNamesInput {
             network_id: 1,
             addresses,
+            include_unverified: false,
         })
         .await
         .expect("failed to quick resolve");
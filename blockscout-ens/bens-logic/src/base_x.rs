@@ -0,0 +1,45 @@
+pub const BASE58_BTC_ALPHABET: &[u8] =
+    b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+pub const BASE36_ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Encode bytes as big number in the base of `alphabet` length
+/// (leading zero bytes are kept as zero digits)
+pub fn base_x_encode(bytes: &[u8], alphabet: &[u8]) -> String {
+    let base = alphabet.len() as u32;
+    let leading_zeros = bytes.iter().take_while(|b| **b == 0).count();
+    // little-endian digits
+    let mut digits: Vec<u32> = vec![];
+    for byte in &bytes[leading_zeros..] {
+        let mut carry = u32::from(*byte);
+        for digit in digits.iter_mut() {
+            carry += *digit << 8;
+            *digit = carry % base;
+            carry /= base;
+        }
+        while carry > 0 {
+            digits.push(carry % base);
+            carry /= base;
+        }
+    }
+    std::iter::repeat(alphabet[0] as char)
+        .take(leading_zeros)
+        .chain(digits.iter().rev().map(|d| alphabet[*d as usize] as char))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn base_x_encode_works() {
+        assert_eq!(base_x_encode(&[], BASE58_BTC_ALPHABET), "");
+        assert_eq!(base_x_encode(&[0, 0, 1], BASE58_BTC_ALPHABET), "112");
+        assert_eq!(
+            base_x_encode(b"hello world", BASE58_BTC_ALPHABET),
+            "StV1DL6CwTryKyV"
+        );
+        assert_eq!(base_x_encode(&[255, 255], BASE36_ALPHABET), "1ekf");
+    }
+}
@@ -17,8 +17,21 @@ pub struct Coin {
     pub encoding: Option<Encoding>,
 }
 
+// https://docs.ens.domains/ensip/11
+const SLIP44_MSB: u32 = 0x80000000;
+
 impl Coin {
     pub fn unknown_type(coin_type: String) -> Self {
+        if let Some(chain_id) = evm_chain_id(&coin_type) {
+            return Self {
+                name: match chain_id {
+                    0 => "EVM".to_string(),
+                    _ => format!("EVM chain ({chain_id})"),
+                },
+                coin_type,
+                encoding: Some(Encoding::CheckSummedHex(None)),
+            };
+        }
         Self {
             name: format!("unknown coin ({coin_type})"),
             coin_type,
@@ -35,6 +48,12 @@ impl Coin {
     }
 }
 
+/// ENSIP-11 coin type of EVM chain is `SLIP44_MSB | chain_id`
+fn evm_chain_id(coin_type: &str) -> Option<u32> {
+    let coin_type = coin_type.parse::<u32>().ok()?;
+    (coin_type & SLIP44_MSB != 0).then_some(coin_type & !SLIP44_MSB)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,10 +65,57 @@ mod tests {
             ("60", "ETH"),
             ("137", "RSK"),
             ("2147483785", "MATIC"),
+            ("2147483648", "EVM"),
+            ("2147492101", "EVM chain (8453)"),
             ("9999999999", "unknown coin (9999999999)"),
         ] {
             let maybe_coin = Coin::find_or_unknown(coin_type);
             assert_eq!(maybe_coin.name.to_string(), expected_name.to_string())
         }
     }
+
+    #[test]
+    fn coin_types_are_encoded() {
+        for (coin_type, address, expected) in [
+            (
+                "0",
+                "76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac",
+                "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa",
+            ),
+            (
+                "2",
+                "0014687c150c26af5493befeed7036043812115ca36c",
+                "ltc1qdp7p2rpx4a2f80h7a4crvppczgg4egmv5c78w8",
+            ),
+            (
+                "3",
+                "76a9144620b70031f0e9437e374a2100934fba4911046088ac",
+                "DBXu2kgc3xtvCUWFcxFE3r9hEYgmuaaCyD",
+            ),
+            (
+                "118",
+                "751e76e8199196d454941c45d1b3a323f1433bd6",
+                "cosmos1w508d6qejxtdg4y5r3zarvary0c5xw7k6ah60c",
+            ),
+            (
+                "501",
+                "06ddf6e1d765a193d9cbe146ceeb79ac1cb485ed5f5b37913a8cf5857eff00a9",
+                "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+            ),
+            (
+                "2147484009",
+                "d8da6bf26964af9d7eed9e03e53415d37aa96045",
+                "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            ),
+            (
+                "2147492101",
+                "d8da6bf26964af9d7eed9e03e53415d37aa96045",
+                "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            ),
+        ] {
+            let coin = Coin::find_or_unknown(coin_type);
+            let encoding = coin.encoding.expect("encoding should be known");
+            assert_eq!(encoding.encode(address).unwrap(), expected, "{coin_type}");
+        }
+    }
 }
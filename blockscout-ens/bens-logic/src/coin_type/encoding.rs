@@ -1,11 +1,27 @@
+use crate::base_x::{base_x_encode, BASE58_BTC_ALPHABET};
 use alloy::primitives::Address;
+use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::str::FromStr;
 
+/// Address formats from https://docs.ens.domains/ensip/9
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum Encoding {
     CheckSummedHex(Option<u64>),
+    /// Bitcoin-like `scriptPubkey`: base58check P2PKH and P2SH,
+    /// bech32 segwit if `segwit_hrp` is provided
+    #[serde(rename_all = "camelCase")]
+    Base58Check {
+        p2pkh_version: Vec<u8>,
+        p2sh_version: Vec<u8>,
+        segwit_hrp: Option<String>,
+    },
+    /// Base58 of raw address bytes (Solana)
+    Base58,
+    /// Bech32 of raw address bytes with human readable part (Cosmos)
+    Bech32(String),
 }
 
 impl Encoding {
@@ -15,6 +31,244 @@ impl Encoding {
                 let address = Address::from_str(address)?;
                 Ok(address.to_checksum(*chain_id))
             }
+            Self::Base58Check {
+                p2pkh_version,
+                p2sh_version,
+                segwit_hrp,
+            } => {
+                let script = hex::decode(address.trim_start_matches("0x"))?;
+                match script.as_slice() {
+                    // OP_DUP OP_HASH160 <20 bytes> OP_EQUALVERIFY OP_CHECKSIG
+                    [0x76, 0xa9, 0x14, hash @ .., 0x88, 0xac] if hash.len() == 20 => Ok(
+                        base58check_encode(&[p2pkh_version.as_slice(), hash].concat()),
+                    ),
+                    // OP_HASH160 <20 bytes> OP_EQUAL
+                    [0xa9, 0x14, hash @ .., 0x87] if hash.len() == 20 => Ok(base58check_encode(
+                        &[p2sh_version.as_slice(), hash].concat(),
+                    )),
+                    _ => {
+                        let hrp = segwit_hrp
+                            .as_deref()
+                            .ok_or_else(|| anyhow!("unsupported script: {address}"))?;
+                        segwit_encode(hrp, &script)
+                    }
+                }
+            }
+            Self::Base58 => {
+                let bytes = hex::decode(address.trim_start_matches("0x"))?;
+                Ok(base_x_encode(&bytes, BASE58_BTC_ALPHABET))
+            }
+            Self::Bech32(hrp) => {
+                let bytes = hex::decode(address.trim_start_matches("0x"))?;
+                Ok(bech32_encode(
+                    hrp,
+                    &convert_bits(&bytes, 8, 5),
+                    BECH32_CONST,
+                ))
+            }
         }
     }
 }
+
+fn base58check_encode(payload: &[u8]) -> String {
+    let checksum = Sha256::digest(Sha256::digest(payload));
+    base_x_encode(&[payload, &checksum[..4]].concat(), BASE58_BTC_ALPHABET)
+}
+
+const BECH32_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+// https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki
+const BECH32_CONST: u32 = 1;
+// https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki
+const BECH32M_CONST: u32 = 0x2bc830a3;
+
+/// Encode segwit `scriptPubkey` (`OP_n <program>`) as segwit address
+fn segwit_encode(hrp: &str, script: &[u8]) -> Result<String, anyhow::Error> {
+    let (version, len, program) = match script {
+        [0x00, len, program @ ..] => (0, *len, program),
+        [op @ 0x51..=0x60, len, program @ ..] => (op - 0x50, *len, program),
+        _ => return Err(anyhow!("unsupported script: {}", hex::encode(script))),
+    };
+    if len as usize != program.len() || !(2..=40).contains(&program.len()) {
+        return Err(anyhow!("invalid witness program length"));
+    }
+    let checksum_const = if version == 0 {
+        BECH32_CONST
+    } else {
+        BECH32M_CONST
+    };
+    let data = [vec![version], convert_bits(program, 8, 5)].concat();
+    Ok(bech32_encode(hrp, &data, checksum_const))
+}
+
+fn bech32_encode(hrp: &str, data: &[u8], checksum_const: u32) -> String {
+    let values = hrp
+        .bytes()
+        .map(|b| b >> 5)
+        .chain(std::iter::once(0))
+        .chain(hrp.bytes().map(|b| b & 31))
+        .chain(data.iter().copied())
+        .chain([0; 6])
+        .collect::<Vec<_>>();
+    let polymod = bech32_polymod(&values) ^ checksum_const;
+    let checksum = (0..6).map(|i| ((polymod >> (5 * (5 - i))) & 31) as u8);
+    let encoded = data
+        .iter()
+        .copied()
+        .chain(checksum)
+        .map(|d| BECH32_CHARSET[d as usize] as char)
+        .collect::<String>();
+    format!("{hrp}1{encoded}")
+}
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    values.iter().fold(1, |chk, value| {
+        let top = chk >> 25;
+        let chk = ((chk & 0x1ffffff) << 5) ^ u32::from(*value);
+        GENERATOR
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| (top >> i) & 1 == 1)
+            .fold(chk, |chk, (_, g)| chk ^ g)
+    })
+}
+
+/// Regroup bits of `data` from `from` bits per item to `to` bits per item with padding
+fn convert_bits(data: &[u8], from: u32, to: u32) -> Vec<u8> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max = (1 << to) - 1;
+    let mut result = vec![];
+    for value in data {
+        acc = (acc << from) | u32::from(*value);
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            result.push(((acc >> bits) & max) as u8);
+        }
+    }
+    if bits > 0 {
+        result.push(((acc << (to - bits)) & max) as u8);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn base58check(p2pkh: u8, p2sh: u8, segwit_hrp: Option<&str>) -> Encoding {
+        Encoding::Base58Check {
+            p2pkh_version: vec![p2pkh],
+            p2sh_version: vec![p2sh],
+            segwit_hrp: segwit_hrp.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn encode_works() {
+        let btc = base58check(0x00, 0x05, Some("bc"));
+        let ltc = base58check(0x30, 0x32, Some("ltc"));
+        let doge = base58check(0x1e, 0x16, None);
+        for (encoding, address, expected) in [
+            (
+                &Encoding::CheckSummedHex(None),
+                "d8da6bf26964af9d7eed9e03e53415d37aa96045",
+                "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            ),
+            (
+                &btc,
+                "76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac",
+                "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa",
+            ),
+            (
+                &btc,
+                "a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1887",
+                "3Ai1JZ8pdJb2ksieUV8FsxSNVJCpoPi8W6",
+            ),
+            (
+                &btc,
+                "0014751e76e8199196d454941c45d1b3a323f1433bd6",
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            ),
+            (
+                &btc,
+                "5120a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c",
+                "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+            ),
+            (
+                &ltc,
+                "76a914a5f4d12ce3685781b227c1f39548ddef429e978388ac",
+                "LaMT348PWRnrqeeWArpwQPbuanpXDZGEUz",
+            ),
+            (
+                &ltc,
+                "0014687c150c26af5493befeed7036043812115ca36c",
+                "ltc1qdp7p2rpx4a2f80h7a4crvppczgg4egmv5c78w8",
+            ),
+            (
+                &doge,
+                "76a9144620b70031f0e9437e374a2100934fba4911046088ac",
+                "DBXu2kgc3xtvCUWFcxFE3r9hEYgmuaaCyD",
+            ),
+            (
+                &Encoding::Base58,
+                "06ddf6e1d765a193d9cbe146ceeb79ac1cb485ed5f5b37913a8cf5857eff00a9",
+                "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+            ),
+            (
+                &Encoding::Base58,
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "11111111111111111111111111111111",
+            ),
+            (
+                &Encoding::Bech32("cosmos".to_string()),
+                "751e76e8199196d454941c45d1b3a323f1433bd6",
+                "cosmos1w508d6qejxtdg4y5r3zarvary0c5xw7k6ah60c",
+            ),
+            (
+                &Encoding::Bech32("thor".to_string()),
+                "751e76e8199196d454941c45d1b3a323f1433bd6",
+                "thor1w508d6qejxtdg4y5r3zarvary0c5xw7ku6wp68",
+            ),
+        ] {
+            assert_eq!(
+                encoding.encode(address).unwrap(),
+                expected,
+                "{encoding:?} {address}"
+            );
+        }
+    }
+
+    #[test]
+    fn encode_fails_on_invalid_input() {
+        let btc = base58check(0x00, 0x05, Some("bc"));
+        let doge = base58check(0x1e, 0x16, None);
+        for (encoding, address) in [
+            (&btc, "not hex"),
+            (&btc, "76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f88ac"),
+            (&btc, "0015751e76e8199196d454941c45d1b3a323f1433bd6"),
+            (&doge, "0014751e76e8199196d454941c45d1b3a323f1433bd6"),
+            (&Encoding::Base58, "0x0z"),
+        ] {
+            assert!(encoding.encode(address).is_err(), "{encoding:?} {address}");
+        }
+    }
+
+    #[test]
+    fn deserialize_works() {
+        let encoding: Encoding = serde_json::from_str(
+            r#"{"base58Check": {"p2pkhVersion": [0], "p2shVersion": [5], "segwitHrp": "bc"}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            encoding,
+            Encoding::Base58Check { segwit_hrp: Some(hrp), .. } if hrp == "bc"
+        ));
+        let encoding: Encoding = serde_json::from_str(r#""base58""#).unwrap();
+        assert!(matches!(encoding, Encoding::Base58));
+        let encoding: Encoding = serde_json::from_str(r#"{"bech32": "cosmos"}"#).unwrap();
+        assert!(matches!(encoding, Encoding::Bech32(hrp) if hrp == "cosmos"));
+    }
+}
@@ -11,7 +11,51 @@ EIP_1191_COIN_NAMES = {
     "RSK": 30
 }
 
+# https://docs.ens.domains/ensip/9
+# (p2pkh, p2sh, segwit hrp)
+BASE58_CHECK_COINS = {
+    "BTC": ([0x00], [0x05], "bc"),
+    "LTC": ([0x30], [0x32], "ltc"),
+    "DOGE": ([0x1e], [0x16], None),
+    "RDD": ([0x3d], [0x05], None),
+    "DASH": ([0x4c], [0x10], None),
+    "PPC": ([0x37], [0x75], None),
+    "VIA": ([0x47], [0x21], "via"),
+    "DGB": ([0x1e], [0x3f], "dgb"),
+    "MONA": ([0x32], [0x37], "mona"),
+    "SYS": ([0x3f], [0x05], "sys"),
+    "XVG": ([0x1e], [0x21], None),
+    "ZEC": ([0x1c, 0xb8], [0x1c, 0xbd], None),
+    "FIRO": ([0x52], [0x07], None),
+    "KMD": ([0x3c], [0x55], None),
+    "BTG": ([0x26], [0x17], "btg"),
+    "RVN": ([0x3c], [0x7a], None),
+    "QTUM": ([0x3a], [0x32], None),
+    "BSV": ([0x00], [0x05], None),
+    "DIVI": ([0x1e], [0x0d], None),
+}
+BASE58_COINS = {"SOL", "SRM"}
+# Cosmos-like coins with bech32 human readable part
+BECH32_COINS = {
+    "ATOM": "cosmos",
+    "LUNA": "terra",
+    "KAVA": "kava",
+    "IRIS": "iaa",
+    "RUNE": "thor",
+    "BNB": "bnb",
+}
+
 def convert_encoding(encoding, coin_name):
+    if coin_name in BASE58_CHECK_COINS:
+        p2pkh, p2sh, hrp = BASE58_CHECK_COINS[coin_name]
+        params = {"p2pkhVersion": p2pkh, "p2shVersion": p2sh}
+        if hrp:
+            params["segwitHrp"] = hrp
+        return {"base58Check": params}
+    if coin_name in BASE58_COINS:
+        return "base58"
+    if coin_name in BECH32_COINS:
+        return {"bech32": BECH32_COINS[coin_name]}
     maybe_chain_id = EIP_1191_COIN_NAMES.get(coin_name)
     return {
         "checksummed-hex": {"checkSummedHex": maybe_chain_id},
@@ -44,5 +88,10 @@ for r in encodings:
     if maybe_coin:
         maybe_coin["encoding"] = encoding
 
+# https://docs.ens.domains/ensip/11
+for coin in result.values():
+    if int(coin["coinType"]) & SLIP44_MSB and not coin.get("encoding"):
+        coin["encoding"] = {"checkSummedHex": None}
+
 with open('coin_types.json', 'w') as f:
     json.dump(list(result.values()), f, indent=2)
@@ -0,0 +1,151 @@
+//! Decoding of `contenthash` resolver records (https://docs.ens.domains/ensip/7)
+//! into human readable URIs (`ipfs://`, `ipns://`, `bzz://`, `ar://`, ...).
+
+use crate::base_x::{base_x_encode, BASE36_ALPHABET, BASE58_BTC_ALPHABET};
+use anyhow::{anyhow, Context};
+use data_encoding::{BASE32_NOPAD, BASE64URL_NOPAD};
+
+// https://github.com/multiformats/multicodec/blob/master/table.csv
+const IPFS_NS: u64 = 0xe3;
+const SWARM_NS: u64 = 0xe4;
+const IPNS_NS: u64 = 0xe5;
+const ARWEAVE_NS: u64 = 0xb19910;
+const ONION: u64 = 0x01bc;
+const ONION3: u64 = 0x01bd;
+
+const CID_V1: u64 = 0x01;
+const DAG_PB: u64 = 0x70;
+const LIBP2P_KEY: u64 = 0x72;
+const SWARM_MANIFEST: u64 = 0xfa;
+const SHA2_256: u64 = 0x12;
+const KECCAK_256: u64 = 0x1b;
+
+/// Decode raw `contenthash` record into URI.
+///
+/// IPFS CIDs are returned in the form that is used by most ENS tools:
+/// CIDv0 (`Qm...`) if possible, base32 CIDv1 otherwise.
+pub fn decode(contenthash: &[u8]) -> Result<String, anyhow::Error> {
+    let (codec, content) = read_varint(contenthash).context("reading protocol code")?;
+    match codec {
+        IPFS_NS => Ok(format!("ipfs://{}", ipfs_cid(content)?)),
+        IPNS_NS => Ok(format!("ipns://{}", ipns_cid(content)?)),
+        SWARM_NS => Ok(format!("bzz://{}", swarm_hash(content)?)),
+        ARWEAVE_NS => Ok(format!("ar://{}", BASE64URL_NOPAD.encode(content))),
+        ONION => Ok(format!("onion://{}", utf8(content)?)),
+        ONION3 => Ok(format!("onion3://{}", utf8(content)?)),
+        _ => Err(anyhow!("unsupported contenthash protocol code {codec:#x}")),
+    }
+}
+
+fn ipfs_cid(cid: &[u8]) -> Result<String, anyhow::Error> {
+    let (version, rest) = read_varint(cid).context("reading cid version")?;
+    if version != CID_V1 {
+        return Err(anyhow!("unsupported cid version {version}"));
+    }
+    let (codec, multihash) = read_varint(rest).context("reading cid codec")?;
+    let (hash_function, _) = read_varint(multihash).context("reading multihash")?;
+    if codec == DAG_PB && hash_function == SHA2_256 {
+        // CIDv0 is just base58 encoded multihash
+        Ok(base_x_encode(multihash, BASE58_BTC_ALPHABET))
+    } else {
+        Ok(format!("b{}", BASE32_NOPAD.encode(cid).to_lowercase()))
+    }
+}
+
+fn ipns_cid(cid: &[u8]) -> Result<String, anyhow::Error> {
+    let (version, rest) = read_varint(cid).context("reading cid version")?;
+    if version != CID_V1 {
+        return Err(anyhow!("unsupported cid version {version}"));
+    }
+    let (codec, _) = read_varint(rest).context("reading cid codec")?;
+    if codec == LIBP2P_KEY {
+        // IPNS keys are conventionally represented in base36
+        Ok(format!("k{}", base_x_encode(cid, BASE36_ALPHABET)))
+    } else {
+        Ok(format!("b{}", BASE32_NOPAD.encode(cid).to_lowercase()))
+    }
+}
+
+fn swarm_hash(cid: &[u8]) -> Result<String, anyhow::Error> {
+    let (version, rest) = read_varint(cid).context("reading cid version")?;
+    let (codec, multihash) = read_varint(rest).context("reading cid codec")?;
+    let (hash_function, rest) = read_varint(multihash).context("reading multihash")?;
+    let (length, hash) = read_varint(rest).context("reading multihash length")?;
+    if version != CID_V1
+        || codec != SWARM_MANIFEST
+        || hash_function != KECCAK_256
+        || length != hash.len() as u64
+    {
+        return Err(anyhow!("invalid swarm cid"));
+    }
+    Ok(hex::encode(hash))
+}
+
+fn utf8(content: &[u8]) -> Result<String, anyhow::Error> {
+    Ok(std::str::from_utf8(content)?.to_string())
+}
+
+/// Read unsigned LEB128 integer (https://github.com/multiformats/unsigned-varint)
+fn read_varint(bytes: &[u8]) -> Result<(u64, &[u8]), anyhow::Error> {
+    let mut value: u64 = 0;
+    for (i, byte) in bytes.iter().enumerate().take(9) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, &bytes[i + 1..]));
+        }
+    }
+    Err(anyhow!("invalid varint"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn decode_hex(contenthash: &str) -> Result<String, anyhow::Error> {
+        decode(&hex::decode(contenthash).unwrap())
+    }
+
+    #[test]
+    fn decode_works() {
+        for (contenthash, expected) in [
+            (
+                "e3010170122029f2d17be6139079dc48696d1f582a8530eb9805b561eda517e22a892c7e3f1f",
+                "ipfs://QmRAQB6YaCyidP37UdDnjFY5vQuiBrcqdyoW1CuDgwxkD4",
+            ),
+            (
+                "e3010170122084b970556c2bcbd1f37d3b001c6c5566fc0ee7c7f1a76fdaedb78aefdca0ed00",
+                "ipfs://QmXGkXcfasLtjLk9i5brs2sMYNbk34c8FGdBDCP864mrhV",
+            ),
+            (
+                "e301015512203a8f89b3a5e0c4df1ec9bc2b2d1e26f5bc3cc7e3ec5aa1b6e9fb41fb0e4da5b9",
+                "ipfs://bafkreib2r6e3hjpaytpr5sn4fmwr4jxvxq6mpy7mlkq3n2p3ih5q4tnfxe",
+            ),
+            (
+                "e50101720024080112200001020304050607080910111213141516171819202122232425262728293031",
+                "ipns://k51qzi5uqu5dg6lcd99r9gmb963khu1fnq80cfj80x5hrm3d1k2qtjsndi1jqp",
+            ),
+            (
+                "e40101fa011b20d1de9994b4d039f6548d191eb26786769f580809256b4685ef316805265ea162",
+                "bzz://d1de9994b4d039f6548d191eb26786769f580809256b4685ef316805265ea162",
+            ),
+            (
+                "90b2c605cacdf63edf2e0bb4eb5711dd38b0723aca5f3c4ab62ceeb7c1110740833d4894",
+                "ar://ys32Pt8uC7TrVxHdOLByOspfPEq2LO63wREHQIM9SJQ",
+            ),
+            (
+                "bc037a716b6e79663275706a346e3671",
+                "onion://zqknyf2upj4n6q",
+            ),
+        ] {
+            assert_eq!(decode_hex(contenthash).unwrap(), expected, "{contenthash}");
+        }
+    }
+
+    #[test]
+    fn decode_fails_on_invalid_input() {
+        for contenthash in ["", "ff", "aa01", "e30102", "e40101fa011b2000"] {
+            assert!(decode_hex(contenthash).is_err(), "{contenthash}");
+        }
+    }
+}
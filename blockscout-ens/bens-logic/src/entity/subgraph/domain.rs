@@ -1,6 +1,6 @@
 use chrono::Utc;
 use sqlx::types::BigDecimal;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, sqlx::FromRow)]
 pub struct DetailedDomain {
@@ -24,6 +24,12 @@ pub struct DetailedDomain {
     pub resolved_with_wildcard: bool,
     #[sqlx(default)]
     pub other_addresses: sqlx::types::Json<HashMap<String, String>>,
+    /// Text records of current resolver, `None` value means that key is known,
+    /// but value is not indexed by subgraph
+    #[sqlx(default)]
+    pub text_records: sqlx::types::Json<BTreeMap<String, Option<String>>>,
+    #[sqlx(default)]
+    pub contenthash: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, sqlx::FromRow)]
@@ -70,6 +76,14 @@ pub struct DomainWithAddress {
     pub id: String,
     pub domain_name: String,
     pub resolved_address: String,
+    /// Name is set as reverse record of `resolved_address`
+    /// and forward resolution of the name points back to it
+    #[sqlx(default)]
+    pub verified: bool,
+    /// Forward resolution of the name was not checked (e.g. too many names
+    /// to check or RPC error), so `verified` is `false`, but the name may be verified
+    #[sqlx(default)]
+    pub verification_skipped: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
@@ -83,6 +97,7 @@ pub struct ReverseRecord {
 pub struct AddrReverseDomainWithActualName {
     pub domain_id: String,
     pub reversed_domain_id: String,
-    pub resolved_address: String,
+    pub resolved_address: Option<String>,
     pub name: String,
+    pub protocol_slug: String,
 }
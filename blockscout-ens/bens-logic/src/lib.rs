@@ -1,5 +1,7 @@
+mod base_x;
 pub mod blockscout;
 pub mod coin_type;
+pub mod contenthash;
 pub mod entity;
 mod metrics;
 pub mod migrations;
@@ -1,6 +1,11 @@
 use super::{domain_id, ProtocolError, Tld};
 use crate::protocols::protocoler::DeployedProtocol;
 use alloy::primitives::{keccak256, Address, B256};
+use ens_normalize_rs::EnsNameNormalizer;
+
+lazy_static::lazy_static! {
+    static ref NORMALIZER: EnsNameNormalizer = EnsNameNormalizer::default();
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DomainName {
@@ -113,7 +118,8 @@ impl<'a> DomainNameOnProtocol<'a> {
     }
 }
 
-// TODO: implement https://docs.ens.domains/ensip/15 here
+/// Normalize name according to https://docs.ens.domains/ensip/15
+/// (emoji sequences, confusables and mixed-script rules included)
 fn ens_normalize(name: &str) -> Result<String, ProtocolError> {
     let name = name.trim().trim_matches(SEPARATOR);
     if name.is_empty() {
@@ -122,7 +128,21 @@ fn ens_normalize(name: &str) -> Result<String, ProtocolError> {
             reason: "empty name".to_string(),
         });
     }
-    Ok(name.to_string())
+    NORMALIZER
+        .normalize(name)
+        .map_err(|err| ProtocolError::NotNormalizable {
+            name: name.to_string(),
+            reason: err.to_string(),
+        })
+}
+
+/// Returns true if `name` is already in ENSIP-15 normalized form.
+/// Names with unknown labels (`[labelhash]`) are not normalized.
+pub fn is_normalized(name: &str) -> bool {
+    NORMALIZER
+        .normalize(name)
+        .map(|normalized| normalized == name)
+        .unwrap_or(false)
 }
 
 #[cfg(test)]
@@ -191,6 +211,60 @@ mod tests {
         }
     }
 
+    #[test]
+    fn normalization_works() {
+        let vitalik_id = "0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835";
+        for (name, expected_name) in [
+            ("vitalik.eth", "vitalik.eth"),
+            ("VitaliK.eth", "vitalik.eth"),
+            (" VITALIK.ETH. ", "vitalik.eth"),
+            ("ＶＩＴＡＬＩＫ.eth", "vitalik.eth"),
+            ("1\u{fe0f}\u{20e3}.eth", "1\u{20e3}.eth"),
+            ("_vitalik.eth", "_vitalik.eth"),
+        ] {
+            let domain_name = DomainName::new(name, None).expect("name should be valid");
+            assert_eq!(domain_name.name, expected_name, "input: {name}");
+            if expected_name == "vitalik.eth" {
+                assert_eq!(domain_name.id, vitalik_id, "input: {name}");
+            }
+        }
+
+        for name in [
+            "vitalik..eth",
+            "vitalik eth",
+            "vit_alik.eth",
+            "ab--cd.eth",
+            // cyrillic `а` mixed with latin letters
+            "\u{430}pple.eth",
+        ] {
+            let err = DomainName::new(name, None).expect_err("name should be invalid");
+            assert!(
+                matches!(err, ProtocolError::NotNormalizable { .. }),
+                "unexpected error for {name}: {err:?}"
+            );
+        }
+        assert!(matches!(
+            DomainName::new(" . ", None),
+            Err(ProtocolError::InvalidName { .. })
+        ));
+    }
+
+    #[test]
+    fn is_normalized_works() {
+        for (name, expected) in [
+            ("vitalik.eth", true),
+            ("wa🇬🇲i.eth", true),
+            ("VitaliK.eth", false),
+            ("1\u{fe0f}\u{20e3}.eth", false),
+            (
+                "[0b0e081f36b3970ff8e337f0ff7bdfad321a702fa00916b6ccfc47877144f7ad].eth",
+                false,
+            ),
+        ] {
+            assert_eq!(is_normalized(name), expected, "name: {name}");
+        }
+    }
+
     #[test]
     fn reverse_works() {
         let addr = Address::from_str("0x43C960FA130e3Eb58e7AaF65f46F76B5C607C3a9").unwrap();
@@ -2,7 +2,7 @@ mod domain_name;
 pub mod hash_name;
 mod protocoler;
 
-pub use domain_name::{DomainName, DomainNameOnProtocol};
+pub use domain_name::{is_normalized, DomainName, DomainNameOnProtocol};
 pub use hash_name::domain_id;
 pub use protocoler::{
     AddressResolveTechnique, DeployedProtocol, Network, Protocol, ProtocolInfo, ProtocolMeta,
@@ -15,6 +15,8 @@ pub enum ProtocolError {
     NetworkNotFound(i64),
     #[error("name '{name}' is invalid: {reason}")]
     InvalidName { name: String, reason: String },
+    #[error("name '{name}' cannot be normalized: {reason}")]
+    NotNormalizable { name: String, reason: String },
     #[error("protocol not found: {0}")]
     ProtocolNotFound(String),
     #[error("internal error: {0}")]
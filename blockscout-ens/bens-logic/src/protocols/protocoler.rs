@@ -85,7 +85,7 @@ pub enum AddressResolveTechnique {
 
 impl Tld {
     pub fn new(tld: &str) -> Tld {
-        Self(tld.trim_start_matches('.').to_string())
+        Self(tld.trim_start_matches('.').to_lowercase())
     }
 
     pub fn from_domain_name(name: &str) -> Option<Tld> {
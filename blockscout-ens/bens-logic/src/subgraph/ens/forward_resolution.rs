@@ -0,0 +1,33 @@
+use super::ccip_read;
+use crate::protocols::DomainNameOnProtocol;
+use alloy::primitives::Address;
+use cached::proc_macro::cached;
+
+/// Check that `name` forward-resolves to `address` by calling its current
+/// resolver (https://docs.ens.domains/ensip/10 and CCIP-read are supported).
+/// Used when subgraph doesn't contain actual resolved address of the name.
+#[cached(
+    key = "String",
+    convert = r#"{
+            format!(
+                "{}-{}-{}",
+                from_user.deployed_protocol.deployment_network.rpc_url(),
+                from_user.inner.id,
+                address
+            )
+        }"#,
+    time = 600,
+    size = 1000,
+    result = true
+)]
+pub async fn forward_resolves_to_with_cache(
+    from_user: &DomainNameOnProtocol<'_>,
+    address: Address,
+) -> Result<bool, anyhow::Error> {
+    let resolver = ccip_read::get_resolver(from_user).await?;
+    if resolver.is_zero() {
+        return Ok(false);
+    }
+    let resolved = ccip_read::call_to_resolver(from_user, resolver).await?;
+    Ok(resolved.addr == address)
+}
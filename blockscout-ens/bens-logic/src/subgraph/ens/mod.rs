@@ -1,5 +1,9 @@
 mod ccip_read;
+mod forward_resolution;
+mod text_records;
 mod wildcard;
 
 pub use ccip_read::*;
+pub use forward_resolution::*;
+pub use text_records::*;
 pub use wildcard::*;
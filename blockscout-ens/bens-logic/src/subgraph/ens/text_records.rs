@@ -0,0 +1,93 @@
+use crate::protocols::DomainNameOnProtocol;
+use alloy::{
+    primitives::{Address, B256},
+    providers::ProviderBuilder,
+    sol,
+};
+use anyhow::Context;
+use cached::proc_macro::cached;
+use std::{collections::BTreeMap, str::FromStr};
+use tracing::instrument;
+
+const MAX_TEXT_RECORDS_TO_CALL: usize = 20;
+
+sol! {
+    #[sol(rpc)]
+    interface ITextResolver {
+        function text(bytes32 node, string calldata key) external view returns (string memory);
+    }
+}
+
+/// Fill text records that were not indexed by subgraph (subgraph stores only keys
+/// for old resolvers) using `text(node, key)` call to resolver contract.
+/// Records with empty values are removed.
+#[instrument(
+    skip_all,
+    fields(name = %from_user.inner.name, resolver = %resolver_address),
+    level = "INFO",
+)]
+pub async fn text_records_with_rpc_fallback(
+    from_user: &DomainNameOnProtocol<'_>,
+    resolver_address: Address,
+    records: BTreeMap<String, Option<String>>,
+) -> BTreeMap<String, String> {
+    let (indexed, missing): (Vec<_>, Vec<_>) =
+        records.into_iter().partition(|(_, value)| value.is_some());
+    if missing.len() > MAX_TEXT_RECORDS_TO_CALL {
+        tracing::warn!(
+            missing = missing.len(),
+            "too many missing text records, only first {MAX_TEXT_RECORDS_TO_CALL} will be requested"
+        );
+    }
+    let fetched =
+        futures::future::join_all(missing.into_iter().take(MAX_TEXT_RECORDS_TO_CALL).map(
+            |(key, _)| async move {
+                let value = text_record_with_cache(from_user, resolver_address, key.clone())
+                    .await
+                    .map_err(|err| tracing::warn!(key, error = ?err, "failed to call text()"))
+                    .ok();
+                (key, value)
+            },
+        ))
+        .await;
+
+    indexed
+        .into_iter()
+        .chain(fetched)
+        .filter_map(|(key, value)| value.filter(|v| !v.is_empty()).map(|v| (key, v)))
+        .collect()
+}
+
+#[cached(
+    key = "String",
+    convert = r#"{
+            format!(
+                "{}-{}-{}-{}",
+                from_user.deployed_protocol.deployment_network.rpc_url(),
+                resolver_address,
+                from_user.inner.id,
+                key
+            )
+        }"#,
+    time = 3600,
+    size = 1000,
+    result = true
+)]
+async fn text_record_with_cache(
+    from_user: &DomainNameOnProtocol<'_>,
+    resolver_address: Address,
+    key: String,
+) -> Result<String, anyhow::Error> {
+    let node = B256::from_str(&from_user.inner.id).context("parse domain id")?;
+    let provider = ProviderBuilder::new()
+        .on_http(from_user.deployed_protocol.deployment_network.rpc_url())
+        .boxed();
+    let resolver = ITextResolver::new(resolver_address, provider);
+    let value = resolver
+        .text(node, key)
+        .call()
+        .await
+        .context("call text(node, key)")?
+        ._0;
+    Ok(value)
+}
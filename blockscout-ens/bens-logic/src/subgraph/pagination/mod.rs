@@ -1,6 +1,8 @@
 mod domain;
 mod paginator;
+mod subdomain;
 
 pub use domain::DomainPaginationInput;
 pub(crate) use paginator::paginate_list;
 pub use paginator::{Order, PaginatedList, PaginationInput, Paginator};
+pub use subdomain::SubdomainPaginationInput;
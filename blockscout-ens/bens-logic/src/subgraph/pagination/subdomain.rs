@@ -0,0 +1,91 @@
+use super::{paginate_list, Order, PaginatedList, PaginationInput, Paginator};
+use crate::{
+    entity::subgraph::domain::Domain,
+    subgraph::{LookupOutput, SubdomainSortField},
+};
+use anyhow::{anyhow, Context};
+use sea_query::{Alias, Expr, SelectStatement};
+
+pub type SubdomainPaginationInput = PaginationInput<SubdomainSortField>;
+
+impl Paginator<LookupOutput> for SubdomainPaginationInput {
+    fn paginate_result(
+        &self,
+        items: Vec<LookupOutput>,
+    ) -> Result<PaginatedList<LookupOutput>, anyhow::Error> {
+        let list = match self.sort {
+            SubdomainSortField::RegistrationDate => {
+                paginate_list!(items, self.page_size, domain.created_at)
+            }
+            SubdomainSortField::ExpiryDate => {
+                let page_size = self.page_size as usize;
+                let next_page_token = items
+                    .get(page_size)
+                    .map(|item| expiry_date_page_token(&item.domain));
+                PaginatedList {
+                    items: items.into_iter().take(page_size).collect(),
+                    next_page_token,
+                }
+            }
+        };
+
+        Ok(list)
+    }
+
+    fn add_to_query(&self, query: &mut SelectStatement) -> Result<(), anyhow::Error> {
+        let order = self.order.to_database_field();
+        query.order_by_expr(self.sort.to_database_field(), order.clone());
+        // many domains share the same expiry date (or have none),
+        // so `id` is used to make the order unambiguous
+        if let SubdomainSortField::ExpiryDate = self.sort {
+            query.order_by(Alias::new("id"), order);
+        }
+        query.limit(self.page_size as u64 + 1);
+
+        if let Some(page_token) = self.page_token.as_ref() {
+            let (left, right) = match self.sort {
+                SubdomainSortField::RegistrationDate => (
+                    Expr::expr(self.sort.to_database_field()),
+                    Expr::val(
+                        page_token
+                            .parse::<u64>()
+                            .context("cannot parse page_token for 'registration_date' sort")?,
+                    ),
+                ),
+                SubdomainSortField::ExpiryDate => {
+                    let (expiry_date, id) = parse_expiry_date_page_token(page_token)
+                        .context("cannot parse page_token for 'expiry_date' sort")?;
+                    (
+                        Expr::tuple([
+                            self.sort.to_database_field(),
+                            Expr::col(Alias::new("id")).into(),
+                        ]),
+                        Expr::tuple([expiry_date.into(), id.into()]),
+                    )
+                }
+            };
+            let expr = match self.order {
+                Order::Asc => left.gte(right),
+                Order::Desc => left.lte(right),
+            };
+            query.and_where(expr);
+        };
+
+        Ok(())
+    }
+}
+
+fn expiry_date_page_token(domain: &Domain) -> String {
+    let expiry_date = domain
+        .expiry_date
+        .map(|date| date.timestamp())
+        .unwrap_or(SubdomainSortField::NEVER_EXPIRES);
+    format!("{expiry_date}:{}", domain.id)
+}
+
+fn parse_expiry_date_page_token(page_token: &str) -> Result<(i64, String), anyhow::Error> {
+    let (expiry_date, id) = page_token
+        .split_once(':')
+        .ok_or_else(|| anyhow!("expected '<expiry_date>:<id>'"))?;
+    Ok((expiry_date.parse()?, id.to_string()))
+}
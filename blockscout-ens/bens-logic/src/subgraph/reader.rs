@@ -8,15 +8,18 @@ use super::{
 use crate::{
     blockscout,
     blockscout::BlockscoutClient,
+    contenthash,
     entity::subgraph::{
-        domain::{DetailedDomain, Domain},
+        domain::{DetailedDomain, Domain, DomainWithAddress},
         domain_event::{DomainEvent, DomainEventTransaction},
     },
+    hex,
     protocols::{
-        AddressResolveTechnique, DeployedProtocol, Network, Protocol, ProtocolError, ProtocolInfo,
-        Protocoler,
+        AddressResolveTechnique, DeployedProtocol, DomainNameOnProtocol, Network, Protocol,
+        ProtocolError, ProtocolInfo, Protocoler,
     },
     subgraph::{
+        ens,
         resolve_addresses::resolve_addresses,
         sql::{CachedView, DbErr},
     },
@@ -27,6 +30,7 @@ use nonempty::{nonempty, NonEmpty};
 use sqlx::postgres::PgPool;
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
+    str::FromStr,
     sync::Arc,
 };
 use thiserror::Error;
@@ -215,8 +219,12 @@ impl SubgraphReader {
                 .patched_detailed_domain(self.pool.clone(), domain, &name);
             let tokens = extract_tokens_from_domain(&domain, &name)
                 .map_err(|e| anyhow!("failed to extract domain tokens: {e}"))?;
+            let text_records = domain_text_records(&domain, &name).await;
+            let contenthash = domain_contenthash(&domain);
             Ok(Some(GetDomainOutput {
                 tokens,
+                text_records,
+                contenthash,
                 domain,
                 protocol: name.deployed_protocol.protocol.clone(),
                 deployment_network: name.deployed_protocol.deployment_network.clone(),
@@ -264,6 +272,7 @@ impl SubgraphReader {
                 input.maybe_filter_protocols,
             ) {
                 Ok(name_options) => sql::FindDomainsInput::Names(name_options),
+                Err(err @ ProtocolError::NotNormalizable { .. }) => return Err(err.into()),
                 Err(_) => return Ok(PaginatedList::empty()),
             }
         } else {
@@ -311,6 +320,30 @@ impl SubgraphReader {
         Ok(paginated)
     }
 
+    pub async fn list_subdomains(
+        &self,
+        input: ListSubdomainsInput,
+    ) -> Result<PaginatedList<LookupOutput>, SubgraphReadError> {
+        let name = self.protocoler.main_name_in_network(
+            &input.name,
+            input.network_id,
+            input.protocol_id.clone().map(|p| nonempty![p]),
+        )?;
+        let domains = sql::find_subdomains(
+            self.pool.as_ref(),
+            &name,
+            input.only_active,
+            &input.pagination,
+        )
+        .await?;
+        let output = lookup_output_from_domains(domains, &self.protocoler)?;
+        let paginated = input
+            .pagination
+            .paginate_result(output)
+            .context("paginating result")?;
+        Ok(paginated)
+    }
+
     pub async fn lookup_address(
         &self,
         input: LookupAddressInput,
@@ -334,39 +367,58 @@ impl SubgraphReader {
     pub async fn get_address(
         &self,
         input: GetAddressInput,
-    ) -> Result<Option<GetDomainOutput>, SubgraphReadError> {
+    ) -> Result<Option<GetAddressOutput>, SubgraphReadError> {
         if address_should_be_ignored(&input.address) {
             return Ok(Default::default());
         }
-        let protocols = self
-            .protocoler
-            .protocols_of_network(
-                input.network_id,
-                input.protocol_id.clone().map(|p| nonempty![p]),
-            )?
-            .map(|p| p.protocol);
-        let maybe_domain_name =
-            resolve_addresses(self.pool.as_ref(), protocols, vec![input.address])
-                .await?
-                .into_iter()
-                .next()
-                .map(|d| d.domain_name);
-        if let Some(domain_name) = maybe_domain_name {
+        let protocols = self.protocoler.protocols_of_network(
+            input.network_id,
+            input.protocol_id.clone().map(|p| nonempty![p]),
+        )?;
+        let maybe_domain = resolve_addresses(
+            self.pool.as_ref(),
+            protocols,
+            vec![input.address],
+            input.include_unverified,
+        )
+        .await?
+        .into_iter()
+        .next();
+        if let Some(DomainWithAddress {
+            domain_name,
+            verified,
+            verification_skipped,
+            ..
+        }) = maybe_domain
+        {
             let result = self
                 .get_domain(GetDomainInput {
                     network_id: input.network_id,
-                    name: domain_name,
+                    name: domain_name.clone(),
                     only_active: true,
                     // protocol will be resolved automatically
                     protocol_id: None,
                 })
-                .await?
-                .ok_or_else(|| {
-                    anyhow!(
-                        "batch search found domain for address, but detailed domain info not found"
-                    )
-                })?;
-            Ok(Some(result))
+                .await;
+            let result = match result {
+                Err(SubgraphReadError::Protocol(ProtocolError::NotNormalizable { .. })) => {
+                    tracing::warn!(
+                        address =% input.address,
+                        domain_name,
+                        "address is resolved to name that cannot be normalized, skip it"
+                    );
+                    return Ok(None);
+                }
+                result => result?,
+            }
+            .ok_or_else(|| {
+                anyhow!("batch search found domain for address, but detailed domain info not found")
+            })?;
+            Ok(Some(GetAddressOutput {
+                domain: result,
+                name_verified: verified,
+                name_verification_skipped: verification_skipped,
+            }))
         } else {
             Ok(None)
         }
@@ -402,26 +454,72 @@ impl SubgraphReader {
     pub async fn batch_resolve_address_names(
         &self,
         input: BatchResolveAddressNamesInput,
-    ) -> Result<BTreeMap<String, String>, SubgraphReadError> {
+    ) -> Result<BTreeMap<String, AddressName>, SubgraphReadError> {
         let protocols = self
             .protocoler
-            .protocols_of_network(input.network_id, None)?
-            .map(|p| p.protocol);
+            .protocols_of_network(input.network_id, None)?;
         // remove duplicates
         let addresses = remove_addresses_from_batch(input.addresses);
         let addresses_len = addresses.len();
-        let result = resolve_addresses(self.pool.as_ref(), protocols, addresses).await?;
+        let result = resolve_addresses(
+            self.pool.as_ref(),
+            protocols,
+            addresses,
+            input.include_unverified,
+        )
+        .await?;
 
-        let address_to_name: BTreeMap<String, String> = iter_to_map(
-            result
-                .into_iter()
-                .map(|d| (d.resolved_address, d.domain_name)),
-        );
+        let address_to_name: BTreeMap<String, AddressName> =
+            iter_to_map(result.into_iter().map(|d| {
+                (
+                    d.resolved_address,
+                    AddressName {
+                        name: d.domain_name,
+                        verified: d.verified,
+                        verification_skipped: d.verification_skipped,
+                    },
+                )
+            }));
         tracing::debug!(address_to_name =? address_to_name, "{}/{addresses_len} names found from batch request", address_to_name.len());
         Ok(address_to_name)
     }
 }
 
+async fn domain_text_records(
+    domain: &DetailedDomain,
+    name: &DomainNameOnProtocol<'_>,
+) -> BTreeMap<String, String> {
+    let records = domain.text_records.0.clone();
+    let has_missing_values = records.values().any(Option::is_none);
+    let maybe_resolver = domain
+        .resolver
+        .as_deref()
+        .and_then(|resolver| ResolverInSubgraph::from_str(resolver).ok());
+    match maybe_resolver {
+        Some(resolver) if has_missing_values => {
+            ens::text_records_with_rpc_fallback(name, resolver.resolver_address, records).await
+        }
+        _ => records
+            .into_iter()
+            .filter_map(|(key, value)| value.filter(|v| !v.is_empty()).map(|v| (key, v)))
+            .collect(),
+    }
+}
+
+fn domain_contenthash(domain: &DetailedDomain) -> Option<String> {
+    let raw = domain.contenthash.as_ref().filter(|raw| !raw.is_empty())?;
+    contenthash::decode(raw)
+        .map_err(|err| {
+            tracing::warn!(
+                domain_id = domain.id,
+                contenthash = hex(raw),
+                error = ?err,
+                "failed to decode contenthash"
+            )
+        })
+        .ok()
+}
+
 // remove duplicates, remove unresolvable addresses, take only MAX_RESOLVE_ADDRESSES
 fn remove_addresses_from_batch(addresses: impl IntoIterator<Item = Address>) -> Vec<Address> {
     addresses
@@ -516,7 +614,11 @@ fn lookup_output_from_domains(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{protocols::DomainNameOnProtocol, subgraph::sql, test_utils::mocked_reader};
+    use crate::{
+        protocols::DomainNameOnProtocol,
+        subgraph::{sql, SubdomainPaginationInput},
+        test_utils::mocked_reader,
+    };
     use alloy::primitives::Address;
     use pretty_assertions::assert_eq;
 
@@ -550,6 +652,20 @@ mod tests {
         }))
         .unwrap();
         assert_eq!(domain.other_addresses, other_addresses.into());
+        assert_eq!(
+            result.text_records,
+            BTreeMap::from_iter([
+                (
+                    "avatar".to_string(),
+                    "https://euc.li/vitalik.eth".to_string()
+                ),
+                ("url".to_string(), "https://vitalik.ca".to_string()),
+            ])
+        );
+        assert_eq!(
+            result.contenthash.as_deref(),
+            Some("ipfs://QmXGkXcfasLtjLk9i5brs2sMYNbk34c8FGdBDCP864mrhV")
+        );
 
         // get expired domain
         let name = "expired.eth".to_string();
@@ -571,6 +687,8 @@ mod tests {
         );
         // since no info in multicoin_addr_changed
         assert!(domain.other_addresses.is_empty());
+        assert!(result.text_records.is_empty());
+        assert_eq!(result.contenthash, None);
 
         // get expired domain with only_active filter
         let result = reader
@@ -614,6 +732,86 @@ mod tests {
         );
     }
 
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn list_subdomains_works(pool: PgPool) {
+        let reader = mocked_reader(pool).await;
+        let list_subdomains = |name: &str, only_active, pagination| {
+            reader.list_subdomains(ListSubdomainsInput {
+                network_id: DEFAULT_CHAIN_ID,
+                name: name.to_string(),
+                only_active,
+                pagination,
+                protocol_id: None,
+            })
+        };
+        let names = |result: &PaginatedList<LookupOutput>| {
+            result
+                .items
+                .iter()
+                .map(|output| output.domain.name.clone().unwrap_or_default())
+                .collect::<Vec<_>>()
+        };
+
+        let result = list_subdomains(
+            "eth",
+            true,
+            SubdomainPaginationInput {
+                page_size: 3,
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("failed to list eth subdomains");
+        assert_eq!(
+            names(&result),
+            vec!["academia.eth", "android.eth", "aliexpress.eth"]
+        );
+        assert_eq!(result.next_page_token.as_deref(), Some("1495263091"));
+
+        // domains with the same expiry date are sorted by id,
+        // domains without expiry date are the last ones
+        let pagination = SubdomainPaginationInput {
+            sort: SubdomainSortField::ExpiryDate,
+            page_size: 2,
+            ..Default::default()
+        };
+        let result = list_subdomains("eth", false, pagination.clone())
+            .await
+            .expect("failed to list eth subdomains");
+        assert_eq!(names(&result), vec!["expired.eth", "wa🇬🇲i.eth"]);
+        assert_eq!(
+            result.next_page_token.as_deref(),
+            Some("2147483647:0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff")
+        );
+        let result = list_subdomains(
+            "eth",
+            false,
+            SubdomainPaginationInput {
+                page_token: result.next_page_token,
+                ..pagination
+            },
+        )
+        .await
+        .expect("failed to list eth subdomains");
+        assert_eq!(
+            names(&result),
+            vec![
+                "sashaxyz.eth",
+                "[0b0e081f36b3970ff8e337f0ff7bdfad321a702fa00916b6ccfc47877144f7ad].eth"
+            ]
+        );
+        assert_eq!(
+            result.next_page_token.as_deref(),
+            Some("2147483647:0xeb4f647bea6caa36333c816d7b46fdcb05f9466ecacc140ea8c66faf15b3d9f1")
+        );
+
+        let result = list_subdomains("vitalik.eth", false, Default::default())
+            .await
+            .expect("failed to list vitalik subdomains");
+        assert!(result.items.is_empty());
+        assert_eq!(result.next_page_token, None);
+    }
+
     #[sqlx::test(migrations = "tests/migrations")]
     async fn lookup_addresses_works(pool: PgPool) {
         let reader = mocked_reader(pool).await;
@@ -773,7 +971,7 @@ mod tests {
                 timestamp: "2021-02-15T17:19:09.000000Z".into(),
                 from_address: addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045"),
                 method: Some("multicall".into()),
-                actions: vec!["addr_changed".into()],
+                actions: vec!["addr_changed".into(), "text_changed".into()],
                 block_number: 11862656,
             },
             DomainEvent {
@@ -817,19 +1015,61 @@ mod tests {
         .into_iter()
         .map(addr)
         .collect();
-        let expected_domains = serde_json::from_value(serde_json::json!({
-            "0x9c996076a85b46061d9a70ff81f013853a86b619": "wa🇬🇲i.eth",
-            "0xd8da6bf26964af9d7eed9e03e53415d37aa96045": "vitalik.eth",
-        }))
-        .unwrap();
+        // both names are verified: reverse records forward-resolve back to the addresses
+        let expected_domains = BTreeMap::from_iter([
+            (
+                "0x9c996076a85b46061d9a70ff81f013853a86b619".to_string(),
+                AddressName {
+                    name: "wa🇬🇲i.eth".to_string(),
+                    verified: true,
+                    verification_skipped: false,
+                },
+            ),
+            (
+                "0xd8da6bf26964af9d7eed9e03e53415d37aa96045".to_string(),
+                AddressName {
+                    name: "vitalik.eth".to_string(),
+                    verified: true,
+                    verification_skipped: false,
+                },
+            ),
+        ]);
         let domains = reader
             .batch_resolve_address_names(BatchResolveAddressNamesInput {
                 network_id: DEFAULT_CHAIN_ID,
-                addresses,
+                addresses: addresses.clone(),
+                include_unverified: false,
             })
             .await
             .expect("failed to resolve addresess");
         assert_eq!(domains, expected_domains);
+
+        // unverified reverse names are returned only on request
+        let domains = reader
+            .batch_resolve_address_names(BatchResolveAddressNamesInput {
+                network_id: DEFAULT_CHAIN_ID,
+                addresses,
+                include_unverified: true,
+            })
+            .await
+            .expect("failed to resolve addresess");
+        for (address, name) in &expected_domains {
+            assert_eq!(domains.get(address), Some(name));
+        }
+
+        let result = reader
+            .get_address(GetAddressInput {
+                network_id: DEFAULT_CHAIN_ID,
+                address: addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045"),
+                protocol_id: None,
+                include_unverified: false,
+            })
+            .await
+            .expect("failed to get address")
+            .expect("vitalik address is not resolved");
+        assert_eq!(result.domain.domain.name.as_deref(), Some("vitalik.eth"));
+        assert!(result.name_verified);
+        assert!(!result.name_verification_skipped);
     }
 
     #[sqlx::test(migrations = "tests/migrations")]
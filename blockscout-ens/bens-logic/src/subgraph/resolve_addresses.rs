@@ -1,7 +1,10 @@
 use crate::{
-    entity::subgraph::domain::DomainWithAddress,
-    protocols::{hash_name::hex, AddressResolveTechnique, DomainName, Protocol},
-    subgraph::{sql, sql::DbErr},
+    entity::subgraph::domain::{AddrReverseDomainWithActualName, DomainWithAddress},
+    protocols::{
+        hash_name::hex, AddressResolveTechnique, DeployedProtocol, DomainName,
+        DomainNameOnProtocol, Protocol,
+    },
+    subgraph::{ens, sql, sql::DbErr},
 };
 use alloy::primitives::Address;
 use nonempty::{nonempty, NonEmpty};
@@ -11,25 +14,35 @@ use std::{
     str::FromStr,
 };
 
+const MAX_FORWARD_RESOLUTION_CALLS: usize = 20;
+
+/// Resolves addresses to their names.
+///
+/// Reverse names that don't forward-resolve back to the address (or that were not
+/// checked) may be set by anyone, so they are returned only if `include_unverified` is set.
 pub async fn resolve_addresses(
     pool: &PgPool,
-    protocols: NonEmpty<&Protocol>,
+    protocols: NonEmpty<DeployedProtocol<'_>>,
     addresses: Vec<Address>,
+    include_unverified: bool,
 ) -> Result<Vec<DomainWithAddress>, DbErr> {
     let mut result = vec![];
-    for (technique, protocols) in
-        grouping_by(protocols, |p| p.info.address_resolve_technique.clone())
-    {
+    for (technique, protocols) in grouping_by(protocols, |p| {
+        p.protocol.info.address_resolve_technique.clone()
+    }) {
         let found_domains = match technique {
             AddressResolveTechnique::AllDomains => {
-                resolve_all_domains_cached(pool, &protocols, &addresses).await?
+                resolve_all_domains_cached(pool, &protocols.map(|p| p.protocol), &addresses).await?
             }
             AddressResolveTechnique::ReverseRegistry => {
-                resolve_addr_reverse_cached(pool, &protocols, &addresses).await?
+                resolve_addr_reverse_cached(pool, &protocols, &addresses, include_unverified)
+                    .await?
             }
         };
         result.extend(found_domains);
     }
+    // verified names go first, so they are preferred over other names of the same address
+    result.sort_by_key(|domain| !domain.verified);
     Ok(result)
 }
 
@@ -58,40 +71,141 @@ async fn resolve_all_domains_cached(
     addresses: &[Address],
 ) -> Result<Vec<DomainWithAddress>, DbErr> {
     let addresses_str: Vec<String> = addresses.iter().map(hex).collect();
-    sql::AddressNamesView::batch_search_addresses(pool, protocols, &addresses_str).await
+    let domains = sql::AddressNamesView::batch_search_addresses(pool, protocols, &addresses_str)
+        .await?
+        .into_iter()
+        // names are found by their resolved address, so they are verified by construction
+        .map(|domain| DomainWithAddress {
+            verified: true,
+            ..domain
+        })
+        .collect();
+    Ok(domains)
 }
 
 async fn resolve_addr_reverse_cached(
     pool: &PgPool,
-    protocols: &NonEmpty<&Protocol>,
+    protocols: &NonEmpty<DeployedProtocol<'_>>,
     addresses: &[Address],
+    include_unverified: bool,
 ) -> Result<Vec<DomainWithAddress>, DbErr> {
-    let addr_reverse_hashes = addresses
+    // mapping of
+    // hash(`{addr}.addr.reverse`) -> addr
+    let addr_reverse_ids: HashMap<String, Address> = addresses
         .iter()
-        .map(|addr| DomainName::addr_reverse(addr).id)
-        .collect::<Vec<String>>();
-    let addr_reverse_domains =
-        sql::AddrReverseNamesView::batch_search_addresses(pool, protocols, &addr_reverse_hashes)
-            .await?;
+        .map(|addr| (DomainName::addr_reverse(addr).id, *addr))
+        .collect();
+    let addr_reverse_hashes = addr_reverse_ids.keys().collect::<Vec<_>>();
+    let addr_reverse_domains = sql::AddrReverseNamesView::batch_search_addresses(
+        pool,
+        &protocols.clone().map(|p| p.protocol),
+        &addr_reverse_hashes,
+    )
+    .await?;
+
+    let mut domains = vec![];
+    let mut not_verified_in_subgraph = vec![];
+    for row in addr_reverse_domains {
+        let Some(address) = addr_reverse_ids.get(&row.reversed_domain_id).copied() else {
+            continue;
+        };
+        let resolved_address = row
+            .resolved_address
+            .as_deref()
+            .and_then(|resolved_address| Address::from_str(resolved_address).ok());
+        if resolved_address == Some(address) {
+            domains.push(verified_domain(row, address));
+        } else {
+            not_verified_in_subgraph.push((row, address));
+        }
+    }
+    domains.extend(
+        verify_forward_resolution_with_rpc(protocols, not_verified_in_subgraph)
+            .await
+            .into_iter()
+            .filter(|domain| include_unverified || domain.verified),
+    );
 
-    let domains: Vec<DomainWithAddress> = addr_reverse_domains
+    Ok(domains)
+}
+
+/// Subgraph doesn't index resolved address for every resolver (offchain, wildcard
+/// or custom resolvers), so reverse names that are not verified using subgraph
+/// are checked with RPC call to the resolver of the name.
+///
+/// All names are returned, so the caller decides whether to keep the unverified ones.
+/// Names that were not checked (because of RPC error or [`MAX_FORWARD_RESOLUTION_CALLS`]
+/// limit) are marked with `verification_skipped`.
+async fn verify_forward_resolution_with_rpc(
+    protocols: &NonEmpty<DeployedProtocol<'_>>,
+    reverse_names: Vec<(AddrReverseDomainWithActualName, Address)>,
+) -> Vec<DomainWithAddress> {
+    if reverse_names.len() > MAX_FORWARD_RESOLUTION_CALLS {
+        tracing::warn!(
+            reverse_names = reverse_names.len(),
+            "too many reverse names to verify, only first {MAX_FORWARD_RESOLUTION_CALLS} will be checked"
+        );
+    }
+    let checks = reverse_names
         .into_iter()
-        .filter_map(|row| {
-            let addr = Address::from_str(&row.resolved_address).ok()?;
-            let addr_reverse_id = DomainName::addr_reverse(&addr).id;
-            if addr_reverse_id == row.reversed_domain_id {
-                Some(DomainWithAddress {
-                    id: row.domain_id,
-                    domain_name: row.name,
-                    resolved_address: row.resolved_address,
-                })
-            } else {
-                None
+        .enumerate()
+        .map(|(i, (row, address))| async move {
+            if i >= MAX_FORWARD_RESOLUTION_CALLS {
+                return reverse_domain(row, address, Verification::Skipped);
             }
-        })
-        .collect();
+            let Some(deployed_protocol) = protocols
+                .iter()
+                .find(|p| p.protocol.info.slug == row.protocol_slug)
+            else {
+                return reverse_domain(row, address, Verification::NotVerified);
+            };
+            let name = match DomainNameOnProtocol::from_str(&row.name, *deployed_protocol) {
+                Ok(name) => name,
+                Err(err) => {
+                    tracing::warn!(name = %row.name, error = ?err, "invalid reverse name");
+                    return reverse_domain(row, address, Verification::NotVerified);
+                }
+            };
+            let verification = match ens::forward_resolves_to_with_cache(&name, address).await {
+                Ok(true) => Verification::Verified,
+                Ok(false) => Verification::NotVerified,
+                Err(err) => {
+                    tracing::warn!(
+                        name = %name.inner.name,
+                        error = ?err,
+                        "failed to check forward resolution"
+                    );
+                    Verification::Skipped
+                }
+            };
+            reverse_domain(row, address, verification)
+        });
+    futures::future::join_all(checks).await
+}
 
-    Ok(domains)
+/// Result of forward resolution check of reverse name
+enum Verification {
+    Verified,
+    NotVerified,
+    Skipped,
+}
+
+fn verified_domain(row: AddrReverseDomainWithActualName, address: Address) -> DomainWithAddress {
+    reverse_domain(row, address, Verification::Verified)
+}
+
+fn reverse_domain(
+    row: AddrReverseDomainWithActualName,
+    address: Address,
+    verification: Verification,
+) -> DomainWithAddress {
+    DomainWithAddress {
+        id: row.domain_id,
+        domain_name: row.name,
+        resolved_address: hex(address),
+        verified: matches!(verification, Verification::Verified),
+        verification_skipped: matches!(verification, Verification::Skipped),
+    }
 }
 
 // async fn resolve_addr_reverse(
@@ -81,9 +81,12 @@ impl AddrReverseNamesView {
                 .expr(Expr::cust("reversed_domain_id"))
                 .expr(Expr::cust("resolved_address"))
                 .expr(Expr::cust("name"))
+                .expr_as(
+                    Expr::cust(format!("'{}'", p.info.slug)),
+                    Alias::new("protocol_slug"),
+                )
                 .from((Alias::new(&p.subgraph_schema), Alias::new(view_table_name)))
                 .and_where(Expr::cust("reversed_domain_id = ANY($1)"))
-                .and_where(Expr::cust("resolved_address IS NOT NULL"))
                 .to_owned()
         }))
         .expect("protocols is nonempty");
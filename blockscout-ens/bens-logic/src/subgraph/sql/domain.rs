@@ -3,10 +3,12 @@ use crate::{
     protocols::{hash_name::hex, DomainNameOnProtocol, Protocol},
     subgraph::{
         sql::{utils, DbErr},
-        DomainPaginationInput, GetDomainInput, LookupAddressInput,
+        DomainPaginationInput, GetDomainInput, LookupAddressInput, Paginator,
+        SubdomainPaginationInput,
     },
 };
 use alloy::primitives::Address;
+use anyhow::Context;
 use nonempty::NonEmpty;
 use sea_query::{Alias, Condition, Expr, PostgresQueryBuilder, SelectStatement};
 use sql_gen::QueryBuilderExt;
@@ -147,7 +149,41 @@ pub async fn get_domain(
             COALESCE(
                 multi_coin_addresses.coin_to_addr,
                 '{{}}'::json
-            ) as other_addresses
+            ) as other_addresses,
+            COALESCE(
+                (
+                    SELECT json_object_agg(text_keys.key, latest_text.value)
+                    FROM (
+                        SELECT unnest(r.texts) AS key
+                        FROM {schema}.resolver r
+                        WHERE
+                            r.id = {schema}.domain.resolver
+                            AND r.{DOMAIN_BLOCK_RANGE_WHERE_CLAUSE}
+                        UNION
+                        SELECT tc.key
+                        FROM {schema}.text_changed tc
+                        WHERE tc.resolver = {schema}.domain.resolver
+                    ) text_keys
+                    LEFT JOIN LATERAL (
+                        SELECT tc.value
+                        FROM {schema}.text_changed tc
+                        WHERE
+                            tc.resolver = {schema}.domain.resolver
+                            AND tc.key = text_keys.key
+                        ORDER BY tc.block_number DESC, tc.vid DESC
+                        LIMIT 1
+                    ) latest_text ON true
+                ),
+                '{{}}'::json
+            ) as text_records,
+            (
+                SELECT r.content_hash
+                FROM {schema}.resolver r
+                WHERE
+                    r.id = {schema}.domain.resolver
+                    AND r.{DOMAIN_BLOCK_RANGE_WHERE_CLAUSE}
+                LIMIT 1
+            ) as contenthash
         FROM {schema}.domain
         LEFT JOIN (
             SELECT 
@@ -240,6 +276,43 @@ pub async fn find_domains(
     Ok(domains)
 }
 
+// Subdomains are always stored in the same subgraph as their parent, so there is
+// no union of protocols and pagination is applied to raw `domain` table columns
+// (sorting by `expiry_date` relies on it)
+#[instrument(
+    skip_all,
+    err(level = "error"),
+    level = "info",
+    fields(
+        domain_name = %parent.inner.name,
+        protocol_slug = %parent.deployed_protocol.protocol.info.slug)
+    )
+]
+pub async fn find_subdomains(
+    pool: &PgPool,
+    parent: &DomainNameOnProtocol<'_>,
+    only_active: bool,
+    pagination: &SubdomainPaginationInput,
+) -> Result<Vec<Domain>, DbErr> {
+    let mut query = sql_gen::domain_select(parent.deployed_protocol.protocol);
+    query
+        .and_where(Expr::cust("parent = $1"))
+        .with_block_range();
+    if only_active {
+        query.with_not_expired();
+    }
+    pagination
+        .add_to_query(&mut query)
+        .context("adding pagination to query")?;
+    let sql = query.to_string(PostgresQueryBuilder);
+    tracing::debug!(sql = sql, "build SQL query for 'find_subdomains'");
+    let domains = sqlx::query_as(&sql)
+        .bind(&parent.inner.id)
+        .fetch_all(pool)
+        .await?;
+    Ok(domains)
+}
+
 #[instrument(
     skip_all,
     err(level = "error"),
@@ -1,13 +1,13 @@
-use super::pagination::{DomainPaginationInput, Order};
+use super::pagination::{DomainPaginationInput, Order, SubdomainPaginationInput};
 use crate::{
     entity::subgraph::domain::{DetailedDomain, Domain},
     protocols::{Network, Protocol},
 };
 use alloy::primitives::Address;
 use nonempty::NonEmpty;
-use sea_query::{Alias, IntoIden};
+use sea_query::{Alias, Expr, IntoIden};
 use serde::Deserialize;
-use std::{fmt::Display, str::FromStr};
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
 
 #[derive(Debug, Clone)]
 pub struct GetDomainInput {
@@ -46,11 +46,22 @@ pub struct LookupAddressInput {
     pub maybe_filter_protocols: Option<NonEmpty<String>>,
 }
 
+#[derive(Debug, Clone)]
+pub struct ListSubdomainsInput {
+    pub network_id: i64,
+    pub name: String,
+    pub only_active: bool,
+    pub pagination: SubdomainPaginationInput,
+    pub protocol_id: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct GetAddressInput {
     pub network_id: i64,
     pub address: Address,
     pub protocol_id: Option<String>,
+    /// Return reverse name even if it is not verified
+    pub include_unverified: bool,
 }
 
 impl Default for DomainPaginationInput {
@@ -64,10 +75,23 @@ impl Default for DomainPaginationInput {
     }
 }
 
+impl Default for SubdomainPaginationInput {
+    fn default() -> Self {
+        Self {
+            sort: Default::default(),
+            order: Default::default(),
+            page_size: 50,
+            page_token: Default::default(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BatchResolveAddressNamesInput {
     pub network_id: i64,
     pub addresses: Vec<Address>,
+    /// Return reverse names even if they are not verified
+    pub include_unverified: bool,
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Default)]
@@ -85,6 +109,30 @@ impl DomainSortField {
     }
 }
 
+/// Sorting of subdomains. Unlike [`DomainSortField`], it is applied to raw columns
+/// of `domain` table (subdomains are not unioned across protocols), so domains
+/// can be sorted by `expiry_date` as well
+#[derive(Debug, Clone, Copy, Deserialize, Default)]
+pub enum SubdomainSortField {
+    #[default]
+    RegistrationDate,
+    ExpiryDate,
+}
+
+impl SubdomainSortField {
+    /// Domains without expiry date never expire, so they are sorted as the latest ones
+    pub const NEVER_EXPIRES: i64 = i64::MAX;
+
+    pub fn to_database_field(&self) -> sea_query::SimpleExpr {
+        match self {
+            SubdomainSortField::RegistrationDate => Expr::col(Alias::new("created_at")).into(),
+            SubdomainSortField::ExpiryDate => {
+                Expr::cust(format!("COALESCE(expiry_date, {})", Self::NEVER_EXPIRES))
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Default)]
 pub enum EventSort {
     #[default]
@@ -103,10 +151,30 @@ impl Display for EventSort {
 pub struct GetDomainOutput {
     pub domain: DetailedDomain,
     pub tokens: Vec<DomainToken>,
+    pub text_records: BTreeMap<String, String>,
+    pub contenthash: Option<String>,
     pub protocol: Protocol,
     pub deployment_network: Network,
 }
 
+#[derive(Debug, Clone)]
+pub struct GetAddressOutput {
+    pub domain: GetDomainOutput,
+    /// Domain name is reverse record of the address, which forward-resolves back to it
+    pub name_verified: bool,
+    /// Forward resolution of the name was not checked, so it may be verified
+    pub name_verification_skipped: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressName {
+    pub name: String,
+    /// Name is reverse record of the address, which forward-resolves back to it
+    pub verified: bool,
+    /// Forward resolution of the name was not checked, so it may be verified
+    pub verification_skipped: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DomainToken {
     pub id: String,
@@ -98,6 +98,12 @@ INSERT INTO sgd1.multicoin_addr_changed VALUES
 (6,'[2464708,)','2464708-0-0','0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41-0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835',2464708,'\x18811D8623AEF9A212D70A0C0C0A8B6C126E3715EF1B28759D3E5D570B34F589',137,'\xf0d485009714ce586358e3761754929904d76b9d')
 ;
 
+INSERT INTO sgd1.text_changed VALUES
+(1,'[11862656,)','11862656-318','0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41-0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835',11862656,'\x160EF4492C731AC6B59BEEBE1E234890CD55D4C556F8847624A0B47125FE4F84','url','https://vitalik.eth.limo'),
+(2,'[11862656,)','11862656-319','0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41-0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835',11862656,'\x160EF4492C731AC6B59BEEBE1E234890CD55D4C556F8847624A0B47125FE4F84','url','https://vitalik.ca'),
+(3,'[11862656,)','11862656-320','0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41-0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835',11862656,'\x160EF4492C731AC6B59BEEBE1E234890CD55D4C556F8847624A0B47125FE4F84','avatar','https://euc.li/vitalik.eth')
+;
+
 -- name changed for reverse records
 INSERT INTO sgd1.name_changed VALUES
 (1,'[7086410,)','7086410-0-12','0xa2c122be93b0074270ebee7f6b7292c7deb45047-0x7aef81fbd30c83431369026d62ee533af8b69f246b63d75b40fe223346e6fa9a',7086410,'\xAC29B8D9F53D4FCF1D117D6F695D253C14E9BCB4F00B8F33B35540CA485FA659','vitalik.eth'),
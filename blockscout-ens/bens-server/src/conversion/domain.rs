@@ -4,10 +4,14 @@ use super::{
 };
 use crate::conversion::order_direction_from_inner;
 use alloy::primitives::Address;
-use bens_logic::subgraph::{
-    BatchResolveAddressNamesInput, DomainPaginationInput, DomainSortField, DomainToken,
-    DomainTokenType, GetAddressInput, GetDomainInput, GetDomainOutput, LookupAddressInput,
-    LookupDomainInput, LookupOutput,
+use bens_logic::{
+    protocols::is_normalized,
+    subgraph::{
+        AddressName, BatchResolveAddressNamesInput, DomainPaginationInput, DomainSortField,
+        DomainToken, DomainTokenType, GetAddressInput, GetDomainInput, GetDomainOutput,
+        ListSubdomainsInput, LookupAddressInput, LookupDomainInput, LookupOutput,
+        SubdomainPaginationInput, SubdomainSortField,
+    },
 };
 use bens_proto::blockscout::bens::v1 as proto;
 use std::{collections::BTreeMap, str::FromStr};
@@ -71,6 +75,26 @@ pub fn lookup_address_from_inner(
     })
 }
 
+pub fn list_subdomains_from_inner(
+    inner: proto::ListSubdomainsRequest,
+) -> Result<ListSubdomainsInput, ConversionError> {
+    let sort = subdomain_sort_from_inner(&inner.sort)?;
+    let order = order_direction_from_inner(inner.order());
+    let name = name_from_inner(inner.name)?;
+    Ok(ListSubdomainsInput {
+        network_id: inner.chain_id,
+        name,
+        only_active: inner.only_active,
+        pagination: SubdomainPaginationInput {
+            sort,
+            order,
+            page_size: page_size_from_inner(inner.page_size),
+            page_token: inner.page_token,
+        },
+        protocol_id: inner.protocol_id,
+    })
+}
+
 pub fn get_address_from_inner(
     inner: proto::GetAddressRequest,
 ) -> Result<GetAddressInput, ConversionError> {
@@ -79,6 +103,7 @@ pub fn get_address_from_inner(
         network_id: inner.chain_id,
         address,
         protocol_id: inner.protocol_id,
+        include_unverified: inner.include_unverified,
     })
 }
 
@@ -91,6 +116,16 @@ pub fn domain_sort_from_inner(inner: &str) -> Result<DomainSortField, Conversion
     }
 }
 
+fn subdomain_sort_from_inner(inner: &str) -> Result<SubdomainSortField, ConversionError> {
+    match inner {
+        "" | "registration_date" | "registrationDate" => Ok(SubdomainSortField::RegistrationDate),
+        "expiry_date" | "expiryDate" => Ok(SubdomainSortField::ExpiryDate),
+        _ => Err(ConversionError::UserRequest(format!(
+            "unknow sort field '{inner}'"
+        ))),
+    }
+}
+
 pub fn batch_resolve_from_inner(
     inner: proto::BatchResolveAddressNamesRequest,
 ) -> Result<BatchResolveAddressNamesInput, ConversionError> {
@@ -102,21 +137,28 @@ pub fn batch_resolve_from_inner(
     Ok(BatchResolveAddressNamesInput {
         network_id: inner.chain_id,
         addresses,
+        include_unverified: inner.include_unverified,
     })
 }
 
 pub fn batch_resolve_from_logic(
-    output: BTreeMap<String, String>,
+    output: BTreeMap<String, AddressName>,
     chain_id: i64,
 ) -> Result<proto::BatchResolveAddressNamesResponse, ConversionError> {
-    let names = output
-        .into_iter()
-        .map(|(address, name)| {
-            let address = address_from_str_logic(&address, chain_id)?.hash;
-            Ok((address, name))
-        })
-        .collect::<Result<_, _>>()?;
-    Ok(proto::BatchResolveAddressNamesResponse { names })
+    let mut names = BTreeMap::new();
+    let mut names_verified = BTreeMap::new();
+    let mut names_verification_skipped = BTreeMap::new();
+    for (address, name) in output {
+        let address = address_from_str_logic(&address, chain_id)?.hash;
+        names_verified.insert(address.clone(), name.verified);
+        names_verification_skipped.insert(address.clone(), name.verification_skipped);
+        names.insert(address, name.name);
+    }
+    Ok(proto::BatchResolveAddressNamesResponse {
+        names,
+        names_verified,
+        names_verification_skipped,
+    })
 }
 
 pub fn detailed_domain_from_logic(
@@ -150,9 +192,11 @@ pub fn detailed_domain_from_logic(
         .map(|t| domain_token_from_logic(t, chain_id))
         .collect();
     let protocol = Some(protocol_from_logic(protocol, network));
+    let name = domain.name.unwrap_or_default();
     Ok(proto::DetailedDomain {
         id: domain.id,
-        name: domain.name.unwrap_or_default(),
+        name_is_normalized: is_normalized(&name),
+        name,
         owner,
         resolved_address,
         registrant,
@@ -165,6 +209,8 @@ pub fn detailed_domain_from_logic(
         stored_offchain: domain.stored_offchain,
         resolved_with_wildcard: domain.resolved_with_wildcard,
         resolver_address,
+        text_records: output.text_records.into_iter().collect(),
+        contenthash: output.contenthash,
     })
 }
 
@@ -187,9 +233,11 @@ pub fn domain_from_logic(
         output.protocol,
         output.deployment_network,
     ));
+    let name = domain.name.unwrap_or_default();
     Ok(proto::Domain {
         id: domain.id,
-        name: domain.name.unwrap_or_default(),
+        name_is_normalized: is_normalized(&name),
+        name,
         owner,
         wrapped_owner,
         resolved_address,
@@ -106,6 +106,28 @@ impl DomainsExtractor for DomainsExtractorService {
         Ok(tonic::Response::new(response))
     }
 
+    async fn list_subdomains(
+        &self,
+        request: tonic::Request<ListSubdomainsRequest>,
+    ) -> Result<tonic::Response<ListSubdomainsResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let chain_id = request.chain_id;
+        let input =
+            conversion::list_subdomains_from_inner(request).map_err(map_convertion_error)?;
+        let page_size = input.pagination.page_size;
+        let result = self
+            .subgraph_reader
+            .list_subdomains(input)
+            .await
+            .map_err(map_subgraph_error)?;
+        let items = from_resolved_domains_result(result.items, chain_id)?;
+        let response = ListSubdomainsResponse {
+            items,
+            next_page_params: pagination_from_logic(result.next_page_token, page_size),
+        };
+        Ok(tonic::Response::new(response))
+    }
+
     async fn get_address(
         &self,
         request: tonic::Request<GetAddressRequest>,
@@ -114,12 +136,16 @@ impl DomainsExtractor for DomainsExtractorService {
         let chain_id = request.chain_id;
         let input = conversion::get_address_from_inner(request).map_err(map_convertion_error)?;
 
-        let domain = self
+        let output = self
             .subgraph_reader
             .get_address(input.clone())
             .await
-            .map_err(map_subgraph_error)?
-            .map(|d| conversion::detailed_domain_from_logic(d, chain_id))
+            .map_err(map_subgraph_error)?;
+        let name_verified = output.as_ref().is_some_and(|o| o.name_verified);
+        let name_verification_skipped =
+            output.as_ref().is_some_and(|o| o.name_verification_skipped);
+        let domain = output
+            .map(|o| conversion::detailed_domain_from_logic(o.domain, chain_id))
             .transpose()
             .map_err(map_convertion_error)?;
 
@@ -131,6 +157,8 @@ impl DomainsExtractor for DomainsExtractorService {
         Ok(tonic::Response::new(GetAddressResponse {
             domain,
             resolved_domains_count,
+            name_verified,
+            name_verification_skipped,
         }))
     }
 
@@ -187,7 +215,9 @@ fn map_subgraph_error(err: SubgraphReadError) -> tonic::Status {
 
 fn map_protocol_error(err: ProtocolError) -> tonic::Status {
     match err {
-        ProtocolError::InvalidName { .. } => tonic::Status::invalid_argument(err.to_string()),
+        ProtocolError::InvalidName { .. } | ProtocolError::NotNormalizable { .. } => {
+            tonic::Status::invalid_argument(err.to_string())
+        }
         ProtocolError::ProtocolNotFound(_) => tonic::Status::not_found(err.to_string()),
         ProtocolError::NetworkNotFound(_) => tonic::Status::not_found(err.to_string()),
         ProtocolError::Internal(_) => {
@@ -58,6 +58,20 @@ async fn eth_protocol_scenario(base: Url, settings: &Settings) {
     let request: Value = send_get_request(&base, "/api/v1/1/domains/vitalik.eth").await;
     let vitalik_detailed_json = data_file_as_json!("domains/vitalik_eth/detailed.json", &context);
     assert_eq!(request, vitalik_detailed_json.clone());
+    // name is normalized according to ENSIP-15 before search
+    let request: Value = send_get_request(&base, "/api/v1/1/domains/VitaliK.ETH").await;
+    assert_eq!(request, vitalik_detailed_json.clone());
+    // names that cannot be normalized are rejected
+    for path in [
+        "/api/v1/1/domains/vit_alik.eth",
+        "/api/v1/1/domains:lookup?name=vit_alik.eth",
+    ] {
+        let status = reqwest::get(&format!("{base}{path}"))
+            .await
+            .unwrap()
+            .status();
+        assert_eq!(status, 400, "invalid status for {path}");
+    }
     // get detailed domain with emojied name and with wrapped token
     let request: Value = send_get_request(&base, "/api/v1/1/domains/wa🇬🇲i.eth").await;
     assert_eq!(
@@ -115,6 +129,37 @@ async fn eth_protocol_scenario(base: Url, settings: &Settings) {
     .await;
     assert_eq!(actual, expected);
 
+    // subdomains list sorted by expiry date
+    let (actual, expected) = check_list_result(
+        &base,
+        "/api/v1/1/domains/eth/subdomains",
+        HashMap::from_iter([
+            ("only_active".into(), "true".into()),
+            ("sort".into(), "expiry_date".into()),
+            ("order".into(), "ASC".into()),
+            ("page_size".into(), "2".into()),
+        ]),
+        vec![expected_domains[1].clone(), expected_domains[0].clone()],
+        Some((
+            2,
+            Some(
+                "2147483647:0xbbe8c4a4631586c84c5b07cdfcbe27f5131ae9dc0176f8ecd324f9b6c5db777b"
+                    .into(),
+            ),
+        )),
+    )
+    .await;
+    assert_eq!(actual, expected);
+    let (actual, expected) = check_list_result(
+        &base,
+        "/api/v1/1/domains/vitalik.eth/subdomains",
+        HashMap::new(),
+        vec![],
+        Some((50, None)),
+    )
+    .await;
+    assert_eq!(actual, expected);
+
     // domain lookup
     let expected_domains = vec![data_file_as_json!(
         "domains/sashaxyz_eth/short.json",
@@ -219,6 +264,14 @@ async fn eth_protocol_scenario(base: Url, settings: &Settings) {
             "names": {
                 "0x9C996076A85B46061D9a70ff81F013853A86b619": "wa🇬🇲i.eth",
                 "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045": "vitalik.eth",
+            },
+            "names_verified": {
+                "0x9C996076A85B46061D9a70ff81F013853A86b619": true,
+                "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045": true,
+            },
+            "names_verification_skipped": {
+                "0x9C996076A85B46061D9a70ff81F013853A86b619": false,
+                "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045": false,
             }
         })
     );
@@ -233,6 +286,8 @@ async fn eth_protocol_scenario(base: Url, settings: &Settings) {
         json!({
             "domain": vitalik_detailed_json,
             "resolved_domains_count": 2,
+            "name_verified": true,
+            "name_verification_skipped": false,
         })
     );
 }
@@ -0,0 +1,240 @@
+use std::collections::HashSet;
+
+use anyhow::Context;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use itertools::Itertools;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use stats::{
+    data_source::{UpdateContext, UpdateParameters},
+    entity::{chart_data, sea_orm_active_enums::ChartType},
+    find_chart,
+    types::timespans::{Hour, Month, Week, Year},
+    ChartKey, MissingDatePolicy, ResolutionKind,
+};
+use stats_server::{DependencyGraph, RuntimeSetup, UpdateGroupEntry};
+
+use crate::{gaps::format_gaps, verify::verify_chart, GraphFormat};
+
+pub fn list_charts(setup: &RuntimeSetup) -> anyhow::Result<()> {
+    let charts = setup
+        .charts_info
+        .iter()
+        .chain(setup.token_charts_info.iter());
+    for (name, entry) in charts {
+        let resolutions = entry.enabled_resolutions.keys().sorted().collect_vec();
+        let Some(chart_type) = entry
+            .enabled_resolutions
+            .values()
+            .next()
+            .map(|r| r.chart_type.clone())
+        else {
+            continue;
+        };
+        let groups: Vec<&str> = setup
+            .update_groups
+            .iter()
+            .filter(|(_, group)| {
+                resolutions.iter().any(|resolution| {
+                    group
+                        .enabled_members
+                        .contains(&ChartKey::new(name.clone(), **resolution))
+                })
+            })
+            .map(|(group_name, _)| group_name.as_str())
+            .collect();
+        let resolutions = resolutions.into_iter().map(|r| String::from(*r)).join(",");
+        println!(
+            "{name}\t{chart_type:?}\t{resolutions}\t{}",
+            groups.join(",")
+        );
+    }
+    Ok(())
+}
+
+pub fn dependency_graph(setup: &RuntimeSetup, format: GraphFormat) -> anyhow::Result<()> {
+    let graph = DependencyGraph::new(&setup.update_groups);
+    match format {
+        GraphFormat::Json => println!("{}", graph.to_json()),
+        GraphFormat::Dot => print!("{}", graph.to_dot()),
+    }
+    Ok(())
+}
+
+/// Group that updates the enabled chart
+fn find_group<'a>(
+    setup: &'a RuntimeSetup,
+    key: &ChartKey,
+) -> anyhow::Result<(&'a String, &'a UpdateGroupEntry)> {
+    setup
+        .update_groups
+        .iter()
+        .find(|(_, group)| group.enabled_members.contains(key))
+        .with_context(|| format!("chart '{key}' is not enabled"))
+}
+
+pub async fn recompute(
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    user_ops: Option<&DatabaseConnection>,
+    setup: &RuntimeSetup,
+    chart: String,
+    resolution: ResolutionKind,
+    from: NaiveDate,
+    to: Option<NaiveDate>,
+) -> anyhow::Result<()> {
+    let key = ChartKey::new(chart, resolution);
+    let (group_name, group_entry) = find_group(setup, &key)?;
+    let update_time_override = match to {
+        Some(to) => {
+            if to < from {
+                anyhow::bail!("`to` must not be earlier than `from`");
+            }
+            // dependencies are updated at the same time, which would move their
+            // `last_updated_at` back
+            let dependencies = group_entry
+                .group
+                .dependency_mutex_ids_of(&key)
+                .unwrap_or_default();
+            if dependencies.len() > 1 {
+                anyhow::bail!(
+                    "`to` is only supported for charts calculated directly from blockscout data; \
+                    '{key}' depends on {:?}",
+                    dependencies
+                        .into_iter()
+                        .filter(|d| d != &key.as_string())
+                        .collect_vec()
+                );
+            }
+            to.succ_opt()
+                .map(|next_day| next_day.and_time(NaiveTime::MIN).and_utc())
+                .filter(|end| end < &Utc::now())
+        }
+        None => None,
+    };
+
+    let removed = group_entry
+        .group
+        .reset_chart_with_mutexes(db, &key, from.and_time(NaiveTime::MIN).and_utc())
+        .await?;
+    println!("{key}: removed {removed} points");
+
+    // dependants of the chart are left as is, similarly to reset in admin api
+    let params = UpdateParameters {
+        db,
+        blockscout,
+        user_ops,
+        chain: "",
+        update_time_override,
+        force_full: false,
+    };
+    group_entry
+        .group
+        .update_charts_with_mutexes(params, &HashSet::from([key.clone()]))
+        .await?;
+    println!("{key}: recalculated with group {group_name}");
+    Ok(())
+}
+
+async fn stored_timestamps(
+    db: &DatabaseConnection,
+    chart_id: i32,
+) -> Result<Vec<NaiveDateTime>, sea_orm::DbErr> {
+    chart_data::Entity::find()
+        .select_only()
+        .column(chart_data::Column::Date)
+        .filter(chart_data::Column::ChartId.eq(chart_id))
+        .order_by_asc(chart_data::Column::Date)
+        .into_tuple()
+        .all(db)
+        .await
+}
+
+pub async fn check_gaps(
+    db: &DatabaseConnection,
+    setup: &RuntimeSetup,
+    chart: Option<String>,
+) -> anyhow::Result<()> {
+    let charts = setup
+        .charts_info
+        .iter()
+        .chain(setup.token_charts_info.iter())
+        .filter(|(name, _)| chart.as_ref().map_or(true, |c| c == *name))
+        .collect_vec();
+    if let Some(chart) = chart.as_ref().filter(|_| charts.is_empty()) {
+        anyhow::bail!("chart '{chart}' is not enabled");
+    }
+
+    let mut charts_with_gaps = 0;
+    for (name, entry) in charts {
+        let line_resolutions = entry
+            .enabled_resolutions
+            .iter()
+            .filter(|(_, props)| props.chart_type == ChartType::Line)
+            .sorted_by_key(|(resolution, _)| **resolution);
+        for (resolution, props) in line_resolutions {
+            let key = ChartKey::new(name.clone(), *resolution);
+            let Some(chart_id) = find_chart(db, &key).await? else {
+                println!("{key}: not initialized");
+                continue;
+            };
+            let timestamps = stored_timestamps(db, chart_id).await?;
+            let gaps = match resolution {
+                ResolutionKind::Hour => format_gaps::<Hour>(&timestamps),
+                ResolutionKind::Day => format_gaps::<NaiveDate>(&timestamps),
+                ResolutionKind::Week => format_gaps::<Week>(&timestamps),
+                ResolutionKind::Month => format_gaps::<Month>(&timestamps),
+                ResolutionKind::Year => format_gaps::<Year>(&timestamps),
+            };
+            if gaps.is_empty() {
+                println!("{key}: no gaps");
+                continue;
+            }
+            charts_with_gaps += 1;
+            let policy_note = match props.missing_date_policy {
+                MissingDatePolicy::FillZero => "missing points are served as zero",
+                MissingDatePolicy::FillPrevious => "missing points are served as previous value",
+            };
+            println!("{key}: {} gap(s), {policy_note}", gaps.len());
+            for gap in gaps {
+                println!("  {gap}");
+            }
+        }
+    }
+    println!("found gaps in {charts_with_gaps} chart(s)");
+    Ok(())
+}
+
+pub async fn verify(
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    user_ops: Option<&DatabaseConnection>,
+    chart: String,
+    resolution: ResolutionKind,
+    samples: usize,
+) -> anyhow::Result<()> {
+    let key = ChartKey::new(chart, resolution);
+    let cx = UpdateContext::from_params_now_or_override(UpdateParameters {
+        db,
+        blockscout,
+        user_ops,
+        chain: "",
+        update_time_override: None,
+        force_full: false,
+    });
+    let checks = verify_chart(&key, &cx, samples)
+        .await
+        .with_context(|| format!("verification is not supported for chart '{key}'"))??;
+    let mismatches = checks.iter().filter(|c| !c.is_ok()).collect_vec();
+    println!(
+        "{key}: {} point(s) checked, {} mismatch(es)",
+        checks.len(),
+        mismatches.len()
+    );
+    for check in &mismatches {
+        println!("  {check}");
+    }
+    if !mismatches.is_empty() {
+        anyhow::bail!("stored data of '{key}' differs from the fresh calculation");
+    }
+    Ok(())
+}
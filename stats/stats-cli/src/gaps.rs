@@ -0,0 +1,146 @@
+use std::fmt::Display;
+
+use chrono::NaiveDateTime;
+use stats::{types::Timespan, ResolutionKind};
+
+/// Consecutive timespans that are missing in the data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gap<T> {
+    /// First missing timespan
+    pub first: T,
+    /// Last missing timespan
+    pub last: T,
+    pub missing: u64,
+}
+
+impl<T: Timespan + Clone> Display for Gap<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} - {} ({} missing)",
+            format_timespan(&self.first),
+            format_timespan(&self.last),
+            self.missing
+        )
+    }
+}
+
+pub fn format_timespan<T: Timespan + Clone>(timespan: &T) -> String {
+    match T::enum_variant() {
+        ResolutionKind::Hour => timespan
+            .saturating_start_timestamp()
+            .naive_utc()
+            .to_string(),
+        _ => timespan.clone().into_date().to_string(),
+    }
+}
+
+/// `timespans` must be sorted
+pub fn find_gaps<T>(timespans: impl IntoIterator<Item = T>) -> Vec<Gap<T>>
+where
+    T: Timespan + Ord + Clone,
+{
+    let mut gaps = vec![];
+    let mut timespans = timespans.into_iter();
+    let Some(mut previous) = timespans.next() else {
+        return gaps;
+    };
+    for current in timespans {
+        let first = previous.saturating_next_timespan();
+        let mut last = first.clone();
+        let mut next = first.clone();
+        let mut missing = 0;
+        while next < current {
+            missing += 1;
+            last = next.clone();
+            next = next.saturating_next_timespan();
+        }
+        if missing > 0 {
+            gaps.push(Gap {
+                first,
+                last,
+                missing,
+            });
+        }
+        previous = current;
+    }
+    gaps
+}
+
+/// Gaps in the stored points of a chart with resolution `T`
+pub fn format_gaps<T>(timestamps: &[NaiveDateTime]) -> Vec<String>
+where
+    T: Timespan + Ord + Clone,
+{
+    let timespans = timestamps
+        .iter()
+        .map(|timestamp| T::from_timestamp(timestamp.and_utc()));
+    find_gaps(timespans)
+        .into_iter()
+        .map(|gap| gap.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use stats::types::timespans::Month;
+
+    fn d(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    #[test]
+    fn find_gaps_works() {
+        assert_eq!(find_gaps(Vec::<NaiveDate>::new()), vec![]);
+        assert_eq!(find_gaps([d("2023-01-01")]), vec![]);
+        assert_eq!(
+            find_gaps([d("2023-01-01"), d("2023-01-02"), d("2023-01-03")]),
+            vec![]
+        );
+        assert_eq!(
+            find_gaps([
+                d("2023-01-01"),
+                d("2023-01-02"),
+                d("2023-01-05"),
+                d("2023-01-06"),
+                d("2023-01-08"),
+            ]),
+            vec![
+                Gap {
+                    first: d("2023-01-03"),
+                    last: d("2023-01-04"),
+                    missing: 2,
+                },
+                Gap {
+                    first: d("2023-01-07"),
+                    last: d("2023-01-07"),
+                    missing: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn find_gaps_ignores_duplicates() {
+        assert_eq!(
+            find_gaps([d("2023-01-01"), d("2023-01-01"), d("2023-01-02")]),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn format_gaps_works() {
+        let timestamps = ["2023-01-01", "2023-02-01", "2023-05-01"]
+            .map(|date| d(date).and_hms_opt(0, 0, 0).unwrap());
+        assert_eq!(
+            format_gaps::<Month>(&timestamps),
+            vec!["2023-03-01 - 2023-04-01 (2 missing)".to_owned()]
+        );
+        assert_eq!(
+            format_gaps::<NaiveDate>(&timestamps[..2]),
+            vec!["2023-01-02 - 2023-01-31 (30 missing)".to_owned()]
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Maintenance tool for stats charts.
+//!
+//! Works with the same stats DB and configs as `stats-server`. Chart data is modified
+//! only through update groups (with DB locks enabled), so the tool can be run alongside
+//! the server, as long as the server has `STATS__DB_UPDATE_LOCKS=true`.
+
+mod commands;
+mod gaps;
+mod verify;
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand, ValueEnum};
+use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+use stats::ResolutionKind;
+use stats_server::{
+    read_charts_config, read_layout_config, read_update_groups_config, RuntimeSetup,
+};
+use tracing_subscriber::EnvFilter;
+
+#[derive(Debug, Parser)]
+#[command(name = "stats-cli", about = "Inspect and recalculate stats charts")]
+struct Cli {
+    /// Postgres URL to stats db. Required for all commands except
+    /// `list-charts` and `dependency-graph`
+    #[arg(long, env = "STATS__DB_URL")]
+    db_url: Option<String>,
+    /// Postgres URL to blockscout db. Required for `recompute` and `verify`
+    #[arg(long, env = "STATS__BLOCKSCOUT_DB_URL")]
+    blockscout_db_url: Option<String>,
+    /// Postgres URL to user-ops-indexer db. Enables account abstraction charts
+    #[arg(long, env = "STATS__USER_OPS_INDEXER__DB_URL")]
+    user_ops_db_url: Option<String>,
+    #[arg(
+        long,
+        env = "STATS__CHARTS_CONFIG",
+        default_value = "config/charts.json"
+    )]
+    charts_config: PathBuf,
+    #[arg(
+        long,
+        env = "STATS__LAYOUT_CONFIG",
+        default_value = "config/layout.json"
+    )]
+    layout_config: PathBuf,
+    #[arg(
+        long,
+        env = "STATS__UPDATE_GROUPS_CONFIG",
+        default_value = "config/update_groups.json"
+    )]
+    update_groups_config: PathBuf,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List enabled charts with their resolutions and update groups
+    ListCharts,
+    /// Remove chart data starting from `from` and calculate it again
+    Recompute {
+        chart: String,
+        #[arg(long, value_enum, default_value_t = Resolution::Day)]
+        resolution: Resolution,
+        /// First date to recalculate
+        #[arg(long)]
+        from: NaiveDate,
+        /// Last date to recalculate. Later points are calculated on the next
+        /// update of the server. Default is up to now
+        #[arg(long)]
+        to: Option<NaiveDate>,
+    },
+    /// Find missing points in stored line charts
+    CheckGaps {
+        /// Check only this chart. Default is all enabled line charts
+        chart: Option<String>,
+    },
+    /// Compare stored values with freshly calculated ones on sample dates
+    Verify {
+        chart: String,
+        #[arg(long, value_enum, default_value_t = Resolution::Day)]
+        resolution: Resolution,
+        /// Number of points to check
+        #[arg(long, default_value_t = 10)]
+        samples: usize,
+    },
+    /// Print update groups with their charts, data sources and shared mutexes
+    DependencyGraph {
+        #[arg(long, value_enum, default_value_t = GraphFormat::Json)]
+        format: GraphFormat,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum GraphFormat {
+    Json,
+    /// Graphviz DOT (e.g. `stats-cli dependency-graph --format dot | dot -Tsvg > graph.svg`)
+    Dot,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Resolution {
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl From<Resolution> for ResolutionKind {
+    fn from(value: Resolution) -> Self {
+        match value {
+            Resolution::Hour => ResolutionKind::Hour,
+            Resolution::Day => ResolutionKind::Day,
+            Resolution::Week => ResolutionKind::Week,
+            Resolution::Month => ResolutionKind::Month,
+            Resolution::Year => ResolutionKind::Year,
+        }
+    }
+}
+
+async fn connect(url: &str) -> anyhow::Result<DatabaseConnection> {
+    let mut opt = ConnectOptions::new(url);
+    opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
+    Ok(Database::connect(opt).await?)
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    // stdout is reserved for the command output
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    let mut setup = RuntimeSetup::new(
+        read_charts_config(&cli.charts_config)?,
+        read_layout_config(&cli.layout_config)?,
+        read_update_groups_config(&cli.update_groups_config)?,
+        cli.user_ops_db_url.is_some(),
+    )?;
+    setup.enable_db_update_locks();
+    let db = || async {
+        let url = cli
+            .db_url
+            .as_deref()
+            .context("stats DB url is required for this command")?;
+        connect(url).await.context("stats DB")
+    };
+    let blockscout = || async {
+        let url = cli
+            .blockscout_db_url
+            .as_deref()
+            .context("blockscout DB url is required for this command")?;
+        connect(url).await.context("blockscout DB")
+    };
+    let user_ops = || async {
+        match cli.user_ops_db_url.as_deref() {
+            Some(url) => {
+                Ok::<_, anyhow::Error>(Some(connect(url).await.context("user-ops-indexer DB")?))
+            }
+            None => Ok(None),
+        }
+    };
+
+    match cli.command {
+        Command::ListCharts => commands::list_charts(&setup),
+        Command::DependencyGraph { format } => commands::dependency_graph(&setup, format),
+        Command::Recompute {
+            chart,
+            resolution,
+            from,
+            to,
+        } => {
+            commands::recompute(
+                &db().await?,
+                &blockscout().await?,
+                user_ops().await?.as_ref(),
+                &setup,
+                chart,
+                resolution.into(),
+                from,
+                to,
+            )
+            .await
+        }
+        Command::CheckGaps { chart } => commands::check_gaps(&db().await?, &setup, chart).await,
+        Command::Verify {
+            chart,
+            resolution,
+            samples,
+        } => {
+            commands::verify(
+                &db().await?,
+                &blockscout().await?,
+                user_ops().await?.as_ref(),
+                chart,
+                resolution.into(),
+                samples,
+            )
+            .await
+        }
+    }
+}
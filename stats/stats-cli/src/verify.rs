@@ -0,0 +1,245 @@
+//! Comparing stored chart data with the data calculated from scratch.
+//!
+//! Only charts that store the output of their main dependency as-is
+//! (i.e. without accumulating or otherwise combining it with previous values)
+//! can be verified point-by-point.
+
+use std::fmt::Display;
+
+use blockscout_metrics_tools::AggregateTimer;
+use stats::{
+    data_source::{DataSource, UpdateContext},
+    lines::*,
+    types::{Timespan, TimespanValue},
+    ChartKey, ChartProperties, MissingDatePolicy, UpdateError,
+};
+
+use crate::gaps::format_timespan;
+
+/// Stored value of a point compared to the fresh one
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleCheck {
+    pub timespan: String,
+    pub stored: String,
+    /// `None` if the value could not be determined
+    pub fresh: Option<String>,
+}
+
+impl SampleCheck {
+    pub fn is_ok(&self) -> bool {
+        self.fresh
+            .as_ref()
+            .is_some_and(|fresh| values_eq(&self.stored, fresh))
+    }
+}
+
+impl Display for SampleCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: stored {}, fresh {}",
+            self.timespan,
+            self.stored,
+            self.fresh.as_deref().unwrap_or("<unknown>")
+        )
+    }
+}
+
+/// Float values are compared with a small tolerance, since they
+/// might be calculated in a different order
+fn values_eq(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => (a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.0),
+        _ => false,
+    }
+}
+
+/// Indices of `samples` points spread evenly over `len` points
+/// (the last point is always included)
+fn sample_indices(len: usize, samples: usize) -> Vec<usize> {
+    match (len, samples) {
+        (0, _) | (_, 0) => vec![],
+        (len, samples) if samples >= len => (0..len).collect(),
+        (len, 1) => vec![len - 1],
+        (len, samples) => (0..samples)
+            .map(|i| i * (len - 1) / (samples - 1))
+            .collect(),
+    }
+}
+
+async fn verify_direct<C>(
+    cx: &UpdateContext<'_>,
+    samples: usize,
+) -> Result<Vec<SampleCheck>, UpdateError>
+where
+    C: ChartProperties,
+    C: DataSource<Output = Vec<TimespanValue<<C as ChartProperties>::Resolution, String>>>,
+    <C as DataSource>::MainDependencies:
+        DataSource<Output = Vec<TimespanValue<<C as ChartProperties>::Resolution, String>>>,
+    <C as ChartProperties>::Resolution: Timespan + Clone + Eq,
+{
+    let mut timer = AggregateTimer::new();
+    let mut stored = C::query_data(cx, None, &mut timer).await?;
+    // approximate points are expected to differ
+    let accurate_len = stored
+        .len()
+        .saturating_sub(C::approximate_trailing_points() as usize);
+    stored.truncate(accurate_len);
+
+    let mut checks = vec![];
+    for i in sample_indices(stored.len(), samples) {
+        let point = &stored[i];
+        let fresh_data = <C as DataSource>::MainDependencies::query_data(
+            cx,
+            Some(point.timespan.clone().into_time_range()),
+            &mut timer,
+        )
+        .await?;
+        let fresh = fresh_data
+            .into_iter()
+            .find(|p| p.timespan == point.timespan)
+            .map(|p| p.value)
+            .or_else(|| {
+                (C::missing_date_policy() == MissingDatePolicy::FillZero).then(|| "0".to_owned())
+            });
+        checks.push(SampleCheck {
+            timespan: format_timespan(&point.timespan),
+            stored: point.value.clone(),
+            fresh,
+        });
+    }
+    Ok(checks)
+}
+
+macro_rules! verifiable_charts {
+    ($($chart:ty),+ $(,)?) => {
+        /// `None` if verification is not supported for the chart
+        pub async fn verify_chart(
+            key: &ChartKey,
+            cx: &UpdateContext<'_>,
+            samples: usize,
+        ) -> Option<Result<Vec<SampleCheck>, UpdateError>> {
+            $(
+                if key == &<$chart as ChartProperties>::key() {
+                    return Some(verify_direct::<$chart>(cx, samples).await);
+                }
+            )+
+            None
+        }
+    };
+}
+
+// `NewAccounts` and `NewNativeCoinHolders` depend on data outside
+// of the requested range, so they are not included
+verifiable_charts!(
+    ActiveAccounts,
+    AccountsGrowthWeekly,
+    AccountsGrowthMonthly,
+    AccountsGrowthYearly,
+    AverageBaseFee,
+    AverageBaseFeeWeekly,
+    AverageBaseFeeMonthly,
+    AverageBaseFeeYearly,
+    AverageBlockRewards,
+    AverageBlockRewardsWeekly,
+    AverageBlockRewardsMonthly,
+    AverageBlockRewardsYearly,
+    AverageBlockSize,
+    AverageBlockSizeWeekly,
+    AverageBlockSizeMonthly,
+    AverageBlockSizeYearly,
+    AverageGasLimit,
+    AverageGasLimitWeekly,
+    AverageGasLimitMonthly,
+    AverageGasLimitYearly,
+    AverageGasPrice,
+    AverageGasPriceHourly,
+    AverageGasPriceWeekly,
+    AverageGasPriceMonthly,
+    AverageGasPriceYearly,
+    AveragePriorityFee,
+    AveragePriorityFeeWeekly,
+    AveragePriorityFeeMonthly,
+    AveragePriorityFeeYearly,
+    AverageTxnFee,
+    AverageTxnFeeWeekly,
+    AverageTxnFeeMonthly,
+    AverageTxnFeeYearly,
+    ContractsGrowthWeekly,
+    ContractsGrowthMonthly,
+    ContractsGrowthYearly,
+    GasUsedGrowthWeekly,
+    GasUsedGrowthMonthly,
+    GasUsedGrowthYearly,
+    NativeCoinSupply,
+    NativeCoinSupplyWeekly,
+    NativeCoinSupplyMonthly,
+    NativeCoinSupplyYearly,
+    NewAccountsWeekly,
+    NewAccountsMonthly,
+    NewAccountsYearly,
+    NewBlocks,
+    NewBlocksHourly,
+    NewBlocksWeekly,
+    NewBlocksMonthly,
+    NewBlocksYearly,
+    NewContracts,
+    NewContractsWeekly,
+    NewContractsMonthly,
+    NewContractsYearly,
+    NewNativeCoinTransfers,
+    NewNativeCoinTransfersWeekly,
+    NewNativeCoinTransfersMonthly,
+    NewNativeCoinTransfersYearly,
+    NewTxns,
+    NewTxnsHourly,
+    NewTxnsWeekly,
+    NewTxnsMonthly,
+    NewTxnsYearly,
+    NewVerifiedContracts,
+    NewVerifiedContractsWeekly,
+    NewVerifiedContractsMonthly,
+    NewVerifiedContractsYearly,
+    TxnsFee,
+    TxnsFeeWeekly,
+    TxnsFeeMonthly,
+    TxnsFeeYearly,
+    TxnsGrowthWeekly,
+    TxnsGrowthMonthly,
+    TxnsGrowthYearly,
+    TxnsSuccessRate,
+    TxnsSuccessRateWeekly,
+    TxnsSuccessRateMonthly,
+    TxnsSuccessRateYearly,
+    VerifiedContractsGrowthWeekly,
+    VerifiedContractsGrowthMonthly,
+    VerifiedContractsGrowthYearly,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_indices_works() {
+        assert_eq!(sample_indices(0, 10), Vec::<usize>::new());
+        assert_eq!(sample_indices(10, 0), Vec::<usize>::new());
+        assert_eq!(sample_indices(3, 10), vec![0, 1, 2]);
+        assert_eq!(sample_indices(10, 1), vec![9]);
+        assert_eq!(sample_indices(10, 2), vec![0, 9]);
+        assert_eq!(sample_indices(10, 4), vec![0, 3, 6, 9]);
+        assert_eq!(sample_indices(100, 3), vec![0, 49, 99]);
+    }
+
+    #[test]
+    fn values_eq_works() {
+        assert!(values_eq("10", "10"));
+        assert!(values_eq("10", "10.0"));
+        assert!(values_eq("0.30000000000000004", "0.3"));
+        assert!(!values_eq("10", "11"));
+        assert!(!values_eq("abc", "abd"));
+    }
+}
@@ -23,7 +23,27 @@ fn compile(
         )
         .field_attribute(".blockscout.stats.v1.Point.is_approximate", "#[serde(skip_serializing_if = \"std::ops::Not::not\")]")
         .field_attribute(".blockscout.stats.v1.Point.is_approximate", "#[serde(default)]")
-        .field_attribute(".blockscout.stats.v1.GetLineChartRequest.resolution", "#[serde(default)]");
+        .field_attribute(".blockscout.stats.v1.GetLineChartRequest.resolution", "#[serde(default)]")
+        .field_attribute(".blockscout.stats.v1.ExportChartsRequest.resolution", "#[serde(default)]")
+        .field_attribute(".blockscout.stats.v1.ExportChartsRequest.format", "#[serde(default)]")
+        .field_attribute(".blockscout.stats.v1.TriggerUpdateRequest.force_full", "#[serde(default)]")
+        .field_attribute(".blockscout.stats.v1.ResetChartRequest.resolution", "#[serde(default)]");
+    for request in [
+        "GetCountersRequest",
+        "GetLineChartsRequest",
+        "GetLineChartRequest",
+        "GetTokenLineChartRequest",
+        "GetTopListRequest",
+        "ExportChartsRequest",
+        "ListUpdateGroupsRequest",
+        "TriggerUpdateRequest",
+        "ResetChartRequest",
+    ] {
+        config.field_attribute(
+            format!(".blockscout.stats.v1.{request}.chain"),
+            "#[serde(default)]",
+        );
+    }
 
     config.compile_protos(protos, includes)?;
     Ok(())
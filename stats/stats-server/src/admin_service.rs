@@ -0,0 +1,272 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    str::FromStr,
+    sync::Arc,
+};
+
+use crate::{
+    chains::chain_not_found,
+    dependency_graph::DependencyGraph,
+    read_service::convert_resolution,
+    update_service::{GroupUpdateStatus, TriggerOutcome, UpdateService},
+};
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use proto_v1::stats_admin_service_server::StatsAdminService;
+use stats::{types::Timespan, ChartKey, UpdateError};
+use stats_proto::blockscout::stats::v1 as proto_v1;
+use tonic::{Request, Response, Status};
+
+const API_KEY_NAME: &str = "x-api-key";
+
+pub struct AdminService {
+    /// Chain name -> its update service (see [`crate::chains`])
+    update_services: BTreeMap<String, Arc<UpdateService>>,
+    authorized_keys: HashSet<String>,
+}
+
+impl AdminService {
+    pub fn new(
+        update_services: BTreeMap<String, Arc<UpdateService>>,
+        authorized_keys: HashSet<String>,
+    ) -> Self {
+        Self {
+            update_services,
+            authorized_keys,
+        }
+    }
+
+    fn update_service(&self, chain: &str) -> Result<&Arc<UpdateService>, Status> {
+        self.update_services
+            .get(chain)
+            .ok_or_else(|| chain_not_found(chain))
+    }
+
+    fn check_authorized<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        let api_key = request
+            .metadata()
+            .get(API_KEY_NAME)
+            .map(|api_key| api_key.to_str())
+            .transpose()
+            .map_err(|err| {
+                Status::invalid_argument(format!("invalid api key value ({API_KEY_NAME}): {err}"))
+            })?;
+        let is_authorized = api_key
+            .map(|key| self.authorized_keys.contains(key))
+            .unwrap_or_default();
+        if is_authorized {
+            Ok(())
+        } else {
+            Err(Status::unauthenticated(format!(
+                "invalid or missing api key ({API_KEY_NAME})"
+            )))
+        }
+    }
+}
+
+fn map_update_error(err: UpdateError) -> Status {
+    match &err {
+        UpdateError::ChartNotFound(_) => Status::not_found(err.to_string()),
+        _ => {
+            tracing::error!(err = ?err, "internal admin request error");
+            Status::internal(err.to_string())
+        }
+    }
+}
+
+fn build_proto_update_group(
+    name: String,
+    charts: Vec<String>,
+    status: GroupUpdateStatus,
+) -> proto_v1::UpdateGroup {
+    let proto_status = match (
+        status.is_running,
+        status.last_finished_at,
+        &status.last_error,
+    ) {
+        (true, _, _) => proto_v1::UpdateStatus::Running,
+        (false, None, _) => proto_v1::UpdateStatus::NotStarted,
+        (false, Some(_), None) => proto_v1::UpdateStatus::Succeeded,
+        (false, Some(_), Some(_)) => proto_v1::UpdateStatus::Failed,
+    };
+    proto_v1::UpdateGroup {
+        name,
+        charts,
+        status: proto_status.into(),
+        last_started_at: status.last_started_at.map(|t| t.to_rfc3339()),
+        last_finished_at: status.last_finished_at.map(|t| t.to_rfc3339()),
+        last_error: status.last_error,
+    }
+}
+
+#[async_trait]
+impl StatsAdminService for AdminService {
+    async fn list_update_groups(
+        &self,
+        request: Request<proto_v1::ListUpdateGroupsRequest>,
+    ) -> Result<Response<proto_v1::UpdateGroups>, Status> {
+        self.check_authorized(&request)?;
+        let update_service = self.update_service(&request.get_ref().chain)?;
+        let statuses = update_service.group_statuses();
+        let groups = update_service.update_groups();
+        let groups = statuses
+            .into_iter()
+            .map(|(name, status)| {
+                let mut charts: Vec<String> = groups
+                    .get(&name)
+                    .map(|entry| {
+                        entry
+                            .enabled_members
+                            .iter()
+                            .map(|k| k.as_string())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                charts.sort();
+                build_proto_update_group(name, charts, status)
+            })
+            .collect();
+        Ok(Response::new(proto_v1::UpdateGroups { groups }))
+    }
+
+    async fn trigger_update(
+        &self,
+        request: Request<proto_v1::TriggerUpdateRequest>,
+    ) -> Result<Response<proto_v1::TriggerUpdateResponse>, Status> {
+        self.check_authorized(&request)?;
+        let request = request.into_inner();
+        let outcome = self
+            .update_service(&request.chain)?
+            .trigger_update(&request.group_name, request.force_full)
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "update group with name '{}' was not found",
+                    request.group_name
+                ))
+            })?;
+        if outcome == TriggerOutcome::Merged {
+            tracing::info!(
+                chain =% request.chain,
+                update_group =% request.group_name,
+                "update of the group is already pending, the trigger is merged into it"
+            );
+        }
+        Ok(Response::new(proto_v1::TriggerUpdateResponse {}))
+    }
+
+    async fn reset_chart(
+        &self,
+        request: Request<proto_v1::ResetChartRequest>,
+    ) -> Result<Response<proto_v1::ResetChartResponse>, Status> {
+        self.check_authorized(&request)?;
+        let request = request.into_inner();
+        let resolution = convert_resolution(request.resolution());
+        let from = NaiveDate::from_str(&request.from).map_err(|err| {
+            Status::invalid_argument(format!("invalid 'from' date '{}': {err}", request.from))
+        })?;
+        let chart = ChartKey::new(request.name, resolution);
+        let (update_group, removed_points) = self
+            .update_service(&request.chain)?
+            .reset_chart(&chart, from.saturating_start_timestamp())
+            .await
+            .map_err(map_update_error)?;
+        Ok(Response::new(proto_v1::ResetChartResponse {
+            update_group,
+            removed_points: removed_points.to_string(),
+        }))
+    }
+
+    async fn get_dependency_graph(
+        &self,
+        request: Request<proto_v1::GetDependencyGraphRequest>,
+    ) -> Result<Response<proto_v1::DependencyGraph>, Status> {
+        self.check_authorized(&request)?;
+        let request = request.into_inner();
+        let graph = DependencyGraph::new(self.update_service(&request.chain)?.update_groups());
+        let (content_type, data) = match request.format() {
+            proto_v1::GraphFormat::Unspecified | proto_v1::GraphFormat::Json => {
+                ("application/json", graph.to_json())
+            }
+            proto_v1::GraphFormat::Dot => ("text/vnd.graphviz", graph.to_dot()),
+        };
+        Ok(Response::new(proto_v1::DependencyGraph {
+            content_type: content_type.to_owned(),
+            data,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tonic::Code;
+
+    fn service() -> AdminService {
+        AdminService::new(BTreeMap::new(), HashSet::from(["key".to_string()]))
+    }
+
+    fn request_with_key<T>(message: T, key: Option<&str>) -> Request<T> {
+        let mut request = Request::new(message);
+        if let Some(key) = key {
+            request
+                .metadata_mut()
+                .insert(API_KEY_NAME, key.parse().unwrap());
+        }
+        request
+    }
+
+    #[test]
+    fn check_authorized_works() {
+        let service = service();
+        assert!(service
+            .check_authorized(&request_with_key((), Some("key")))
+            .is_ok());
+        for key in [None, Some("wrong-key"), Some("")] {
+            assert_eq!(
+                service
+                    .check_authorized(&request_with_key((), key))
+                    .unwrap_err()
+                    .code(),
+                Code::Unauthenticated,
+                "key: {key:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn requests_are_checked() {
+        let service = service();
+        let request = proto_v1::TriggerUpdateRequest {
+            group_name: "SomeGroup".to_string(),
+            force_full: false,
+            chain: String::new(),
+        };
+        let err = service
+            .trigger_update(request_with_key(request.clone(), None))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::Unauthenticated);
+        // no chains are served
+        let err = service
+            .trigger_update(request_with_key(request, Some("key")))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::NotFound);
+
+        let err = service
+            .reset_chart(request_with_key(
+                proto_v1::ResetChartRequest {
+                    name: "newTxns".to_string(),
+                    resolution: proto_v1::Resolution::Day.into(),
+                    from: "2022-13-01".to_string(),
+                    chain: String::new(),
+                },
+                Some("key"),
+            ))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+    }
+}
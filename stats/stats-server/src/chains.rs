@@ -0,0 +1,257 @@
+//! Serving several chains from one instance.
+//!
+//! Each chain has its own blockscout DB, stats DB (or schema in a shared one)
+//! and runtime setup. Configs are shared, but can be adjusted per chain
+//! (see [`ChainSettings`]).
+
+use std::collections::HashSet;
+
+use anyhow::Context;
+use blockscout_service_launcher::database::initialize_postgres;
+use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection};
+
+use crate::{
+    config::{
+        read_charts_config_with_override, read_layout_config_with_override,
+        read_update_groups_config_with_override,
+    },
+    runtime_setup::RuntimeSetup,
+    settings::{ChainSettings, Settings},
+};
+
+/// Name of the chain configured by top-level settings.
+/// Requests without chain are served for it.
+pub const DEFAULT_CHAIN: &str = "";
+
+/// Paths under `/api/v1/` that would conflict with chain names
+const RESERVED_CHAIN_NAMES: [&str; 8] = [
+    "admin",
+    "counters",
+    "docs",
+    "export",
+    "lines",
+    "tokens",
+    "top-lists",
+    "updates",
+];
+
+/// Everything needed to start serving a chain
+pub struct ChainSetup {
+    pub name: String,
+    pub db_url: String,
+    pub db_schema: Option<String>,
+    pub blockscout_db_url: String,
+    pub user_ops_db_url: Option<String>,
+    pub runtime: RuntimeSetup,
+}
+
+/// Chains to serve, according to the settings
+pub fn chains_setup(settings: &Settings) -> anyhow::Result<Vec<ChainSetup>> {
+    check_chains(settings)?;
+    let default_chain = (!settings.blockscout_db_url.is_empty()).then(|| {
+        let settings = ChainSettings {
+            blockscout_db_url: settings.blockscout_db_url.clone(),
+            user_ops_indexer: settings.user_ops_indexer.clone(),
+            db_url: None,
+            db_schema: None,
+            charts: Default::default(),
+            layout: Default::default(),
+            update_groups: Default::default(),
+        };
+        (DEFAULT_CHAIN.to_owned(), settings)
+    });
+    let named_chains = settings
+        .chains
+        .iter()
+        .map(|(name, chain)| (name.clone(), chain.clone()));
+    default_chain
+        .into_iter()
+        .chain(named_chains)
+        .map(|(name, chain)| {
+            let user_ops_db_url = chain.user_ops_indexer.map(|s| s.db_url);
+            let runtime = RuntimeSetup::new(
+                read_charts_config_with_override(&settings.charts_config, Some(chain.charts))?,
+                read_layout_config_with_override(&settings.layout_config, Some(chain.layout))?,
+                read_update_groups_config_with_override(
+                    &settings.update_groups_config,
+                    Some(chain.update_groups),
+                )?,
+                user_ops_db_url.is_some(),
+            )
+            .with_context(|| format!("setting up chain '{name}'"))?;
+            Ok(ChainSetup {
+                name,
+                db_url: chain.db_url.unwrap_or_else(|| settings.db_url.clone()),
+                db_schema: chain.db_schema,
+                blockscout_db_url: chain.blockscout_db_url,
+                user_ops_db_url,
+                runtime,
+            })
+        })
+        .collect()
+}
+
+/// Error for requests to a chain that is not served
+pub fn chain_not_found(name: &str) -> tonic::Status {
+    if name == DEFAULT_CHAIN {
+        tonic::Status::not_found("chain must be specified")
+    } else {
+        tonic::Status::not_found(format!("chain '{name}' was not found"))
+    }
+}
+
+fn is_valid_chain_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        && !RESERVED_CHAIN_NAMES.contains(&name)
+}
+
+fn is_valid_schema_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn check_chains(settings: &Settings) -> anyhow::Result<()> {
+    if settings.blockscout_db_url.is_empty() && settings.chains.is_empty() {
+        anyhow::bail!("no chains to serve: either `blockscout_db_url` or `chains` must be set");
+    }
+    let mut used_storages = HashSet::new();
+    if !settings.blockscout_db_url.is_empty() {
+        used_storages.insert((settings.db_url.as_str(), None));
+    }
+    for (name, chain) in &settings.chains {
+        if !is_valid_chain_name(name) {
+            anyhow::bail!(
+                "invalid chain name '{name}': expected lowercase letters, digits, '-' or '_', \
+                and none of {RESERVED_CHAIN_NAMES:?}"
+            );
+        }
+        if let Some(schema) = chain.db_schema.as_deref() {
+            if !is_valid_schema_name(schema) {
+                anyhow::bail!("invalid db schema '{schema}' for chain '{name}'");
+            }
+        }
+        let db_url = chain.db_url.as_deref().unwrap_or(&settings.db_url);
+        if !used_storages.insert((db_url, chain.db_schema.as_deref())) {
+            anyhow::bail!(
+                "chain '{name}' uses the same stats DB and schema as another chain; \
+                set different `db_url` or `db_schema`"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Connect to the stats DB of the chain, creating it and
+/// running migrations (if enabled in settings)
+pub async fn connect_stats_db(
+    settings: &Settings,
+    chain: &ChainSetup,
+) -> anyhow::Result<DatabaseConnection> {
+    let mut opt = ConnectOptions::new(chain.db_url.clone());
+    opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
+    let create_database = match &chain.db_schema {
+        Some(schema) => {
+            initialize_postgres::<stats::migration::Migrator>(
+                opt.clone(),
+                settings.create_database,
+                false,
+            )
+            .await?;
+            if settings.run_migrations {
+                // migrations are applied inside the schema, so it must exist
+                let db = Database::connect(opt.clone()).await?;
+                db.execute_unprepared(&format!("CREATE SCHEMA IF NOT EXISTS \"{schema}\""))
+                    .await?;
+            }
+            opt.set_schema_search_path(schema.clone());
+            false
+        }
+        None => settings.create_database,
+    };
+    initialize_postgres::<stats::migration::Migrator>(
+        opt.clone(),
+        create_database,
+        settings.run_migrations,
+    )
+    .await?;
+    Database::connect(opt).await.context("stats DB")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(db_url: Option<&str>, db_schema: Option<&str>) -> ChainSettings {
+        ChainSettings {
+            blockscout_db_url: "postgres://blockscout".to_owned(),
+            user_ops_indexer: None,
+            db_url: db_url.map(str::to_owned),
+            db_schema: db_schema.map(str::to_owned),
+            charts: Default::default(),
+            layout: Default::default(),
+            update_groups: Default::default(),
+        }
+    }
+
+    fn settings(with_default: bool, chains: Vec<(&str, ChainSettings)>) -> Settings {
+        Settings {
+            db_url: "postgres://stats".to_owned(),
+            blockscout_db_url: if with_default {
+                "postgres://blockscout".to_owned()
+            } else {
+                String::new()
+            },
+            chains: chains
+                .into_iter()
+                .map(|(name, chain)| (name.to_owned(), chain))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn check_chains_works() {
+        check_chains(&settings(true, vec![])).unwrap();
+        check_chains(&settings(false, vec![("eth", chain(None, None))])).unwrap();
+        check_chains(&settings(
+            true,
+            vec![
+                ("eth", chain(None, Some("eth"))),
+                ("optimism", chain(None, Some("optimism"))),
+                ("base-sepolia", chain(Some("postgres://other"), None)),
+            ],
+        ))
+        .unwrap();
+    }
+
+    #[test]
+    fn check_chains_rejects_invalid_settings() {
+        // nothing to serve
+        check_chains(&settings(false, vec![])).unwrap_err();
+        // same storage
+        check_chains(&settings(true, vec![("eth", chain(None, None))])).unwrap_err();
+        check_chains(&settings(
+            false,
+            vec![
+                ("eth", chain(None, Some("chain"))),
+                ("gnosis", chain(None, Some("chain"))),
+            ],
+        ))
+        .unwrap_err();
+        // names
+        for name in ["lines", "admin", "Eth", "eth/1", ""] {
+            check_chains(&settings(false, vec![(name, chain(None, None))])).unwrap_err();
+        }
+        check_chains(&settings(
+            false,
+            vec![("eth", chain(None, Some("eth\"; drop table")))],
+        ))
+        .unwrap_err();
+    }
+}
@@ -1,11 +1,13 @@
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
-use crate::config::types::{AllChartSettings, ResolutionsSettings};
+use crate::config::types::{AllChartSettings, ChainType, ResolutionsSettings};
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default, deny_unknown_fields)]
 pub struct ResolutionsEnabledOverwrite {
+    hour: Option<bool>,
     day: Option<bool>,
     week: Option<bool>,
     month: Option<bool>,
@@ -15,6 +17,7 @@ pub struct ResolutionsEnabledOverwrite {
 impl From<ResolutionsEnabledOverwrite> for ResolutionsSettings {
     fn from(value: ResolutionsEnabledOverwrite) -> Self {
         ResolutionsSettings {
+            hour: value.hour,
             day: value.day,
             week: value.week,
             month: value.month,
@@ -61,6 +64,7 @@ impl ChartSettingsOverwrite {
             }
         );
         target.units = self.units.or(target.units.take());
+        target.resolutions.hour = self.resolutions.hour.or(target.resolutions.hour);
         target.resolutions.day = self.resolutions.day.or(target.resolutions.day);
         target.resolutions.week = self.resolutions.week.or(target.resolutions.week);
         target.resolutions.month = self.resolutions.month.or(target.resolutions.month);
@@ -85,6 +89,8 @@ impl TryFrom<ChartSettingsOverwrite> for AllChartSettings {
                 description,
                 units,
                 resolutions: resolutions.into(),
+                custom: None,
+                derived: None,
             }),
             _ => {
                 let mut missing_fields = vec![];
@@ -109,8 +115,11 @@ impl TryFrom<ChartSettingsOverwrite> for AllChartSettings {
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default, deny_unknown_fields)]
 pub struct Config {
+    pub chain_type: Option<ChainType>,
+    pub timezone: Option<Tz>,
     pub counters: BTreeMap<String, ChartSettingsOverwrite>,
     pub line_charts: BTreeMap<String, ChartSettingsOverwrite>,
+    pub top_lists: BTreeMap<String, ChartSettingsOverwrite>,
     pub template_values: BTreeMap<String, serde_json::Value>,
 }
 
@@ -137,8 +146,11 @@ mod tests {
             )]
             .into(),
             Config {
+                chain_type: None,
+                timezone: None,
                 counters: BTreeMap::new(),
                 line_charts: BTreeMap::new(),
+                top_lists: BTreeMap::new(),
                 template_values: BTreeMap::from([(
                     "native_coin_symbol".to_owned(),
                     serde_json::Value::String("USDT".to_owned()),
@@ -158,6 +170,8 @@ mod tests {
             )]
             .into(),
             Config {
+                chain_type: None,
+                timezone: None,
                 counters: BTreeMap::new(),
                 line_charts: BTreeMap::from([(
                     "average_txn_fee".to_owned(),
@@ -169,6 +183,7 @@ mod tests {
                         resolutions: ResolutionsEnabledOverwrite::default(),
                     },
                 )]),
+                top_lists: BTreeMap::new(),
                 template_values: BTreeMap::new(),
             },
         )
@@ -182,6 +197,8 @@ mod tests {
             )]
             .into(),
             Config {
+                chain_type: None,
+                timezone: None,
                 counters: BTreeMap::new(),
                 line_charts: BTreeMap::from([(
                     "average_txn_fee".to_owned(),
@@ -193,6 +210,7 @@ mod tests {
                         resolutions: ResolutionsEnabledOverwrite::default(),
                     },
                 )]),
+                top_lists: BTreeMap::new(),
                 template_values: BTreeMap::new(),
             },
         )
@@ -206,6 +224,8 @@ mod tests {
             )]
             .into(),
             Config {
+                chain_type: None,
+                timezone: None,
                 counters: BTreeMap::new(),
                 line_charts: BTreeMap::from([(
                     "average_txn_fee".to_owned(),
@@ -215,6 +235,7 @@ mod tests {
                         description: None,
                         units: None,
                         resolutions: ResolutionsEnabledOverwrite {
+                            hour: None,
                             day: Some(true),
                             week: None,
                             month: None,
@@ -222,6 +243,7 @@ mod tests {
                         },
                     },
                 )]),
+                top_lists: BTreeMap::new(),
                 template_values: BTreeMap::new(),
             },
         )
@@ -238,6 +260,8 @@ mod tests {
             )]
             .into(),
             Config {
+                chain_type: None,
+                timezone: None,
                 counters: BTreeMap::from([(
                     "average_block_time".to_owned(),
                     ChartSettingsOverwrite {
@@ -249,6 +273,7 @@ mod tests {
                     },
                 )]),
                 line_charts: BTreeMap::new(),
+                top_lists: BTreeMap::new(),
                 template_values: BTreeMap::new(),
             },
         )
@@ -304,11 +329,14 @@ mod tests {
             "STATS_CHARTS",
             envs,
             Config {
+                chain_type: None,
+                timezone: None,
                 counters: BTreeMap::from([("average_block_time".to_owned(), expected_counter)]),
                 line_charts: BTreeMap::from([(
                     "average_txn_fee".to_owned(),
                     expected_line_category,
                 )]),
+                top_lists: BTreeMap::new(),
                 template_values: BTreeMap::from([(
                     "native_coin_symbol".to_owned(),
                     serde_json::Value::String("USDC".to_owned()),
@@ -1,13 +1,25 @@
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
-use crate::config::types::AllChartSettings;
+use crate::config::types::{AllChartSettings, ChainType};
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct Config {
+    /// Charts specific to the chain type are enabled only if it's set
+    pub chain_type: Option<ChainType>,
+    /// IANA timezone to calculate line charts in (UTC if not set).
+    /// Applies to custom charts and to built-in charts from [`stats::local_time`];
+    /// other built-in charts are calculated in UTC.
+    pub timezone: Option<Tz>,
     pub counters: BTreeMap<String, AllChartSettings>,
     pub line_charts: BTreeMap<String, AllChartSettings>,
+    /// Addresses of (ERC-20) tokens to calculate `token_line_charts` for
+    pub tracked_tokens: Vec<String>,
+    /// Charts calculated for each of `tracked_tokens`
+    pub token_line_charts: BTreeMap<String, AllChartSettings>,
+    pub top_lists: BTreeMap<String, AllChartSettings>,
     pub template_values: BTreeMap<String, serde_json::Value>,
 }
 
@@ -24,6 +36,10 @@ impl Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::types::{
+        CustomChartSettings, DerivedChartSettings, MissingDatePolicySetting,
+        ResolutionAggregationSetting,
+    };
     use pretty_assertions::assert_eq;
 
     const EXAMPLE_CONFIG: &str = r#"{
@@ -62,6 +78,134 @@ mod tests {
         let _: Config = serde_json::from_str(EXAMPLE_CONFIG).expect("should be valid config");
     }
 
+    #[test]
+    fn custom_chart_config_parses() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "line_charts": {
+                    "new_deposits": {
+                        "title": "New deposits",
+                        "description": "Number of deposits",
+                        "custom": {
+                            "sql": "SELECT date, value FROM deposits WHERE ts >= {from} AND ts < {to}",
+                            "resolution_aggregation": "average",
+                            "timezone": "Asia/Tokyo"
+                        }
+                    }
+                }
+            }"#,
+        )
+        .expect("should be valid config");
+        assert_eq!(
+            config.line_charts["new_deposits"].custom,
+            Some(CustomChartSettings {
+                sql: "SELECT date, value FROM deposits WHERE ts >= {from} AND ts < {to}".into(),
+                missing_date_policy: MissingDatePolicySetting::FillZero,
+                resolution_aggregation: ResolutionAggregationSetting::Average,
+                timezone: Some(chrono_tz::Asia::Tokyo),
+            })
+        );
+    }
+
+    #[test]
+    fn derived_chart_config_parses() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "line_charts": {
+                    "new_txns_7d_average": {
+                        "title": "New transactions (7-day average)",
+                        "description": "Moving average of new transactions",
+                        "derived": {
+                            "transform": "moving_average",
+                            "chart": "new_txns",
+                            "days": 7
+                        }
+                    },
+                    "failed_txns_share": {
+                        "title": "Failed transactions share",
+                        "description": "Share of failed transactions",
+                        "derived": {
+                            "transform": "ratio",
+                            "numerator": "new_failed_txns",
+                            "denominator": "new_txns"
+                        }
+                    }
+                }
+            }"#,
+        )
+        .expect("should be valid config");
+        assert_eq!(
+            config.line_charts["new_txns_7d_average"].derived,
+            Some(DerivedChartSettings::MovingAverage {
+                chart: "new_txns".into(),
+                days: 7
+            })
+        );
+        assert_eq!(
+            config.line_charts["failed_txns_share"].derived,
+            Some(DerivedChartSettings::Ratio {
+                numerator: "new_failed_txns".into(),
+                denominator: "new_txns".into()
+            })
+        );
+
+        let unknown_field: Result<Config, _> = serde_json::from_str(
+            r#"{
+                "line_charts": {
+                    "total_txns_derived": {
+                        "derived": { "transform": "cumulative", "chart": "new_txns", "days": 7 }
+                    }
+                }
+            }"#,
+        );
+        assert!(unknown_field.is_err());
+    }
+
+    #[test]
+    fn token_charts_config_parses() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "tracked_tokens": ["0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"],
+                "token_line_charts": {
+                    "token_transfers": {
+                        "title": "Token transfers",
+                        "description": "Number of token transfers",
+                        "resolutions": {
+                            "year": false
+                        }
+                    }
+                }
+            }"#,
+        )
+        .expect("should be valid config");
+        assert_eq!(
+            config.tracked_tokens,
+            vec!["0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_owned()]
+        );
+        assert_eq!(
+            config.token_line_charts["token_transfers"].resolutions.year,
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn chain_type_parses() {
+        let config: Config = serde_json::from_str(r#"{ "chain_type": "optimism" }"#)
+            .expect("should be valid config");
+        assert_eq!(config.chain_type, Some(ChainType::Optimism));
+        let config: Config = serde_json::from_str("{}").expect("should be valid config");
+        assert_eq!(config.chain_type, None);
+        serde_json::from_str::<Config>(r#"{ "chain_type": "zksync" }"#).unwrap_err();
+    }
+
+    #[test]
+    fn timezone_parses() {
+        let config: Config = serde_json::from_str(r#"{ "timezone": "Asia/Tokyo" }"#)
+            .expect("should be valid config");
+        assert_eq!(config.timezone, Some(chrono_tz::Asia::Tokyo));
+        serde_json::from_str::<Config>(r#"{ "timezone": "Mars/Olympus_Mons" }"#).unwrap_err();
+    }
+
     #[test]
     fn render_works() {
         let config: Config = serde_json::from_str(EXAMPLE_CONFIG).expect("should be valid config");
@@ -3,4 +3,8 @@ mod json;
 mod read;
 pub mod types;
 
+pub use env::{
+    charts::Config as ChartsConfigOverride, layout::Config as LayoutConfigOverride,
+    update_groups::Config as UpdateGroupsConfigOverride,
+};
 pub use read::*;
@@ -1,13 +1,22 @@
 use std::collections::BTreeMap;
 
-use crate::config::{json, types::AllChartSettings};
+use crate::config::{
+    json,
+    types::{AllChartSettings, ChainType},
+};
+use chrono_tz::Tz;
 use convert_case::{Case, Casing};
 use serde::Deserialize;
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config<ChartSettings> {
+    pub chain_type: Option<ChainType>,
+    pub timezone: Option<Tz>,
     pub counters: BTreeMap<String, ChartSettings>,
     pub lines: BTreeMap<String, ChartSettings>,
+    pub tracked_tokens: Vec<String>,
+    pub token_lines: BTreeMap<String, ChartSettings>,
+    pub top_lists: BTreeMap<String, ChartSettings>,
 }
 
 impl From<json::charts::Config> for Config<AllChartSettings> {
@@ -22,6 +31,24 @@ impl From<json::charts::Config> for Config<AllChartSettings> {
             .into_iter()
             .map(|(id, s)| (id.from_case(Case::Snake).to_case(Case::Camel), s))
             .collect();
-        Self { counters, lines }
+        let token_lines = value
+            .token_line_charts
+            .into_iter()
+            .map(|(id, s)| (id.from_case(Case::Snake).to_case(Case::Camel), s))
+            .collect();
+        let top_lists = value
+            .top_lists
+            .into_iter()
+            .map(|(id, s)| (id.from_case(Case::Snake).to_case(Case::Camel), s))
+            .collect();
+        Self {
+            chain_type: value.chain_type,
+            timezone: value.timezone,
+            counters,
+            lines,
+            tracked_tokens: value.tracked_tokens,
+            token_lines,
+            top_lists,
+        }
     }
 }
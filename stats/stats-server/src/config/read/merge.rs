@@ -15,9 +15,17 @@ pub fn override_charts(
     target: &mut json::charts::Config,
     source: env::charts::Config,
 ) -> Result<(), anyhow::Error> {
+    if source.chain_type.is_some() {
+        target.chain_type = source.chain_type;
+    }
+    if source.timezone.is_some() {
+        target.timezone = source.timezone;
+    }
     override_charts_settings(&mut target.counters, source.counters).context("updating counters")?;
     override_charts_settings(&mut target.line_charts, source.line_charts)
         .context("updating line categories")?;
+    override_charts_settings(&mut target.top_lists, source.top_lists)
+        .context("updating top lists")?;
     target.template_values.extend(source.template_values);
     Ok(())
 }
@@ -245,6 +253,8 @@ mod tests {
                     ("STATS_CHARTS__COUNTERS__TOTAL_BLOCKS__ENABLED", "false"),
                     ("STATS_CHARTS__COUNTERS__TOTAL_TXNS__ENABLED", "true"),
                     ("STATS_CHARTS__LINE_CHARTS__TXNS_FEE__UNITS", "k USDC"),
+                    ("STATS_CHARTS__CHAIN_TYPE", "arbitrum"),
+                    ("STATS_CHARTS__TIMEZONE", "Asia/Tokyo"),
                 ]
                 .map(|(a, b)| (a.to_owned(), b.to_owned())),
             ),
@@ -255,6 +265,8 @@ mod tests {
         let overridden_config = serde_json::to_value(json_config).unwrap();
 
         let expected_config: json::charts::Config = serde_json::from_str(r#"{
+            "chain_type": "arbitrum",
+            "timezone": "Asia/Tokyo",
             "counters": {
                 "total_blocks": {
                     "title": "Total Blocks",
@@ -15,10 +15,13 @@ pub mod layout;
 mod merge;
 pub mod update_groups;
 
+/// `extra_override` is applied after the values from env
+/// (e.g. chain-specific values)
 fn read_json_override_from_env_config<JsonConfig, EnvConfig>(
     json_path: &Path,
     env_prefix: &'static str,
-    override_fn: impl FnOnce(&mut JsonConfig, EnvConfig) -> Result<(), anyhow::Error>,
+    extra_override: Option<EnvConfig>,
+    override_fn: impl Fn(&mut JsonConfig, EnvConfig) -> Result<(), anyhow::Error>,
 ) -> Result<JsonConfig, anyhow::Error>
 where
     JsonConfig: Serialize + DeserializeOwned,
@@ -43,6 +46,9 @@ where
             .try_deserialize()
             .context("envs parse")?;
         override_fn(&mut json_config, env_config).context("overriding values")?;
+        if let Some(extra_override) = extra_override {
+            override_fn(&mut json_config, extra_override).context("overriding extra values")?;
+        }
         Ok(json_config)
     } else {
         Err(anyhow::anyhow!(
@@ -52,10 +58,17 @@ where
 }
 
 pub fn read_charts_config(path: &Path) -> Result<charts::Config<AllChartSettings>, anyhow::Error> {
+    read_charts_config_with_override(path, None)
+}
+
+pub fn read_charts_config_with_override(
+    path: &Path,
+    extra_override: Option<env::charts::Config>,
+) -> Result<charts::Config<AllChartSettings>, anyhow::Error> {
     let overridden_json_config = read_json_override_from_env_config::<
         json::charts::Config,
         env::charts::Config,
-    >(path, "STATS_CHARTS", override_charts)
+    >(path, "STATS_CHARTS", extra_override, override_charts)
     .context("charts config")?;
     let rendered_config = overridden_json_config
         .render_with_template_values()
@@ -64,19 +77,38 @@ pub fn read_charts_config(path: &Path) -> Result<charts::Config<AllChartSettings
 }
 
 pub fn read_layout_config(path: &Path) -> Result<layout::Config, anyhow::Error> {
+    read_layout_config_with_override(path, None)
+}
+
+pub fn read_layout_config_with_override(
+    path: &Path,
+    extra_override: Option<env::layout::Config>,
+) -> Result<layout::Config, anyhow::Error> {
     let overridden_json_config = read_json_override_from_env_config::<
         json::layout::Config,
         env::layout::Config,
-    >(path, "STATS_LAYOUT", override_layout)
+    >(path, "STATS_LAYOUT", extra_override, override_layout)
     .context("layout config")?;
     Ok(overridden_json_config.into())
 }
 
 pub fn read_update_groups_config(path: &Path) -> Result<update_groups::Config, anyhow::Error> {
+    read_update_groups_config_with_override(path, None)
+}
+
+pub fn read_update_groups_config_with_override(
+    path: &Path,
+    extra_override: Option<env::update_groups::Config>,
+) -> Result<update_groups::Config, anyhow::Error> {
     let overridden_json_config = read_json_override_from_env_config::<
         json::update_groups::Config,
         env::update_groups::Config,
-    >(path, "STATS_UPDATE_GROUPS", override_update_groups)
+    >(
+        path,
+        "STATS_UPDATE_GROUPS",
+        extra_override,
+        override_update_groups,
+    )
     .context("update groups config")?;
     Ok(overridden_json_config.into())
 }
@@ -1,11 +1,15 @@
 //! Common types for the configs
 
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
+use chrono_tz::Tz;
 use cron::Schedule;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
-use stats::ResolutionKind;
+use stats::{
+    custom::LowerResolutionAggregation, ChartKey, ChartUpdateStatus, MissingDatePolicy,
+    ResolutionKind,
+};
 use stats_proto::blockscout::stats::v1 as proto_v1;
 
 use crate::runtime_setup::EnabledChartEntry;
@@ -14,6 +18,7 @@ use crate::runtime_setup::EnabledChartEntry;
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct ResolutionsSettings {
+    pub hour: Option<bool>,
     pub day: Option<bool>,
     pub week: Option<bool>,
     pub month: Option<bool>,
@@ -23,6 +28,7 @@ pub struct ResolutionsSettings {
 impl ResolutionsSettings {
     pub fn into_list(self) -> Vec<(ResolutionKind, Option<bool>)> {
         vec![
+            (ResolutionKind::Hour, self.hour),
             (ResolutionKind::Day, self.day),
             (ResolutionKind::Week, self.week),
             (ResolutionKind::Month, self.month),
@@ -51,19 +57,21 @@ impl ResolutionsSettings {
         self,
         available_resolutions: &HashSet<ResolutionKind>,
     ) -> Result<ResolutionsEnabled, Vec<ResolutionKind>> {
+        let hour = Self::into_enabled_field(self.hour, ResolutionKind::Hour, available_resolutions);
         let day = Self::into_enabled_field(self.day, ResolutionKind::Day, available_resolutions);
         let week = Self::into_enabled_field(self.week, ResolutionKind::Week, available_resolutions);
         let month =
             Self::into_enabled_field(self.month, ResolutionKind::Month, available_resolutions);
         let year = Self::into_enabled_field(self.year, ResolutionKind::Year, available_resolutions);
-        match (day, week, month, year) {
-            (Ok(day), Ok(week), Ok(month), Ok(year)) => Ok(ResolutionsEnabled {
+        match (hour, day, week, month, year) {
+            (Ok(hour), Ok(day), Ok(week), Ok(month), Ok(year)) => Ok(ResolutionsEnabled {
+                hour,
                 day,
                 week,
                 month,
                 year,
             }),
-            (d, w, m, y) => Err([d, w, m, y]
+            (h, d, w, m, y) => Err([h, d, w, m, y]
                 .into_iter()
                 .filter_map(|res| res.err())
                 .collect()),
@@ -74,6 +82,7 @@ impl ResolutionsSettings {
 /// `true` if the resolution is active for the chart
 #[derive(Debug, Clone)]
 pub struct ResolutionsEnabled {
+    pub hour: bool,
     pub day: bool,
     pub week: bool,
     pub month: bool,
@@ -83,6 +92,7 @@ pub struct ResolutionsEnabled {
 impl ResolutionsEnabled {
     pub fn into_list(self) -> Vec<ResolutionKind> {
         [
+            ResolutionKind::Hour,
             ResolutionKind::Day,
             ResolutionKind::Week,
             ResolutionKind::Month,
@@ -92,6 +102,7 @@ impl ResolutionsEnabled {
         .filter(|res| match res {
             // add new corresponding resolution
             // to the array above
+            ResolutionKind::Hour => self.hour,
             ResolutionKind::Day => self.day,
             ResolutionKind::Week => self.week,
             ResolutionKind::Month => self.month,
@@ -102,6 +113,7 @@ impl ResolutionsEnabled {
 
     pub fn is_enabled(&self, resolution: &ResolutionKind) -> bool {
         match resolution {
+            ResolutionKind::Hour => self.hour,
             ResolutionKind::Day => self.day,
             ResolutionKind::Week => self.week,
             ResolutionKind::Month => self.month,
@@ -121,6 +133,10 @@ pub struct AllChartSettings {
     pub units: Option<String>,
     #[serde(default = "Default::default")]
     pub resolutions: ResolutionsSettings,
+    /// Define the chart with SQL query instead of using built-in one
+    pub custom: Option<CustomChartSettings>,
+    /// Define the (line) chart as a transform of other line charts
+    pub derived: Option<DerivedChartSettings>,
 }
 
 fn enabled_default() -> bool {
@@ -144,6 +160,107 @@ impl AllChartSettings {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct CustomChartSettings {
+    /// Query for blockscout DB. See [`stats::custom`] for the requirements.
+    pub sql: String,
+    #[serde(default)]
+    pub missing_date_policy: MissingDatePolicySetting,
+    /// How weekly, monthly and yearly values are obtained from daily ones
+    #[serde(default)]
+    pub resolution_aggregation: ResolutionAggregationSetting,
+    /// IANA timezone to calculate line chart in (deployment `timezone` if not set).
+    /// Days start at local midnight, see [`stats::custom`] for the details.
+    #[serde(default)]
+    pub timezone: Option<Tz>,
+}
+
+/// See [`stats::derived::DerivedTransform`] for the details.
+/// Charts are referenced by their names in the config (e.g. `new_txns`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "transform", rename_all = "snake_case", deny_unknown_fields)]
+pub enum DerivedChartSettings {
+    MovingAverage {
+        chart: String,
+        days: u32,
+    },
+    Ratio {
+        numerator: String,
+        denominator: String,
+    },
+    Cumulative {
+        chart: String,
+    },
+    Difference {
+        minuend: String,
+        subtrahend: String,
+    },
+}
+
+impl DerivedChartSettings {
+    /// Config names of the charts the chart is derived from
+    pub fn sources(&self) -> Vec<&String> {
+        match self {
+            DerivedChartSettings::MovingAverage { chart, .. }
+            | DerivedChartSettings::Cumulative { chart } => vec![chart],
+            DerivedChartSettings::Ratio {
+                numerator,
+                denominator,
+            } => vec![numerator, denominator],
+            DerivedChartSettings::Difference {
+                minuend,
+                subtrahend,
+            } => vec![minuend, subtrahend],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingDatePolicySetting {
+    #[default]
+    FillZero,
+    FillPrevious,
+}
+
+impl From<MissingDatePolicySetting> for MissingDatePolicy {
+    fn from(value: MissingDatePolicySetting) -> Self {
+        match value {
+            MissingDatePolicySetting::FillZero => MissingDatePolicy::FillZero,
+            MissingDatePolicySetting::FillPrevious => MissingDatePolicy::FillPrevious,
+        }
+    }
+}
+
+/// Type of the chain, enables charts specific to it
+/// (see [`stats::lines::rollups`])
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainType {
+    Optimism,
+    Arbitrum,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionAggregationSetting {
+    #[default]
+    Sum,
+    Average,
+    Last,
+}
+
+impl From<ResolutionAggregationSetting> for LowerResolutionAggregation {
+    fn from(value: ResolutionAggregationSetting) -> Self {
+        match value {
+            ResolutionAggregationSetting::Sum => LowerResolutionAggregation::Sum,
+            ResolutionAggregationSetting::Average => LowerResolutionAggregation::Average,
+            ResolutionAggregationSetting::Last => LowerResolutionAggregation::Last,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct EnabledChartSettings {
     pub title: String,
@@ -199,11 +316,15 @@ impl LineChartCategory {
     pub fn intersect_info(
         self,
         info: &BTreeMap<String, EnabledChartEntry>,
+        update_statuses: &HashMap<ChartKey, ChartUpdateStatus>,
     ) -> proto_v1::LineChartSection {
         let charts: Vec<_> = self
             .charts_order
             .into_iter()
-            .flat_map(|c: String| info.get(&c).map(|e| e.build_proto_line_chart_info(c)))
+            .flat_map(|c: String| {
+                info.get(&c)
+                    .map(|e| e.build_proto_line_chart_info(c, update_statuses))
+            })
             .collect();
         proto_v1::LineChartSection {
             id: self.id,
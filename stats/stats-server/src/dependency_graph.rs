@@ -0,0 +1,415 @@
+//! Dependency graph of update groups (for introspection).
+//!
+//! Nodes of the graph are update groups and data sources that store
+//! their data (identified by mutex ids, which are equal to chart keys, e.g.
+//! `newTxns_DAY`). Each source is connected to the sources it reads
+//! during its update. Data manipulation and remote sources do not have mutex ids,
+//! so they are not shown.
+//!
+//! Mutexes of all dependencies of the enabled members are held during the
+//! group update (see [`stats::update_group::SyncUpdateGroup`]), so groups that share
+//! some dependencies can't be updated at the same time.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Write},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use cron::Schedule;
+use itertools::Itertools;
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+use crate::runtime_setup::UpdateGroupEntry;
+
+/// Schedules are checked for collisions within this period
+const SCHEDULE_CHECK_PERIOD_DAYS: i64 = 7;
+/// Limit for very frequent schedules
+const MAX_CHECKED_RUNS: usize = 10_000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyGraph {
+    pub groups: Vec<GroupNode>,
+    pub sources: Vec<SourceNode>,
+    pub dependencies: Vec<DependencyEdge>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupNode {
+    pub name: String,
+    /// `None` means the default schedule
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub schedule: Option<Schedule>,
+    /// Members enabled in the charts config
+    pub members: Vec<String>,
+    pub disabled_members: Vec<String>,
+    /// Held during the group update
+    pub mutexes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SourceNode {
+    pub id: String,
+    /// Group that has the source as a member.
+    /// `None` for the sources that are only dependencies
+    pub group: Option<String>,
+    /// Whether it is an enabled chart
+    pub enabled: bool,
+}
+
+impl SourceNode {
+    fn new(id: String) -> Self {
+        Self {
+            id,
+            group: None,
+            enabled: false,
+        }
+    }
+}
+
+/// `from` reads data of `to` during the update
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct DependencyEdge {
+    pub from: String,
+    pub to: String,
+}
+
+/// Groups that start updating at the same time while holding the same mutexes,
+/// i.e. one of them waits for another and the order of updates is undefined
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleConflict {
+    pub groups: (String, String),
+    pub shared_mutexes: Vec<String>,
+    /// Some time when both groups are started
+    pub at: DateTime<Utc>,
+}
+
+impl Display for ScheduleConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "update groups '{}' and '{}' share dependencies ({}) but are scheduled \
+            at the same time (e.g. {}); one of them will wait for another",
+            self.groups.0,
+            self.groups.1,
+            self.shared_mutexes.join(", "),
+            self.at.to_rfc3339(),
+        )
+    }
+}
+
+impl DependencyGraph {
+    pub fn new(update_groups: &BTreeMap<String, UpdateGroupEntry>) -> Self {
+        let mut sources: BTreeMap<String, SourceNode> = BTreeMap::new();
+        let mut dependencies = BTreeSet::new();
+        let mut groups = vec![];
+        for (name, entry) in update_groups {
+            let (members, disabled_members): (Vec<_>, Vec<_>) = entry
+                .group
+                .list_charts()
+                .into_iter()
+                .map(|chart| chart.key)
+                .partition(|key| entry.enabled_members.contains(key));
+            let mutexes: BTreeSet<String> = members
+                .iter()
+                .filter_map(|key| entry.group.dependency_mutex_ids_of(key))
+                .flatten()
+                .collect();
+            for id in entry.group.list_dependency_mutex_ids() {
+                sources
+                    .entry(id.clone())
+                    .or_insert_with(|| SourceNode::new(id));
+            }
+            let members: Vec<String> = members.into_iter().map(String::from).sorted().collect();
+            let disabled_members: Vec<String> = disabled_members
+                .into_iter()
+                .map(String::from)
+                .sorted()
+                .collect();
+            for (id, enabled) in members
+                .iter()
+                .map(|id| (id, true))
+                .chain(disabled_members.iter().map(|id| (id, false)))
+            {
+                let node = sources
+                    .entry(id.clone())
+                    .or_insert_with(|| SourceNode::new(id.clone()));
+                // the group that updates the chart takes precedence
+                if enabled || node.group.is_none() {
+                    node.group = Some(name.clone());
+                    node.enabled = enabled;
+                }
+            }
+            dependencies.extend(
+                entry
+                    .group
+                    .dependency_mutex_edges()
+                    .into_iter()
+                    .map(|(from, to)| DependencyEdge { from, to }),
+            );
+            groups.push(GroupNode {
+                name: name.clone(),
+                schedule: entry.update_schedule.clone(),
+                members,
+                disabled_members,
+                mutexes: mutexes.into_iter().collect(),
+            });
+        }
+        for edge in &dependencies {
+            for id in [&edge.from, &edge.to] {
+                sources
+                    .entry(id.clone())
+                    .or_insert_with(|| SourceNode::new(id.clone()));
+            }
+        }
+        Self {
+            groups,
+            sources: sources.into_values().collect(),
+            dependencies: dependencies.into_iter().collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("graph serialization must not fail")
+    }
+
+    /// Graphviz DOT. Groups are clusters with their members, disabled charts are
+    /// dashed, sources that are not group members are ellipses.
+    pub fn to_dot(&self) -> String {
+        let mut dot =
+            String::from("digraph dependencies {\n    rankdir=LR;\n    node [shape=box];\n");
+        // writing to `String` never fails
+        for group in &self.groups {
+            let schedule = group
+                .schedule
+                .as_ref()
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| "default schedule".to_owned());
+            let _ = writeln!(
+                dot,
+                "    subgraph {} {{",
+                quote(&format!("cluster_{}", group.name))
+            );
+            let _ = writeln!(
+                dot,
+                "        label={};",
+                quote(&format!("{}\n{}", group.name, schedule))
+            );
+            for source in self
+                .sources
+                .iter()
+                .filter(|s| s.group.as_ref() == Some(&group.name))
+            {
+                let _ = writeln!(dot, "        {}{};", quote(&source.id), node_style(source));
+            }
+            dot.push_str("    }\n");
+        }
+        for source in self.sources.iter().filter(|s| s.group.is_none()) {
+            let _ = writeln!(dot, "    {}{};", quote(&source.id), node_style(source));
+        }
+        for edge in &self.dependencies {
+            let _ = writeln!(dot, "    {} -> {};", quote(&edge.from), quote(&edge.to));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Pairs of groups with shared mutexes that are started at the same time
+    /// within a week since `start`
+    pub fn schedule_conflicts(
+        &self,
+        default_schedule: &Schedule,
+        start: DateTime<Utc>,
+    ) -> Vec<ScheduleConflict> {
+        let end = start + Duration::days(SCHEDULE_CHECK_PERIOD_DAYS);
+        let runs: Vec<BTreeSet<DateTime<Utc>>> = self
+            .groups
+            .iter()
+            .map(|group| {
+                group
+                    .schedule
+                    .as_ref()
+                    .unwrap_or(default_schedule)
+                    .after(&start)
+                    .take_while(|time| *time < end)
+                    .take(MAX_CHECKED_RUNS)
+                    .collect()
+            })
+            .collect();
+        let mut conflicts = vec![];
+        for ((idx_a, a), (idx_b, b)) in self
+            .groups
+            .iter()
+            .enumerate()
+            .filter(|(_, group)| !group.members.is_empty())
+            .tuple_combinations()
+        {
+            let shared_mutexes = a
+                .mutexes
+                .iter()
+                .filter(|mutex| b.mutexes.contains(mutex))
+                .cloned()
+                .collect_vec();
+            if shared_mutexes.is_empty() {
+                continue;
+            }
+            if let Some(at) = runs[idx_a].intersection(&runs[idx_b]).next() {
+                conflicts.push(ScheduleConflict {
+                    groups: (a.name.clone(), b.name.clone()),
+                    shared_mutexes,
+                    at: *at,
+                });
+            }
+        }
+        conflicts
+    }
+}
+
+fn quote(id: &str) -> String {
+    format!(
+        "\"{}\"",
+        id.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+fn node_style(source: &SourceNode) -> &'static str {
+    match (&source.group, source.enabled) {
+        (None, _) => " [shape=ellipse]",
+        (Some(_), false) => " [style=dashed]",
+        (Some(_), true) => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_charts_config, read_layout_config, read_update_groups_config, RuntimeSetup};
+    use pretty_assertions::assert_eq;
+    use std::{path::Path, str::FromStr};
+
+    fn group(name: &str, schedule: Option<&str>, mutexes: &[&str]) -> GroupNode {
+        GroupNode {
+            name: name.into(),
+            schedule: schedule.map(|s| Schedule::from_str(s).unwrap()),
+            members: vec![mutexes[0].into()],
+            disabled_members: vec![],
+            mutexes: mutexes.iter().map(|m| m.to_string()).collect(),
+        }
+    }
+
+    fn source(id: &str, group: Option<&str>, enabled: bool) -> SourceNode {
+        SourceNode {
+            id: id.into(),
+            group: group.map(String::from),
+            enabled,
+        }
+    }
+
+    fn edge(from: &str, to: &str) -> DependencyEdge {
+        DependencyEdge {
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+
+    #[test]
+    fn dot_is_formatted() {
+        let graph = DependencyGraph {
+            groups: vec![group(
+                "TxnsGrowthGroup",
+                Some("0 0 1 * * * *"),
+                &["txnsGrowth_DAY", "newTxns_DAY"],
+            )],
+            sources: vec![
+                source("newTxns_DAY", None, false),
+                source("txnsGrowth_DAY", Some("TxnsGrowthGroup"), true),
+                source("txnsGrowth_WEEK", Some("TxnsGrowthGroup"), false),
+            ],
+            dependencies: vec![
+                edge("txnsGrowth_DAY", "newTxns_DAY"),
+                edge("txnsGrowth_WEEK", "txnsGrowth_DAY"),
+            ],
+        };
+        assert_eq!(
+            graph.to_dot(),
+            r#"digraph dependencies {
+    rankdir=LR;
+    node [shape=box];
+    subgraph "cluster_TxnsGrowthGroup" {
+        label="TxnsGrowthGroup\n0 0 1 * * * *";
+        "txnsGrowth_DAY";
+        "txnsGrowth_WEEK" [style=dashed];
+    }
+    "newTxns_DAY" [shape=ellipse];
+    "txnsGrowth_DAY" -> "newTxns_DAY";
+    "txnsGrowth_WEEK" -> "txnsGrowth_DAY";
+}
+"#
+        );
+    }
+
+    #[test]
+    fn schedule_conflicts_are_found() {
+        let default_schedule = Schedule::from_str("0 0 1 * * * *").unwrap();
+        let start = DateTime::parse_from_rfc3339("2024-07-01T00:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let graph = DependencyGraph {
+            groups: vec![
+                group("NewTxnsGroup", Some("0 0 */3 * * * *"), &["newTxns_DAY"]),
+                // shares `newTxns_DAY` and starts at 3:00 as well
+                group(
+                    "TxnsGrowthGroup",
+                    Some("0 0 3 * * * *"),
+                    &["txnsGrowth_DAY", "newTxns_DAY"],
+                ),
+                // shares `newTxns_DAY`, but at different time
+                group("NewTxnsMaGroup", None, &["newTxnsMa_DAY", "newTxns_DAY"]),
+                // same time, but independent
+                group("NewBlocksGroup", Some("0 0 3 * * * *"), &["newBlocks_DAY"]),
+            ],
+            sources: vec![],
+            dependencies: vec![],
+        };
+        assert_eq!(
+            graph.schedule_conflicts(&default_schedule, start),
+            vec![ScheduleConflict {
+                groups: ("NewTxnsGroup".into(), "TxnsGrowthGroup".into()),
+                shared_mutexes: vec!["newTxns_DAY".into()],
+                at: DateTime::parse_from_rfc3339("2024-07-01T03:00:00Z")
+                    .unwrap()
+                    .with_timezone(&Utc),
+            }]
+        );
+    }
+
+    #[test]
+    fn graph_of_default_config_is_built() {
+        let setup = RuntimeSetup::new(
+            read_charts_config(Path::new("../config/charts.json")).unwrap(),
+            read_layout_config(Path::new("../config/layout.json")).unwrap(),
+            read_update_groups_config(Path::new("../config/update_groups.json")).unwrap(),
+            false,
+        )
+        .unwrap();
+        let graph = DependencyGraph::new(&setup.update_groups);
+        assert!(graph
+            .dependencies
+            .contains(&edge("newTxns_WEEK", "newTxns_DAY")));
+        assert!(graph
+            .sources
+            .contains(&source("newTxns_DAY", Some("NewTxnsGroup"), true)));
+        let new_txns_group = graph
+            .groups
+            .iter()
+            .find(|group| group.name == "NewTxnsGroup")
+            .unwrap();
+        assert!(new_txns_group.members.contains(&"newTxns_DAY".to_owned()));
+        // must be valid json
+        let _: serde_json::Value = serde_json::from_str(&graph.to_json()).unwrap();
+    }
+}
@@ -0,0 +1,301 @@
+//! Formatting of several charts into a single table
+//! (for export).
+//!
+//! Handlers generated by `actix-prost` can only return JSON-encoded messages,
+//! so HTTP export is served by [`route_export_charts`] and
+//! [`route_chain_export_charts`], which respond with the table itself
+//! with the content type of the requested format.
+//!
+//! The table is built in memory, so the number of exported points
+//! is limited (see [`crate::settings::LimitsSettings::export_max_points`]).
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use stats_proto::blockscout::stats::v1::{ExportChartsRequest, ExportFormat, Point};
+
+use crate::{read_cache::status_response, ReadService};
+
+const CSV_CONTENT_TYPE: &str = "text/csv";
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Values of several charts joined by date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JoinedCharts {
+    /// Chart names, each followed by `{chart}.{series}` columns
+    /// for series of multi-series charts
+    columns: Vec<String>,
+    /// `date -> (date_to, values in order of columns)`
+    rows: BTreeMap<String, (String, Vec<Option<String>>)>,
+}
+
+impl JoinedCharts {
+    /// Join charts (`(name, points)`) by date.
+    ///
+    /// All charts are expected to have the same resolution,
+    /// so that `date`s are comparable.
+    pub fn join(charts: Vec<(String, Vec<Point>)>) -> Self {
+        let mut columns = Vec::new();
+        // `date -> (date_to, column index -> value)`
+        let mut cells: BTreeMap<String, (String, BTreeMap<usize, String>)> = BTreeMap::new();
+        for (name, points) in charts {
+            let value_column = columns.len();
+            columns.push(name.clone());
+            let mut series_columns: BTreeMap<String, usize> = BTreeMap::new();
+            for point in points {
+                let (_, row) = cells
+                    .entry(point.date)
+                    .or_insert_with(|| (point.date_to, BTreeMap::new()));
+                row.insert(value_column, point.value);
+                for series in point.series {
+                    let column = *series_columns
+                        .entry(series.name)
+                        .or_insert_with_key(|series| {
+                            columns.push(format!("{name}.{series}"));
+                            columns.len() - 1
+                        });
+                    row.insert(column, series.value);
+                }
+            }
+        }
+        let rows = cells
+            .into_iter()
+            .map(|(date, (date_to, mut row))| {
+                let values = (0..columns.len()).map(|i| row.remove(&i)).collect();
+                (date, (date_to, values))
+            })
+            .collect();
+        Self { columns, rows }
+    }
+
+    pub fn content_type(format: ExportFormat) -> &'static str {
+        match format {
+            ExportFormat::Unspecified | ExportFormat::Csv => CSV_CONTENT_TYPE,
+            ExportFormat::Ndjson => NDJSON_CONTENT_TYPE,
+        }
+    }
+
+    /// Lines of the formatted table (each ends with `\n`)
+    fn into_lines(self, format: ExportFormat) -> impl Iterator<Item = String> + Send {
+        let Self { columns, rows } = self;
+        let header = match format {
+            ExportFormat::Unspecified | ExportFormat::Csv => Some(csv_line(
+                ["date", "date_to"]
+                    .into_iter()
+                    .chain(columns.iter().map(String::as_str)),
+            )),
+            ExportFormat::Ndjson => None,
+        };
+        let rows = rows
+            .into_iter()
+            .map(move |(date, (date_to, values))| match format {
+                ExportFormat::Unspecified | ExportFormat::Csv => csv_line(
+                    [date.as_str(), date_to.as_str()]
+                        .into_iter()
+                        .chain(values.iter().map(|v| v.as_deref().unwrap_or_default())),
+                ),
+                ExportFormat::Ndjson => ndjson_line(&columns, date, date_to, values),
+            });
+        header.into_iter().chain(rows)
+    }
+
+    /// Returns `(content_type, data)`
+    pub fn format(self, format: ExportFormat) -> (&'static str, String) {
+        (
+            Self::content_type(format),
+            self.into_lines(format).collect(),
+        )
+    }
+}
+
+fn csv_line<'a>(fields: impl Iterator<Item = &'a str>) -> String {
+    let mut line = fields.map(csv_escape).collect::<Vec<_>>().join(",");
+    line.push('\n');
+    line
+}
+
+fn ndjson_line(
+    columns: &[String],
+    date: String,
+    date_to: String,
+    values: Vec<Option<String>>,
+) -> String {
+    let mut object = serde_json::Map::new();
+    object.insert("date".to_owned(), date.into());
+    object.insert("date_to".to_owned(), date_to.into());
+    for (column, value) in columns.iter().zip(values) {
+        object.insert(column.clone(), value.into());
+    }
+    let mut line = serde_json::Value::Object(object).to_string();
+    line.push('\n');
+    line
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportPath {
+    chain: String,
+}
+
+/// `POST /api/v1/export`.
+///
+/// Must be registered before routes of `StatsService` to take precedence.
+pub fn route_export_charts(config: &mut web::ServiceConfig, service: Arc<ReadService>) {
+    config.service(
+        web::resource("/api/v1/export")
+            .app_data(web::Data::from(service))
+            .route(web::post().to(export_charts)),
+    );
+}
+
+/// `POST /api/v1/{chain}/export`.
+///
+/// Must be registered before routes of `ChainStatsService` to take precedence.
+pub fn route_chain_export_charts(config: &mut web::ServiceConfig, service: Arc<ReadService>) {
+    config.service(
+        web::resource("/api/v1/{chain}/export")
+            .app_data(web::Data::from(service))
+            .route(web::post().to(export_chain_charts)),
+    );
+}
+
+async fn export_charts(
+    service: web::Data<ReadService>,
+    request: web::Json<ExportChartsRequest>,
+) -> HttpResponse {
+    respond_export(&service, request.into_inner()).await
+}
+
+async fn export_chain_charts(
+    service: web::Data<ReadService>,
+    path: web::Path<ExportPath>,
+    request: web::Json<ExportChartsRequest>,
+) -> HttpResponse {
+    let request = ExportChartsRequest {
+        chain: path.into_inner().chain,
+        ..request.into_inner()
+    };
+    respond_export(&service, request).await
+}
+
+async fn respond_export(service: &ReadService, request: ExportChartsRequest) -> HttpResponse {
+    let format = request.format();
+    match service.export(request).await {
+        Ok(joined) => {
+            let (content_type, data) = joined.format(format);
+            HttpResponse::Ok().content_type(content_type).body(data)
+        }
+        Err(status) => status_response(status),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use stats_proto::blockscout::stats::v1::SeriesValue;
+
+    fn point(date: &str, value: &str) -> Point {
+        Point {
+            date: date.to_owned(),
+            date_to: date.to_owned(),
+            value: value.to_owned(),
+            is_approximate: false,
+            series: vec![],
+        }
+    }
+
+    fn point_with_series(date: &str, series: &[(&str, &str)]) -> Point {
+        let total: u64 = series.iter().map(|(_, v)| v.parse::<u64>().unwrap()).sum();
+        Point {
+            series: series
+                .iter()
+                .map(|(name, value)| SeriesValue {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                })
+                .collect(),
+            ..point(date, &total.to_string())
+        }
+    }
+
+    fn joined() -> JoinedCharts {
+        JoinedCharts::join(vec![
+            (
+                "newTxns".to_owned(),
+                vec![point("2024-01-01", "10"), point("2024-01-02", "12")],
+            ),
+            (
+                "averageGasPrice".to_owned(),
+                vec![point("2024-01-02", "1.5"), point("2024-01-03", "2")],
+            ),
+        ])
+    }
+
+    #[test]
+    fn export_csv_works() {
+        let (content_type, data) = joined().format(ExportFormat::Csv);
+        assert_eq!(content_type, CSV_CONTENT_TYPE);
+        assert_eq!(
+            data,
+            "date,date_to,newTxns,averageGasPrice\n\
+            2024-01-01,2024-01-01,10,\n\
+            2024-01-02,2024-01-02,12,1.5\n\
+            2024-01-03,2024-01-03,,2\n"
+        );
+    }
+
+    #[test]
+    fn export_ndjson_works() {
+        let (content_type, data) = joined().format(ExportFormat::Ndjson);
+        assert_eq!(content_type, NDJSON_CONTENT_TYPE);
+        let rows: Vec<serde_json::Value> = data
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                serde_json::json!({"date": "2024-01-01", "date_to": "2024-01-01", "newTxns": "10", "averageGasPrice": null}),
+                serde_json::json!({"date": "2024-01-02", "date_to": "2024-01-02", "newTxns": "12", "averageGasPrice": "1.5"}),
+                serde_json::json!({"date": "2024-01-03", "date_to": "2024-01-03", "newTxns": null, "averageGasPrice": "2"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn export_multi_series_works() {
+        let joined = JoinedCharts::join(vec![
+            (
+                "txnsByType".to_owned(),
+                vec![
+                    point_with_series("2024-01-01", &[("transfer", "3"), ("call", "4")]),
+                    point_with_series("2024-01-02", &[("call", "5"), ("deploy", "1")]),
+                ],
+            ),
+            ("newTxns".to_owned(), vec![point("2024-01-02", "6")]),
+        ]);
+        let (_, data) = joined.format(ExportFormat::Csv);
+        assert_eq!(
+            data,
+            "date,date_to,txnsByType,txnsByType.transfer,txnsByType.call,txnsByType.deploy,newTxns\n\
+            2024-01-01,2024-01-01,7,3,4,,\n\
+            2024-01-02,2024-01-02,6,,5,1,6\n"
+        );
+    }
+
+    #[test]
+    fn csv_escaping_works() {
+        assert_eq!(csv_escape("plain"), "plain");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
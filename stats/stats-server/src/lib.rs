@@ -1,14 +1,24 @@
+mod admin_service;
+mod chains;
 mod config;
+mod dependency_graph;
+mod export;
 mod health;
+mod read_cache;
 mod read_service;
 mod runtime_setup;
 mod serializers;
 mod server;
 mod settings;
 mod update_service;
+mod updates;
 
-pub use read_service::ReadService;
-pub use runtime_setup::RuntimeSetup;
+pub use config::{read_charts_config, read_layout_config, read_update_groups_config};
+pub use dependency_graph::{DependencyGraph, ScheduleConflict};
+pub use read_cache::{CachedLineChart, ReadCache};
+pub use read_service::{ReadChain, ReadService};
+pub use runtime_setup::{EnabledChartEntry, RuntimeSetup, UpdateGroupEntry};
 pub use server::stats;
-pub use settings::Settings;
+pub use settings::{ChainSettings, ReadCacheSettings, Settings, UserOpsIndexerSettings};
 pub use update_service::UpdateService;
+pub use updates::{ChartUpdateEvent, UpdatesBroadcast};
@@ -0,0 +1,578 @@
+//! In-process cache of line chart responses.
+//!
+//! Reading a line chart takes several queries to stats DB (plus filling of
+//! missing dates), while the data changes only when the update group of the
+//! chart is updated. Therefore responses are cached by chart key and requested
+//! range, and [`crate::UpdateService`] invalidates the members of a group
+//! each time it finishes updating the group (regardless of the result, since
+//! update status is a part of the response).
+//!
+//! The cache is bounded by (approximate) memory size; least recently used
+//! responses are evicted first.
+//!
+//! Cached responses have `ETag` and `Last-Modified` headers. Handlers generated
+//! by `actix-prost` do not pass response metadata to HTTP responses, so
+//! line chart endpoints are served by [`route_line_chart`] and
+//! [`route_chain_line_chart`], which also support conditional requests
+//! (`If-None-Match`, `If-Modified-Since`).
+
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, HashMap},
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use actix_web::{
+    http::{
+        header::{self, EntityTag, HttpDate, IfModifiedSince, IfNoneMatch},
+        StatusCode,
+    },
+    web, HttpRequest, HttpResponse,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use prost::Message;
+use serde::Deserialize;
+use stats::{
+    metrics::{READ_CACHE_HITS, READ_CACHE_MISSES, READ_CACHE_SIZE},
+    ChartKey,
+};
+use stats_proto::blockscout::stats::v1 as proto_v1;
+use tonic::{Code, Response, Status};
+
+use crate::{settings::ReadCacheSettings, ReadService};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub chain: String,
+    pub chart: ChartKey,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl CacheKey {
+    pub fn new(
+        chain: String,
+        chart: ChartKey,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Self {
+        Self {
+            chain,
+            chart,
+            from,
+            to,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedLineChart {
+    pub chart: Arc<proto_v1::LineChart>,
+    /// Hash of the response contents
+    pub etag: String,
+    /// The latest update (or failed update) of the chart
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+impl CachedLineChart {
+    pub fn new(chart: proto_v1::LineChart) -> Self {
+        let mut hasher = DefaultHasher::new();
+        chart.encode_to_vec().hash(&mut hasher);
+        let etag = format!("{:016x}", hasher.finish());
+        let last_modified = chart
+            .info
+            .iter()
+            .flat_map(|info| &info.update_statuses)
+            .flat_map(|status| [&status.last_updated_at, &status.last_error_at])
+            .flatten()
+            .filter_map(|time| DateTime::parse_from_rfc3339(time).ok())
+            .map(|time| time.with_timezone(&Utc))
+            .max();
+        Self {
+            chart: Arc::new(chart),
+            etag,
+            last_modified,
+        }
+    }
+
+    /// Approximate memory occupied by the response
+    fn size(&self) -> usize {
+        self.chart.encoded_len()
+            + self.chart.chart.len() * std::mem::size_of::<proto_v1::Point>()
+            + std::mem::size_of::<proto_v1::LineChart>()
+    }
+
+    fn entity_tag(&self) -> EntityTag {
+        EntityTag::new_strong(self.etag.clone())
+    }
+
+    fn http_last_modified(&self) -> Option<HttpDate> {
+        // http dates have precision of seconds
+        let seconds = u64::try_from(self.last_modified?.timestamp()).ok()?;
+        Some(HttpDate::from(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(seconds),
+        ))
+    }
+
+    /// gRPC response with `etag` and `last-modified` metadata
+    pub fn into_grpc_response(self) -> Response<proto_v1::LineChart> {
+        let etag = self.entity_tag().to_string();
+        let last_modified = self.http_last_modified();
+        let chart = Arc::try_unwrap(self.chart).unwrap_or_else(|chart| (*chart).clone());
+        let mut response = Response::new(chart);
+        let metadata = response.metadata_mut();
+        if let Ok(etag) = etag.parse() {
+            metadata.insert("etag", etag);
+        }
+        if let Some(Ok(last_modified)) = last_modified.map(|date| date.to_string().parse()) {
+            metadata.insert("last-modified", last_modified);
+        }
+        response
+    }
+
+    /// `true` if the client already has the up-to-date response
+    fn is_not_modified(&self, request: &HttpRequest) -> bool {
+        // `If-Modified-Since` is ignored if `If-None-Match` is present
+        match request.get_header::<IfNoneMatch>() {
+            Some(IfNoneMatch::Any) => return true,
+            Some(IfNoneMatch::Items(tags)) if !tags.is_empty() => {
+                let etag = self.entity_tag();
+                return tags.iter().any(|tag| tag.weak_eq(&etag));
+            }
+            _ => {}
+        }
+        match (
+            request.get_header::<IfModifiedSince>(),
+            self.http_last_modified(),
+        ) {
+            (Some(IfModifiedSince(since)), Some(last_modified)) => {
+                SystemTime::from(last_modified) <= SystemTime::from(since)
+            }
+            _ => false,
+        }
+    }
+
+    fn into_http_response(self, request: &HttpRequest) -> HttpResponse {
+        let not_modified = self.is_not_modified(request);
+        let mut response = if not_modified {
+            HttpResponse::NotModified()
+        } else {
+            HttpResponse::Ok()
+        };
+        response.insert_header(header::ETag(self.entity_tag()));
+        if let Some(last_modified) = self.http_last_modified() {
+            response.insert_header(header::LastModified(last_modified));
+        }
+        if not_modified {
+            response.finish()
+        } else {
+            response.json(self.chart.as_ref())
+        }
+    }
+}
+
+struct CacheEntry {
+    value: CachedLineChart,
+    size: usize,
+    /// Key in [`CacheState::by_usage`]
+    last_used: u64,
+    /// Data depends on the current date (e.g. missing dates are filled up to today),
+    /// so responses are not reused on the next day
+    cached_on: NaiveDate,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<CacheKey, CacheEntry>,
+    /// Usage counter -> entry. The first one is least recently used
+    by_usage: BTreeMap<u64, CacheKey>,
+    usage_counter: u64,
+    size: usize,
+    /// Incremented on each invalidation
+    generation: u64,
+}
+
+impl CacheState {
+    fn touch(&mut self, key: &CacheKey) {
+        self.usage_counter += 1;
+        let counter = self.usage_counter;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.by_usage.remove(&entry.last_used);
+            self.by_usage.insert(counter, key.clone());
+            entry.last_used = counter;
+        }
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.by_usage.remove(&entry.last_used);
+            self.size -= entry.size;
+        }
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some((_, key)) = self.by_usage.pop_first() {
+            self.remove(&key);
+        }
+    }
+}
+
+/// Line chart responses shared between [`ReadService`] and update services
+/// of all chains
+pub struct ReadCache {
+    /// In bytes; the cache is disabled if zero
+    max_size: usize,
+    state: Mutex<CacheState>,
+}
+
+impl ReadCache {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self::new(0)
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.max_size > 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state.lock().expect("read cache mutex is poisoned")
+    }
+
+    pub fn get(&self, key: &CacheKey) -> Option<CachedLineChart> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let today = Utc::now().date_naive();
+        let mut state = self.lock();
+        let result = match state.entries.get(key) {
+            Some(entry) if entry.cached_on == today => {
+                let value = entry.value.clone();
+                state.touch(key);
+                Some(value)
+            }
+            Some(_outdated) => {
+                state.remove(key);
+                READ_CACHE_SIZE.set(state.size as i64);
+                None
+            }
+            None => None,
+        };
+        let metric = match result {
+            Some(_) => &*READ_CACHE_HITS,
+            None => &*READ_CACHE_MISSES,
+        };
+        metric.with_label_values(&[key.chain.as_str()]).inc();
+        result
+    }
+
+    /// Value to pass to [`Self::insert`] for the data read after this call
+    pub fn generation(&self) -> u64 {
+        self.lock().generation
+    }
+
+    /// Store the response read at `generation`.
+    ///
+    /// Nothing is stored if the cache was invalidated since then, because the
+    /// response might have been read before the update.
+    pub fn insert(&self, key: CacheKey, value: CachedLineChart, generation: u64) {
+        let size = value.size() + std::mem::size_of::<CacheKey>() + key.chart.name().len();
+        if size > self.max_size {
+            return;
+        }
+        let mut state = self.lock();
+        if state.generation != generation {
+            return;
+        }
+        state.remove(&key);
+        while state.size + size > self.max_size && !state.entries.is_empty() {
+            state.evict_least_recently_used();
+        }
+        state.usage_counter += 1;
+        let last_used = state.usage_counter;
+        state.by_usage.insert(last_used, key.clone());
+        state.entries.insert(
+            key,
+            CacheEntry {
+                value,
+                size,
+                last_used,
+                cached_on: Utc::now().date_naive(),
+            },
+        );
+        state.size += size;
+        READ_CACHE_SIZE.set(state.size as i64);
+    }
+
+    /// Remove responses for all resolutions of the charts
+    pub fn invalidate<'a>(&self, chain: &str, charts: impl IntoIterator<Item = &'a ChartKey>) {
+        if !self.is_enabled() {
+            return;
+        }
+        let names: Vec<&str> = charts.into_iter().map(|key| key.name()).collect();
+        let mut state = self.lock();
+        state.generation += 1;
+        let outdated: Vec<CacheKey> = state
+            .entries
+            .keys()
+            .filter(|key| key.chain == chain && names.contains(&key.chart.name()))
+            .cloned()
+            .collect();
+        for key in outdated {
+            state.remove(&key);
+        }
+        READ_CACHE_SIZE.set(state.size as i64);
+    }
+}
+
+impl From<ReadCacheSettings> for ReadCache {
+    fn from(value: ReadCacheSettings) -> Self {
+        if value.enabled {
+            let max_size = value.max_size_mb.saturating_mul(1024 * 1024);
+            Self::new(usize::try_from(max_size).unwrap_or(usize::MAX))
+        } else {
+            Self::disabled()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LineChartPath {
+    /// Not present for the default chain
+    #[serde(default)]
+    chain: Option<String>,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LineChartQuery {
+    from: Option<String>,
+    to: Option<String>,
+    resolution: Option<String>,
+    /// Chain selected in legacy paths (without chain)
+    #[serde(default)]
+    chain: String,
+}
+
+/// `/api/v1/lines/{name}`.
+///
+/// Must be registered before routes of `StatsService` to take precedence.
+pub fn route_line_chart(config: &mut web::ServiceConfig, service: Arc<ReadService>) {
+    config.service(
+        web::resource("/api/v1/lines/{name}")
+            .app_data(web::Data::from(service))
+            .route(web::get().to(get_line_chart)),
+    );
+}
+
+/// `/api/v1/{chain}/lines/{name}`.
+///
+/// Must be registered before routes of `ChainStatsService` to take precedence.
+pub fn route_chain_line_chart(config: &mut web::ServiceConfig, service: Arc<ReadService>) {
+    config.service(
+        web::resource("/api/v1/{chain}/lines/{name}")
+            .app_data(web::Data::from(service))
+            .route(web::get().to(get_line_chart)),
+    );
+}
+
+async fn get_line_chart(
+    service: web::Data<ReadService>,
+    path: web::Path<LineChartPath>,
+    query: web::Query<LineChartQuery>,
+    request: HttpRequest,
+) -> HttpResponse {
+    let path = path.into_inner();
+    let query = query.into_inner();
+    let resolution = match query.resolution.as_deref() {
+        None | Some("") => proto_v1::Resolution::Unspecified,
+        Some(resolution) => match proto_v1::Resolution::from_str_name(&resolution.to_uppercase()) {
+            Some(resolution) => resolution,
+            None => {
+                return status_response(Status::invalid_argument(format!(
+                    "unknown resolution '{resolution}'"
+                )))
+            }
+        },
+    };
+    let line_chart_request = proto_v1::GetLineChartRequest {
+        name: path.name,
+        from: query.from,
+        to: query.to,
+        resolution: resolution.into(),
+        chain: path.chain.unwrap_or(query.chain),
+    };
+    match service.line_chart(line_chart_request).await {
+        Ok(chart) => chart.into_http_response(&request),
+        Err(status) => status_response(status),
+    }
+}
+
+/// Same format as errors of other (generated) endpoints
+pub(crate) fn status_response(status: Status) -> HttpResponse {
+    let http_status = match status.code() {
+        Code::InvalidArgument | Code::FailedPrecondition | Code::OutOfRange => {
+            StatusCode::BAD_REQUEST
+        }
+        Code::NotFound => StatusCode::NOT_FOUND,
+        Code::Unauthenticated => StatusCode::UNAUTHORIZED,
+        Code::PermissionDenied => StatusCode::FORBIDDEN,
+        Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    HttpResponse::build(http_status).json(serde_json::json!({
+        "code": status.code() as i32,
+        "message": status.message(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use pretty_assertions::assert_eq;
+    use stats::ResolutionKind;
+
+    fn key(chain: &str, name: &str, resolution: ResolutionKind) -> CacheKey {
+        CacheKey::new(
+            chain.into(),
+            ChartKey::new(name.into(), resolution),
+            None,
+            None,
+        )
+    }
+
+    fn chart(values: &[&str], last_updated_at: Option<&str>) -> CachedLineChart {
+        CachedLineChart::new(proto_v1::LineChart {
+            chart: values
+                .iter()
+                .map(|value| proto_v1::Point {
+                    date: "2024-07-01".into(),
+                    date_to: "2024-07-01".into(),
+                    value: value.to_string(),
+                    is_approximate: false,
+                    series: vec![],
+                })
+                .collect(),
+            info: Some(proto_v1::LineChartInfo {
+                id: "newTxns".into(),
+                update_statuses: vec![proto_v1::UpdateStatusInfo {
+                    resolution: "DAY".into(),
+                    last_updated_at: last_updated_at.map(String::from),
+                    last_error: None,
+                    last_error_at: None,
+                }],
+                ..Default::default()
+            }),
+        })
+    }
+
+    #[test]
+    fn cache_is_invalidated() {
+        let cache = ReadCache::new(1024 * 1024);
+        let new_txns_day = key("", "newTxns", ResolutionKind::Day);
+        let new_txns_week = key("", "newTxns", ResolutionKind::Week);
+        let other_chain = key("other", "newTxns", ResolutionKind::Day);
+        let new_blocks = key("", "newBlocks", ResolutionKind::Day);
+        assert_eq!(cache.get(&new_txns_day), None);
+        for key in [&new_txns_day, &new_txns_week, &other_chain, &new_blocks] {
+            cache.insert(key.clone(), chart(&["1"], None), cache.generation());
+        }
+        assert_eq!(cache.get(&new_txns_day), Some(chart(&["1"], None)));
+
+        cache.invalidate("", [&new_txns_day.chart]);
+        assert_eq!(cache.get(&new_txns_day), None);
+        assert_eq!(cache.get(&new_txns_week), None);
+        assert!(cache.get(&other_chain).is_some());
+        assert!(cache.get(&new_blocks).is_some());
+    }
+
+    #[test]
+    fn outdated_response_is_not_stored() {
+        let cache = ReadCache::new(1024 * 1024);
+        let key = key("", "newTxns", ResolutionKind::Day);
+        let generation = cache.generation();
+        // e.g. group update has finished during reading
+        cache.invalidate("", [&key.chart]);
+        cache.insert(key.clone(), chart(&["1"], None), generation);
+        assert_eq!(cache.get(&key), None);
+    }
+
+    #[test]
+    fn least_recently_used_is_evicted() {
+        let entry_size =
+            chart(&["1"], None).size() + std::mem::size_of::<CacheKey>() + "newTxns".len();
+        let cache = ReadCache::new(entry_size * 2);
+        let day = key("", "newTxns", ResolutionKind::Day);
+        let week = key("", "newTxns", ResolutionKind::Week);
+        let month = key("", "newTxns", ResolutionKind::Month);
+        cache.insert(day.clone(), chart(&["1"], None), cache.generation());
+        cache.insert(week.clone(), chart(&["1"], None), cache.generation());
+        assert!(cache.get(&day).is_some());
+        cache.insert(month.clone(), chart(&["1"], None), cache.generation());
+        assert!(cache.get(&day).is_some());
+        assert_eq!(cache.get(&week), None);
+        assert!(cache.get(&month).is_some());
+
+        // too large to be cached at all
+        cache.insert(week.clone(), chart(&["1"; 100], None), cache.generation());
+        assert_eq!(cache.get(&week), None);
+        assert!(cache.get(&day).is_some());
+
+        let disabled = ReadCache::disabled();
+        disabled.insert(day.clone(), chart(&["1"], None), disabled.generation());
+        assert_eq!(disabled.get(&day), None);
+    }
+
+    #[test]
+    fn conditional_requests_work() {
+        let cached = chart(&["1", "2"], Some("2024-07-01T12:00:00.500+00:00"));
+        assert_eq!(
+            cached.etag,
+            chart(&["1", "2"], Some("2024-07-01T12:00:00.500+00:00")).etag
+        );
+        assert_ne!(cached.etag, chart(&["1", "3"], None).etag);
+        let etag = cached.entity_tag().to_string();
+
+        let request = TestRequest::default().to_http_request();
+        let response = cached.clone().into_http_response(&request);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(header::ETAG).unwrap(), etag.as_str());
+        assert_eq!(
+            response.headers().get(header::LAST_MODIFIED).unwrap(),
+            "Mon, 01 Jul 2024 12:00:00 GMT"
+        );
+
+        let request = TestRequest::default()
+            .insert_header((header::IF_NONE_MATCH, etag.as_str()))
+            .to_http_request();
+        let response = cached.clone().into_http_response(&request);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let request = TestRequest::default()
+            .insert_header((header::IF_NONE_MATCH, "\"other\""))
+            .insert_header((header::IF_MODIFIED_SINCE, "Mon, 01 Jul 2024 12:00:00 GMT"))
+            .to_http_request();
+        let response = cached.clone().into_http_response(&request);
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = TestRequest::default()
+            .insert_header((header::IF_MODIFIED_SINCE, "Mon, 01 Jul 2024 12:00:00 GMT"))
+            .to_http_request();
+        let response = cached.clone().into_http_response(&request);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let request = TestRequest::default()
+            .insert_header((header::IF_MODIFIED_SINCE, "Mon, 01 Jul 2024 11:59:59 GMT"))
+            .to_http_request();
+        let response = cached.into_http_response(&request);
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -1,54 +1,297 @@
-use std::{clone::Clone, cmp::Ord, collections::BTreeMap, fmt::Debug, str::FromStr, sync::Arc};
+use std::{
+    clone::Clone,
+    cmp::Ord,
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    str::FromStr,
+    sync::Arc,
+};
 
 use crate::{
+    chains::chain_not_found,
     config::types,
-    runtime_setup::{EnabledChartEntry, RuntimeSetup},
-    serializers::serialize_line_points,
+    export::JoinedCharts,
+    read_cache::{CacheKey, CachedLineChart, ReadCache},
+    runtime_setup::{
+        build_proto_update_status, EnabledChartEntry, EnabledResolutionEntry, RuntimeSetup,
+    },
+    serializers::{serialize_line_points, serialize_top_list},
     settings::LimitsSettings,
 };
 
 use async_trait::async_trait;
 use chrono::{Duration, NaiveDate, Utc};
-use proto_v1::stats_service_server::StatsService;
+use itertools::Itertools;
+use proto_v1::{chain_stats_service_server::ChainStatsService, stats_service_server::StatsService};
 use sea_orm::{DatabaseConnection, DbErr};
 use stats::{
     entity::sea_orm_active_enums::ChartType,
+    exclusive_datetime_range_to_inclusive,
+    tokens::{TokenAddress, TokenChartKind},
     types::{
-        timespans::{Month, Week, Year},
+        timespans::{Hour, Month, Week, Year},
         Timespan,
     },
-    MissingDatePolicy, ReadError, ResolutionKind,
+    ChartKey, ChartUpdateStatus, MissingDatePolicy, ReadError, ResolutionKind,
 };
 use stats_proto::blockscout::stats::v1::{self as proto_v1, Point};
 use tonic::{Request, Response, Status};
 
 #[derive(Clone)]
 pub struct ReadService {
-    db: Arc<DatabaseConnection>,
-    charts: Arc<RuntimeSetup>,
+    /// Chain name -> its data (see [`crate::chains`])
+    chains: Arc<BTreeMap<String, ReadChain>>,
     limits: ReadLimits,
+    /// See [`crate::Settings::counter_comparison_days`]
+    counter_comparison_window: Duration,
+    /// Shared with update services, which invalidate the cached responses
+    cache: Arc<ReadCache>,
+}
+
+/// Data of a single served chain
+#[derive(Clone)]
+pub struct ReadChain {
+    pub db: Arc<DatabaseConnection>,
+    pub charts: Arc<RuntimeSetup>,
 }
 
 impl ReadService {
     pub async fn new(
-        db: Arc<DatabaseConnection>,
-        charts: Arc<RuntimeSetup>,
+        chains: BTreeMap<String, ReadChain>,
         limits: ReadLimits,
+        counter_comparison_window: Duration,
+        cache: Arc<ReadCache>,
     ) -> Result<Self, DbErr> {
-        Ok(Self { db, charts, limits })
+        Ok(Self {
+            chains: Arc::new(chains),
+            limits,
+            counter_comparison_window,
+            cache,
+        })
+    }
+
+    fn chain(&self, name: &str) -> Result<&ReadChain, Status> {
+        self.chains.get(name).ok_or_else(|| chain_not_found(name))
+    }
+
+    /// Line chart response (with cache lookup)
+    pub async fn line_chart(
+        &self,
+        request: proto_v1::GetLineChartRequest,
+    ) -> Result<CachedLineChart, Status> {
+        let resolution = convert_resolution(request.resolution());
+        let chain = self.chain(&request.chain)?;
+        let (chart_entry, resolution_info) = chain.line_chart_entry(&request.name, resolution)?;
+        self.read_line_chart_cached(
+            request.chain,
+            request.name,
+            chart_entry,
+            resolution_info,
+            resolution,
+            parse_date_opt(request.from),
+            parse_date_opt(request.to),
+        )
+        .await
+    }
+
+    /// Token line chart response (with cache lookup)
+    pub async fn token_line_chart(
+        &self,
+        request: proto_v1::GetTokenLineChartRequest,
+    ) -> Result<CachedLineChart, Status> {
+        let resolution = convert_resolution(request.resolution());
+        let chain = self.chain(&request.chain)?;
+        let (chart_name, chart_entry, resolution_info) =
+            chain.token_line_chart_entry(&request.address, &request.name, resolution)?;
+        self.read_line_chart_cached(
+            request.chain,
+            chart_name,
+            chart_entry,
+            resolution_info,
+            resolution,
+            parse_date_opt(request.from),
+            parse_date_opt(request.to),
+        )
+        .await
+    }
+
+    /// Values of the requested line charts joined by date
+    /// (see [`crate::export`])
+    pub async fn export(
+        &self,
+        request: proto_v1::ExportChartsRequest,
+    ) -> Result<JoinedCharts, Status> {
+        let chain = self.chain(&request.chain)?;
+        let resolution = convert_resolution(request.resolution());
+        if request.names.is_empty() {
+            return Err(Status::invalid_argument(
+                "no charts to export were provided",
+            ));
+        }
+        if request.names.len() > self.limits.export_max_charts as usize {
+            return Err(Status::invalid_argument(format!(
+                "too many charts requested (max: {})",
+                self.limits.export_max_charts
+            )));
+        }
+        let from = parse_date_opt(request.from);
+        let to = parse_date_opt(request.to);
+
+        let mut charts = Vec::with_capacity(request.names.len());
+        let mut total_points = 0;
+        for chart_name in request.names {
+            let (_, resolution_info) = chain.line_chart_entry(&chart_name, resolution)?;
+            let points = get_serialized_line_chart_data_resolution_dispatch(
+                &chain.db,
+                resolution_info.name.clone(),
+                resolution,
+                from,
+                to,
+                Some(self.limits.request_interval_limit),
+                resolution_info.missing_date_policy,
+                resolution_info.approximate_trailing_points,
+            )
+            .await
+            .map_err(map_read_error)?;
+            total_points += points.len();
+            if total_points > self.limits.export_max_points as usize {
+                return Err(Status::invalid_argument(format!(
+                    "too many points requested (max: {}), narrow down the interval or export less charts",
+                    self.limits.export_max_points
+                )));
+            }
+            charts.push((chart_name, points));
+        }
+        Ok(JoinedCharts::join(charts))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn read_line_chart_cached(
+        &self,
+        chain_name: String,
+        chart_name: String,
+        chart_entry: &EnabledChartEntry,
+        resolution_info: &EnabledResolutionEntry,
+        resolution: ResolutionKind,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<CachedLineChart, Status> {
+        // the chart can be stored under another name (e.g. calculated in local time)
+        let stored_name = resolution_info.name.clone();
+        let key = CacheKey::new(
+            chain_name,
+            ChartKey::new(stored_name.clone(), resolution),
+            from,
+            to,
+        );
+        if let Some(cached) = self.cache.get(&key) {
+            return Ok(cached);
+        }
+        let generation = self.cache.generation();
+        let chain = self.chain(&key.chain)?;
+        let serialized_chart = get_serialized_line_chart_data_resolution_dispatch(
+            &chain.db,
+            stored_name,
+            resolution,
+            from,
+            to,
+            Some(self.limits.request_interval_limit),
+            resolution_info.missing_date_policy,
+            resolution_info.approximate_trailing_points,
+        )
+        .await
+        .map_err(map_read_error)?;
+        let update_statuses =
+            stats::get_charts_update_status(&chain.db, &chart_entry.enabled_keys().collect_vec())
+                .await
+                .map_err(map_read_error)?;
+        let chart = CachedLineChart::new(proto_v1::LineChart {
+            chart: serialized_chart,
+            info: Some(chart_entry.build_proto_line_chart_info(chart_name, &update_statuses)),
+        });
+        self.cache.insert(key, chart.clone(), generation);
+        Ok(chart)
+    }
+}
+
+impl ReadChain {
+    /// Find enabled line chart with the resolution.
+    ///
+    /// Daily history of counters is available as line charts as well.
+    fn line_chart_entry(
+        &self,
+        chart_name: &str,
+        resolution: ResolutionKind,
+    ) -> Result<(&EnabledChartEntry, &EnabledResolutionEntry), Status> {
+        find_line_chart_entry(&self.charts.charts_info, chart_name, resolution)
+    }
+
+    /// Find enabled token line chart with the resolution.
+    ///
+    /// Returns full name of the chart as well
+    fn token_line_chart_entry(
+        &self,
+        address: &str,
+        chart_id: &str,
+        resolution: ResolutionKind,
+    ) -> Result<(String, &EnabledChartEntry, &EnabledResolutionEntry), Status> {
+        let token =
+            TokenAddress::from_str(address).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        if !self.charts.tracked_tokens.contains(&token) {
+            return Err(Status::not_found(format!("token '{token}' is not tracked")));
+        }
+        let kind = TokenChartKind::from_id(chart_id).ok_or_else(|| {
+            Status::not_found(format!("token chart with name '{chart_id}' was not found"))
+        })?;
+        let chart_name = kind.chart_name(&token);
+        let (chart_entry, resolution_info) =
+            find_line_chart_entry(&self.charts.token_charts_info, &chart_name, resolution)?;
+        Ok((chart_name, chart_entry, resolution_info))
     }
 }
 
+fn find_line_chart_entry<'a>(
+    charts_info: &'a BTreeMap<String, EnabledChartEntry>,
+    chart_name: &str,
+    resolution: ResolutionKind,
+) -> Result<(&'a EnabledChartEntry, &'a EnabledResolutionEntry), Status> {
+    let chart_entry = charts_info.get(chart_name).ok_or_else(|| {
+        Status::not_found(format!("chart with name '{}' was not found", chart_name))
+    })?;
+    let resolution_info = chart_entry
+        .enabled_resolutions
+        .get(&resolution)
+        .filter(|static_info| {
+            static_info.chart_type == ChartType::Line
+                || (static_info.chart_type == ChartType::Counter
+                    && resolution == ResolutionKind::Day)
+        })
+        .ok_or_else(|| {
+            Status::not_found(format!(
+                "resolution '{}' for chart '{}' was not found",
+                String::from(resolution),
+                chart_name,
+            ))
+        })?;
+    Ok((chart_entry, resolution_info))
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ReadLimits {
     /// See [`LimitsSettings::request_interval_limit_days`]
     pub request_interval_limit: Duration,
+    /// See [`LimitsSettings::export_max_charts`]
+    pub export_max_charts: u32,
+    /// See [`LimitsSettings::export_max_points`]
+    pub export_max_points: u32,
 }
 
 impl From<LimitsSettings> for ReadLimits {
     fn from(value: LimitsSettings) -> Self {
         Self {
             request_interval_limit: Duration::days(value.request_interval_limit_days.into()),
+            export_max_charts: value.export_max_charts,
+            export_max_points: value.export_max_points,
         }
     }
 }
@@ -64,22 +307,41 @@ fn map_read_error(err: ReadError) -> Status {
     }
 }
 
+/// Change from `previous` to `current` in percents
+fn percent_change(previous: &str, current: &str) -> Option<f64> {
+    let previous = previous.parse::<f64>().ok()?;
+    let current = current.parse::<f64>().ok()?;
+    if previous == 0.0 {
+        return None;
+    }
+    let change = (current - previous) / previous.abs() * 100.0;
+    change.is_finite().then_some(change)
+}
+
+fn parse_date_opt(date: Option<String>) -> Option<NaiveDate> {
+    date.and_then(|date| NaiveDate::from_str(&date).ok())
+}
+
 /// Add chart information to each chart id in layout
 ///
 /// Returns `None` if info were not found for some chart.
 fn add_chart_info_to_layout(
     layout: Vec<types::LineChartCategory>,
     chart_info: BTreeMap<String, EnabledChartEntry>,
+    update_statuses: &HashMap<ChartKey, ChartUpdateStatus>,
 ) -> Vec<proto_v1::LineChartSection> {
     layout
         .into_iter()
-        .map(|cat| cat.intersect_info(&chart_info))
+        .map(|cat| cat.intersect_info(&chart_info, update_statuses))
+        // e.g. categories of charts specific to other chain types
+        .filter(|section| !section.charts.is_empty())
         .collect()
 }
 
-fn convert_resolution(input: proto_v1::Resolution) -> ResolutionKind {
+pub fn convert_resolution(input: proto_v1::Resolution) -> ResolutionKind {
     match input {
         proto_v1::Resolution::Unspecified | proto_v1::Resolution::Day => ResolutionKind::Day,
+        proto_v1::Resolution::Hour => ResolutionKind::Hour,
         proto_v1::Resolution::Week => ResolutionKind::Week,
         proto_v1::Resolution::Month => ResolutionKind::Month,
         proto_v1::Resolution::Year => ResolutionKind::Year,
@@ -99,24 +361,30 @@ where
     Resolution: Timespan + Clone + Ord + Debug,
 {
     let from = from.map(|f| Resolution::from_date(f));
-    let to = to.map(|t| Resolution::from_date(t));
+    // include the whole day for resolutions shorter than a day
+    let to = to.map(|t| {
+        Resolution::from_timestamp(
+            *exclusive_datetime_range_to_inclusive(t.into_time_range()).end(),
+        )
+    });
     let data = stats::get_line_chart_data::<Resolution>(
         db,
         &chart_name,
-        from,
-        to,
+        from.clone(),
+        to.clone(),
         interval_limit,
         policy,
         true,
         mark_approx,
     )
     .await?;
-    Ok(serialize_line_points(data))
+    let series = stats::get_line_chart_series::<Resolution>(db, &chart_name, from, to).await?;
+    Ok(serialize_line_points(data, series))
 }
 
 /// enum dispatch for `get_serialized_line_chart_data`
 #[allow(clippy::too_many_arguments)]
-async fn get_serialized_line_chart_data_resolution_dispatch(
+pub(crate) async fn get_serialized_line_chart_data_resolution_dispatch(
     db: &DatabaseConnection,
     chart_name: String,
     resolution: ResolutionKind,
@@ -127,6 +395,18 @@ async fn get_serialized_line_chart_data_resolution_dispatch(
     mark_approx: u64,
 ) -> Result<Vec<Point>, ReadError> {
     match resolution {
+        ResolutionKind::Hour => {
+            get_serialized_line_chart_data::<Hour>(
+                db,
+                chart_name,
+                from,
+                to,
+                interval_limit,
+                policy,
+                mark_approx,
+            )
+            .await
+        }
         ResolutionKind::Day => {
             get_serialized_line_chart_data::<NaiveDate>(
                 db,
@@ -182,13 +462,18 @@ async fn get_serialized_line_chart_data_resolution_dispatch(
 impl StatsService for ReadService {
     async fn get_counters(
         &self,
-        _request: Request<proto_v1::GetCountersRequest>,
+        request: Request<proto_v1::GetCountersRequest>,
     ) -> Result<Response<proto_v1::Counters>, Status> {
-        let mut data = stats::get_raw_counters(&self.db)
+        let chain = self.chain(&request.get_ref().chain)?;
+        let mut data = stats::get_raw_counters(&chain.db)
             .await
             .map_err(map_read_error)?;
-
-        let counters = self
+        let today = Utc::now().date_naive();
+        let comparison_date = today - self.counter_comparison_window;
+        let mut previous_data = stats::get_raw_counters_at(&chain.db, comparison_date)
+            .await
+            .map_err(map_read_error)?;
+        let counters_info = chain
             .charts
             .charts_info
             .iter()
@@ -198,6 +483,17 @@ impl StatsService for ReadService {
                     .iter()
                     .all(|(_, static_info)| static_info.chart_type == ChartType::Counter)
             })
+            .collect_vec();
+        let counter_keys = counters_info
+            .iter()
+            .flat_map(|(_, counter)| counter.enabled_keys())
+            .collect_vec();
+        let update_statuses = stats::get_charts_update_status(&chain.db, &counter_keys)
+            .await
+            .map_err(map_read_error)?;
+
+        let counters = counters_info
+            .into_iter()
             .filter_map(|(name, counter)| {
                 data.remove(name).and_then(|point| {
                     // resolutions other than day are currently not supported
@@ -210,17 +506,35 @@ impl StatsService for ReadService {
                         );
                         return None;
                     };
-                    let point = if static_info.missing_date_policy == MissingDatePolicy::FillZero {
-                        point.relevant_or_zero(Utc::now().date_naive())
+                    let fill_zero = static_info.missing_date_policy == MissingDatePolicy::FillZero;
+                    let point = if fill_zero {
+                        point.relevant_or_zero(today)
                     } else {
                         point
                     };
+                    let previous_value = previous_data.remove(name).map(|previous| {
+                        if fill_zero {
+                            previous.relevant_or_zero(comparison_date).value
+                        } else {
+                            previous.value
+                        }
+                    });
+                    let change_percent = previous_value
+                        .as_ref()
+                        .and_then(|previous| percent_change(previous, &point.value));
                     Some(proto_v1::Counter {
                         id: static_info.name.clone(),
                         value: point.value,
                         title: counter.settings.title.clone(),
                         description: counter.settings.description.clone(),
                         units: counter.settings.units.clone(),
+                        update_status: Some(build_proto_update_status(
+                            &static_info.name,
+                            ResolutionKind::Day,
+                            &update_statuses,
+                        )),
+                        previous_value,
+                        change_percent,
                     })
                 })
             })
@@ -233,57 +547,156 @@ impl StatsService for ReadService {
         &self,
         request: Request<proto_v1::GetLineChartRequest>,
     ) -> Result<Response<proto_v1::LineChart>, Status> {
+        let chart = self.line_chart(request.into_inner()).await?;
+        Ok(chart.into_grpc_response())
+    }
+
+    async fn get_token_line_chart(
+        &self,
+        request: Request<proto_v1::GetTokenLineChartRequest>,
+    ) -> Result<Response<proto_v1::LineChart>, Status> {
+        let chart = self.token_line_chart(request.into_inner()).await?;
+        Ok(chart.into_grpc_response())
+    }
+
+    async fn get_top_list(
+        &self,
+        request: Request<proto_v1::GetTopListRequest>,
+    ) -> Result<Response<proto_v1::TopList>, Status> {
         let request = request.into_inner();
-        let resolution = convert_resolution(request.resolution());
+        let chain = self.chain(&request.chain)?;
         let chart_name = request.name;
-        let chart_entry = self.charts.charts_info.get(&chart_name).ok_or_else(|| {
-            Status::not_found(format!("chart with name '{}' was not found", chart_name))
-        })?;
-        let resolution_info = chart_entry
-            .enabled_resolutions
-            .get(&resolution)
-            .filter(|static_info| static_info.chart_type == ChartType::Line)
+        let chart_entry = chain
+            .charts
+            .charts_info
+            .get(&chart_name)
+            .filter(|entry| {
+                entry
+                    .enabled_resolutions
+                    .values()
+                    .any(|static_info| static_info.chart_type == ChartType::TopList)
+            })
             .ok_or_else(|| {
-                Status::not_found(format!(
-                    "resolution '{}' for chart '{}' was not found",
-                    String::from(resolution),
-                    chart_name,
-                ))
+                Status::not_found(format!("top list with name '{}' was not found", chart_name))
             })?;
-
-        let from = request
-            .from
-            .and_then(|date| NaiveDate::from_str(&date).ok());
-        let to = request.to.and_then(|date| NaiveDate::from_str(&date).ok());
-        let policy = resolution_info.missing_date_policy;
-        let mark_approx = resolution_info.approximate_trailing_points;
-        let interval_limit = Some(self.limits.request_interval_limit);
-        let serialized_chart = get_serialized_line_chart_data_resolution_dispatch(
-            &self.db,
-            chart_name.clone(),
-            resolution,
-            from,
-            to,
-            interval_limit,
-            policy,
-            mark_approx,
+        let data = stats::get_top_list_data(&chain.db, &chart_name)
+            .await
+            .map_err(map_read_error)?;
+        let update_statuses = stats::get_charts_update_status(
+            &chain.db,
+            [&ChartKey::new(chart_name.clone(), ResolutionKind::Day)],
         )
         .await
         .map_err(map_read_error)?;
-        Ok(Response::new(proto_v1::LineChart {
-            chart: serialized_chart,
-            info: Some(chart_entry.build_proto_line_chart_info(chart_name)),
+        let settings = chart_entry.settings.clone();
+        Ok(Response::new(proto_v1::TopList {
+            entries: serialize_top_list(data, request.limit),
+            info: Some(proto_v1::TopListInfo {
+                update_status: Some(build_proto_update_status(
+                    &chart_name,
+                    ResolutionKind::Day,
+                    &update_statuses,
+                )),
+                id: chart_name,
+                title: settings.title,
+                description: settings.description,
+                units: settings.units,
+            }),
         }))
     }
 
     async fn get_line_charts(
         &self,
-        _request: Request<proto_v1::GetLineChartsRequest>,
+        request: Request<proto_v1::GetLineChartsRequest>,
     ) -> Result<Response<proto_v1::LineCharts>, Status> {
-        let layout = self.charts.lines_layout.clone();
-        let info = self.charts.charts_info.clone();
-        let sections = add_chart_info_to_layout(layout, info);
+        let chain = self.chain(&request.get_ref().chain)?;
+        let layout = chain.charts.lines_layout.clone();
+        let info = chain.charts.charts_info.clone();
+        let layout_keys = layout
+            .iter()
+            .flat_map(|category| &category.charts_order)
+            .filter_map(|name| info.get(name))
+            .flat_map(|entry| entry.enabled_keys())
+            .collect_vec();
+        let update_statuses = stats::get_charts_update_status(&chain.db, &layout_keys)
+            .await
+            .map_err(map_read_error)?;
+        let sections = add_chart_info_to_layout(layout, info, &update_statuses);
 
         Ok(Response::new(proto_v1::LineCharts { sections }))
     }
+
+    async fn export_charts(
+        &self,
+        request: Request<proto_v1::ExportChartsRequest>,
+    ) -> Result<Response<proto_v1::ExportedCharts>, Status> {
+        let request = request.into_inner();
+        let format = request.format();
+        let (content_type, data) = self.export(request).await?.format(format);
+        Ok(Response::new(proto_v1::ExportedCharts {
+            content_type: content_type.to_owned(),
+            data,
+        }))
+    }
+}
+
+// chain is taken from the path, otherwise it is the same as `StatsService`
+#[async_trait]
+impl ChainStatsService for ReadService {
+    async fn get_counters(
+        &self,
+        request: Request<proto_v1::GetCountersRequest>,
+    ) -> Result<Response<proto_v1::Counters>, Status> {
+        StatsService::get_counters(self, request).await
+    }
+
+    async fn get_line_charts(
+        &self,
+        request: Request<proto_v1::GetLineChartsRequest>,
+    ) -> Result<Response<proto_v1::LineCharts>, Status> {
+        StatsService::get_line_charts(self, request).await
+    }
+
+    async fn get_line_chart(
+        &self,
+        request: Request<proto_v1::GetLineChartRequest>,
+    ) -> Result<Response<proto_v1::LineChart>, Status> {
+        StatsService::get_line_chart(self, request).await
+    }
+
+    async fn export_charts(
+        &self,
+        request: Request<proto_v1::ExportChartsRequest>,
+    ) -> Result<Response<proto_v1::ExportedCharts>, Status> {
+        StatsService::export_charts(self, request).await
+    }
+
+    async fn get_token_line_chart(
+        &self,
+        request: Request<proto_v1::GetTokenLineChartRequest>,
+    ) -> Result<Response<proto_v1::LineChart>, Status> {
+        StatsService::get_token_line_chart(self, request).await
+    }
+
+    async fn get_top_list(
+        &self,
+        request: Request<proto_v1::GetTopListRequest>,
+    ) -> Result<Response<proto_v1::TopList>, Status> {
+        StatsService::get_top_list(self, request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_change_works() {
+        assert_eq!(percent_change("200", "250"), Some(25.0));
+        assert_eq!(percent_change("1.5", "0.75"), Some(-50.0));
+        assert_eq!(percent_change("-10", "-5"), Some(50.0));
+        assert_eq!(percent_change("0", "5"), None);
+        assert_eq!(percent_change("abc", "5"), None);
+        assert_eq!(percent_change("5", ""), None);
+    }
 }
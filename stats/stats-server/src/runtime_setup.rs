@@ -11,20 +11,54 @@
 //!     2.2. Configure the group update schedule in `update_groups.json` config
 //! 3. Add the new charts to `charts.json` and `layout.json` (if needed)
 //!
+//! Alternatively, charts can be defined with an SQL query right in `charts.json`
+//! (`custom` field, see [`stats::custom`]). Each such chart gets its own update group
+//! (`<chart_name>_group` in `update_groups.json`).
+//!
+//! Line charts can also be defined as a transform (e.g. moving average) of other
+//! line charts (`derived` field, see [`stats::derived`]). They are updated the same
+//! way as the charts defined with SQL.
+//!
+//! Charts specific to the chain type (see [`stats::lines::rollups`]) are available only
+//! if `chain_type` is set in `charts.json`. Similarly, account abstraction charts
+//! (see [`stats::lines::user_ops`]) are available only with `user-ops-indexer` database.
+//! Settings of unavailable charts and groups are ignored, so the same configs can be
+//! used for any chain.
+//!
+//! Token charts (see [`stats::tokens`]) are configured in `token_line_charts` and are
+//! calculated for each of `tracked_tokens`. All of them are updated within
+//! `token_charts_group`.
+//!
+//! If `timezone` is set in `charts.json`, custom line charts (without their own `timezone`)
+//! and built-in charts from [`stats::local_time`] are calculated in local time. Local
+//! built-in charts are updated within `local_charts_group`. Charts in non-UTC timezone are
+//! stored separately (see [`stats::custom::timezone_variant_name`]) and served instead of
+//! the UTC ones, which are still calculated for their dependants. Other built-in charts
+//! are calculated in UTC regardless of `timezone` (reported on start).
+//!
 
 use crate::config::{
     self,
-    types::{AllChartSettings, EnabledChartSettings, LineChartCategory},
+    types::{
+        AllChartSettings, ChainType, DerivedChartSettings, EnabledChartSettings, LineChartCategory,
+    },
 };
+use chrono_tz::Tz;
+use convert_case::{Case, Casing};
 use cron::Schedule;
 use itertools::Itertools;
+use sea_orm::DatabaseConnection;
 use stats::{
+    custom::{timezone_variant_name, CustomChartDefinition, CustomChartGroup},
+    derived::{DerivedSource, DerivedTransform},
     entity::sea_orm_active_enums::ChartType,
+    local_time::{local_chart, local_charts_group, LocalChartKind},
+    tokens::{token_chart, token_charts_group, TokenAddress, TokenChartKind},
     update_group::{ArcUpdateGroup, SyncUpdateGroup},
-    ChartKey, ChartPropertiesObject, ResolutionKind,
+    ChartKey, ChartPropertiesObject, ChartUpdateStatus, ResolutionKind,
 };
 use std::{
-    collections::{btree_map::Entry, BTreeMap, HashMap, HashSet},
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet},
     sync::Arc,
 };
 use tokio::sync::Mutex;
@@ -37,9 +71,17 @@ pub struct EnabledChartEntry {
 }
 
 impl EnabledChartEntry {
+    /// Keys of the enabled resolutions (as stored in DB)
+    pub fn enabled_keys(&self) -> impl Iterator<Item = ChartKey> + '_ {
+        self.enabled_resolutions
+            .iter()
+            .map(|(resolution, entry)| ChartKey::new(entry.name.clone(), *resolution))
+    }
+
     pub fn build_proto_line_chart_info(
         &self,
         id: String,
+        update_statuses: &HashMap<ChartKey, ChartUpdateStatus>,
     ) -> stats_proto::blockscout::stats::v1::LineChartInfo {
         let settings = self.settings.clone();
         stats_proto::blockscout::stats::v1::LineChartInfo {
@@ -52,10 +94,34 @@ impl EnabledChartEntry {
                 .keys()
                 .map(|r| String::from(*r))
                 .collect_vec(),
+            update_statuses: self
+                .enabled_resolutions
+                .iter()
+                .map(|(resolution, entry)| {
+                    build_proto_update_status(&entry.name, *resolution, update_statuses)
+                })
+                .collect_vec(),
         }
     }
 }
 
+/// Default (empty) status if the chart is not found in `update_statuses`
+pub fn build_proto_update_status(
+    chart_name: &str,
+    resolution: ResolutionKind,
+    update_statuses: &HashMap<ChartKey, ChartUpdateStatus>,
+) -> stats_proto::blockscout::stats::v1::UpdateStatusInfo {
+    let status = update_statuses.get(&ChartKey::new(chart_name.to_owned(), resolution));
+    stats_proto::blockscout::stats::v1::UpdateStatusInfo {
+        resolution: resolution.into(),
+        last_updated_at: status
+            .and_then(|s| s.last_updated_at)
+            .map(|t| t.to_rfc3339()),
+        last_error: status.and_then(|s| s.last_error.clone()),
+        last_error_at: status.and_then(|s| s.last_error_at).map(|t| t.to_rfc3339()),
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct EnabledResolutionEntry {
     pub name: String,
@@ -90,6 +156,11 @@ pub struct RuntimeSetup {
     pub lines_layout: Vec<LineChartCategory>,
     pub update_groups: BTreeMap<String, UpdateGroupEntry>,
     pub charts_info: BTreeMap<String, EnabledChartEntry>,
+    pub tracked_tokens: BTreeSet<TokenAddress>,
+    /// Enabled token charts by their full name (see [`TokenChartKind::chart_name`])
+    pub token_charts_info: BTreeMap<String, EnabledChartEntry>,
+    /// Enabled charts defined in the charts config (see [`stats::custom`])
+    pub custom_charts: Vec<CustomChartDefinition>,
 }
 
 /// Combine 2 disjoint (by key) maps into a single map.
@@ -109,36 +180,109 @@ fn combine_disjoint_maps<K: Ord + Clone, V>(
 }
 
 impl RuntimeSetup {
+    /// `with_user_ops` - whether `user-ops-indexer` database is available
     pub fn new(
         charts: config::charts::Config<AllChartSettings>,
         layout: config::layout::Config,
         update_groups: config::update_groups::Config,
+        with_user_ops: bool,
     ) -> anyhow::Result<Self> {
-        Self::validated_and_initialized(charts, layout, update_groups)
+        Self::validated_and_initialized(charts, layout, update_groups, with_user_ops)
+    }
+
+    /// See [`SyncUpdateGroup::enable_db_locks`]
+    pub fn enable_db_update_locks(&mut self) {
+        for entry in self.update_groups.values_mut() {
+            entry.group.enable_db_locks();
+        }
     }
 
     fn validated_and_initialized(
-        charts: config::charts::Config<AllChartSettings>,
+        mut charts: config::charts::Config<AllChartSettings>,
         layout: config::layout::Config,
-        update_groups: config::update_groups::Config,
+        mut update_groups: config::update_groups::Config,
+        with_user_ops: bool,
     ) -> anyhow::Result<Self> {
-        let charts_info = Self::build_charts_info(charts)?;
-        let update_groups = Self::init_update_groups(update_groups, &charts_info)?;
+        Self::remove_unavailable_settings(&mut charts, &mut update_groups, with_user_ops);
+        let tracked_tokens = Self::tracked_tokens(&charts)?;
+        let custom_charts = Self::custom_charts(&charts)?;
+        let local_charts = Self::local_charts(&charts)?;
+        let stored_keys = Self::stored_keys(custom_charts.iter().chain(&local_charts));
+        let mut extra_groups: Vec<ArcUpdateGroup> = custom_charts
+            .iter()
+            .map(|chart| Arc::new(CustomChartGroup::new(chart.clone())) as ArcUpdateGroup)
+            .collect();
+        extra_groups.push(Self::token_update_group(&tracked_tokens)?);
+        extra_groups.push(Arc::new(local_charts_group(local_charts)));
+        let all_update_groups =
+            Self::all_update_groups(charts.chain_type, with_user_ops, extra_groups);
+        let token_charts_info =
+            Self::build_token_charts_info(&charts, &tracked_tokens, &all_update_groups)?;
+        let charts_info = Self::build_charts_info(charts, &all_update_groups, &stored_keys)?;
+        let custom_charts = custom_charts
+            .into_iter()
+            .filter(|chart| charts_info.contains_key(chart.name()))
+            .collect();
+        let update_groups = Self::init_update_groups(
+            all_update_groups,
+            update_groups,
+            &charts_info,
+            &token_charts_info,
+        )?;
         Ok(Self {
             lines_layout: layout.line_chart_categories,
             update_groups,
             charts_info,
+            tracked_tokens,
+            token_charts_info,
+            custom_charts,
         })
     }
 
+    /// Check queries of enabled custom charts against blockscout DB
+    /// (see [`CustomChartDefinition::check_query`])
+    pub async fn check_custom_queries(
+        &self,
+        blockscout: &DatabaseConnection,
+    ) -> anyhow::Result<()> {
+        for chart in &self.custom_charts {
+            chart
+                .check_query(blockscout)
+                .await
+                .map_err(|e| anyhow::anyhow!("invalid custom chart '{}': {e}", chart.name()))?;
+        }
+        Ok(())
+    }
+
+    /// Id and resolution entry of the enabled chart stored under `key`
+    /// (see [`Self::stored_keys`])
+    pub fn find_enabled_by_stored_key(
+        &self,
+        key: &ChartKey,
+    ) -> Option<(&str, &EnabledResolutionEntry)> {
+        self.charts_info
+            .iter()
+            .chain(self.token_charts_info.iter())
+            .find_map(|(id, entry)| {
+                entry
+                    .enabled_resolutions
+                    .get(key.resolution())
+                    .filter(|resolution_entry| resolution_entry.name == key.name())
+                    .map(|resolution_entry| (id.as_str(), resolution_entry))
+            })
+    }
+
     /// Build charts info from settings for one type of charts.
     ///
+    /// `stored_keys` - charts that are stored under another name (see [`Self::stored_keys`]).
+    ///
     /// `Err(Vec<ChartKey>)` - some unknown charts+resolutions are present in settings
     fn charts_info_from_settings(
         charts_settings: BTreeMap<String, AllChartSettings>,
         settings_chart_type: ChartType,
+        available_resolutions: &BTreeMap<ChartKey, ChartPropertiesObject>,
+        stored_keys: &HashMap<ChartKey, ChartKey>,
     ) -> Result<BTreeMap<String, EnabledChartEntry>, Vec<ChartKey>> {
-        let available_resolutions = Self::all_members();
         let mut unknown_charts = vec![];
 
         let mut charts_info = BTreeMap::new();
@@ -149,7 +293,7 @@ impl RuntimeSetup {
                 for (resolution, resolution_setting) in settings.resolutions.into_list() {
                     let key = ChartKey::new(name.clone(), resolution);
                     let resolution_properties = available_resolutions
-                        .get(&key)
+                        .get(stored_keys.get(&key).unwrap_or(&key))
                         .filter(|props| props.chart_type == settings_chart_type)
                         .cloned();
                     match (resolution_setting, resolution_properties) {
@@ -183,41 +327,477 @@ impl RuntimeSetup {
 
     fn build_charts_info(
         charts_config: config::charts::Config<AllChartSettings>,
+        all_update_groups: &[ArcUpdateGroup],
+        stored_keys: &HashMap<ChartKey, ChartKey>,
     ) -> anyhow::Result<BTreeMap<String, EnabledChartEntry>> {
-        let counters_info =
-            Self::charts_info_from_settings(charts_config.counters, ChartType::Counter);
-        let lines_info = Self::charts_info_from_settings(charts_config.lines, ChartType::Line);
-
-        let (counters_info, lines_info) = match (counters_info, lines_info) {
-            (Ok(c), Ok(l)) => (c, l),
-            (counters_result, lines_result) => {
-                let mut unknown_charts = vec![];
-                if let Err(c) = counters_result {
-                    unknown_charts.extend(c);
-                }
-                if let Err(l) = lines_result {
-                    unknown_charts.extend(l);
+        let available_resolutions = Self::all_members(all_update_groups);
+        let infos = [
+            (charts_config.counters, ChartType::Counter),
+            (charts_config.lines, ChartType::Line),
+            (charts_config.top_lists, ChartType::TopList),
+        ]
+        .into_iter()
+        .map(|(settings, chart_type)| {
+            Self::charts_info_from_settings(
+                settings,
+                chart_type,
+                &available_resolutions,
+                stored_keys,
+            )
+        });
+
+        let mut unknown_charts = vec![];
+        let mut charts_info = BTreeMap::new();
+        for info in infos {
+            match info {
+                Ok(info) => {
+                    charts_info =
+                        combine_disjoint_maps(charts_info, info).map_err(|duplicate_name| {
+                            anyhow::anyhow!("duplicate chart name: {duplicate_name:?}")
+                        })?;
                 }
+                Err(unknown) => unknown_charts.extend(unknown),
+            }
+        }
+        if !unknown_charts.is_empty() {
+            return Err(anyhow::anyhow!(
+                "non-existent charts+resolutions are present in settings: {unknown_charts:?}",
+            ));
+        }
+        Ok(charts_info)
+    }
+
+    /// Charts defined with SQL or derived from other charts
+    /// in the charts config.
+    fn custom_charts(
+        charts_config: &config::charts::Config<AllChartSettings>,
+    ) -> anyhow::Result<Vec<CustomChartDefinition>> {
+        let custom_charts = charts_config
+            .counters
+            .iter()
+            .map(|c| (c, ChartType::Counter))
+            .chain(charts_config.lines.iter().map(|c| (c, ChartType::Line)))
+            .filter(|((_, settings), _)| settings.custom.is_some() || settings.derived.is_some());
+        let built_in_groups = Self::all_update_groups(None, false, vec![])
+            .into_iter()
+            .chain(Self::all_optional_update_groups())
+            .collect_vec();
+        let built_in_members = Self::all_members(&built_in_groups);
+        let built_in_names: HashSet<String> = built_in_members
+            .values()
+            .map(|props| props.name.clone())
+            .collect();
+        let mut custom_names = HashSet::new();
+        let mut definitions = vec![];
+        for ((name, settings), chart_type) in custom_charts {
+            if built_in_names.contains(name) || !custom_names.insert(name.clone()) {
                 return Err(anyhow::anyhow!(
-                    "non-existent charts+resolutions are present in settings: {unknown_charts:?}",
+                    "custom chart name '{name}' is already taken"
                 ));
             }
+            let resolutions = match chart_type {
+                ChartType::Counter | ChartType::TopList => vec![ResolutionKind::Day],
+                ChartType::Line => vec![
+                    ResolutionKind::Day,
+                    ResolutionKind::Week,
+                    ResolutionKind::Month,
+                    ResolutionKind::Year,
+                ],
+            };
+            let definition = match (&settings.custom, &settings.derived, chart_type) {
+                (Some(_), Some(_), _) => Err(anyhow::anyhow!(
+                    "chart '{name}' can't be both custom and derived"
+                )),
+                (Some(custom), None, chart_type) => CustomChartDefinition::new(
+                    name.clone(),
+                    chart_type.clone(),
+                    custom.sql.clone(),
+                    custom.missing_date_policy.into(),
+                    custom.resolution_aggregation.into(),
+                    resolutions,
+                )
+                .and_then(|definition| {
+                    let timezone = match chart_type {
+                        ChartType::Line => Self::line_chart_timezone(charts_config, name),
+                        // deployment timezone is not applicable to counters
+                        ChartType::Counter | ChartType::TopList => custom.timezone,
+                    };
+                    match timezone {
+                        Some(timezone) => definition.with_timezone(timezone),
+                        None => Ok(definition),
+                    }
+                })
+                .map_err(anyhow::Error::from),
+                (None, Some(derived), ChartType::Line) => Self::derived_transform(
+                    charts_config,
+                    &built_in_members,
+                    derived,
+                )
+                .and_then(|transform| {
+                    let timezone = Self::derived_timezone(charts_config, derived)?;
+                    let definition =
+                        CustomChartDefinition::derived(name.clone(), transform, None, resolutions)?;
+                    match timezone {
+                        Some(timezone) => definition.with_timezone(timezone),
+                        None => Ok(definition),
+                    }
+                    .map_err(anyhow::Error::from)
+                }),
+                (None, Some(_), _) => Err(anyhow::anyhow!("only line charts can be derived")),
+                (None, None, _) => unreachable!("filtered above"),
+            }
+            .map_err(|e| anyhow::anyhow!("invalid custom chart '{name}': {e}"))?;
+            definitions.push(definition);
+        }
+        Ok(definitions)
+    }
+
+    /// Resolve and check the sources of derived chart.
+    ///
+    /// Sources must be enabled (with daily resolution) line charts
+    /// that are not derived themselves.
+    fn derived_transform(
+        charts_config: &config::charts::Config<AllChartSettings>,
+        built_in_members: &BTreeMap<ChartKey, ChartPropertiesObject>,
+        settings: &DerivedChartSettings,
+    ) -> anyhow::Result<DerivedTransform> {
+        let source = |config_name: &String| -> anyhow::Result<DerivedSource> {
+            let name = config_name.from_case(Case::Snake).to_case(Case::Camel);
+            let source_settings = charts_config
+                .lines
+                .get(&name)
+                .filter(|s| s.enabled && s.resolutions.day != Some(false))
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "source chart '{config_name}' is not an enabled daily line chart"
+                    )
+                })?;
+            if source_settings.derived.is_some() {
+                return Err(anyhow::anyhow!(
+                    "source chart '{config_name}' must not be derived"
+                ));
+            }
+            let missing_date_policy = match &source_settings.custom {
+                Some(custom) => custom.missing_date_policy.into(),
+                None => {
+                    built_in_members
+                        .get(&ChartKey::new(name.clone(), ResolutionKind::Day))
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("source chart '{config_name}' has no daily resolution")
+                        })?
+                        .missing_date_policy
+                }
+            };
+            let timezone = Self::line_chart_timezone(charts_config, &name);
+            Ok(DerivedSource::new(
+                timezone_variant_name(&name, timezone.as_ref()),
+                missing_date_policy,
+            ))
+        };
+        Ok(match settings {
+            DerivedChartSettings::MovingAverage { chart, days } => {
+                DerivedTransform::MovingAverage {
+                    source: source(chart)?,
+                    days: *days,
+                }
+            }
+            DerivedChartSettings::Ratio {
+                numerator,
+                denominator,
+            } => DerivedTransform::Ratio {
+                numerator: source(numerator)?,
+                denominator: source(denominator)?,
+            },
+            DerivedChartSettings::Cumulative { chart } => DerivedTransform::Cumulative {
+                source: source(chart)?,
+            },
+            DerivedChartSettings::Difference {
+                minuend,
+                subtrahend,
+            } => DerivedTransform::Difference {
+                minuend: source(minuend)?,
+                subtrahend: source(subtrahend)?,
+            },
+        })
+    }
+
+    /// Timezone that the (non-derived) line chart is calculated in.
+    ///
+    /// `None` means UTC.
+    fn line_chart_timezone(
+        charts_config: &config::charts::Config<AllChartSettings>,
+        name: &str,
+    ) -> Option<Tz> {
+        let custom = charts_config
+            .lines
+            .get(name)
+            .and_then(|s| s.custom.as_ref());
+        let timezone = match custom {
+            Some(custom) => custom.timezone.or(charts_config.timezone),
+            None if LocalChartKind::from_chart_name(name).is_some() => charts_config.timezone,
+            // other built-in charts are always in UTC
+            None => None,
+        };
+        timezone.filter(|timezone| timezone != &Tz::UTC)
+    }
+
+    /// Timezone of derived chart. All of its sources must be calculated
+    /// in the same timezone (see [`Self::line_chart_timezone`]).
+    fn derived_timezone(
+        charts_config: &config::charts::Config<AllChartSettings>,
+        settings: &DerivedChartSettings,
+    ) -> anyhow::Result<Option<Tz>> {
+        let timezones: HashSet<Option<Tz>> = settings
+            .sources()
+            .into_iter()
+            .map(|config_name| {
+                let name = config_name.from_case(Case::Snake).to_case(Case::Camel);
+                Self::line_chart_timezone(charts_config, &name)
+            })
+            .collect();
+        match timezones.into_iter().exactly_one() {
+            Ok(timezone) => Ok(timezone),
+            Err(_) => Err(anyhow::anyhow!(
+                "source charts must be calculated in the same timezone"
+            )),
+        }
+    }
+
+    /// Built-in charts calculated in the deployment timezone
+    /// (none if it's not set or is UTC).
+    ///
+    /// The group is present even without the timezone
+    /// (so that its schedule in the config is always valid).
+    fn local_charts(
+        charts_config: &config::charts::Config<AllChartSettings>,
+    ) -> anyhow::Result<Vec<CustomChartDefinition>> {
+        let Some(timezone) = charts_config.timezone.filter(|tz| tz != &Tz::UTC) else {
+            return Ok(vec![]);
         };
+        Self::warn_utc_only_charts(charts_config, &timezone);
+        LocalChartKind::all()
+            .into_iter()
+            .map(|kind| {
+                local_chart(kind, timezone, kind.supported_resolutions().iter().copied()).map_err(
+                    |e| {
+                        anyhow::anyhow!(
+                            "invalid local chart '{}': {e}",
+                            kind.local_chart_name(&timezone)
+                        )
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Only some built-in charts can be calculated in local time (see [`LocalChartKind`]),
+    /// so the enabled ones that stay in UTC are reported
+    fn warn_utc_only_charts(
+        charts_config: &config::charts::Config<AllChartSettings>,
+        timezone: &Tz,
+    ) {
+        let utc_only_charts = charts_config
+            .lines
+            .iter()
+            .filter(|(name, settings)| {
+                settings.enabled
+                    && settings.custom.is_none()
+                    && settings.derived.is_none()
+                    && LocalChartKind::from_chart_name(name).is_none()
+            })
+            .chain(
+                charts_config
+                    .token_lines
+                    .iter()
+                    .filter(|(_, settings)| settings.enabled),
+            )
+            .map(|(name, _)| name)
+            .collect_vec();
+        if !utc_only_charts.is_empty() {
+            tracing::warn!(
+                timezone = %timezone,
+                "Built-in line charts can't be calculated in the configured timezone, \
+                they are calculated in UTC: {:?}", utc_only_charts
+            )
+        }
+    }
+
+    /// Keys of the charts that are stored under another name than in the config
+    /// (i.e. calculated in non-UTC timezone) mapped to the stored ones
+    fn stored_keys<'a>(
+        charts: impl IntoIterator<Item = &'a CustomChartDefinition>,
+    ) -> HashMap<ChartKey, ChartKey> {
+        charts
+            .into_iter()
+            .flat_map(|chart| {
+                chart.resolutions().iter().map(move |resolution| {
+                    (
+                        ChartKey::new(chart.name().to_owned(), *resolution),
+                        ChartKey::new(chart.stored_name(), *resolution),
+                    )
+                })
+            })
+            .filter(|(key, stored_key)| key != stored_key)
+            .collect()
+    }
+
+    fn tracked_tokens(
+        charts_config: &config::charts::Config<AllChartSettings>,
+    ) -> anyhow::Result<BTreeSet<TokenAddress>> {
+        charts_config
+            .tracked_tokens
+            .iter()
+            .map(|address| address.parse().map_err(anyhow::Error::from))
+            .collect()
+    }
+
+    /// Group with all possible charts for each of the tracked tokens.
+    ///
+    /// The group is present even without tracked tokens
+    /// (so that its schedule in the config is always valid).
+    fn token_update_group(
+        tracked_tokens: &BTreeSet<TokenAddress>,
+    ) -> anyhow::Result<ArcUpdateGroup> {
+        let mut charts = vec![];
+        for token in tracked_tokens {
+            for kind in TokenChartKind::all() {
+                let chart = token_chart(kind, token, kind.supported_resolutions().iter().copied())
+                    .map_err(|e| {
+                        anyhow::anyhow!("invalid token chart '{}': {e}", kind.chart_name(token))
+                    })?;
+                charts.push(chart);
+            }
+        }
+        Ok(Arc::new(token_charts_group(charts)))
+    }
+
+    /// Token chart settings are applied to the charts of each tracked token
+    fn build_token_charts_info(
+        charts_config: &config::charts::Config<AllChartSettings>,
+        tracked_tokens: &BTreeSet<TokenAddress>,
+        all_update_groups: &[ArcUpdateGroup],
+    ) -> anyhow::Result<BTreeMap<String, EnabledChartEntry>> {
+        let mut settings_per_token = BTreeMap::new();
+        for (id, settings) in &charts_config.token_lines {
+            let kind = TokenChartKind::from_id(id)
+                .ok_or_else(|| anyhow::anyhow!("unknown token chart: {id}"))?;
+            for token in tracked_tokens {
+                settings_per_token.insert(kind.chart_name(token), settings.clone());
+            }
+        }
+        let available_resolutions = Self::all_members(all_update_groups);
+        Self::charts_info_from_settings(
+            settings_per_token,
+            ChartType::Line,
+            &available_resolutions,
+            &HashMap::new(),
+        )
+        .map_err(|unknown_charts| {
+            anyhow::anyhow!(
+                "non-existent token charts+resolutions are present in settings: {unknown_charts:?}",
+            )
+        })
+    }
+
+    /// Groups of the charts that are available only for the chain type
+    fn chain_type_update_groups(chain_type: Option<ChainType>) -> Vec<ArcUpdateGroup> {
+        use stats::update_groups::{arbitrum, optimism};
 
-        combine_disjoint_maps(counters_info, lines_info)
-            .map_err(|duplicate_name| anyhow::anyhow!("duplicate chart name: {duplicate_name:?}",))
+        match chain_type {
+            Some(ChainType::Optimism) => vec![
+                Arc::new(optimism::NewBatchesGroup),
+                Arc::new(optimism::AverageBatchSizeGroup),
+                Arc::new(optimism::L1DataFeesGroup),
+                Arc::new(optimism::NewBlobsGroup),
+            ],
+            Some(ChainType::Arbitrum) => vec![
+                Arc::new(arbitrum::NewBatchesGroup),
+                Arc::new(arbitrum::AverageBatchSizeGroup),
+                Arc::new(arbitrum::L1DataFeesGroup),
+            ],
+            None => vec![],
+        }
     }
 
-    fn all_update_groups() -> Vec<ArcUpdateGroup> {
+    /// Groups of the charts calculated from `user-ops-indexer` database
+    fn user_ops_update_groups() -> Vec<ArcUpdateGroup> {
         use stats::update_groups::*;
 
         vec![
+            Arc::new(ActiveSmartAccountsGroup),
+            Arc::new(NewUserOpsGroup),
+            Arc::new(NewSmartAccountsGroup),
+            Arc::new(SponsoredUserOpsShareGroup),
+            Arc::new(TopBundlersByFeesGroup),
+        ]
+    }
+
+    /// Groups that are available depending on the chain
+    fn optional_update_groups(
+        chain_type: Option<ChainType>,
+        with_user_ops: bool,
+    ) -> Vec<ArcUpdateGroup> {
+        let mut groups = Self::chain_type_update_groups(chain_type);
+        if with_user_ops {
+            groups.extend(Self::user_ops_update_groups());
+        }
+        groups
+    }
+
+    fn all_optional_update_groups() -> Vec<ArcUpdateGroup> {
+        [ChainType::Optimism, ChainType::Arbitrum]
+            .into_iter()
+            .flat_map(|chain_type| Self::chain_type_update_groups(Some(chain_type)))
+            .chain(Self::user_ops_update_groups())
+            .collect()
+    }
+
+    /// Remove settings of the optional charts and groups that are
+    /// not available for the chain.
+    fn remove_unavailable_settings(
+        charts: &mut config::charts::Config<AllChartSettings>,
+        update_groups: &mut config::update_groups::Config,
+        with_user_ops: bool,
+    ) {
+        let available_groups = Self::optional_update_groups(charts.chain_type, with_user_ops);
+        let available_charts: HashSet<String> = available_groups
+            .iter()
+            .flat_map(|g| g.list_charts())
+            .map(|props| props.name)
+            .collect();
+        let available_groups: HashSet<String> = available_groups.iter().map(|g| g.name()).collect();
+        for group in Self::all_optional_update_groups() {
+            let name = group.name();
+            if !available_groups.contains(&name) {
+                update_groups.schedules.remove(&name);
+            }
+            for chart in group.list_charts() {
+                if !available_charts.contains(&chart.name) {
+                    charts.counters.remove(&chart.name);
+                    charts.lines.remove(&chart.name);
+                    charts.top_lists.remove(&chart.name);
+                }
+            }
+        }
+    }
+
+    /// Built-in groups (including the available optional ones) together with `custom_groups`
+    fn all_update_groups(
+        chain_type: Option<ChainType>,
+        with_user_ops: bool,
+        custom_groups: Vec<ArcUpdateGroup>,
+    ) -> Vec<ArcUpdateGroup> {
+        use stats::update_groups::*;
+
+        let built_in: Vec<ArcUpdateGroup> = vec![
             // singletons
             Arc::new(ActiveAccountsGroup),
             Arc::new(AverageBlockRewardsGroup),
             Arc::new(AverageBlockSizeGroup),
             Arc::new(AverageGasLimitGroup),
+            Arc::new(AverageBaseFeeGroup),
             Arc::new(AverageGasPriceGroup),
+            Arc::new(AveragePriorityFeeGroup),
             Arc::new(AverageTxnFeeGroup),
             Arc::new(GasUsedGrowthGroup),
             Arc::new(NativeCoinSupplyGroup),
@@ -226,17 +806,31 @@ impl RuntimeSetup {
             Arc::new(TxnsSuccessRateGroup),
             Arc::new(AverageBlockTimeGroup),
             Arc::new(CompletedTxnsGroup),
+            Arc::new(NewTxnsByStatusGroup),
             Arc::new(TotalAddressesGroup),
             Arc::new(TotalBlocksGroup),
             Arc::new(TotalTokensGroup),
+            Arc::new(AverageGasPriceHourlyGroup),
+            Arc::new(NewBlocksHourlyGroup),
+            Arc::new(NewTxnsHourlyGroup),
             // complex groups
+            Arc::new(GasPricePercentilesGroup),
             Arc::new(NewAccountsGroup),
             Arc::new(NewContractsGroup),
             Arc::new(NewTxnsGroup),
             Arc::new(NewVerifiedContractsGroup),
             Arc::new(NativeCoinHoldersGrowthGroup),
             Arc::new(NewNativeCoinTransfersGroup),
-        ]
+            // top lists
+            Arc::new(TopContractsByTxnsGroup),
+            Arc::new(TopGasConsumersGroup),
+            Arc::new(TopSendersGroup),
+        ];
+        built_in
+            .into_iter()
+            .chain(Self::optional_update_groups(chain_type, with_user_ops))
+            .chain(custom_groups)
+            .collect()
     }
 
     fn create_all_dependencies_mutexes(
@@ -292,9 +886,29 @@ impl RuntimeSetup {
                 "AverageGasLimitGroup",
                 vec!["newBlocks_DAY", "newBlocks_MONTH"],
             ),
+            (
+                "AverageBaseFeeGroup",
+                vec!["newBlocks_DAY", "newBlocks_MONTH"],
+            ),
             ("AverageGasPriceGroup", vec!["newTxns_DAY", "newTxns_MONTH"]),
+            (
+                "AveragePriorityFeeGroup",
+                vec!["newTxns_DAY", "newTxns_MONTH"],
+            ),
+            (
+                "GasPricePercentilesGroup",
+                vec!["newTxns_DAY", "newTxns_MONTH"],
+            ),
             ("AverageTxnFeeGroup", vec!["newTxns_DAY", "newTxns_MONTH"]),
             ("TxnsSuccessRateGroup", vec!["newTxns_DAY", "newTxns_MONTH"]),
+            (
+                "SponsoredUserOpsShareGroup",
+                vec!["newUserOps_DAY", "newUserOps_MONTH"],
+            ),
+            (
+                "AverageBatchSizeGroup",
+                vec!["newBatches_DAY", "newBatches_MONTH"],
+            ),
         ]
         .map(|(group_name, allowed_missing)| {
             (
@@ -353,10 +967,11 @@ impl RuntimeSetup {
 
     /// All initialization of update groups happens here
     fn init_update_groups(
+        update_groups: Vec<ArcUpdateGroup>,
         groups_config: config::update_groups::Config,
         charts_info: &BTreeMap<String, EnabledChartEntry>,
+        token_charts_info: &BTreeMap<String, EnabledChartEntry>,
     ) -> anyhow::Result<BTreeMap<String, UpdateGroupEntry>> {
-        let update_groups = Self::all_update_groups();
         let dep_mutexes = Self::create_all_dependencies_mutexes(update_groups.clone());
         let update_groups = Self::build_group_map(update_groups)?;
         let mut result = BTreeMap::new();
@@ -365,6 +980,18 @@ impl RuntimeSetup {
         Self::verify_groups_config(&update_groups, &groups_config)?;
         Self::warn_non_member_charts(&update_groups);
 
+        // charts can be stored under another name than in the config
+        let enabled_keys: HashSet<ChartKey> = charts_info
+            .values()
+            .chain(token_charts_info.values())
+            .flat_map(|entry| {
+                entry
+                    .enabled_resolutions
+                    .iter()
+                    .map(|(resolution, info)| ChartKey::new(info.name.clone(), *resolution))
+            })
+            .collect();
+
         for (name, group) in update_groups {
             let update_schedule = groups_config
                 .schedules
@@ -373,11 +1000,7 @@ impl RuntimeSetup {
             let enabled_members = group
                 .list_charts()
                 .into_iter()
-                .filter(|m| {
-                    charts_info
-                        .get(m.key.name())
-                        .is_some_and(|a| a.enabled_resolutions.contains_key(m.key.resolution()))
-                })
+                .filter(|m| enabled_keys.contains(&m.key))
                 .map(|m| m.key)
                 .collect();
             let sync_group = SyncUpdateGroup::new(&dep_mutexes, group)?;
@@ -394,9 +1017,9 @@ impl RuntimeSetup {
     }
 
     /// List all charts+resolutions that are members of at least 1 group.
-    fn all_members() -> BTreeMap<ChartKey, ChartPropertiesObject> {
-        let members_with_duplicates = Self::all_update_groups()
-            .into_iter()
+    fn all_members(update_groups: &[ArcUpdateGroup]) -> BTreeMap<ChartKey, ChartPropertiesObject> {
+        let members_with_duplicates = update_groups
+            .iter()
             .flat_map(|g| g.list_charts())
             .collect_vec();
         let mut members = BTreeMap::new();
@@ -1,23 +1,157 @@
+use std::collections::BTreeMap;
+
 use stats::{
     exclusive_datetime_range_to_inclusive,
-    types::{ExtendedTimespanValue, Timespan},
+    types::{ExtendedTimespanValue, SeriesValues, Timespan, TimespanValue, TopListEntry},
+    ResolutionKind,
 };
-use stats_proto::blockscout::stats::v1::Point;
+use stats_proto::blockscout::stats::v1::{self as proto_v1, Point, SeriesValue};
+
+const HOUR_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
 
-pub fn serialize_line_points<Resolution: Timespan>(
+/// `series` are values of named series of multi-series charts (if any).
+/// They are attached to the points with the same timespan.
+pub fn serialize_line_points<Resolution: Timespan + Ord>(
     data: Vec<ExtendedTimespanValue<Resolution, String>>,
+    series: Vec<TimespanValue<Resolution, SeriesValues>>,
 ) -> Vec<Point> {
+    let mut series: BTreeMap<Resolution, SeriesValues> =
+        series.into_iter().map(|s| (s.timespan, s.value)).collect();
     data.into_iter()
         .map(|point| {
+            let point_series = series
+                .remove(&point.timespan)
+                .map(|s| {
+                    s.0.into_iter()
+                        .map(|(name, value)| SeriesValue { name, value })
+                        .collect()
+                })
+                .unwrap_or_default();
             let time_range =
                 exclusive_datetime_range_to_inclusive(point.timespan.into_time_range());
-            let date_range = { time_range.start().date_naive()..=time_range.end().date_naive() };
+            let (date, date_to) = match Resolution::enum_variant() {
+                // both bounds are the same for timespans shorter than a day,
+                // so the start of the timespan is returned instead
+                ResolutionKind::Hour => {
+                    let start = time_range.start().format(HOUR_FORMAT).to_string();
+                    (start.clone(), start)
+                }
+                ResolutionKind::Day
+                | ResolutionKind::Week
+                | ResolutionKind::Month
+                | ResolutionKind::Year => (
+                    time_range.start().date_naive().to_string(),
+                    time_range.end().date_naive().to_string(),
+                ),
+            };
             Point {
-                date: date_range.start().to_string(),
-                date_to: date_range.end().to_string(),
+                date,
+                date_to,
                 value: point.value,
                 is_approximate: point.is_approximate,
+                series: point_series,
             }
         })
         .collect()
 }
+
+/// `limit` - max number of (top) entries to return
+pub fn serialize_top_list(
+    data: Vec<TopListEntry>,
+    limit: Option<u32>,
+) -> Vec<proto_v1::TopListEntry> {
+    let limit = limit.map(|l| l as usize).unwrap_or(usize::MAX);
+    data.into_iter()
+        .take(limit)
+        .zip(1..)
+        .map(|(entry, rank)| proto_v1::TopListEntry {
+            rank,
+            key: entry.key,
+            value: entry.value,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    fn d(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    #[test]
+    fn series_are_attached_to_points() {
+        let data = vec![
+            ExtendedTimespanValue {
+                timespan: d("2022-11-09"),
+                value: "3".to_string(),
+                is_approximate: false,
+            },
+            ExtendedTimespanValue {
+                timespan: d("2022-11-10"),
+                value: "0".to_string(),
+                is_approximate: false,
+            },
+        ];
+        let series = vec![TimespanValue {
+            timespan: d("2022-11-09"),
+            value: SeriesValues(
+                [("failed", "1"), ("success", "2")]
+                    .into_iter()
+                    .map(|(n, v)| (n.to_string(), v.to_string()))
+                    .collect(),
+            ),
+        }];
+        let points = serialize_line_points(data, series);
+        assert_eq!(
+            points[0].series,
+            vec![
+                SeriesValue {
+                    name: "failed".to_string(),
+                    value: "1".to_string()
+                },
+                SeriesValue {
+                    name: "success".to_string(),
+                    value: "2".to_string()
+                },
+            ]
+        );
+        assert_eq!(points[0].value, "3");
+        assert!(points[1].series.is_empty());
+    }
+
+    #[test]
+    fn top_list_is_ranked_and_limited() {
+        let data: Vec<_> = [("0x01", "10"), ("0x02", "5"), ("0x03", "1")]
+            .into_iter()
+            .map(|(key, value)| TopListEntry {
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+            .collect();
+        let entries = serialize_top_list(data.clone(), None);
+        assert_eq!(
+            entries.iter().map(|e| e.rank).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        let entries = serialize_top_list(data, Some(2));
+        assert_eq!(
+            entries,
+            vec![
+                proto_v1::TopListEntry {
+                    rank: 1,
+                    key: "0x01".to_string(),
+                    value: "10".to_string(),
+                },
+                proto_v1::TopListEntry {
+                    rank: 2,
+                    key: "0x02".to_string(),
+                    value: "5".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -1,12 +1,16 @@
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc, time::Duration};
 
 use crate::{
-    config::{read_charts_config, read_layout_config, read_update_groups_config},
+    admin_service::AdminService,
+    chains::{chains_setup, connect_stats_db},
+    dependency_graph::DependencyGraph,
+    export::{route_chain_export_charts, route_export_charts},
     health::HealthService,
-    read_service::ReadService,
-    runtime_setup::RuntimeSetup,
+    read_cache::{route_chain_line_chart, route_line_chart, ReadCache},
+    read_service::{ReadChain, ReadService},
     settings::Settings,
     update_service::UpdateService,
+    updates::{route_updates, UpdatesBroadcast},
 };
 
 use anyhow::Context;
@@ -14,26 +18,40 @@ use blockscout_endpoint_swagger::route_swagger;
 use blockscout_service_launcher::launcher::{self, LaunchSettings};
 use sea_orm::{ConnectOptions, Database};
 use stats_proto::blockscout::stats::v1::{
+    chain_stats_service_actix::route_chain_stats_service,
+    chain_stats_service_server::{ChainStatsService, ChainStatsServiceServer},
     health_actix::route_health,
     health_server::HealthServer,
+    stats_admin_service_actix::route_stats_admin_service,
+    stats_admin_service_server::{StatsAdminService, StatsAdminServiceServer},
     stats_service_actix::route_stats_service,
     stats_service_server::{StatsService, StatsServiceServer},
 };
+use tokio::sync::Semaphore;
 
 const SERVICE_NAME: &str = "stats";
+const STALENESS_METRICS_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Clone)]
-struct HttpRouter<S: StatsService> {
-    stats: Arc<S>,
+struct HttpRouter<A: StatsAdminService> {
+    stats: Arc<ReadService>,
+    admin: Arc<A>,
     health: Arc<HealthService>,
+    updates: Arc<UpdatesBroadcast>,
     swagger_path: PathBuf,
 }
 
-impl<S: StatsService> launcher::HttpRouter for HttpRouter<S> {
+impl<A: StatsAdminService> launcher::HttpRouter for HttpRouter<A> {
     fn register_routes(&self, service_config: &mut actix_web::web::ServiceConfig) {
         service_config
             .configure(|config| route_health(config, self.health.clone()))
+            // replaces the generated handler (see `crate::read_cache`)
+            .configure(|config| route_line_chart(config, self.stats.clone()))
+            // replaces the generated handler (see `crate::export`)
+            .configure(|config| route_export_charts(config, self.stats.clone()))
             .configure(|config| route_stats_service(config, self.stats.clone()))
+            .configure(|config| route_stats_admin_service(config, self.admin.clone()))
+            .configure(|config| route_updates(config, self.updates.clone()))
             .configure(|config| {
                 route_swagger(
                     config,
@@ -42,17 +60,25 @@ impl<S: StatsService> launcher::HttpRouter for HttpRouter<S> {
                     // the swagger itself
                     "/api/v1/docs/swagger.yaml",
                 )
-            });
+            })
+            // registered last, so that `{chain}` in the path does not
+            // shadow other routes
+            .configure(|config| route_chain_line_chart(config, self.stats.clone()))
+            .configure(|config| route_chain_export_charts(config, self.stats.clone()))
+            .configure(|config| route_chain_stats_service(config, self.stats.clone()));
     }
 }
 
-fn grpc_router<S: StatsService>(
+fn grpc_router<S: StatsService + ChainStatsService, A: StatsAdminService>(
     stats: Arc<S>,
+    admin: Arc<A>,
     health: Arc<HealthService>,
 ) -> tonic::transport::server::Router {
     tonic::transport::Server::builder()
         .add_service(HealthServer::from_arc(health))
-        .add_service(StatsServiceServer::from_arc(stats))
+        .add_service(StatsServiceServer::from_arc(stats.clone()))
+        .add_service(ChainStatsServiceServer::from_arc(stats))
+        .add_service(StatsAdminServiceServer::from_arc(admin))
 }
 
 pub async fn stats(settings: Settings) -> Result<(), anyhow::Error> {
@@ -61,63 +87,138 @@ pub async fn stats(settings: Settings) -> Result<(), anyhow::Error> {
         &settings.tracing,
         &settings.jaeger,
     )?;
-    let charts_config = read_charts_config(&settings.charts_config)?;
-    let layout_config = read_layout_config(&settings.layout_config)?;
-    let update_groups_config = read_update_groups_config(&settings.update_groups_config)?;
-    let mut opt = ConnectOptions::new(settings.db_url.clone());
-    opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
-    blockscout_service_launcher::database::initialize_postgres::<stats::migration::Migrator>(
-        opt.clone(),
-        settings.create_database,
-        settings.run_migrations,
-    )
-    .await?;
-    let db = Arc::new(Database::connect(opt).await.context("stats DB")?);
-
-    let mut opt = ConnectOptions::new(settings.blockscout_db_url.clone());
-    opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
-    // we'd like to have each batch to resolve in under 1 hour
-    // as it seems to be the middleground between too many steps & occupying DB for too long
-    opt.sqlx_slow_statements_logging_settings(
-        tracing::log::LevelFilter::Warn,
-        Duration::from_secs(3600),
-    );
-    let blockscout = Arc::new(Database::connect(opt).await.context("blockscout DB")?);
-
-    let charts = Arc::new(RuntimeSetup::new(
-        charts_config,
-        layout_config,
-        update_groups_config,
-    )?);
-
-    // TODO: maybe run this with migrations or have special config
-    for group_entry in charts.update_groups.values() {
-        group_entry
-            .group
-            .create_charts_with_mutexes(&db, None, &group_entry.enabled_members)
-            .await?;
+    let chains = chains_setup(&settings)?;
+    // shared between chains, so that the number of simultaneous
+    // updates does not grow with the number of chains
+    if settings.max_concurrent_updates == 0 {
+        return Err(anyhow::anyhow!("max_concurrent_updates must be positive"));
     }
-
-    let update_service =
-        Arc::new(UpdateService::new(db.clone(), blockscout, charts.clone()).await?);
-
-    tokio::spawn(async move {
-        update_service
-            .force_async_update_and_run(
-                settings.concurrent_start_updates,
-                settings.default_schedule,
-                settings.force_update_on_start,
+    let update_permits = Arc::new(Semaphore::new(settings.max_concurrent_updates));
+    let mut read_chains = BTreeMap::new();
+    let mut update_services = BTreeMap::new();
+    let updates = Arc::new(UpdatesBroadcast::default());
+    let read_cache = Arc::new(ReadCache::from(settings.read_cache.clone()));
+
+    for chain in chains {
+        let db = Arc::new(
+            connect_stats_db(&settings, &chain)
+                .await
+                .with_context(|| format!("chain '{}'", chain.name))?,
+        );
+
+        let mut opt = ConnectOptions::new(chain.blockscout_db_url.clone());
+        opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
+        // we'd like to have each batch to resolve in under 1 hour
+        // as it seems to be the middleground between too many steps & occupying DB for too long
+        opt.sqlx_slow_statements_logging_settings(
+            tracing::log::LevelFilter::Warn,
+            Duration::from_secs(3600),
+        );
+        let blockscout = Arc::new(
+            Database::connect(opt)
+                .await
+                .with_context(|| format!("blockscout DB of chain '{}'", chain.name))?,
+        );
+
+        let user_ops = match &chain.user_ops_db_url {
+            Some(url) => {
+                let mut opt = ConnectOptions::new(url.clone());
+                opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
+                let db = Database::connect(opt)
+                    .await
+                    .with_context(|| format!("user-ops-indexer DB of chain '{}'", chain.name))?;
+                Some(Arc::new(db))
+            }
+            None => None,
+        };
+
+        let mut charts = chain.runtime;
+        charts
+            .check_custom_queries(&blockscout)
+            .await
+            .with_context(|| format!("chain '{}'", chain.name))?;
+        if settings.db_update_locks {
+            charts.enable_db_update_locks();
+        }
+        let schedule_conflicts = DependencyGraph::new(&charts.update_groups)
+            .schedule_conflicts(&settings.default_schedule, chrono::Utc::now());
+        for conflict in schedule_conflicts {
+            tracing::warn!(chain =% chain.name, "{conflict}");
+        }
+        let charts = Arc::new(charts);
+
+        // TODO: maybe run this with migrations or have special config
+        for group_entry in charts.update_groups.values() {
+            group_entry
+                .group
+                .create_charts_with_mutexes(&db, None, &group_entry.enabled_members)
+                .await?;
+        }
+
+        let update_service = Arc::new(
+            UpdateService::new(
+                chain.name.clone(),
+                update_permits.clone(),
+                db.clone(),
+                blockscout,
+                user_ops,
+                charts.clone(),
+                updates.clone(),
+                read_cache.clone(),
             )
-            .await;
-    });
+            .await?,
+        );
+
+        tokio::spawn(
+            update_service
+                .clone()
+                .run_staleness_metrics_updater(STALENESS_METRICS_INTERVAL),
+        );
+
+        tokio::spawn({
+            let update_service = update_service.clone();
+            let concurrent_start_updates = settings.concurrent_start_updates;
+            let default_schedule = settings.default_schedule.clone();
+            let force_update_on_start = settings.force_update_on_start;
+            async move {
+                update_service
+                    .force_async_update_and_run(
+                        concurrent_start_updates,
+                        default_schedule,
+                        force_update_on_start,
+                    )
+                    .await;
+            }
+        });
+
+        read_chains.insert(chain.name.clone(), ReadChain { db, charts });
+        update_services.insert(chain.name, update_service);
+    }
 
-    let read_service = Arc::new(ReadService::new(db, charts, settings.limits.into()).await?);
+    let authorized_keys = settings
+        .authorized_keys
+        .values()
+        .map(|key| key.key.clone())
+        .collect();
+    let admin_service = Arc::new(AdminService::new(update_services, authorized_keys));
+
+    let read_service = Arc::new(
+        ReadService::new(
+            read_chains,
+            settings.limits.into(),
+            chrono::Duration::days(settings.counter_comparison_days.into()),
+            read_cache,
+        )
+        .await?,
+    );
     let health = Arc::new(HealthService::default());
 
-    let grpc_router = grpc_router(read_service.clone(), health.clone());
+    let grpc_router = grpc_router(read_service.clone(), admin_service.clone(), health.clone());
     let http_router = HttpRouter {
         stats: read_service,
+        admin: admin_service,
         health: health.clone(),
+        updates,
         swagger_path: settings.swagger_file,
     };
 
@@ -7,26 +7,54 @@ use blockscout_service_launcher::{
 use cron::Schedule;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
-use std::{net::SocketAddr, path::PathBuf, str::FromStr};
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+    path::PathBuf,
+    str::FromStr,
+};
+
+use crate::config::{ChartsConfigOverride, LayoutConfigOverride, UpdateGroupsConfigOverride};
 
 #[serde_as]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default, deny_unknown_fields)]
 pub struct Settings {
     pub db_url: String,
     pub create_database: bool,
     pub run_migrations: bool,
     pub blockscout_db_url: String,
+    /// Enables account abstraction charts (see [`stats::lines::user_ops`])
+    pub user_ops_indexer: Option<UserOpsIndexerSettings>,
     #[serde_as(as = "DisplayFromStr")]
     pub default_schedule: Schedule,
     pub force_update_on_start: Option<bool>, // None = no update
     pub concurrent_start_updates: usize,
+    /// Maximum number of update groups that are updated at the same time
+    /// (across all chains). Must be positive
+    pub max_concurrent_updates: usize,
+    /// Synchronize chart updates with other processes (e.g. `stats-cli`)
+    /// through advisory locks in stats DB
+    pub db_update_locks: bool,
     pub limits: LimitsSettings,
+    pub read_cache: ReadCacheSettings,
+    /// Counters are compared with their values this number of days ago
+    /// (`previous_value` and `change_percent` in the response)
+    pub counter_comparison_days: u32,
     pub charts_config: PathBuf,
     pub layout_config: PathBuf,
     pub update_groups_config: PathBuf,
     /// Location of swagger file to serve
     pub swagger_file: PathBuf,
+    /// Keys that are allowed to use admin api (`x-api-key` header).
+    /// Admin api is not accessible if empty.
+    pub authorized_keys: HashMap<String, ApiKey>,
+    /// Additional chains served by the instance (name -> settings).
+    ///
+    /// The chain configured by top-level settings (if `blockscout_db_url`
+    /// is set) is served at `/api/v1/...` and chain `<name>` - at
+    /// `/api/v1/<name>/...`.
+    pub chains: BTreeMap<String, ChainSettings>,
 
     pub server: ServerSettings,
     pub metrics: MetricsSettings,
@@ -53,12 +81,19 @@ impl Default for Settings {
             default_schedule: Schedule::from_str("0 0 1 * * * *").unwrap(),
             force_update_on_start: Some(false),
             concurrent_start_updates: 3,
+            max_concurrent_updates: 3,
+            db_update_locks: false,
             limits: Default::default(),
+            read_cache: Default::default(),
+            counter_comparison_days: 1,
             charts_config: PathBuf::from_str("config/charts.json").unwrap(),
             layout_config: PathBuf::from_str("config/layout.json").unwrap(),
             update_groups_config: PathBuf::from_str("config/update_groups.json").unwrap(),
             swagger_file: PathBuf::from("../stats-proto/swagger/stats.swagger.yaml"),
+            authorized_keys: Default::default(),
+            chains: Default::default(),
             blockscout_db_url: Default::default(),
+            user_ops_indexer: Default::default(),
             create_database: Default::default(),
             run_migrations: Default::default(),
             metrics: Default::default(),
@@ -68,6 +103,45 @@ impl Default for Settings {
     }
 }
 
+/// Chain served in addition to the one from top-level settings.
+///
+/// Configs are taken from top-level settings and can be adjusted with
+/// overrides (same format as for env variables, e.g. `STATS_CHARTS__...`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ChainSettings {
+    pub blockscout_db_url: String,
+    #[serde(default)]
+    pub user_ops_indexer: Option<UserOpsIndexerSettings>,
+    /// Stats DB for the chain. Top-level `db_url` is used if not set
+    #[serde(default)]
+    pub db_url: Option<String>,
+    /// Postgres schema for the chain data in stats DB.
+    /// Required if the DB is shared with other chains
+    #[serde(default)]
+    pub db_schema: Option<String>,
+    #[serde(default)]
+    pub charts: ChartsConfigOverride,
+    #[serde(default)]
+    pub layout: LayoutConfigOverride,
+    #[serde(default)]
+    pub update_groups: UpdateGroupsConfigOverride,
+}
+
+/// Database of `user-ops-indexer` service, the source of account
+/// abstraction charts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct UserOpsIndexerSettings {
+    pub db_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKey {
+    pub key: String,
+}
+
 /// Various limits like rate limiting and restrictions on input.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(default, deny_unknown_fields)]
@@ -78,6 +152,14 @@ pub struct LimitsSettings {
     /// If start or end of the range is left empty, min/max values
     /// from DB are considered.
     pub request_interval_limit_days: u32,
+    /// Maximum number of charts in a single export request.
+    ///
+    /// Interval of each exported chart is limited by `request_interval_limit_days`.
+    pub export_max_charts: u32,
+    /// Maximum number of points (of all charts together) in a single export request.
+    ///
+    /// The exported table is built in memory, so its size is limited.
+    pub export_max_points: u32,
 }
 
 impl Default for LimitsSettings {
@@ -85,6 +167,27 @@ impl Default for LimitsSettings {
         Self {
             // ~500 years seems reasonable
             request_interval_limit_days: 182500,
+            export_max_charts: 50,
+            // e.g. 50 daily charts for ~25 years
+            export_max_points: 500_000,
+        }
+    }
+}
+
+/// In-process cache of line chart responses (see [`crate::read_cache`])
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReadCacheSettings {
+    pub enabled: bool,
+    /// Approximate memory limit for cached responses (in megabytes)
+    pub max_size_mb: u64,
+}
+
+impl Default for ReadCacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_size_mb: 64,
         }
     }
 }
@@ -1,14 +1,101 @@
-use crate::runtime_setup::{RuntimeSetup, UpdateGroupEntry};
-use chrono::Utc;
+use crate::{
+    read_cache::ReadCache,
+    read_service::get_serialized_line_chart_data_resolution_dispatch,
+    runtime_setup::{RuntimeSetup, UpdateGroupEntry},
+    updates::{ChartUpdateEvent, UpdatesBroadcast},
+};
+use chrono::{DateTime, NaiveDate, Utc};
 use cron::Schedule;
+use itertools::Itertools;
 use sea_orm::{DatabaseConnection, DbErr};
-use stats::data_source::types::UpdateParameters;
-use std::sync::Arc;
+use stats::{
+    data_source::types::UpdateParameters,
+    entity::sea_orm_active_enums::ChartType,
+    metrics::{CHART_LAST_UPDATE_FAILED, CHART_STALENESS},
+    types::{
+        timespans::{Hour, Month, Week, Year},
+        Timespan,
+    },
+    ChartKey, MissingDatePolicy, ReadError, ResolutionKind, UpdateError,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+use tokio::sync::Semaphore;
 
 pub struct UpdateService {
+    /// Name of the served chain (see [`crate::chains`])
+    chain: String,
+    /// Limits concurrent group updates. Can be shared between several services
+    update_permits: Arc<Semaphore>,
     db: Arc<DatabaseConnection>,
     blockscout: Arc<DatabaseConnection>,
+    user_ops: Option<Arc<DatabaseConnection>>,
     charts: Arc<RuntimeSetup>,
+    /// Update group name -> its latest update status
+    statuses: Mutex<HashMap<String, GroupUpdateStatus>>,
+    /// Update group name -> lock held during its update
+    group_locks: HashMap<String, tokio::sync::Mutex<()>>,
+    /// Triggered updates that have not started yet
+    requested_updates: RequestedUpdates,
+    /// Receives new values of the charts after group updates.
+    /// Can be shared between several services
+    updates: Arc<UpdatesBroadcast>,
+    /// Responses of updated charts are removed from it.
+    /// Can be shared between several services
+    read_cache: Arc<ReadCache>,
+}
+
+/// Information about the latest update of a group
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GroupUpdateStatus {
+    pub is_running: bool,
+    pub last_started_at: Option<DateTime<Utc>>,
+    pub last_finished_at: Option<DateTime<Utc>>,
+    /// Error of the latest finished update
+    pub last_error: Option<String>,
+}
+
+/// Result of [`UpdateService::trigger_update`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerOutcome {
+    /// New update of the group is scheduled
+    Scheduled,
+    /// Update of the group was already triggered and has not started yet,
+    /// so the trigger is merged into it
+    Merged,
+}
+
+/// Triggered updates that have not started yet (group name -> `force_full`)
+#[derive(Debug, Default)]
+struct RequestedUpdates(Mutex<HashMap<String, bool>>);
+
+impl RequestedUpdates {
+    /// Returns `false` if an update of the group is already requested.
+    /// In this case the requests are merged, i.e. full update is performed
+    /// if any of them requires it.
+    fn request(&self, group_name: &str, force_full: bool) -> bool {
+        let mut requested = self.0.lock().expect("requested updates mutex is poisoned");
+        match requested.get_mut(group_name) {
+            Some(requested_force_full) => {
+                *requested_force_full |= force_full;
+                false
+            }
+            None => {
+                requested.insert(group_name.to_owned(), force_full);
+                true
+            }
+        }
+    }
+
+    /// Take the request to start the update
+    fn take(&self, group_name: &str) -> Option<bool> {
+        self.0
+            .lock()
+            .expect("requested updates mutex is poisoned")
+            .remove(group_name)
+    }
 }
 
 fn time_till_next_call(schedule: &Schedule) -> std::time::Duration {
@@ -21,18 +108,134 @@ fn time_till_next_call(schedule: &Schedule) -> std::time::Duration {
         .map_or(default, |t| (t - now).to_std().unwrap_or(default))
 }
 
+/// Date of the start of the timespan preceding the one containing `now`
+fn previous_timespan_start(resolution: ResolutionKind, now: DateTime<Utc>) -> NaiveDate {
+    fn start<T: Timespan>(now: DateTime<Utc>) -> NaiveDate {
+        T::from_timestamp(now)
+            .saturating_previous_timespan()
+            .into_date()
+    }
+    match resolution {
+        ResolutionKind::Hour => start::<Hour>(now),
+        ResolutionKind::Day => start::<NaiveDate>(now),
+        ResolutionKind::Week => start::<Week>(now),
+        ResolutionKind::Month => start::<Month>(now),
+        ResolutionKind::Year => start::<Year>(now),
+    }
+}
+
 impl UpdateService {
     pub async fn new(
+        chain: String,
+        update_permits: Arc<Semaphore>,
         db: Arc<DatabaseConnection>,
         blockscout: Arc<DatabaseConnection>,
+        user_ops: Option<Arc<DatabaseConnection>>,
         charts: Arc<RuntimeSetup>,
+        updates: Arc<UpdatesBroadcast>,
+        read_cache: Arc<ReadCache>,
     ) -> Result<Self, DbErr> {
+        let group_locks = charts
+            .update_groups
+            .keys()
+            .map(|name| (name.clone(), tokio::sync::Mutex::new(())))
+            .collect();
         Ok(Self {
+            chain,
+            update_permits,
             db,
             blockscout,
+            user_ops,
             charts,
+            statuses: Mutex::new(HashMap::new()),
+            group_locks,
+            requested_updates: RequestedUpdates::default(),
+            updates,
+            read_cache,
         })
     }
+
+    pub fn update_groups(&self) -> &BTreeMap<String, UpdateGroupEntry> {
+        &self.charts.update_groups
+    }
+
+    /// Statuses of all update groups (by name)
+    pub fn group_statuses(&self) -> BTreeMap<String, GroupUpdateStatus> {
+        let statuses = self.statuses.lock().expect("statuses mutex is poisoned");
+        self.charts
+            .update_groups
+            .keys()
+            .map(|name| {
+                (
+                    name.clone(),
+                    statuses.get(name).cloned().unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    /// Start update of the group in background.
+    ///
+    /// Repeated triggers are coalesced: while the triggered update waits for
+    /// the running update of the group (or for a permit), new triggers are
+    /// merged into it. I.e. at most one triggered update of the group is
+    /// pending at a time.
+    ///
+    /// Returns `None` if the group is not found.
+    pub fn trigger_update(
+        self: &Arc<Self>,
+        group_name: &str,
+        force_full: bool,
+    ) -> Option<TriggerOutcome> {
+        let group_entry = self.charts.update_groups.get(group_name)?.clone();
+        if !self.requested_updates.request(group_name, force_full) {
+            return Some(TriggerOutcome::Merged);
+        }
+        let this = self.clone();
+        tokio::spawn(async move { this.run_requested_update(group_entry).await });
+        Some(TriggerOutcome::Scheduled)
+    }
+
+    async fn run_requested_update(self: Arc<Self>, group_entry: UpdateGroupEntry) {
+        let group_name = group_entry.group.name();
+        let _group_guard = self.lock_group(&group_name).await;
+        let _permit = self.acquire_update_permit().await;
+        // taken only now, so that the triggers made while waiting are merged
+        let Some(force_full) = self.requested_updates.take(&group_name) else {
+            return;
+        };
+        self.update_locked(&group_entry, force_full).await
+    }
+
+    /// Remove data of the chart starting from `from` and trigger update of its group
+    /// to recalculate the data.
+    ///
+    /// Returns name of the triggered group and number of removed points.
+    pub async fn reset_chart(
+        self: &Arc<Self>,
+        chart: &ChartKey,
+        from: DateTime<Utc>,
+    ) -> Result<(String, u64), UpdateError> {
+        let (group_name, group_entry) = self
+            .charts
+            .update_groups
+            .iter()
+            .find(|(_, entry)| entry.enabled_members.contains(chart))
+            .ok_or_else(|| UpdateError::ChartNotFound(chart.clone()))?;
+        let removed = group_entry
+            .group
+            .reset_chart_with_mutexes(&self.db, chart, from)
+            .await?;
+        self.read_cache
+            .invalidate(&self.chain, std::iter::once(chart));
+        self.trigger_update(group_name, false);
+        Ok((group_name.clone(), removed))
+    }
+
+    fn modify_status(&self, group_name: String, modify: impl FnOnce(&mut GroupUpdateStatus)) {
+        let mut statuses = self.statuses.lock().expect("statuses mutex is poisoned");
+        modify(statuses.entry(group_name).or_default());
+    }
     pub async fn force_async_update_and_run(
         self: Arc<Self>,
         concurrent_tasks: usize,
@@ -59,7 +262,7 @@ impl UpdateService {
             })
             .collect::<Vec<_>>();
         futures::future::join_all(tasks).await;
-        tracing::info!("initial update is done");
+        tracing::info!(chain =% self.chain, "initial update is done");
     }
 
     fn spawn_group_updater(
@@ -78,16 +281,45 @@ impl UpdateService {
     }
 
     async fn update(self: Arc<Self>, group_entry: UpdateGroupEntry, force_full: bool) {
+        let _group_guard = self.lock_group(&group_entry.group.name()).await;
+        let _permit = self.acquire_update_permit().await;
+        self.update_locked(&group_entry, force_full).await
+    }
+
+    async fn lock_group(&self, group_name: &str) -> tokio::sync::MutexGuard<'_, ()> {
+        self.group_locks
+            .get(group_name)
+            .expect("locks are created for all update groups")
+            .lock()
+            .await
+    }
+
+    async fn acquire_update_permit(&self) -> tokio::sync::SemaphorePermit<'_> {
+        self.update_permits
+            .acquire()
+            .await
+            .expect("update permits semaphore is closed")
+    }
+
+    /// Expects the group lock and update permit to be held
+    async fn update_locked(&self, group_entry: &UpdateGroupEntry, force_full: bool) {
         tracing::info!(
             // instrumentation is inside `update_charts_with_mutexes`
+            chain =% self.chain,
             update_group = group_entry.group.name(),
             force_update = force_full,
             "updating group of charts"
         );
+        self.modify_status(group_entry.group.name(), |status| {
+            status.is_running = true;
+            status.last_started_at = Some(Utc::now());
+        });
         let result = {
             let update_parameters = UpdateParameters {
                 db: &self.db,
                 blockscout: &self.blockscout,
+                user_ops: self.user_ops.as_deref(),
+                chain: &self.chain,
                 update_time_override: None,
                 force_full,
             };
@@ -96,18 +328,138 @@ impl UpdateService {
                 .update_charts_with_mutexes(update_parameters, &group_entry.enabled_members)
                 .await
         };
+        self.modify_status(group_entry.group.name(), |status| {
+            status.is_running = false;
+            status.last_finished_at = Some(Utc::now());
+            status.last_error = result.as_ref().err().map(|err| err.to_string());
+        });
+        self.read_cache
+            .invalidate(&self.chain, &group_entry.enabled_members);
         if let Err(err) = result {
             tracing::error!(
+                chain =% self.chain,
                 update_group = group_entry.group.name(),
                 "error during updating group: {}",
                 err
             );
         } else {
             tracing::info!(
+                chain =% self.chain,
                 update_group = group_entry.group.name(),
                 "successfully updated group"
             );
+            if let Err(err) = self.notify_updated(group_entry).await {
+                tracing::error!(
+                    chain =% self.chain,
+                    update_group = group_entry.group.name(),
+                    "failed to read updated values: {}",
+                    err
+                );
+            }
+        }
+    }
+
+    /// Send the latest values of the group's counters and line charts
+    /// to [`UpdatesBroadcast`] subscribers
+    async fn notify_updated(&self, group_entry: &UpdateGroupEntry) -> Result<(), ReadError> {
+        if !self.updates.has_subscribers() {
+            return Ok(());
         }
+        let now = Utc::now();
+        let today = now.date_naive();
+        let mut counters = None;
+        for key in &group_entry.enabled_members {
+            let Some((id, info)) = self.charts.find_enabled_by_stored_key(key) else {
+                continue;
+            };
+            let mut event = ChartUpdateEvent {
+                chain: self.chain.clone(),
+                id: id.to_owned(),
+                resolution: String::from(*key.resolution()),
+                points: vec![],
+                value: None,
+            };
+            match info.chart_type {
+                ChartType::Counter => {
+                    if counters.is_none() {
+                        counters = Some(stats::get_raw_counters(&self.db).await?);
+                    }
+                    let Some(point) = counters.as_ref().and_then(|c| c.get(key.name())) else {
+                        continue;
+                    };
+                    let point = if info.missing_date_policy == MissingDatePolicy::FillZero {
+                        point.clone().relevant_or_zero(today)
+                    } else {
+                        point.clone()
+                    };
+                    event.value = Some(point.value);
+                }
+                ChartType::Line => {
+                    let from = previous_timespan_start(*key.resolution(), now);
+                    let mut points = get_serialized_line_chart_data_resolution_dispatch(
+                        &self.db,
+                        key.name().to_owned(),
+                        *key.resolution(),
+                        Some(from),
+                        None,
+                        None,
+                        info.missing_date_policy,
+                        info.approximate_trailing_points,
+                    )
+                    .await?;
+                    // `from` is a date, so hourly data is read since the start of the day
+                    event.points = points.split_off(points.len().saturating_sub(2));
+                }
+                ChartType::TopList => continue,
+            }
+            self.updates.send(event);
+        }
+        Ok(())
+    }
+
+    /// Periodically export staleness of enabled charts to prometheus
+    pub async fn run_staleness_metrics_updater(self: Arc<Self>, interval: std::time::Duration) {
+        loop {
+            if let Err(err) = self.update_staleness_metrics().await {
+                tracing::warn!("failed to update charts staleness metrics: {}", err);
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    async fn update_staleness_metrics(&self) -> Result<(), ReadError> {
+        let all_charts_info = self
+            .charts
+            .charts_info
+            .iter()
+            .chain(self.charts.token_charts_info.iter());
+        let enabled_keys = all_charts_info
+            .clone()
+            .flat_map(|(_, entry)| entry.enabled_keys())
+            .collect_vec();
+        let statuses = stats::get_charts_update_status(&self.db, &enabled_keys).await?;
+        let now = Utc::now();
+        for (name, entry) in all_charts_info {
+            for (resolution, info) in &entry.enabled_resolutions {
+                let Some(status) = statuses.get(&ChartKey::new(info.name.clone(), *resolution))
+                else {
+                    continue;
+                };
+                let labels = [
+                    self.chain.as_str(),
+                    name.as_str(),
+                    &String::from(*resolution),
+                ];
+                if let Some(last_updated_at) = status.last_updated_at {
+                    let staleness = (now - last_updated_at).num_milliseconds() as f64 / 1000.0;
+                    CHART_STALENESS.with_label_values(&labels).set(staleness);
+                }
+                CHART_LAST_UPDATE_FAILED
+                    .with_label_values(&labels)
+                    .set(status.last_error.is_some().into());
+            }
+        }
+        Ok(())
     }
 
     async fn run_cron(self: Arc<Self>, group_entry: UpdateGroupEntry, schedule: Schedule) {
@@ -123,3 +475,42 @@ impl UpdateService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use stats::tests::point_construction::{d, dt};
+
+    #[test]
+    fn previous_timespan_start_works() {
+        // wednesday
+        let now = dt("2024-07-17T00:30:00").and_utc();
+        for (resolution, expected) in [
+            (ResolutionKind::Hour, "2024-07-16"),
+            (ResolutionKind::Day, "2024-07-16"),
+            (ResolutionKind::Week, "2024-07-08"),
+            (ResolutionKind::Month, "2024-06-01"),
+            (ResolutionKind::Year, "2023-01-01"),
+        ] {
+            assert_eq!(previous_timespan_start(resolution, now), d(expected));
+        }
+    }
+
+    #[test]
+    fn requested_updates_are_merged() {
+        let requested = RequestedUpdates::default();
+        assert!(requested.request("group", false));
+        assert!(!requested.request("group", true));
+        assert!(!requested.request("group", false));
+        assert!(requested.request("another_group", false));
+
+        assert_eq!(requested.take("group"), Some(true));
+        assert_eq!(requested.take("group"), None);
+        assert_eq!(requested.take("another_group"), Some(false));
+
+        // can be requested again after the update is started
+        assert!(requested.request("group", false));
+        assert_eq!(requested.take("group"), Some(false));
+    }
+}
@@ -0,0 +1,163 @@
+//! Stream of chart updates for clients (server-sent events).
+//!
+//! When an update group finishes updating, an event with the latest
+//! values is emitted for each of its enabled counters and line charts
+//! (see [`crate::UpdateService`]). Clients subscribe with
+//! `GET /api/v1/updates?chain=<name>` (default chain if `chain` is empty)
+//! and receive `chart_updated` events with JSON-encoded [`ChartUpdateEvent`]s.
+
+use std::{convert::Infallible, sync::Arc, time::Duration};
+
+use actix_web::{web, HttpResponse};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use stats_proto::blockscout::stats::v1::Point;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+pub const UPDATES_PATH: &str = "/api/v1/updates";
+
+/// Events that were not received by a slow client are dropped
+/// after this number of newer events
+const CHANNEL_CAPACITY: usize = 1024;
+/// Comments are sent periodically so that idle connections
+/// are not closed by proxies
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChartUpdateEvent {
+    pub chain: String,
+    pub id: String,
+    pub resolution: String,
+    /// Latest points of line chart (the current timespan and the previous one)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub points: Vec<Point>,
+    /// Value of counter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+/// Delivers events to all connected clients
+pub struct UpdatesBroadcast {
+    sender: broadcast::Sender<Arc<ChartUpdateEvent>>,
+}
+
+impl Default for UpdatesBroadcast {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl UpdatesBroadcast {
+    /// `false` means that events can be skipped
+    /// (e.g. there is no need to read the values)
+    pub fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+
+    pub fn send(&self, event: ChartUpdateEvent) {
+        // error means there are no subscribers, which is fine
+        let _ = self.sender.send(Arc::new(event));
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<Arc<ChartUpdateEvent>> {
+        self.sender.subscribe()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamParams {
+    #[serde(default)]
+    chain: String,
+}
+
+pub fn route_updates(config: &mut web::ServiceConfig, broadcast: Arc<UpdatesBroadcast>) {
+    config.service(
+        web::resource(UPDATES_PATH)
+            .app_data(web::Data::from(broadcast))
+            .route(web::get().to(stream_updates)),
+    );
+}
+
+async fn stream_updates(
+    broadcast: web::Data<UpdatesBroadcast>,
+    params: web::Query<StreamParams>,
+) -> HttpResponse {
+    let receiver = broadcast.subscribe();
+    let chain = params.into_inner().chain;
+    let stream = futures::stream::unfold((receiver, chain), |(mut receiver, chain)| async move {
+        loop {
+            let message = match tokio::time::timeout(KEEP_ALIVE_INTERVAL, receiver.recv()).await {
+                Err(_elapsed) => Bytes::from_static(b": keep-alive\n\n"),
+                Ok(Ok(event)) if event.chain == chain => match format_event(&event) {
+                    Ok(message) => message,
+                    Err(err) => {
+                        tracing::error!(err =? err, "failed to serialize chart update event");
+                        continue;
+                    }
+                },
+                Ok(Ok(_)) => continue,
+                Ok(Err(RecvError::Lagged(skipped))) => {
+                    tracing::warn!(
+                        skipped = skipped,
+                        "client is too slow, skipped update events"
+                    );
+                    continue;
+                }
+                Ok(Err(RecvError::Closed)) => return None,
+            };
+            return Some((Ok::<_, Infallible>(message), (receiver, chain)));
+        }
+    });
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(stream)
+}
+
+fn format_event(event: &ChartUpdateEvent) -> Result<Bytes, serde_json::Error> {
+    let data = serde_json::to_string(event)?;
+    Ok(Bytes::from(format!(
+        "event: chart_updated\ndata: {data}\n\n"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn event_is_formatted() {
+        let counter = ChartUpdateEvent {
+            chain: "".into(),
+            id: "totalBlocks".into(),
+            resolution: "DAY".into(),
+            points: vec![],
+            value: Some("123".into()),
+        };
+        assert_eq!(
+            format_event(&counter).unwrap(),
+            Bytes::from(
+                "event: chart_updated\ndata: {\"chain\":\"\",\"id\":\"totalBlocks\",\"resolution\":\"DAY\",\"value\":\"123\"}\n\n"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn events_are_broadcasted() {
+        let broadcast = UpdatesBroadcast::default();
+        assert!(!broadcast.has_subscribers());
+        let mut receiver = broadcast.subscribe();
+        assert!(broadcast.has_subscribers());
+        let event = ChartUpdateEvent {
+            chain: "chain".into(),
+            id: "newTxns".into(),
+            resolution: "WEEK".into(),
+            points: vec![],
+            value: None,
+        };
+        broadcast.send(event.clone());
+        assert_eq!(*receiver.recv().await.unwrap(), event);
+    }
+}
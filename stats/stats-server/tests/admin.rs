@@ -0,0 +1,120 @@
+use blockscout_service_launcher::{
+    launcher::ConfigSettings,
+    test_server::{get_test_server_settings, init_server, send_get_request},
+};
+use chrono::NaiveDate;
+use pretty_assertions::assert_eq;
+use reqwest::{RequestBuilder, StatusCode};
+use serde_json::json;
+use stats::tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data};
+use stats_proto::blockscout::stats::v1::{LineChart, ResetChartResponse, UpdateGroups};
+use stats_server::{stats, Settings};
+use std::{path::PathBuf, str::FromStr};
+
+const API_KEY: &str = "test-admin-key";
+
+fn with_key(request: RequestBuilder) -> RequestBuilder {
+    request.header("x-api-key", API_KEY)
+}
+
+#[tokio::test]
+#[ignore = "needs database"]
+async fn test_admin_api_ok() {
+    let (stats_db, blockscout_db) = init_db_all("test_admin_api_ok").await;
+    fill_mock_blockscout_data(&blockscout_db, NaiveDate::from_str("2023-03-01").unwrap()).await;
+
+    std::env::set_var("STATS__CONFIG", "./tests/config/test.toml");
+    std::env::set_var("STATS__AUTHORIZED_KEYS__TEST__KEY", API_KEY);
+    let mut settings = Settings::build().expect("Failed to build settings");
+    let (server_settings, base) = get_test_server_settings();
+    settings.server = server_settings;
+    settings.charts_config = PathBuf::from_str("../config/charts.json").unwrap();
+    settings.layout_config = PathBuf::from_str("../config/layout.json").unwrap();
+    settings.update_groups_config = PathBuf::from_str("../config/update_groups.json").unwrap();
+    settings.db_url = stats_db.db_url();
+    settings.blockscout_db_url = blockscout_db.db_url();
+
+    init_server(|| stats(settings), &base).await;
+
+    // Sleep until server will start and calculate all values
+    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+
+    let client = reqwest::Client::new();
+    let groups_url = base.join("/api/v1/admin/update-groups").unwrap();
+
+    // missing or wrong api key
+    let response = client.get(groups_url.clone()).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = client
+        .get(groups_url.clone())
+        .header("x-api-key", "wrong-key")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let groups: UpdateGroups = with_key(client.get(groups_url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(groups
+        .groups
+        .iter()
+        .any(|g| g.charts.contains(&"newTxns_DAY".to_string())));
+
+    // unknown group
+    let response = with_key(
+        client.post(
+            base.join("/api/v1/admin/update-groups/unknownGroup/trigger")
+                .unwrap(),
+        ),
+    )
+    .json(&json!({}))
+    .send()
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // reset of the chart range
+    let chart_path = "/api/v1/lines/newTxns?from=2022-12-01&to=2023-03-01";
+    let chart_before: LineChart = send_get_request(&base, chart_path).await;
+    let reset_url = base.join("/api/v1/admin/charts/newTxns/reset").unwrap();
+    let response = with_key(client.post(reset_url.clone()))
+        .json(&json!({ "from": "not-a-date" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = with_key(
+        client.post(
+            base.join("/api/v1/admin/charts/unknownChart/reset")
+                .unwrap(),
+        ),
+    )
+    .json(&json!({ "from": "2022-12-01" }))
+    .send()
+    .await
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let reset: ResetChartResponse = with_key(client.post(reset_url))
+        .json(&json!({ "from": "2022-12-01" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(!reset.update_group.is_empty());
+    // 2022-12-01, 2023-01-01, 2023-02-01 and 2023-03-01;
+    // earlier points are kept
+    assert_eq!(reset.removed_points, "4");
+
+    // the data is recalculated by the triggered update
+    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    let chart_after: LineChart = send_get_request(&base, chart_path).await;
+    assert_eq!(chart_before.chart, chart_after.chart);
+}
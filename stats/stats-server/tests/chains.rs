@@ -0,0 +1,76 @@
+use blockscout_service_launcher::{
+    launcher::ConfigSettings,
+    test_server::{get_test_server_settings, init_server, send_get_request},
+};
+use chrono::NaiveDate;
+use pretty_assertions::assert_eq;
+use stats::tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data};
+use stats_proto::blockscout::stats::v1::{Counters, LineChart};
+use stats_server::{stats, Settings};
+use std::{collections::HashSet, path::PathBuf, str::FromStr};
+
+fn counter_names(counters: &Counters) -> HashSet<&str> {
+    counters.counters.iter().map(|c| c.id.as_str()).collect()
+}
+
+#[tokio::test]
+#[ignore = "needs database"]
+async fn test_several_chains_ok() {
+    let (stats_db, blockscout_db) = init_db_all("test_several_chains_ok").await;
+    fill_mock_blockscout_data(&blockscout_db, NaiveDate::from_str("2023-03-01").unwrap()).await;
+
+    std::env::set_var("STATS__CONFIG", "./tests/config/test.toml");
+    // the second chain shares both DBs with the default one,
+    // but stores its data in a separate schema
+    std::env::set_var(
+        "STATS__CHAINS__SECOND__BLOCKSCOUT_DB_URL",
+        blockscout_db.db_url(),
+    );
+    std::env::set_var("STATS__CHAINS__SECOND__DB_SCHEMA", "second");
+    std::env::set_var(
+        "STATS__CHAINS__SECOND__CHARTS__COUNTERS__TOTAL_TOKENS__ENABLED",
+        "false",
+    );
+    let mut settings = Settings::build().expect("Failed to build settings");
+    let (server_settings, base) = get_test_server_settings();
+    settings.server = server_settings;
+    settings.charts_config = PathBuf::from_str("../config/charts.json").unwrap();
+    settings.layout_config = PathBuf::from_str("../config/layout.json").unwrap();
+    settings.update_groups_config = PathBuf::from_str("../config/update_groups.json").unwrap();
+    settings.db_url = stats_db.db_url();
+    settings.blockscout_db_url = blockscout_db.db_url();
+    settings.run_migrations = true;
+    settings.max_concurrent_updates = 2;
+
+    init_server(|| stats(settings), &base).await;
+
+    // Sleep until server will start and calculate all values
+    tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+
+    let default_counters: Counters = send_get_request(&base, "/api/v1/counters").await;
+    let second_counters: Counters = send_get_request(&base, "/api/v1/second/counters").await;
+    let default_names = counter_names(&default_counters);
+    let second_names = counter_names(&second_counters);
+    assert!(default_names.contains("totalTokens"));
+    assert!(!second_names.contains("totalTokens"));
+    assert_eq!(
+        default_names.difference(&second_names).collect::<Vec<_>>(),
+        vec![&"totalTokens"]
+    );
+
+    // same data in both chains
+    let path = "/lines/newTxns?from=2022-11-09&to=2022-11-11";
+    let default_chart: LineChart = send_get_request(&base, &format!("/api/v1{path}")).await;
+    let second_chart: LineChart = send_get_request(&base, &format!("/api/v1/second{path}")).await;
+    assert!(!default_chart.chart.is_empty());
+    assert_eq!(default_chart.chart, second_chart.chart);
+    // legacy paths can select the chain as well
+    let second_chart_query: LineChart =
+        send_get_request(&base, &format!("/api/v1{path}&chain=second")).await;
+    assert_eq!(second_chart.chart, second_chart_query.chart);
+
+    let response = reqwest::get(base.join("/api/v1/unknown/counters").unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
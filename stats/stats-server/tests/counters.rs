@@ -4,7 +4,7 @@ use blockscout_service_launcher::{
 };
 use chrono::NaiveDate;
 use stats::tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data};
-use stats_proto::blockscout::stats::v1::Counters;
+use stats_proto::blockscout::stats::v1::{Counters, LineChart};
 use stats_server::{stats, Settings};
 use std::{collections::HashSet, path::PathBuf, str::FromStr};
 
@@ -54,4 +54,8 @@ async fn test_counters_ok() {
     .collect();
 
     assert_eq!(counter_names, expected_counter_names);
+
+    // history of counters is available as line charts
+    let history: LineChart = send_get_request(&base, "/api/v1/lines/totalBlocks").await;
+    assert!(!history.chart.is_empty());
 }
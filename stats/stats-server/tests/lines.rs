@@ -2,9 +2,13 @@ use blockscout_service_launcher::{
     launcher::ConfigSettings,
     test_server::{get_test_server_settings, init_server, send_get_request},
 };
-use chrono::NaiveDate;
+use chrono::{DurationRound, NaiveDate, NaiveDateTime, TimeDelta, Utc};
 use stats::{
-    tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data},
+    lines::HOURLY_CHARTS_RETENTION_HOURS,
+    tests::{
+        init_db::init_db_all,
+        mock_blockscout::{fill_mock_blocks_at, fill_mock_blockscout_data},
+    },
     ResolutionKind,
 };
 use stats_server::{stats, Settings};
@@ -15,6 +19,22 @@ use std::{collections::HashMap, path::PathBuf, str::FromStr};
 async fn test_lines_ok() {
     let (stats_db, blockscout_db) = init_db_all("test_lines_ok").await;
     fill_mock_blockscout_data(&blockscout_db, NaiveDate::from_str("2023-03-01").unwrap()).await;
+    // hourly charts store only recent points, so some blocks are added
+    // within the retention period (and one right before it)
+    let now = Utc::now().naive_utc();
+    let retention = TimeDelta::hours(HOURLY_CHARTS_RETENTION_HOURS as i64);
+    let retention_start = (now - retention)
+        .duration_trunc(TimeDelta::hours(1))
+        .unwrap();
+    fill_mock_blocks_at(
+        &blockscout_db,
+        &[
+            now - retention - TimeDelta::hours(48),
+            now - TimeDelta::hours(5),
+            now - TimeDelta::hours(2),
+        ],
+    )
+    .await;
 
     std::env::set_var("STATS__CONFIG", "./tests/config/test.toml");
     let mut settings = Settings::build().expect("Failed to build settings");
@@ -63,6 +83,9 @@ async fn test_lines_ok() {
         "newAccounts",
         "averageGasLimit",
         "averageGasPrice",
+        "gasPricePercentiles",
+        "averageBaseFee",
+        "averagePriorityFee",
         "averageTxnFee",
         "gasUsedGrowth",
         // "nativeCoinHoldersGrowth",
@@ -71,6 +94,7 @@ async fn test_lines_ok() {
         "newBlocks",
         "newNativeCoinTransfers",
         "newTxns",
+        "newTxnsByStatus",
         "txnsFee",
         "txnsGrowth",
         "txnsSuccessRate",
@@ -100,10 +124,16 @@ async fn test_lines_ok() {
                 .as_array()
                 .expect("'chart' field has to be json array");
 
-            assert!(
-                !chart_data.is_empty(),
-                "chart '{line_name}' '{resolution}' is empty"
-            );
+            if resolution == &String::from(ResolutionKind::Hour) {
+                // only recent mock blocks (without transactions) are within
+                // retention period, so hourly charts may be empty
+                assert_hourly_points_retained(line_name, chart_data, retention_start);
+            } else {
+                assert!(
+                    !chart_data.is_empty(),
+                    "chart '{line_name}' '{resolution}' is empty"
+                );
+            }
 
             let info = chart
                 .get("info")
@@ -119,4 +149,79 @@ async fn test_lines_ok() {
         let _chart: serde_json::Value =
             send_get_request(&base, &format!("/api/v1/lines/{line_name}")).await;
     }
+
+    let chart: serde_json::Value =
+        send_get_request(&base, "/api/v1/lines/newBlocks?resolution=HOUR").await;
+    let chart_data = chart["chart"]
+        .as_array()
+        .expect("'chart' field has to be json array");
+    assert_hourly_points_retained("newBlocks", chart_data, retention_start);
+    let blocks_in_retention_period: u64 = chart_data
+        .iter()
+        .map(|point| point["value"].as_str().unwrap().parse::<u64>().unwrap())
+        .sum();
+    assert_eq!(blocks_in_retention_period, 2);
+
+    let response = reqwest::Client::new()
+        .post(base.join("/api/v1/export").unwrap())
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(
+            serde_json::json!({
+                "names": ["newTxns", "newBlocks"],
+                "resolution": "MONTH",
+                "format": "CSV",
+            })
+            .to_string(),
+        )
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .unwrap(),
+        "text/csv"
+    );
+    let exported = response.text().await.unwrap();
+    let mut rows = exported.lines();
+    assert_eq!(rows.next(), Some("date,date_to,newTxns,newBlocks"));
+    assert!(rows.next().is_some(), "exported data is empty");
+
+    // repeated requests can be conditional
+    let url = base.join("/api/v1/lines/newTxns").unwrap();
+    let response = reqwest::get(url.clone()).await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert!(response
+        .headers()
+        .contains_key(reqwest::header::LAST_MODIFIED));
+    let etag = response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .expect("line chart response must have etag")
+        .clone();
+    let response = reqwest::Client::new()
+        .get(url)
+        .header(reqwest::header::IF_NONE_MATCH, etag)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_MODIFIED);
+}
+
+fn assert_hourly_points_retained(
+    line_name: &str,
+    chart_data: &[serde_json::Value],
+    retention_start: NaiveDateTime,
+) {
+    for point in chart_data {
+        let date = point["date"].as_str().expect("'date' has to be a string");
+        let date = NaiveDateTime::from_str(date)
+            .unwrap_or_else(|_| panic!("hourly point of '{line_name}' has invalid date {date}"));
+        assert!(
+            date >= retention_start,
+            "hourly point of '{line_name}' at {date} is outside of retention period"
+        );
+    }
 }
@@ -0,0 +1,52 @@
+use blockscout_service_launcher::{
+    launcher::ConfigSettings,
+    test_server::{get_test_server_settings, init_server, send_get_request},
+};
+use chrono::NaiveDate;
+use stats::tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data};
+use stats_proto::blockscout::stats::v1::TopList;
+use stats_server::{stats, Settings};
+use std::{path::PathBuf, str::FromStr};
+
+#[tokio::test]
+#[ignore = "needs database"]
+async fn test_top_lists_ok() {
+    let (stats_db, blockscout_db) = init_db_all("test_top_lists_ok").await;
+    fill_mock_blockscout_data(&blockscout_db, NaiveDate::from_str("2023-03-01").unwrap()).await;
+
+    std::env::set_var("STATS__CONFIG", "./tests/config/test.toml");
+    let mut settings = Settings::build().expect("Failed to build settings");
+    let (server_settings, base) = get_test_server_settings();
+    settings.server = server_settings;
+    settings.charts_config = PathBuf::from_str("../config/charts.json").unwrap();
+    settings.layout_config = PathBuf::from_str("../config/layout.json").unwrap();
+    settings.update_groups_config = PathBuf::from_str("../config/update_groups.json").unwrap();
+    settings.db_url = stats_db.db_url();
+    settings.blockscout_db_url = blockscout_db.db_url();
+
+    init_server(|| stats(settings), &base).await;
+
+    // Sleep until server will start and calculate all values
+    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+
+    for name in [
+        "topContractsByTxns24h",
+        "topContractsByTxns7d",
+        "topContractsByTxns30d",
+        "topGasConsumers24h",
+        "topGasConsumers7d",
+        "topGasConsumers30d",
+        "topSenders24h",
+        "topSenders7d",
+        "topSenders30d",
+    ] {
+        // mock data is located in the past, so the lists themselves are empty
+        let list: TopList =
+            send_get_request(&base, &format!("/api/v1/top-lists/{name}?limit=10")).await;
+        let info = list.info.expect("must return top list info");
+        assert_eq!(info.id, name);
+        assert!(!info.title.is_empty());
+        assert!(!info.description.is_empty());
+        assert!(list.entries.len() <= 10);
+    }
+}
@@ -8,7 +8,7 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub chart_id: i32,
-    pub date: Date,
+    pub date: DateTime,
     pub value: String,
     pub created_at: DateTimeWithTimeZone,
     pub min_blockscout_block: Option<i64>,
@@ -13,12 +13,21 @@ pub struct Model {
     pub created_at: DateTimeWithTimeZone,
     pub last_updated_at: Option<DateTimeWithTimeZone>,
     pub resolution: ChartResolution,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub last_update_error: Option<String>,
+    pub last_update_error_at: Option<DateTimeWithTimeZone>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub timezone: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::chart_data::Entity")]
     ChartData,
+    #[sea_orm(has_many = "super::chart_data_series::Entity")]
+    ChartDataSeries,
+    #[sea_orm(has_many = "super::top_list_data::Entity")]
+    TopListData,
 }
 
 impl Related<super::chart_data::Entity> for Entity {
@@ -27,4 +36,16 @@ impl Related<super::chart_data::Entity> for Entity {
     }
 }
 
+impl Related<super::chart_data_series::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ChartDataSeries.def()
+    }
+}
+
+impl Related<super::top_list_data::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TopListData.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
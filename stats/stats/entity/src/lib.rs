@@ -3,5 +3,7 @@
 pub mod prelude;
 
 pub mod chart_data;
+pub mod chart_data_series;
 pub mod charts;
 pub mod sea_orm_active_enums;
+pub mod top_list_data;
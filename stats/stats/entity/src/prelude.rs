@@ -1,3 +1,6 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.15
 
-pub use super::{chart_data::Entity as ChartData, charts::Entity as Charts};
+pub use super::{
+    chart_data::Entity as ChartData, chart_data_series::Entity as ChartDataSeries,
+    charts::Entity as Charts, top_list_data::Entity as TopListData,
+};
@@ -7,6 +7,8 @@ use sea_orm::entity::prelude::*;
 pub enum ChartResolution {
     #[sea_orm(string_value = "DAY")]
     Day,
+    #[sea_orm(string_value = "HOUR")]
+    Hour,
     #[sea_orm(string_value = "MONTH")]
     Month,
     #[sea_orm(string_value = "WEEK")]
@@ -21,4 +23,6 @@ pub enum ChartType {
     Counter,
     #[sea_orm(string_value = "LINE")]
     Line,
+    #[sea_orm(string_value = "TOP_LIST")]
+    TopList,
 }
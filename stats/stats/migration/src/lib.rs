@@ -5,6 +5,13 @@ mod m20220101_000001_init;
 mod m20230814_105206_drop_zero_timestamp;
 mod m20240416_090545_add_updated_at_column;
 mod m20240719_133448_add_resolution_column;
+mod m20240926_101510_add_hour_resolution_value;
+mod m20240926_101520_add_hour_resolution;
+mod m20240930_083012_add_last_update_error;
+mod m20241007_101305_add_chart_data_series;
+mod m20241010_134500_add_top_list_chart_type;
+mod m20241010_134512_add_top_lists;
+mod m20241016_093000_add_chart_timezone;
 
 pub struct Migrator;
 
@@ -16,6 +23,13 @@ impl MigratorTrait for Migrator {
             Box::new(m20230814_105206_drop_zero_timestamp::Migration),
             Box::new(m20240416_090545_add_updated_at_column::Migration),
             Box::new(m20240719_133448_add_resolution_column::Migration),
+            Box::new(m20240926_101510_add_hour_resolution_value::Migration),
+            Box::new(m20240926_101520_add_hour_resolution::Migration),
+            Box::new(m20240930_083012_add_last_update_error::Migration),
+            Box::new(m20241007_101305_add_chart_data_series::Migration),
+            Box::new(m20241010_134500_add_top_list_chart_type::Migration),
+            Box::new(m20241010_134512_add_top_lists::Migration),
+            Box::new(m20241016_093000_add_chart_timezone::Migration),
         ]
     }
 }
@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // add `HOUR` to `chart_resolution` enumeration.
+        // `ALTER TYPE ... ADD VALUE` is kept as a separate single statement:
+        // it cannot be batched with other statements in a transaction on
+        // older postgres versions, and the new value cannot be used
+        // in the transaction that added it.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"ALTER TYPE "chart_resolution" ADD VALUE IF NOT EXISTS 'HOUR' BEFORE 'DAY'"#,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // postgres does not support removing values from enum,
+        // so the type is recreated
+        let sql = r#"
+            DELETE FROM chart_data
+                WHERE chart_id IN (SELECT id FROM charts WHERE resolution = 'HOUR');
+            DELETE FROM charts WHERE resolution = 'HOUR';
+
+            ALTER TABLE charts
+                ALTER COLUMN resolution DROP DEFAULT;
+            ALTER TYPE "chart_resolution" RENAME TO "chart_resolution_old";
+            CREATE TYPE "chart_resolution" AS ENUM (
+                'DAY',
+                'WEEK',
+                'MONTH',
+                'YEAR'
+            );
+            ALTER TABLE charts
+                ALTER COLUMN resolution TYPE chart_resolution USING resolution::text::chart_resolution,
+                ALTER COLUMN resolution SET DEFAULT 'DAY';
+            DROP TYPE "chart_resolution_old";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,35 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // store start of the timespan instead of date, because
+        // hours can't be represented by dates.
+        // note: changing the column type rewrites the whole `chart_data` table
+        // (and its indexes) under `ACCESS EXCLUSIVE` lock, so charts can't be
+        // read or updated until the migration is finished
+        let sql = r#"
+            ALTER TABLE chart_data
+                ALTER COLUMN date TYPE timestamp USING date::timestamp;
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // hourly points of the same day would collide in the
+        // unique (`chart_id`, `date`) index after the cast,
+        // so hourly charts are removed first
+        let sql = r#"
+            DELETE FROM chart_data
+                WHERE chart_id IN (SELECT id FROM charts WHERE resolution = 'HOUR');
+            DELETE FROM charts WHERE resolution = 'HOUR';
+
+            ALTER TABLE chart_data
+                ALTER COLUMN date TYPE date USING date::date;
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,35 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+CREATE TABLE "chart_data_series" (
+  "id" INT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+  "chart_id" int NOT NULL,
+  "series" varchar(256) NOT NULL,
+  "date" timestamp NOT NULL,
+  "value" varchar(64) NOT NULL,
+  "created_at" timestamptz NOT NULL DEFAULT (now()),
+  "min_blockscout_block" bigint
+);
+
+CREATE UNIQUE INDEX ON "chart_data_series" ("chart_id", "series", "date");
+
+COMMENT ON TABLE "chart_data_series" IS 'Table contains values of named series of multi-series charts';
+
+ALTER TABLE "chart_data_series" ADD FOREIGN KEY ("chart_id") REFERENCES "charts" ("id");
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+DROP TABLE "chart_data_series";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // add `TOP_LIST` to `chart_type` enumeration.
+        // kept as a separate single statement, same as `HOUR` resolution
+        // (see `m20240926_101510_add_hour_resolution_value`)
+        manager
+            .get_connection()
+            .execute_unprepared(r#"ALTER TYPE "chart_type" ADD VALUE IF NOT EXISTS 'TOP_LIST'"#)
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // postgres does not support removing values from enum,
+        // so the type is recreated
+        let sql = r#"
+DELETE FROM chart_data
+    WHERE chart_id IN (SELECT id FROM charts WHERE chart_type = 'TOP_LIST');
+DELETE FROM chart_data_series
+    WHERE chart_id IN (SELECT id FROM charts WHERE chart_type = 'TOP_LIST');
+DELETE FROM charts WHERE chart_type = 'TOP_LIST';
+
+ALTER TYPE "chart_type" RENAME TO "chart_type_old";
+CREATE TYPE "chart_type" AS ENUM (
+  'COUNTER',
+  'LINE'
+);
+ALTER TABLE charts
+    ALTER COLUMN chart_type TYPE chart_type USING chart_type::text::chart_type;
+DROP TYPE "chart_type_old";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
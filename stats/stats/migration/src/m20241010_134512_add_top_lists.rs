@@ -0,0 +1,35 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+CREATE TABLE "top_list_data" (
+  "id" INT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+  "chart_id" int NOT NULL,
+  "rank" int NOT NULL,
+  "key" varchar(256) NOT NULL,
+  "value" varchar(64) NOT NULL,
+  "created_at" timestamptz NOT NULL DEFAULT (now()),
+  "min_blockscout_block" bigint
+);
+
+CREATE UNIQUE INDEX ON "top_list_data" ("chart_id", "rank");
+
+COMMENT ON TABLE "top_list_data" IS 'Table contains ranked rows of top lists';
+
+ALTER TABLE "top_list_data" ADD FOREIGN KEY ("chart_id") REFERENCES "charts" ("id");
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+DROP TABLE "top_list_data";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,24 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // `NULL` means UTC, i.e. all existing charts stay unchanged
+        let sql = r#"
+            ALTER TABLE charts
+                ADD COLUMN timezone text;
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE charts
+                DROP COLUMN timezone;
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
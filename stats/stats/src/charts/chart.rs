@@ -18,6 +18,8 @@ pub enum UpdateError {
     BlockscoutDB(DbErr),
     #[error("stats database error: {0}")]
     StatsDB(DbErr),
+    #[error("user-ops-indexer database error: {0}")]
+    UserOpsDB(DbErr),
     #[error("chart {0} not found")]
     ChartNotFound(ChartKey),
     #[error("date interval limit ({limit}) is exceeded; choose smaller time interval.")]
@@ -54,6 +56,7 @@ pub enum MissingDatePolicy {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ResolutionKind {
+    Hour,
     Day,
     Week,
     Month,
@@ -63,6 +66,7 @@ pub enum ResolutionKind {
 impl From<ChartResolution> for ResolutionKind {
     fn from(value: ChartResolution) -> Self {
         match value {
+            ChartResolution::Hour => ResolutionKind::Hour,
             ChartResolution::Day => ResolutionKind::Day,
             ChartResolution::Week => ResolutionKind::Week,
             ChartResolution::Month => ResolutionKind::Month,
@@ -74,6 +78,7 @@ impl From<ChartResolution> for ResolutionKind {
 impl From<ResolutionKind> for ChartResolution {
     fn from(value: ResolutionKind) -> Self {
         match value {
+            ResolutionKind::Hour => ChartResolution::Hour,
             ResolutionKind::Day => ChartResolution::Day,
             ResolutionKind::Week => ChartResolution::Week,
             ResolutionKind::Month => ChartResolution::Month,
@@ -85,6 +90,7 @@ impl From<ResolutionKind> for ChartResolution {
 impl From<ResolutionKind> for String {
     fn from(value: ResolutionKind) -> Self {
         match value {
+            ResolutionKind::Hour => "HOUR",
             ResolutionKind::Day => "DAY",
             ResolutionKind::Week => "WEEK",
             ResolutionKind::Month => "MONTH",
@@ -185,13 +191,26 @@ pub trait ChartProperties: Sync + Named {
     /// one less point is considered approximate, because we've got full data
     /// for one timespan.
     fn approximate_trailing_points() -> u64 {
-        if Self::chart_type() == ChartType::Counter {
-            // there's only one value in counter
+        if matches!(Self::chart_type(), ChartType::Counter | ChartType::TopList) {
+            // there's only one value in counter (and one list in top list)
             0
         } else {
             1
         }
     }
+    /// Number of latest timespans to keep in local database.
+    /// Older points are removed after each update and are never calculated
+    /// during (full) updates.
+    ///
+    /// `None` means the whole history is stored.
+    ///
+    /// Intended for high resolutions (e.g. hours), because the
+    /// amount of such points grows quickly. Should be used only with
+    /// [`MissingDatePolicy::FillZero`], since values before the retention
+    /// period are not available.
+    fn max_stored_timespans() -> Option<u64> {
+        None
+    }
 }
 
 #[macro_export]
@@ -80,10 +80,12 @@ mod tests {
     use crate::{
         data_source::{DataSource, UpdateContext, UpdateParameters},
         get_raw_counters,
-        tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data},
+        tests::{
+            init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data,
+            point_construction::dt,
+        },
         Named,
     };
-    use chrono::NaiveDate;
     use entity::chart_data;
     use pretty_assertions::assert_eq;
     use sea_orm::{DatabaseConnection, EntityTrait, Set};
@@ -103,7 +105,7 @@ mod tests {
 
         chart_data::Entity::insert(chart_data::ActiveModel {
             chart_id: Set(1),
-            date: Set(NaiveDate::from_str("2022-11-10").unwrap()),
+            date: Set(dt("2022-11-10T00:00:00")),
             value: Set(1.to_string()),
             ..Default::default()
         })
@@ -116,6 +118,8 @@ mod tests {
         let parameters = UpdateParameters {
             db: &db,
             blockscout: &blockscout,
+            user_ops: None,
+            chain: "",
             update_time_override: Some(current_time),
             force_full: true,
         };
@@ -142,6 +146,8 @@ mod tests {
         let parameters = UpdateParameters {
             db: &db,
             blockscout: &blockscout,
+            user_ops: None,
+            chain: "",
             update_time_override: Some(current_time),
             force_full: true,
         };
@@ -165,7 +171,7 @@ mod tests {
 
         chart_data::Entity::insert(chart_data::ActiveModel {
             chart_id: Set(1),
-            date: Set(NaiveDate::from_str("2022-11-11").unwrap()),
+            date: Set(dt("2022-11-11T00:00:00")),
             value: Set(1.to_string()),
             ..Default::default()
         })
@@ -178,6 +184,8 @@ mod tests {
         let parameters = UpdateParameters {
             db: &db,
             blockscout: &blockscout,
+            user_ops: None,
+            chain: "",
             update_time_override: Some(current_time),
             force_full: true,
         };
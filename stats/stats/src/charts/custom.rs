@@ -0,0 +1,1724 @@
+//! Charts that are defined at runtime (e.g. in the config) with an SQL query
+//! for the blockscout DB instead of a dedicated type.
+//!
+//! Each custom chart forms its own update group ([`CustomChartGroup`]).
+//! Line charts are calculated with daily resolution by the query; other
+//! resolutions are derived from the stored daily data with one of
+//! [`LowerResolutionAggregation`]s.
+//!
+//! ## Query template
+//!
+//! The query must return columns `date` (`DATE`) and `value` (`TEXT`) for line charts and
+//! a single row with column `value` (`TEXT`) for counters. The query is checked against
+//! blockscout DB on startup ([`CustomChartDefinition::check_query`]) and is always executed
+//! in a read-only transaction.
+//!
+//! `{from}` and `{to}` placeholders are bound to the (`TIMESTAMPTZ`) range of the update
+//! (`{from}` inclusive, `{to}` exclusive). It is highly recommended to use them for filtering
+//! line chart data, since otherwise the whole history is recalculated on each update:
+//! ```sql
+//! SELECT date(b.timestamp) as date, COUNT(*)::TEXT as value
+//! FROM blocks b
+//! WHERE b.consensus = true AND b.timestamp >= {from} AND b.timestamp < {to}
+//! GROUP BY date
+//! ```
+//!
+//! ## Timezone
+//!
+//! By default days (and weeks, months, years) start at UTC midnight. Line charts can be
+//! calculated in another timezone instead ([`CustomChartDefinition::with_timezone`]).
+//! In this case `{from}` and `{to}` are local day borders and the query must group
+//! the data by local date. Timezone name is bound to `{timezone}` placeholder (`'UTC'`
+//! by default), so the query from above becomes
+//! ```sql
+//! SELECT date(b.timestamp AT TIME ZONE 'UTC' AT TIME ZONE {timezone}) as date, COUNT(*)::TEXT as value
+//! FROM blocks b
+//! WHERE b.consensus = true AND b.timestamp >= {from} AND b.timestamp < {to}
+//! GROUP BY date
+//! ```
+//!
+//! The chart calculated in another timezone is stored separately from the UTC one
+//! (see [`timezone_variant_name`]), so changing the timezone never overwrites existing
+//! data. Built-in charts can be calculated in local time as well (see
+//! [`local_time`](crate::local_time)).
+//!
+//! ## Derived charts
+//!
+//! Line charts can also be calculated from other line charts instead of the query
+//! (see [`derived`](crate::derived) and [`CustomChartDefinition::derived`]).
+
+use std::{collections::HashSet, fmt::Debug, ops::Range, str::FromStr};
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use entity::sea_orm_active_enums::ChartType;
+use itertools::Itertools;
+use rust_decimal::Decimal;
+use sea_orm::{
+    prelude::DateTimeUtc, AccessMode, DatabaseConnection, DbBackend, DbErr, FromQueryResult,
+    Statement, TransactionTrait, Value,
+};
+use sqlx::{postgres::PgTypeInfo, Column, Executor, Postgres, Type, TypeInfo};
+use thiserror::Error;
+
+use crate::{
+    charts::{
+        chart::ChartMetadata,
+        db_interaction::{
+            read::{
+                get_line_chart_data, get_min_block_blockscout, get_min_date_blockscout,
+                last_accurate_point_of_chart,
+            },
+            write::{create_chart_in_timezone, insert_data_many, set_last_updated_at},
+        },
+        derived::DerivedTransform,
+        local_time::LocalChartKind,
+    },
+    data_source::{
+        kinds::{
+            data_manipulation::resolutions::{
+                average::lower_res_average_from, last_value::last_value_lower_resolution,
+                sum::sum_lower_resolution,
+            },
+            local_db::{chart_metadata_for_update, observe_chart_update},
+        },
+        UpdateContext, UpdateParameters,
+    },
+    types::{
+        timespans::{Month, Week, Year},
+        ConsistsOf, Timespan, TimespanValue,
+    },
+    update_group::{record_members_update_error, UpdateGroup},
+    ChartKey, ChartPropertiesObject, MissingDatePolicy, ResolutionKind, UpdateError,
+};
+
+const FROM_PLACEHOLDER: &str = "{from}";
+const TO_PLACEHOLDER: &str = "{to}";
+const TIMEZONE_PLACEHOLDER: &str = "{timezone}";
+
+/// How to get values for resolutions lower than a day
+/// (week, month, year) from daily values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LowerResolutionAggregation {
+    /// Sum of values (e.g. for "new"/"delta" charts).
+    /// Values must be numbers.
+    Sum,
+    /// Average of values (each present day has the same weight).
+    /// Values must be numbers.
+    Average,
+    /// Last value within the timespan (e.g. for "growth" charts).
+    Last,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum InvalidCustomChart {
+    #[error("query must not be empty")]
+    EmptyQuery,
+    #[error("unknown placeholder in query: {0}; only {{from}}, {{to}}, {{timezone}} and chart parameters are supported")]
+    UnknownPlaceholder(String),
+    #[error("counters can only have daily resolution; got {0:?}")]
+    CounterResolutions(Vec<ResolutionKind>),
+    #[error("line charts must have daily resolution enabled")]
+    MissingDailyResolution,
+    #[error("resolution {0:?} is not supported for custom charts")]
+    UnsupportedResolution(ResolutionKind),
+    #[error("chart type {0:?} is not supported for custom charts")]
+    UnsupportedChartType(ChartType),
+    #[error("moving average window must be at least 1 day")]
+    EmptyMovingAverageWindow,
+    #[error("timezone can only be set for line charts")]
+    CounterTimezone,
+    #[error("query can't be prepared in blockscout DB: {0}")]
+    InvalidQuery(String),
+    #[error("query must return column `{0}`")]
+    MissingColumn(&'static str),
+    #[error("column `{column}` must have type {expected}; got {actual}")]
+    WrongColumnType {
+        column: &'static str,
+        expected: &'static str,
+        actual: String,
+    },
+}
+
+/// `(name, type name, type check)` of a column returned by the query
+type ExpectedColumn = (&'static str, &'static str, fn(&PgTypeInfo) -> bool);
+
+/// Where daily values of the chart come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CustomChartSource {
+    Query {
+        query: String,
+        /// Additional (text) values bound to `{<name>}` placeholders
+        parameters: Vec<(String, String)>,
+    },
+    Derived(DerivedTransform),
+    /// Statement of the built-in chart
+    Local(LocalChartKind),
+}
+
+/// Name that the chart calculated in `timezone` is stored under.
+///
+/// UTC (or `None`) charts keep their name, charts in other timezones
+/// are stored as `<name>_<IANA timezone name>`.
+pub fn timezone_variant_name(name: &str, timezone: Option<&Tz>) -> String {
+    match timezone {
+        Some(timezone) if timezone != &Tz::UTC => format!("{name}_{}", timezone.name()),
+        _ => name.to_owned(),
+    }
+}
+
+/// Validate resolutions of the line chart that is not defined with a query
+fn line_resolutions(
+    resolutions: impl IntoIterator<Item = ResolutionKind>,
+) -> Result<Vec<ResolutionKind>, InvalidCustomChart> {
+    let resolutions: Vec<_> = resolutions.into_iter().sorted().dedup().collect();
+    if !resolutions.contains(&ResolutionKind::Day) {
+        return Err(InvalidCustomChart::MissingDailyResolution);
+    }
+    if let Some(r) = resolutions.iter().find(|r| r == &&ResolutionKind::Hour) {
+        return Err(InvalidCustomChart::UnsupportedResolution(*r));
+    }
+    Ok(resolutions)
+}
+
+/// Definition of the chart
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomChartDefinition {
+    name: String,
+    chart_type: ChartType,
+    source: CustomChartSource,
+    missing_date_policy: MissingDatePolicy,
+    aggregation: LowerResolutionAggregation,
+    resolutions: Vec<ResolutionKind>,
+    /// `None` means UTC
+    timezone: Option<Tz>,
+}
+
+impl CustomChartDefinition {
+    /// Validates the definition.
+    ///
+    /// `resolutions` are ignored for counters (only daily resolution is possible).
+    pub fn new(
+        name: String,
+        chart_type: ChartType,
+        query: String,
+        missing_date_policy: MissingDatePolicy,
+        aggregation: LowerResolutionAggregation,
+        resolutions: impl IntoIterator<Item = ResolutionKind>,
+    ) -> Result<Self, InvalidCustomChart> {
+        Self::new_with_parameters(
+            name,
+            chart_type,
+            query,
+            missing_date_policy,
+            aggregation,
+            resolutions,
+            vec![],
+        )
+    }
+
+    /// Same as [`Self::new`], but `{<name>}` placeholders are additionally bound to the
+    /// provided `(name, value)` text values.
+    pub fn new_with_parameters(
+        name: String,
+        chart_type: ChartType,
+        query: String,
+        missing_date_policy: MissingDatePolicy,
+        aggregation: LowerResolutionAggregation,
+        resolutions: impl IntoIterator<Item = ResolutionKind>,
+        parameters: Vec<(String, String)>,
+    ) -> Result<Self, InvalidCustomChart> {
+        if query.trim().is_empty() {
+            return Err(InvalidCustomChart::EmptyQuery);
+        }
+        let parameter_names = parameters
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect_vec();
+        if let Some(unknown) = unknown_placeholders(&query, &parameter_names)
+            .into_iter()
+            .next()
+        {
+            return Err(InvalidCustomChart::UnknownPlaceholder(unknown));
+        }
+        let resolutions: Vec<_> = resolutions.into_iter().sorted().dedup().collect();
+        match chart_type {
+            ChartType::Counter => {
+                if resolutions.iter().any(|r| r != &ResolutionKind::Day) {
+                    return Err(InvalidCustomChart::CounterResolutions(resolutions));
+                }
+            }
+            ChartType::Line => {
+                if !resolutions.contains(&ResolutionKind::Day) {
+                    return Err(InvalidCustomChart::MissingDailyResolution);
+                }
+                if let Some(r) = resolutions.iter().find(|r| r == &&ResolutionKind::Hour) {
+                    return Err(InvalidCustomChart::UnsupportedResolution(*r));
+                }
+            }
+            ChartType::TopList => return Err(InvalidCustomChart::UnsupportedChartType(chart_type)),
+        }
+        let resolutions = match chart_type {
+            ChartType::Counter | ChartType::TopList => vec![ResolutionKind::Day],
+            ChartType::Line => resolutions,
+        };
+        Ok(Self {
+            name,
+            chart_type,
+            source: CustomChartSource::Query { query, parameters },
+            missing_date_policy,
+            aggregation,
+            resolutions,
+            timezone: None,
+        })
+    }
+
+    /// Line chart calculated from other charts (see [`derived`](crate::derived)).
+    ///
+    /// `aggregation` defaults to [`DerivedTransform::default_aggregation`].
+    pub fn derived(
+        name: String,
+        transform: DerivedTransform,
+        aggregation: Option<LowerResolutionAggregation>,
+        resolutions: impl IntoIterator<Item = ResolutionKind>,
+    ) -> Result<Self, InvalidCustomChart> {
+        if let DerivedTransform::MovingAverage { days: 0, .. } = transform {
+            return Err(InvalidCustomChart::EmptyMovingAverageWindow);
+        }
+        Ok(Self {
+            name,
+            chart_type: ChartType::Line,
+            missing_date_policy: transform.missing_date_policy(),
+            aggregation: aggregation.unwrap_or_else(|| transform.default_aggregation()),
+            source: CustomChartSource::Derived(transform),
+            resolutions: line_resolutions(resolutions)?,
+            timezone: None,
+        })
+    }
+
+    /// Line chart calculated with the statement of the built-in chart
+    /// (see [`local_time`](crate::local_time)). Other resolutions are summed up
+    /// from the daily one, same as for the built-in chart.
+    pub fn local(
+        kind: LocalChartKind,
+        resolutions: impl IntoIterator<Item = ResolutionKind>,
+    ) -> Result<Self, InvalidCustomChart> {
+        Ok(Self {
+            name: kind.chart_name(),
+            chart_type: ChartType::Line,
+            source: CustomChartSource::Local(kind),
+            missing_date_policy: MissingDatePolicy::FillZero,
+            aggregation: LowerResolutionAggregation::Sum,
+            resolutions: line_resolutions(resolutions)?,
+            timezone: None,
+        })
+    }
+
+    /// Calculate the chart with timespans starting at local midnight in `timezone`
+    /// (instead of UTC one). The chart is then stored under another name
+    /// (see [`Self::stored_name`]).
+    ///
+    /// Derived charts do not recalculate anything from blockscout data, so
+    /// the timezone must be the same as in their sources.
+    pub fn with_timezone(mut self, timezone: Tz) -> Result<Self, InvalidCustomChart> {
+        if self.chart_type != ChartType::Line {
+            return Err(InvalidCustomChart::CounterTimezone);
+        }
+        self.timezone = (timezone != Tz::UTC).then_some(timezone);
+        Ok(self)
+    }
+
+    /// Name of the chart in the config (or of the built-in one)
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Name of the chart in stats DB (see [`timezone_variant_name`])
+    pub fn stored_name(&self) -> String {
+        timezone_variant_name(&self.name, self.timezone.as_ref())
+    }
+
+    pub fn resolutions(&self) -> &[ResolutionKind] {
+        &self.resolutions
+    }
+
+    pub fn timezone(&self) -> Option<&Tz> {
+        self.timezone.as_ref()
+    }
+
+    fn timezone_or_utc(&self) -> Tz {
+        self.timezone.unwrap_or(Tz::UTC)
+    }
+
+    fn key(&self, resolution: ResolutionKind) -> ChartKey {
+        ChartKey::new(self.stored_name(), resolution)
+    }
+
+    fn daily_key(&self) -> ChartKey {
+        self.key(ResolutionKind::Day)
+    }
+
+    /// Keys of the charts whose data is read during the update
+    /// (apart from the chart itself)
+    fn source_keys(&self) -> Vec<ChartKey> {
+        match &self.source {
+            CustomChartSource::Query { .. } | CustomChartSource::Local(_) => vec![],
+            CustomChartSource::Derived(transform) => transform
+                .sources()
+                .into_iter()
+                .map(|source| ChartKey::new(source.name.clone(), ResolutionKind::Day))
+                .collect(),
+        }
+    }
+
+    /// Query with placeholders replaced by the arguments.
+    ///
+    /// `None` for derived charts.
+    fn statement(&self, range: Range<DateTimeUtc>) -> Option<Statement> {
+        let (query, parameters) = match &self.source {
+            CustomChartSource::Query { query, parameters } => (query, parameters),
+            CustomChartSource::Local(kind) => {
+                return Some(kind.statement(range, &self.timezone_or_utc()))
+            }
+            CustomChartSource::Derived(_) => return None,
+        };
+        let mut sql = query.clone();
+        let mut values: Vec<Value> = vec![];
+        let range_values: [(String, Value); 3] = [
+            (FROM_PLACEHOLDER.to_owned(), range.start.into()),
+            (TO_PLACEHOLDER.to_owned(), range.end.into()),
+            (
+                TIMEZONE_PLACEHOLDER.to_owned(),
+                self.timezone_or_utc().name().into(),
+            ),
+        ];
+        let parameter_values = parameters
+            .iter()
+            .map(|(name, value)| (format!("{{{name}}}"), Value::from(value.clone())));
+        for (placeholder, value) in range_values.into_iter().chain(parameter_values) {
+            if sql.contains(&placeholder) {
+                values.push(value);
+                sql = sql.replace(&placeholder, &format!("${}", values.len()));
+            }
+        }
+        Some(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &sql,
+            values,
+        ))
+    }
+
+    /// Check the query against blockscout DB without executing it.
+    ///
+    /// The statement is prepared, so that syntax errors or unknown tables and columns
+    /// are found before the first update. Columns returned by the query are compared
+    /// with the expected ones (see [module docs](self)). Derived charts are not checked.
+    pub async fn check_query(
+        &self,
+        blockscout: &DatabaseConnection,
+    ) -> Result<(), InvalidCustomChart> {
+        let now = Utc::now();
+        let Some(statement) = self.statement(now..now) else {
+            return Ok(());
+        };
+        let description = blockscout
+            .get_postgres_connection_pool()
+            .describe(&statement.sql)
+            .await
+            .map_err(|e| InvalidCustomChart::InvalidQuery(e.to_string()))?;
+        for (name, expected, is_compatible) in self.expected_columns() {
+            let column = description
+                .columns()
+                .iter()
+                .find(|column| column.name() == name)
+                .ok_or(InvalidCustomChart::MissingColumn(name))?;
+            if !is_compatible(column.type_info()) {
+                return Err(InvalidCustomChart::WrongColumnType {
+                    column: name,
+                    expected,
+                    actual: column.type_info().name().to_owned(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Columns that the query must return
+    fn expected_columns(&self) -> Vec<ExpectedColumn> {
+        let date: ExpectedColumn = ("date", "DATE", <NaiveDate as Type<Postgres>>::compatible);
+        let value: ExpectedColumn = ("value", "TEXT", <String as Type<Postgres>>::compatible);
+        match self.chart_type {
+            ChartType::Line => vec![date, value],
+            ChartType::Counter | ChartType::TopList => vec![value],
+        }
+    }
+
+    fn properties(&self, resolution: ResolutionKind) -> ChartPropertiesObject {
+        ChartPropertiesObject {
+            key: self.key(resolution),
+            name: self.stored_name(),
+            chart_type: self.chart_type.clone(),
+            resolution,
+            missing_date_policy: self.missing_date_policy,
+            approximate_trailing_points: match self.chart_type {
+                ChartType::Counter | ChartType::TopList => 0,
+                ChartType::Line => 1,
+            },
+        }
+    }
+}
+
+/// Placeholder-like substrings (`{some_name}`) that are not
+/// supported (i.e. not range or one of `parameter_names`)
+fn unknown_placeholders(query: &str, parameter_names: &[&str]) -> Vec<String> {
+    let mut result = vec![];
+    let mut rest = query;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let candidate = &rest[..=end];
+        let inner = &candidate[1..candidate.len() - 1];
+        let is_placeholder_like =
+            !inner.is_empty() && inner.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        let is_known = candidate == FROM_PLACEHOLDER
+            || candidate == TO_PLACEHOLDER
+            || candidate == TIMEZONE_PLACEHOLDER
+            || parameter_names.contains(&inner);
+        if is_placeholder_like && !is_known {
+            result.push(candidate.to_owned());
+        }
+        rest = &rest[1..];
+    }
+    result
+}
+
+/// Queries come from the config, so they are executed in a read-only transaction
+/// to make sure that blockscout data can't be modified by them
+async fn query_read_only<T: FromQueryResult>(
+    blockscout: &DatabaseConnection,
+    statement: Statement,
+) -> Result<Vec<T>, DbErr> {
+    let txn = blockscout
+        .begin_with_config(None, Some(AccessMode::ReadOnly))
+        .await?;
+    let result = T::find_by_statement(statement).all(&txn).await?;
+    txn.commit().await?;
+    Ok(result)
+}
+
+#[derive(FromQueryResult)]
+struct CounterValue {
+    value: String,
+}
+
+/// Update group consisting of custom charts (with all of their resolutions).
+///
+/// Other resolutions depend on the daily one, so it is updated whenever
+/// any of the resolutions is enabled.
+#[derive(Debug, Clone)]
+pub struct CustomChartGroup {
+    name: String,
+    charts: Vec<CustomChartDefinition>,
+}
+
+impl CustomChartGroup {
+    /// Group with a single chart
+    pub fn new(chart: CustomChartDefinition) -> Self {
+        Self {
+            name: Self::group_name(&chart.name),
+            charts: vec![chart],
+        }
+    }
+
+    /// Group with several (independent) charts
+    pub fn with_charts(name: String, charts: Vec<CustomChartDefinition>) -> Self {
+        Self { name, charts }
+    }
+
+    /// Group name for the chart. Same as for (built-in) single-chart groups.
+    pub fn group_name(chart_name: &str) -> String {
+        let mut chars = chart_name.chars();
+        let pascal_case_name: String = chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default();
+        format!("{pascal_case_name}Group")
+    }
+}
+
+impl CustomChartDefinition {
+    fn is_any_enabled(&self, enabled_charts: &HashSet<ChartKey>) -> bool {
+        self.resolutions
+            .iter()
+            .any(|r| enabled_charts.contains(&self.key(*r)))
+    }
+
+    async fn update(&self, cx: &UpdateContext<'_>, key: &ChartKey) -> Result<(), UpdateError> {
+        observe_chart_update(cx, key, async {
+            match (&self.chart_type, key.resolution()) {
+                (ChartType::Counter, _) => self.update_counter(cx).await,
+                (ChartType::Line, ResolutionKind::Day) => self.update_daily(cx).await,
+                (ChartType::Line, ResolutionKind::Week) => {
+                    self.update_lower_resolution::<Week>(cx).await
+                }
+                (ChartType::Line, ResolutionKind::Month) => {
+                    self.update_lower_resolution::<Month>(cx).await
+                }
+                (ChartType::Line, ResolutionKind::Year) => {
+                    self.update_lower_resolution::<Year>(cx).await
+                }
+                (ChartType::Line, ResolutionKind::Hour) => Err(UpdateError::Internal(
+                    "hourly resolution is not supported for custom charts".into(),
+                )),
+                (ChartType::TopList, _) => Err(UpdateError::Internal(
+                    "top lists are not supported for custom charts".into(),
+                )),
+            }
+        })
+        .await
+    }
+
+    async fn update_counter(&self, cx: &UpdateContext<'_>) -> Result<(), UpdateError> {
+        let key = self.daily_key();
+        let Some(metadata) = chart_metadata_for_update(cx, &key).await? else {
+            return Ok(());
+        };
+        let chart_id = metadata.id;
+        let min_blockscout_block = get_min_block_blockscout(cx.blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        let range = self.full_range(cx).await?;
+        let statement = self
+            .statement(range)
+            .ok_or_else(|| UpdateError::Internal("derived counters are not supported".into()))?;
+        let value = query_read_only::<CounterValue>(cx.blockscout, statement)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| UpdateError::Internal("query returned nothing".into()))?;
+        let point = TimespanValue {
+            timespan: cx.time.date_naive(),
+            value: value.value,
+        };
+        insert_data_many(
+            cx.db,
+            [point.active_model(chart_id, Some(min_blockscout_block))],
+        )
+        .await
+        .map_err(UpdateError::StatsDB)?;
+        set_last_updated_at(chart_id, cx.db, cx.time)
+            .await
+            .map_err(UpdateError::StatsDB)
+    }
+
+    async fn update_daily(&self, cx: &UpdateContext<'_>) -> Result<(), UpdateError> {
+        let key = self.daily_key();
+        let Some(metadata) = chart_metadata_for_update(cx, &key).await? else {
+            return Ok(());
+        };
+        let chart_id = metadata.id;
+        let min_blockscout_block = self.min_blockscout_block(cx).await?;
+        let values = match &self.source {
+            CustomChartSource::Query { .. } | CustomChartSource::Local(_) => {
+                let range = match self
+                    .update_from::<NaiveDate>(cx, &metadata, min_blockscout_block)
+                    .await?
+                {
+                    Some(from) => {
+                        from.saturating_start_timestamp_in(&self.timezone_or_utc())..cx.time
+                    }
+                    None => self.full_range(cx).await?,
+                };
+                tracing::info!(range =? range, chart =% key, "updating chart values");
+                let statement = self
+                    .statement(range)
+                    .expect("query charts always have statement");
+                let mut data =
+                    query_read_only::<TimespanValue<NaiveDate, String>>(cx.blockscout, statement)
+                        .await
+                        .map_err(UpdateError::BlockscoutDB)?;
+                data.sort_unstable_by(|a, b| a.timespan.cmp(&b.timespan));
+                data.iter()
+                    .map(|p| p.active_model(chart_id, min_blockscout_block))
+                    .collect_vec()
+            }
+            CustomChartSource::Derived(transform) => {
+                let last_accurate_point = self.derived_last_accurate_point(cx, &metadata).await?;
+                tracing::info!(last_accurate_point =? last_accurate_point, chart =% key, "updating derived chart values");
+                let sources_from = last_accurate_point
+                    .as_ref()
+                    .map(|p| transform.sources_start_after(p.timespan));
+                let mut sources_data = vec![];
+                for source in transform.sources() {
+                    let data = get_line_chart_data::<NaiveDate>(
+                        cx.db,
+                        &source.name,
+                        sources_from,
+                        None,
+                        None,
+                        source.missing_date_policy,
+                        true,
+                        0,
+                    )
+                    .await?
+                    .into_iter()
+                    .map(TimespanValue::from)
+                    .collect();
+                    sources_data.push(data);
+                }
+                let data = match &last_accurate_point {
+                    Some(point) => transform.apply_after(sources_data, point)?,
+                    None => transform.apply(sources_data)?,
+                };
+                data.iter()
+                    .map(|p| p.active_model(chart_id, None))
+                    .collect_vec()
+            }
+        };
+        insert_data_many(cx.db, values)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        set_last_updated_at(chart_id, cx.db, cx.time)
+            .await
+            .map_err(UpdateError::StatsDB)
+    }
+
+    /// Recalculate `LowerRes` points from the stored daily data.
+    async fn update_lower_resolution<LowerRes>(
+        &self,
+        cx: &UpdateContext<'_>,
+    ) -> Result<(), UpdateError>
+    where
+        LowerRes: Timespan + ConsistsOf<NaiveDate> + Ord + Clone + Debug,
+    {
+        let key = self.key(LowerRes::enum_variant());
+        let Some(metadata) = chart_metadata_for_update(cx, &key).await? else {
+            return Ok(());
+        };
+        let chart_id = metadata.id;
+        let min_blockscout_block = self.min_blockscout_block(cx).await?;
+        let daily_from = self
+            .update_from::<LowerRes>(cx, &metadata, min_blockscout_block)
+            .await?
+            .map(|from| from.saturating_start_timestamp().date_naive());
+        let daily_data: Vec<TimespanValue<NaiveDate, String>> = get_line_chart_data::<NaiveDate>(
+            cx.db,
+            &self.stored_name(),
+            daily_from,
+            None,
+            None,
+            self.missing_date_policy,
+            false,
+            0,
+        )
+        .await?
+        .into_iter()
+        .map(TimespanValue::from)
+        .collect();
+        let data = aggregate_lower_resolution::<LowerRes>(daily_data, self.aggregation)?;
+        let values = data
+            .iter()
+            .map(|p| p.active_model(chart_id, min_blockscout_block))
+            .collect_vec();
+        insert_data_many(cx.db, values)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        set_last_updated_at(chart_id, cx.db, cx.time)
+            .await
+            .map_err(UpdateError::StatsDB)
+    }
+
+    /// Min block of blockscout DB, which is stored with the points to detect
+    /// reindexing (same as for built-in charts).
+    ///
+    /// `None` for derived charts, as they are not calculated from blockscout data.
+    async fn min_blockscout_block(
+        &self,
+        cx: &UpdateContext<'_>,
+    ) -> Result<Option<i64>, UpdateError> {
+        match &self.source {
+            CustomChartSource::Query { .. } | CustomChartSource::Local(_) => {
+                get_min_block_blockscout(cx.blockscout)
+                    .await
+                    .map(Some)
+                    .map_err(UpdateError::BlockscoutDB)
+            }
+            CustomChartSource::Derived(_) => Ok(None),
+        }
+    }
+
+    /// Range covering all blockscout data
+    async fn full_range(&self, cx: &UpdateContext<'_>) -> Result<Range<DateTimeUtc>, UpdateError> {
+        let min_date = get_min_date_blockscout(cx.blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        let timezone = self.timezone_or_utc();
+        let min_local_date = NaiveDate::from_timestamp_in(min_date.and_utc(), &timezone);
+        Ok(min_local_date.saturating_start_timestamp_in(&timezone)..cx.time)
+    }
+
+    /// (Local) timespan to start partial update from.
+    ///
+    /// Charts with `min_blockscout_block` are updated after their last accurate point
+    /// (see [`last_accurate_point_of_chart`]), so the whole chart is recalculated
+    /// after blockscout reindex. Otherwise the last updated timespan is recalculated.
+    ///
+    /// `None` means that full update is required.
+    async fn update_from<R>(
+        &self,
+        cx: &UpdateContext<'_>,
+        metadata: &ChartMetadata,
+        min_blockscout_block: Option<i64>,
+    ) -> Result<Option<R>, UpdateError>
+    where
+        R: Timespan + Ord + Clone + Debug,
+    {
+        let Some(min_blockscout_block) = min_blockscout_block else {
+            return Ok(if cx.force_full {
+                None
+            } else {
+                metadata
+                    .last_updated_at
+                    .map(|t| R::from_timestamp_in(t, &self.timezone_or_utc()))
+            });
+        };
+        let last_accurate_point = last_accurate_point_of_chart::<R>(
+            metadata.id,
+            min_blockscout_block,
+            cx.db,
+            cx.force_full,
+            self.properties(R::enum_variant())
+                .approximate_trailing_points,
+            self.missing_date_policy,
+        )
+        .await?;
+        Ok(last_accurate_point.map(|p| p.timespan.saturating_next_timespan()))
+    }
+
+    /// Stored value of the derived chart for the day before the last updated one
+    /// (the last updated day is recalculated, as the sources could have been updated since then).
+    ///
+    /// `None` means that full update is required, including the case when the value is
+    /// not present (e.g. the chart has no history yet).
+    async fn derived_last_accurate_point(
+        &self,
+        cx: &UpdateContext<'_>,
+        metadata: &ChartMetadata,
+    ) -> Result<Option<TimespanValue<NaiveDate, String>>, UpdateError> {
+        let Some(last_accurate_date) = self
+            .update_from::<NaiveDate>(cx, metadata, None)
+            .await?
+            .and_then(|last_updated_date| last_updated_date.pred_opt())
+        else {
+            return Ok(None);
+        };
+        let point = get_line_chart_data::<NaiveDate>(
+            cx.db,
+            &self.stored_name(),
+            Some(last_accurate_date),
+            Some(last_accurate_date),
+            None,
+            self.missing_date_policy,
+            false,
+            0,
+        )
+        .await?
+        .into_iter()
+        .find(|p| p.timespan == last_accurate_date)
+        .map(TimespanValue::from);
+        Ok(point)
+    }
+
+    /// Create the chart (calculated in the chart timezone) if it does not exist
+    async fn create_chart(
+        &self,
+        db: &DatabaseConnection,
+        key: ChartKey,
+        creation_time: &DateTime<Utc>,
+    ) -> Result<(), DbErr> {
+        create_chart_in_timezone(
+            db,
+            key,
+            self.chart_type.clone(),
+            creation_time,
+            self.timezone.as_ref(),
+        )
+        .await
+    }
+}
+
+pub(super) fn parse_values<V>(
+    data: Vec<TimespanValue<NaiveDate, String>>,
+) -> Result<Vec<TimespanValue<NaiveDate, V>>, UpdateError>
+where
+    V: FromStr,
+    V::Err: std::fmt::Display,
+{
+    data.into_iter()
+        .map(|p| {
+            let value = p.value.parse::<V>().map_err(|e| {
+                UpdateError::Internal(format!(
+                    "failed to parse value '{}' ({}): {e}",
+                    p.value, p.timespan
+                ))
+            })?;
+            Ok(TimespanValue {
+                timespan: p.timespan,
+                value,
+            })
+        })
+        .collect()
+}
+
+pub(super) fn values_to_string<T, V: ToString>(
+    data: Vec<TimespanValue<T, V>>,
+) -> Vec<TimespanValue<T, String>> {
+    data.into_iter()
+        .map(|p| TimespanValue {
+            timespan: p.timespan,
+            value: p.value.to_string(),
+        })
+        .collect()
+}
+
+fn aggregate_lower_resolution<LowerRes>(
+    daily_data: Vec<TimespanValue<NaiveDate, String>>,
+    aggregation: LowerResolutionAggregation,
+) -> Result<Vec<TimespanValue<LowerRes, String>>, UpdateError>
+where
+    LowerRes: Timespan + ConsistsOf<NaiveDate> + Eq + Debug,
+{
+    Ok(match aggregation {
+        LowerResolutionAggregation::Sum => {
+            let values = parse_values::<Decimal>(daily_data)?;
+            values_to_string(sum_lower_resolution::<LowerRes, _, _>(values))
+        }
+        LowerResolutionAggregation::Average => {
+            let averages = parse_values::<f64>(daily_data)?;
+            let weights = averages
+                .iter()
+                .map(|p| TimespanValue {
+                    timespan: p.timespan,
+                    value: 1,
+                })
+                .collect();
+            values_to_string(lower_res_average_from::<LowerRes, _>(averages, weights))
+        }
+        LowerResolutionAggregation::Last => last_value_lower_resolution(daily_data),
+    })
+}
+
+#[async_trait]
+impl UpdateGroup for CustomChartGroup {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn list_charts(&self) -> Vec<ChartPropertiesObject> {
+        self.charts
+            .iter()
+            .flat_map(|chart| chart.resolutions.iter().map(|r| chart.properties(*r)))
+            .collect()
+    }
+
+    fn list_dependency_mutex_ids(&self) -> HashSet<String> {
+        self.charts
+            .iter()
+            .flat_map(|chart| {
+                chart
+                    .resolutions
+                    .iter()
+                    .map(|r| chart.key(*r))
+                    .chain(chart.source_keys())
+                    .map(String::from)
+            })
+            .collect()
+    }
+
+    fn dependency_mutex_ids_of(&self, chart_id: &ChartKey) -> Option<HashSet<String>> {
+        let chart = self.charts.iter().find(|chart| {
+            chart_id.name() == chart.stored_name()
+                && chart.resolutions.contains(chart_id.resolution())
+        })?;
+        let mut ids = HashSet::from([chart.daily_key().into(), chart_id.clone().into()]);
+        ids.extend(chart.source_keys().into_iter().map(String::from));
+        Some(ids)
+    }
+
+    fn dependency_mutex_edges(&self) -> HashSet<(String, String)> {
+        let mut edges = HashSet::new();
+        for chart in &self.charts {
+            let daily_id: String = chart.daily_key().into();
+            for resolution in chart
+                .resolutions
+                .iter()
+                .filter(|r| **r != ResolutionKind::Day)
+            {
+                edges.insert((chart.key(*resolution).into(), daily_id.clone()));
+            }
+            for source in chart.source_keys() {
+                edges.insert((daily_id.clone(), source.into()));
+            }
+        }
+        edges
+    }
+
+    async fn create_charts(
+        &self,
+        db: &DatabaseConnection,
+        creation_time_override: Option<DateTime<Utc>>,
+        enabled_charts: &HashSet<ChartKey>,
+    ) -> Result<(), DbErr> {
+        let current_time = creation_time_override.unwrap_or_else(Utc::now);
+        for chart in &self.charts {
+            if !chart.is_any_enabled(enabled_charts) {
+                continue;
+            }
+            // daily one is a dependency of the others
+            chart
+                .create_chart(db, chart.daily_key(), &current_time)
+                .await?;
+            for resolution in &chart.resolutions {
+                let key = chart.key(*resolution);
+                if enabled_charts.contains(&key) {
+                    chart.create_chart(db, key, &current_time).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all, fields(update_group=self.name(), update_time), level = tracing::Level::INFO)]
+    async fn update_charts<'a>(
+        &self,
+        params: UpdateParameters<'a>,
+        enabled_charts: &HashSet<ChartKey>,
+    ) -> Result<(), UpdateError> {
+        let cx = UpdateContext::from_params_now_or_override(params);
+        tracing::Span::current().record("update_time", format!("{}", &cx.time));
+        let mut result = Ok(());
+        // enabled members that were not updated because of the error
+        let mut failed_members = vec![];
+        for chart in &self.charts {
+            if !chart.is_any_enabled(enabled_charts) {
+                continue;
+            }
+            // daily one is a dependency of the others
+            let keys = std::iter::once(chart.daily_key()).chain(
+                chart
+                    .resolutions
+                    .iter()
+                    .filter(|r| **r != ResolutionKind::Day)
+                    .map(|r| chart.key(*r))
+                    .filter(|key| enabled_charts.contains(key)),
+            );
+            for key in keys {
+                if result.is_ok() {
+                    result = chart.update(&cx, &key).await;
+                }
+                if result.is_err() && enabled_charts.contains(&key) {
+                    failed_members.push(key);
+                }
+            }
+        }
+        if let Err(err) = &result {
+            record_members_update_error(&cx, &failed_members, err).await;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        derived::DerivedSource,
+        tests::{
+            init_db::init_db_all,
+            mock_blockscout::fill_mock_blockscout_data,
+            point_construction::{d_v, dt, w_v},
+        },
+    };
+    use blockscout_db::entity::blocks;
+    use pretty_assertions::assert_eq;
+    use sea_orm::{ConnectionTrait, EntityTrait, PaginatorTrait};
+    use std::str::FromStr;
+
+    fn line_chart(query: &str) -> Result<CustomChartDefinition, InvalidCustomChart> {
+        CustomChartDefinition::new(
+            "customChart".into(),
+            ChartType::Line,
+            query.into(),
+            MissingDatePolicy::FillZero,
+            LowerResolutionAggregation::Sum,
+            [ResolutionKind::Day, ResolutionKind::Week],
+        )
+    }
+
+    #[test]
+    fn definition_validation_works() {
+        assert!(line_chart("SELECT date, value FROM t WHERE ts >= {from} AND ts < {to}").is_ok());
+        assert!(line_chart("SELECT '{}'::jsonb as value").is_ok());
+        assert_eq!(line_chart("  "), Err(InvalidCustomChart::EmptyQuery));
+        assert_eq!(
+            line_chart("SELECT date, value FROM t WHERE ts >= {start}"),
+            Err(InvalidCustomChart::UnknownPlaceholder("{start}".into()))
+        );
+        assert_eq!(
+            CustomChartDefinition::new(
+                "customChart".into(),
+                ChartType::Line,
+                "SELECT 1".into(),
+                MissingDatePolicy::FillZero,
+                LowerResolutionAggregation::Sum,
+                [ResolutionKind::Week],
+            ),
+            Err(InvalidCustomChart::MissingDailyResolution)
+        );
+        assert_eq!(
+            CustomChartDefinition::new(
+                "customCounter".into(),
+                ChartType::Counter,
+                "SELECT 1".into(),
+                MissingDatePolicy::FillZero,
+                LowerResolutionAggregation::Sum,
+                [ResolutionKind::Day, ResolutionKind::Month],
+            ),
+            Err(InvalidCustomChart::CounterResolutions(vec![
+                ResolutionKind::Day,
+                ResolutionKind::Month
+            ]))
+        );
+    }
+
+    #[test]
+    fn statement_binds_placeholders() {
+        let range = dt("2024-01-01T00:00:00").and_utc()..dt("2024-01-02T00:00:00").and_utc();
+        let statement =
+            line_chart("SELECT * FROM t WHERE ts < {to} AND ts >= {from} AND {to} > ts")
+                .unwrap()
+                .statement(range.clone())
+                .unwrap();
+        assert_eq!(
+            statement.sql,
+            "SELECT * FROM t WHERE ts < $2 AND ts >= $1 AND $2 > ts"
+        );
+        assert_eq!(
+            statement.values.unwrap().0,
+            vec![range.start.into(), range.end.into()]
+        );
+
+        let statement = line_chart("SELECT * FROM t WHERE ts < {to}")
+            .unwrap()
+            .statement(range.clone())
+            .unwrap();
+        assert_eq!(statement.sql, "SELECT * FROM t WHERE ts < $1");
+        assert_eq!(statement.values.unwrap().0, vec![range.end.into()]);
+
+        let statement = CustomChartDefinition::new_with_parameters(
+            "customChart".into(),
+            ChartType::Line,
+            "SELECT * FROM t WHERE addr = {address} AND ts < {to}".into(),
+            MissingDatePolicy::FillZero,
+            LowerResolutionAggregation::Sum,
+            [ResolutionKind::Day],
+            vec![("address".into(), "abcd".into())],
+        )
+        .unwrap()
+        .statement(range.clone())
+        .unwrap();
+        assert_eq!(statement.sql, "SELECT * FROM t WHERE addr = $2 AND ts < $1");
+        assert_eq!(
+            statement.values.unwrap().0,
+            vec![range.end.into(), "abcd".into()]
+        );
+    }
+
+    #[test]
+    fn timezone_is_bound() {
+        let range = dt("2024-01-01T00:00:00").and_utc()..dt("2024-01-02T00:00:00").and_utc();
+        let query = "SELECT date(ts AT TIME ZONE {timezone}) as date FROM t WHERE ts >= {from}";
+        let statement = line_chart(query).unwrap().statement(range.clone()).unwrap();
+        assert_eq!(
+            statement.sql,
+            "SELECT date(ts AT TIME ZONE $2) as date FROM t WHERE ts >= $1"
+        );
+        assert_eq!(
+            statement.values.unwrap().0,
+            vec![range.start.into(), "UTC".into()]
+        );
+
+        let statement = line_chart(query)
+            .unwrap()
+            .with_timezone(chrono_tz::Asia::Tokyo)
+            .unwrap()
+            .statement(range.clone())
+            .unwrap();
+        assert_eq!(
+            statement.values.unwrap().0,
+            vec![range.start.into(), "Asia/Tokyo".into()]
+        );
+
+        let counter = CustomChartDefinition::new(
+            "customCounter".into(),
+            ChartType::Counter,
+            "SELECT 1".into(),
+            MissingDatePolicy::FillZero,
+            LowerResolutionAggregation::Sum,
+            [ResolutionKind::Day],
+        )
+        .unwrap();
+        assert_eq!(
+            counter.with_timezone(chrono_tz::Asia::Tokyo),
+            Err(InvalidCustomChart::CounterTimezone)
+        );
+    }
+
+    #[test]
+    fn timezone_variant_is_stored_separately() {
+        let chart = line_chart("SELECT date, value FROM t").unwrap();
+        assert_eq!(chart.stored_name(), "customChart");
+        assert_eq!(
+            chart.key(ResolutionKind::Week),
+            ChartKey::new("customChart".into(), ResolutionKind::Week)
+        );
+        let tokyo_chart = chart.clone().with_timezone(chrono_tz::Asia::Tokyo).unwrap();
+        assert_eq!(tokyo_chart.name(), "customChart");
+        assert_eq!(tokyo_chart.stored_name(), "customChart_Asia/Tokyo");
+        assert_eq!(
+            tokyo_chart.key(ResolutionKind::Week),
+            ChartKey::new("customChart_Asia/Tokyo".into(), ResolutionKind::Week)
+        );
+        assert_eq!(
+            CustomChartGroup::new(tokyo_chart).name(),
+            "CustomChartGroup"
+        );
+        let utc_chart = chart.with_timezone(Tz::UTC).unwrap();
+        assert_eq!(utc_chart.timezone(), None);
+        assert_eq!(utc_chart.stored_name(), "customChart");
+    }
+
+    #[test]
+    fn aggregation_works() {
+        let daily = vec![
+            d_v("2024-07-08", "1"),
+            d_v("2024-07-10", "2.5"),
+            d_v("2024-07-15", "4"),
+        ];
+        let result =
+            |aggregation| aggregate_lower_resolution::<Week>(daily.clone(), aggregation).unwrap();
+        assert_eq!(
+            result(LowerResolutionAggregation::Sum),
+            vec![w_v("2024-07-08", "3.5"), w_v("2024-07-15", "4")]
+        );
+        assert_eq!(
+            result(LowerResolutionAggregation::Average),
+            vec![w_v("2024-07-08", "1.75"), w_v("2024-07-15", "4")]
+        );
+        assert_eq!(
+            result(LowerResolutionAggregation::Last),
+            vec![w_v("2024-07-08", "2.5"), w_v("2024-07-15", "4")]
+        );
+        assert!(aggregate_lower_resolution::<Week>(
+            vec![d_v("2024-07-08", "abc")],
+            LowerResolutionAggregation::Sum
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn derived_definition_validation_works() {
+        let source = || DerivedSource::new("newTxns".into(), MissingDatePolicy::FillZero);
+        let chart = CustomChartDefinition::derived(
+            "newTxnsMa".into(),
+            DerivedTransform::MovingAverage {
+                source: source(),
+                days: 7,
+            },
+            None,
+            [ResolutionKind::Day, ResolutionKind::Month],
+        )
+        .unwrap();
+        assert_eq!(chart.aggregation, LowerResolutionAggregation::Average);
+        assert!(chart
+            .statement(dt("2024-01-01T00:00:00").and_utc()..Utc::now())
+            .is_none());
+        assert_eq!(
+            CustomChartGroup::new(chart.clone()).list_dependency_mutex_ids(),
+            HashSet::from([
+                "newTxnsMa_DAY".to_owned(),
+                "newTxnsMa_MONTH".to_owned(),
+                "newTxns_DAY".to_owned()
+            ])
+        );
+        assert_eq!(
+            CustomChartGroup::new(chart.clone()).dependency_mutex_edges(),
+            HashSet::from([
+                ("newTxnsMa_MONTH".to_owned(), "newTxnsMa_DAY".to_owned()),
+                ("newTxnsMa_DAY".to_owned(), "newTxns_DAY".to_owned()),
+            ])
+        );
+        assert_eq!(
+            CustomChartDefinition::derived(
+                "newTxnsMa".into(),
+                DerivedTransform::MovingAverage {
+                    source: source(),
+                    days: 0,
+                },
+                None,
+                [ResolutionKind::Day],
+            ),
+            Err(InvalidCustomChart::EmptyMovingAverageWindow)
+        );
+        assert_eq!(
+            CustomChartDefinition::derived(
+                "totalTxns".into(),
+                DerivedTransform::Cumulative { source: source() },
+                None,
+                [ResolutionKind::Week],
+            ),
+            Err(InvalidCustomChart::MissingDailyResolution)
+        );
+    }
+
+    #[test]
+    fn group_name_works() {
+        assert_eq!(
+            CustomChartGroup::group_name("newCustomThings"),
+            "NewCustomThingsGroup"
+        );
+    }
+
+    async fn read_chart<T>(db: &DatabaseConnection, name: &str) -> Vec<(String, String)>
+    where
+        T: Timespan + Ord + Clone + Debug,
+    {
+        get_line_chart_data::<T>(
+            db,
+            &name.to_owned(),
+            None,
+            None,
+            None,
+            MissingDatePolicy::FillZero,
+            false,
+            0,
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|p| (p.timespan.into_date().to_string(), p.value))
+        .collect()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn check_query_works() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (_db, blockscout) = init_db_all("check_custom_chart_query").await;
+
+        let valid = line_chart(
+            "SELECT date(timestamp) as date, COUNT(*)::TEXT as value FROM blocks \
+            WHERE timestamp >= {from} AND timestamp < {to} GROUP BY date",
+        )
+        .unwrap();
+        assert_eq!(valid.check_query(&blockscout).await, Ok(()));
+
+        let syntax_error = line_chart("SELEC date, value FROM blocks").unwrap();
+        assert!(matches!(
+            syntax_error.check_query(&blockscout).await,
+            Err(InvalidCustomChart::InvalidQuery(_))
+        ));
+        let unknown_table = line_chart("SELECT date, value FROM unknown_table").unwrap();
+        assert!(matches!(
+            unknown_table.check_query(&blockscout).await,
+            Err(InvalidCustomChart::InvalidQuery(_))
+        ));
+        let missing_column =
+            line_chart("SELECT date(timestamp) as day, '1' as value FROM blocks").unwrap();
+        assert_eq!(
+            missing_column.check_query(&blockscout).await,
+            Err(InvalidCustomChart::MissingColumn("date"))
+        );
+        let wrong_type = line_chart(
+            "SELECT date(timestamp) as date, COUNT(*) as value FROM blocks GROUP BY date",
+        )
+        .unwrap();
+        assert_eq!(
+            wrong_type.check_query(&blockscout).await,
+            Err(InvalidCustomChart::WrongColumnType {
+                column: "value",
+                expected: "TEXT",
+                actual: "INT8".into()
+            })
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn custom_queries_cannot_modify_blockscout() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("custom_queries_cannot_modify_blockscout").await;
+        let current_time = DateTime::from_str("2023-03-01T12:00:00Z").unwrap();
+        fill_mock_blockscout_data(&blockscout, current_time.date_naive()).await;
+        let blocks_count = blocks::Entity::find().count(&blockscout).await.unwrap();
+        assert!(blocks_count > 0);
+
+        let chart = CustomChartDefinition::new(
+            "deletingCounter".into(),
+            ChartType::Counter,
+            "WITH deleted AS (DELETE FROM blocks RETURNING number) \
+            SELECT COUNT(*)::TEXT as value FROM deleted"
+                .into(),
+            MissingDatePolicy::FillPrevious,
+            LowerResolutionAggregation::Last,
+            [ResolutionKind::Day],
+        )
+        .unwrap();
+        let group = CustomChartGroup::new(chart.clone());
+        let enabled = HashSet::from([chart.key(ResolutionKind::Day)]);
+        group
+            .create_charts(&db, Some(current_time), &enabled)
+            .await
+            .unwrap();
+        let parameters = UpdateParameters {
+            db: &db,
+            blockscout: &blockscout,
+            user_ops: None,
+            chain: "",
+            update_time_override: Some(current_time),
+            force_full: true,
+        };
+        assert!(group.update_charts(parameters, &enabled).await.is_err());
+        assert_eq!(
+            blocks::Entity::find().count(&blockscout).await.unwrap(),
+            blocks_count
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_custom_line_chart() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("update_custom_line_chart").await;
+        let current_time = DateTime::from_str("2023-03-01T12:00:00Z").unwrap();
+        fill_mock_blockscout_data(&blockscout, current_time.date_naive()).await;
+
+        let chart = CustomChartDefinition::new(
+            "customNewBlocks".into(),
+            ChartType::Line,
+            r#"
+                SELECT date(timestamp) as date, COUNT(*)::TEXT as value
+                FROM blocks
+                WHERE consensus = true AND timestamp >= {from} AND timestamp < {to}
+                GROUP BY date
+            "#
+            .into(),
+            MissingDatePolicy::FillZero,
+            LowerResolutionAggregation::Sum,
+            [ResolutionKind::Day, ResolutionKind::Week],
+        )
+        .unwrap();
+        let group = CustomChartGroup::new(chart.clone());
+        let enabled = HashSet::from([
+            chart.key(ResolutionKind::Day),
+            chart.key(ResolutionKind::Week),
+        ]);
+        group
+            .create_charts(&db, Some(current_time), &enabled)
+            .await
+            .unwrap();
+
+        // partial update happens later, otherwise the charts are considered up to date
+        let later_time = current_time + chrono::Duration::hours(1);
+        for (force_full, update_time) in [(true, current_time), (false, later_time)] {
+            let parameters = UpdateParameters {
+                db: &db,
+                blockscout: &blockscout,
+                user_ops: None,
+                chain: "",
+                update_time_override: Some(update_time),
+                force_full,
+            };
+            group.update_charts(parameters, &enabled).await.unwrap();
+            assert_eq!(
+                read_chart::<NaiveDate>(&db, "customNewBlocks").await,
+                [
+                    ("2022-11-09", "1"),
+                    ("2022-11-10", "3"),
+                    ("2022-11-11", "4"),
+                    ("2022-11-12", "1"),
+                    ("2022-12-01", "1"),
+                    ("2023-01-01", "1"),
+                    ("2023-02-01", "1"),
+                    ("2023-03-01", "1"),
+                ]
+                .map(|(d, v)| (d.to_owned(), v.to_owned()))
+            );
+            assert_eq!(
+                read_chart::<Week>(&db, "customNewBlocks").await,
+                [
+                    ("2022-11-07", "9"),
+                    ("2022-11-28", "1"),
+                    ("2022-12-26", "1"),
+                    ("2023-01-30", "1"),
+                    ("2023-02-27", "1"),
+                ]
+                .map(|(d, v)| (d.to_owned(), v.to_owned()))
+            );
+        }
+
+        // min block changes after reindex, so the charts are fully recalculated
+        // even without `force_full`
+        blockscout
+            .execute_unprepared("UPDATE blocks SET consensus = false WHERE number IN (0, 1)")
+            .await
+            .unwrap();
+        let parameters = UpdateParameters {
+            db: &db,
+            blockscout: &blockscout,
+            user_ops: None,
+            chain: "",
+            update_time_override: Some(later_time + chrono::Duration::hours(1)),
+            force_full: false,
+        };
+        group.update_charts(parameters, &enabled).await.unwrap();
+        // points outside of the new blockscout range are kept
+        assert_eq!(
+            read_chart::<NaiveDate>(&db, "customNewBlocks").await[..2],
+            [("2022-11-09", "1"), ("2022-11-10", "2")].map(|(d, v)| (d.to_owned(), v.to_owned()))
+        );
+        assert_eq!(
+            read_chart::<Week>(&db, "customNewBlocks").await[0],
+            ("2022-11-07".to_owned(), "8".to_owned())
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_custom_line_chart_in_timezone() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("update_custom_line_chart_in_timezone").await;
+        let current_time = DateTime::from_str("2023-03-01T12:00:00Z").unwrap();
+        fill_mock_blockscout_data(&blockscout, current_time.date_naive()).await;
+
+        let utc_chart = CustomChartDefinition::new(
+            "customNewBlocks".into(),
+            ChartType::Line,
+            r#"
+                SELECT
+                    date(timestamp AT TIME ZONE 'UTC' AT TIME ZONE {timezone}) as date,
+                    COUNT(*)::TEXT as value
+                FROM blocks
+                WHERE consensus = true AND timestamp >= {from} AND timestamp < {to}
+                GROUP BY date
+            "#
+            .into(),
+            MissingDatePolicy::FillZero,
+            LowerResolutionAggregation::Sum,
+            [ResolutionKind::Day, ResolutionKind::Week],
+        )
+        .unwrap();
+        // UTC+9, blocks after 15:00 UTC belong to the next local day
+        let tokyo_chart = utc_chart
+            .clone()
+            .with_timezone(chrono_tz::Asia::Tokyo)
+            .unwrap();
+        let local_chart = crate::local_time::local_chart(
+            LocalChartKind::NewBlocks,
+            chrono_tz::Asia::Tokyo,
+            [ResolutionKind::Day],
+        )
+        .unwrap();
+        let enabled = HashSet::from([
+            utc_chart.key(ResolutionKind::Day),
+            utc_chart.key(ResolutionKind::Week),
+            tokyo_chart.key(ResolutionKind::Day),
+            tokyo_chart.key(ResolutionKind::Week),
+            local_chart.key(ResolutionKind::Day),
+        ]);
+        let update = |group: CustomChartGroup| {
+            let (db, blockscout, enabled) = (&db, &blockscout, &enabled);
+            async move {
+                group
+                    .create_charts(db, Some(current_time), enabled)
+                    .await
+                    .unwrap();
+                let later_time = current_time + chrono::Duration::hours(1);
+                for (force_full, update_time) in [(true, current_time), (false, later_time)] {
+                    let parameters = UpdateParameters {
+                        db,
+                        blockscout,
+                        user_ops: None,
+                        chain: "",
+                        update_time_override: Some(update_time),
+                        force_full,
+                    };
+                    group.update_charts(parameters, enabled).await.unwrap();
+                }
+            }
+        };
+        let utc_daily = [
+            ("2022-11-09", "1"),
+            ("2022-11-10", "3"),
+            ("2022-11-11", "4"),
+            ("2022-11-12", "1"),
+            ("2022-12-01", "1"),
+            ("2023-01-01", "1"),
+            ("2023-02-01", "1"),
+            ("2023-03-01", "1"),
+        ]
+        .map(|(d, v)| (d.to_owned(), v.to_owned()));
+        let tokyo_daily = [
+            ("2022-11-10", "3"),
+            ("2022-11-11", "3"),
+            ("2022-11-12", "3"),
+            ("2022-12-01", "1"),
+            ("2023-01-01", "1"),
+            ("2023-02-01", "1"),
+            ("2023-03-01", "1"),
+        ]
+        .map(|(d, v)| (d.to_owned(), v.to_owned()));
+
+        update(CustomChartGroup::new(utc_chart)).await;
+        assert_eq!(
+            read_chart::<NaiveDate>(&db, "customNewBlocks").await,
+            utc_daily
+        );
+
+        // stored separately, UTC data stays unchanged
+        update(CustomChartGroup::new(tokyo_chart)).await;
+        assert_eq!(
+            read_chart::<NaiveDate>(&db, "customNewBlocks_Asia/Tokyo").await,
+            tokyo_daily
+        );
+        assert_eq!(
+            read_chart::<Week>(&db, "customNewBlocks_Asia/Tokyo").await,
+            [
+                ("2022-11-07", "9"),
+                ("2022-11-28", "1"),
+                ("2022-12-26", "1"),
+                ("2023-01-30", "1"),
+                ("2023-02-27", "1"),
+            ]
+            .map(|(d, v)| (d.to_owned(), v.to_owned()))
+        );
+        assert_eq!(
+            read_chart::<NaiveDate>(&db, "customNewBlocks").await,
+            utc_daily
+        );
+
+        // built-in statement in the same timezone
+        update(crate::local_time::local_charts_group(vec![local_chart])).await;
+        assert_eq!(
+            read_chart::<NaiveDate>(&db, "newBlocks_Asia/Tokyo").await,
+            tokyo_daily
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_derived_line_chart() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("update_derived_line_chart").await;
+        let current_time = DateTime::from_str("2023-03-01T12:00:00Z").unwrap();
+        fill_mock_blockscout_data(&blockscout, current_time.date_naive()).await;
+
+        let source = CustomChartDefinition::new(
+            "customNewBlocks".into(),
+            ChartType::Line,
+            r#"
+                SELECT date(timestamp) as date, COUNT(*)::TEXT as value
+                FROM blocks
+                WHERE consensus = true AND timestamp >= {from} AND timestamp < {to}
+                GROUP BY date
+            "#
+            .into(),
+            MissingDatePolicy::FillZero,
+            LowerResolutionAggregation::Sum,
+            [ResolutionKind::Day],
+        )
+        .unwrap();
+        let derived = CustomChartDefinition::derived(
+            "customTotalBlocks".into(),
+            DerivedTransform::Cumulative {
+                source: DerivedSource::new("customNewBlocks".into(), MissingDatePolicy::FillZero),
+            },
+            None,
+            [ResolutionKind::Day, ResolutionKind::Month],
+        )
+        .unwrap();
+        let group = CustomChartGroup::with_charts(
+            "CustomBlocksGroup".into(),
+            vec![source.clone(), derived.clone()],
+        );
+        let enabled = HashSet::from([
+            source.key(ResolutionKind::Day),
+            derived.key(ResolutionKind::Day),
+            derived.key(ResolutionKind::Month),
+        ]);
+        group
+            .create_charts(&db, Some(current_time), &enabled)
+            .await
+            .unwrap();
+        let parameters = UpdateParameters {
+            db: &db,
+            blockscout: &blockscout,
+            user_ops: None,
+            chain: "",
+            update_time_override: Some(current_time),
+            force_full: false,
+        };
+        group.update_charts(parameters, &enabled).await.unwrap();
+
+        let daily = read_chart::<NaiveDate>(&db, "customTotalBlocks").await;
+        let daily_value = |date: &str| {
+            daily
+                .iter()
+                .find(|(d, _)| d == date)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(daily_value("2022-11-09"), Some("1"));
+        assert_eq!(daily_value("2022-11-12"), Some("9"));
+        // missing dates of the source are filled
+        assert_eq!(daily_value("2022-11-20"), Some("9"));
+        assert_eq!(daily_value("2023-03-01"), Some("13"));
+        assert_eq!(
+            read_chart::<Month>(&db, "customTotalBlocks").await,
+            [
+                ("2022-11-01", "9"),
+                ("2022-12-01", "10"),
+                ("2023-01-01", "11"),
+                ("2023-02-01", "12"),
+                ("2023-03-01", "13"),
+            ]
+            .map(|(d, v)| (d.to_owned(), v.to_owned()))
+        );
+
+        // partial update recalculates only the last updated day, continuing the
+        // sum from the stored values (older days are not recalculated, so the moved
+        // block is still counted on 2023-02-01)
+        blockscout
+            .execute_unprepared(
+                "UPDATE blocks SET timestamp = '2023-03-01T12:30:00' WHERE number = 11",
+            )
+            .await
+            .unwrap();
+        let parameters = UpdateParameters {
+            db: &db,
+            blockscout: &blockscout,
+            user_ops: None,
+            chain: "",
+            update_time_override: Some(current_time + chrono::Duration::hours(1)),
+            force_full: false,
+        };
+        group.update_charts(parameters, &enabled).await.unwrap();
+        assert_eq!(
+            read_chart::<Month>(&db, "customTotalBlocks").await[3..],
+            [("2023-02-01", "12"), ("2023-03-01", "14")].map(|(d, v)| (d.to_owned(), v.to_owned()))
+        );
+    }
+}
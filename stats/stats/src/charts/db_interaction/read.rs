@@ -3,22 +3,30 @@ use crate::{
     data_source::kinds::local_db::parameter_traits::QueryBehaviour,
     missing_date::{fill_and_filter_chart, fit_into_range},
     types::{
-        timespans::DateValue, ExtendedTimespanValue, Timespan, TimespanDuration, TimespanValue,
+        timespans::DateValue, ExtendedTimespanValue, SeriesValues, Timespan, TimespanDuration,
+        TimespanValue, TopListEntry,
     },
     utils::exclusive_datetime_range_to_inclusive,
-    ChartProperties, MissingDatePolicy, UpdateError,
+    ChartProperties, MissingDatePolicy, ResolutionKind, UpdateError,
 };
 
 use blockscout_db::entity::blocks;
 use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
-use entity::{chart_data, charts, sea_orm_active_enums::ChartResolution};
+use chrono_tz::Tz;
+use entity::{
+    chart_data, chart_data_series, charts, sea_orm_active_enums::ChartResolution, top_list_data,
+};
 use itertools::Itertools;
 use sea_orm::{
     sea_query::{self, Expr},
-    ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait,
+    ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait,
     FromQueryResult, QueryFilter, QueryOrder, QuerySelect, Statement,
 };
-use std::{collections::HashMap, fmt::Debug};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    str::FromStr,
+};
 use thiserror::Error;
 use tracing::instrument;
 
@@ -66,7 +74,7 @@ pub async fn get_raw_counters(
     let data = CounterData::find_by_statement(Statement::from_string(
         DbBackend::Postgres,
         r#"
-            SELECT distinct on (charts.id) charts.name, data.date, data.value
+            SELECT distinct on (charts.id) charts.name, data.date::date as date, data.value
             FROM "chart_data" "data"
             INNER JOIN "charts"
                 ON data.chart_id = charts.id
@@ -93,6 +101,48 @@ pub async fn get_raw_counters(
     Ok(counters)
 }
 
+/// Get counters with raw values as they were on `date`
+/// (i.e. the latest values recorded not later than `date`).
+///
+/// Counters keep a point for each day they were updated on, so
+/// this is the history that current values can be compared with.
+pub async fn get_raw_counters_at(
+    db: &DatabaseConnection,
+    date: NaiveDate,
+) -> Result<HashMap<String, DateValue<String>>, ReadError> {
+    let data = CounterData::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+            SELECT distinct on (charts.id) charts.name, data.date::date as date, data.value
+            FROM "chart_data" "data"
+            INNER JOIN "charts"
+                ON data.chart_id = charts.id
+            WHERE
+                charts.chart_type = 'COUNTER' AND
+                data.date <= $1
+            ORDER BY charts.id, data.date DESC;
+        "#,
+        vec![date.into()],
+    ))
+    .all(db)
+    .await?;
+
+    let counters: HashMap<_, _> = data
+        .into_iter()
+        .map(|data| {
+            (
+                data.name,
+                DateValue::<String> {
+                    timespan: data.date,
+                    value: data.value,
+                },
+            )
+        })
+        .collect();
+
+    Ok(counters)
+}
+
 /// Get counter value for the requested date
 pub async fn get_counter_data(
     db: &DatabaseConnection,
@@ -104,7 +154,7 @@ pub async fn get_counter_data(
     let raw_data = DateValue::<String>::find_by_statement(Statement::from_sql_and_values(
         DbBackend::Postgres,
         r#"
-            SELECT distinct on (charts.id) data.date, data.value
+            SELECT distinct on (charts.id) data.date::date as date, data.value
             FROM "chart_data" "data"
             INNER JOIN "charts"
                 ON data.chart_id = charts.id
@@ -180,22 +230,86 @@ pub async fn get_chart_metadata(
     })
 }
 
+/// Information about the latest updates of a chart
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChartUpdateStatus {
+    /// Time of the last successful update
+    pub last_updated_at: Option<DateTime<Utc>>,
+    /// Error of the latest update. Present only if it failed
+    /// (i.e. cleared on successful update).
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+}
+
+/// Update statuses of the charts with `keys`.
+///
+/// Charts that are not present in the database are omitted.
+pub async fn get_charts_update_status<'a>(
+    db: &DatabaseConnection,
+    keys: impl IntoIterator<Item = &'a ChartKey>,
+) -> Result<HashMap<ChartKey, ChartUpdateStatus>, ReadError> {
+    let keys = keys.into_iter().collect_vec();
+    if keys.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let condition = keys.into_iter().fold(Condition::any(), |condition, key| {
+        condition.add(
+            Condition::all()
+                .add(charts::Column::Name.eq(key.name()))
+                .add(charts::Column::Resolution.eq(ChartResolution::from(*key.resolution()))),
+        )
+    });
+    let charts = charts::Entity::find().filter(condition).all(db).await?;
+    let statuses = charts
+        .into_iter()
+        .map(|chart| {
+            let key = ChartKey::new(chart.name, chart.resolution.into());
+            let status = ChartUpdateStatus {
+                last_updated_at: chart.last_updated_at.map(|t| t.with_timezone(&Utc)),
+                last_error: chart.last_update_error,
+                last_error_at: chart.last_update_error_at.map(|t| t.with_timezone(&Utc)),
+            };
+            (key, status)
+        })
+        .collect();
+    Ok(statuses)
+}
+
+/// Timezone that the chart data is calculated in.
+///
+/// `NULL` (i.e. the default) is UTC.
+fn stored_timezone(chart: &charts::Model) -> Tz {
+    match chart.timezone.as_deref().map(Tz::from_str) {
+        Some(Ok(timezone)) => timezone,
+        Some(Err(e)) => {
+            tracing::warn!(
+                chart_name = chart.name,
+                "invalid timezone stored for the chart, assuming UTC: {e}"
+            );
+            Tz::UTC
+        }
+        None => Tz::UTC,
+    }
+}
+
 /// Returns tuple with:
 /// - latest resolution that has relevant data
 /// - does # of approximate points needs to be decreased by 1
 fn relevant_data_until<R: Timespan>(
     last_updated_at: Option<DateTime<chrono::FixedOffset>>,
+    timezone: &Tz,
 ) -> (Option<R>, bool) {
     if let Some(t) = last_updated_at {
         let t = t.to_utc();
         let last_updated_at_timespan_border =
-            R::from_date(t.date_naive()).saturating_start_timestamp() == t;
+            R::from_timestamp_in(t, timezone).saturating_start_timestamp_in(timezone) == t;
         // last_updated_at timestamp is not included in the range
         let inclusive_last_updated_at_end =
             exclusive_datetime_range_to_inclusive(DateTime::<Utc>::MIN_UTC..t);
         (
-            Some(R::from_date(
-                inclusive_last_updated_at_end.end().date_naive(),
+            Some(R::from_timestamp_in(
+                *inclusive_last_updated_at_end.end(),
+                timezone,
             )),
             last_updated_at_timespan_border,
         )
@@ -321,7 +435,8 @@ where
     let db_data =
         get_raw_line_chart_data::<Resolution>(db, chart.id, from.clone(), to.clone()).await?;
 
-    let (relevant_until, decrement_approx_points) = relevant_data_until(chart.last_updated_at);
+    let (relevant_until, decrement_approx_points) =
+        relevant_data_until(chart.last_updated_at, &stored_timezone(&chart));
     if relevant_until.is_none() && !db_data.is_empty() {
         tracing::warn!(
             chart_name = chart.name,
@@ -359,6 +474,14 @@ where
     Ok(data)
 }
 
+/// Data point as it's stored in `chart_data`
+#[derive(Debug, FromQueryResult)]
+struct StoredValue {
+    /// Start of the timespan
+    date: NaiveDateTime,
+    value: String,
+}
+
 /// Get data points at least within the provided range.
 ///
 /// I.e. if today there was no data, but `from` is today, yesterday's
@@ -379,38 +502,118 @@ where
         .order_by_asc(chart_data::Column::Date);
 
     if let Some(from) = from {
-        let from = from.into_date();
+        let from = from.saturating_start_timestamp().naive_utc();
         let custom_where = Expr::cust_with_values::<_, sea_orm::sea_query::Value, _>(
-            "date >= (SELECT COALESCE(MAX(date), '1900-01-01'::date) FROM chart_data WHERE chart_id = $1 AND date <= $2)",
+            "date >= (SELECT COALESCE(MAX(date), '1900-01-01'::timestamp) FROM chart_data WHERE chart_id = $1 AND date <= $2)",
             [chart_id.into(), from.into()],
         );
         QuerySelect::query(&mut data_request).cond_where(custom_where);
     }
     if let Some(to) = to {
-        let to = to.into_date();
+        let to = to.saturating_start_timestamp().naive_utc();
         let custom_where = Expr::cust_with_values::<_, sea_orm::sea_query::Value, _>(
-            "date <= (SELECT COALESCE(MIN(date), '9999-12-31'::date) FROM chart_data WHERE chart_id = $1 AND date >= $2)",
+            "date <= (SELECT COALESCE(MIN(date), '9999-12-31'::timestamp) FROM chart_data WHERE chart_id = $1 AND date >= $2)",
             [chart_id.into(), to.into()],
         );
         QuerySelect::query(&mut data_request).cond_where(custom_where);
     };
 
-    let data: Vec<DateValue<String>> = data_request.into_model().all(db).await?;
+    let data: Vec<StoredValue> = data_request.into_model().all(db).await?;
     let data = data
         .into_iter()
-        .map(
-            |TimespanValue {
-                 timespan: date,
-                 value,
-             }| TimespanValue {
-                timespan: Resolution::from_date(date),
-                value,
-            },
-        )
+        .map(|StoredValue { date, value }| TimespanValue {
+            timespan: Resolution::from_timestamp(date.and_utc()),
+            value,
+        })
         .collect_vec();
     Ok(data)
 }
 
+/// Value of a series as it's stored in `chart_data_series`
+#[derive(Debug, FromQueryResult)]
+struct StoredSeriesValue {
+    /// Start of the timespan
+    date: NaiveDateTime,
+    series: String,
+    value: String,
+}
+
+/// Get values of named series of multi-series chart within the range (inclusive).
+///
+/// Only stored points are returned, i.e. dates filled according to
+/// missing date policy do not have any series values. Single-series
+/// charts have no series at all.
+pub async fn get_line_chart_series<Resolution>(
+    db: &DatabaseConnection,
+    chart_name: &str,
+    from: Option<Resolution>,
+    to: Option<Resolution>,
+) -> Result<Vec<TimespanValue<Resolution, SeriesValues>>, ReadError>
+where
+    Resolution: Timespan + Ord,
+{
+    let key = ChartKey::new(chart_name.into(), Resolution::enum_variant());
+    let chart_id = find_chart(db, &key)
+        .await?
+        .ok_or_else(|| ReadError::ChartNotFound(key))?;
+    let mut data_request = chart_data_series::Entity::find()
+        .column(chart_data_series::Column::Date)
+        .column(chart_data_series::Column::Series)
+        .column(chart_data_series::Column::Value)
+        .filter(chart_data_series::Column::ChartId.eq(chart_id))
+        .order_by_asc(chart_data_series::Column::Date);
+    if let Some(from) = from {
+        data_request = data_request.filter(
+            chart_data_series::Column::Date.gte(from.saturating_start_timestamp().naive_utc()),
+        );
+    }
+    if let Some(to) = to {
+        data_request = data_request.filter(
+            chart_data_series::Column::Date.lte(to.saturating_start_timestamp().naive_utc()),
+        );
+    }
+    let data: Vec<StoredSeriesValue> = data_request.into_model().all(db).await?;
+    let mut grouped: BTreeMap<Resolution, SeriesValues> = BTreeMap::new();
+    for StoredSeriesValue {
+        date,
+        series,
+        value,
+    } in data
+    {
+        grouped
+            .entry(Resolution::from_timestamp(date.and_utc()))
+            .or_default()
+            .0
+            .insert(series, value);
+    }
+    Ok(grouped
+        .into_iter()
+        .map(|(timespan, value)| TimespanValue { timespan, value })
+        .collect())
+}
+
+/// Get the whole top list (ordered by rank)
+pub async fn get_top_list_data(
+    db: &DatabaseConnection,
+    chart_name: &str,
+) -> Result<Vec<TopListEntry>, ReadError> {
+    // top lists have only daily 'resolution'
+    let key = ChartKey::new(chart_name.into(), ResolutionKind::Day);
+    let chart_id = find_chart(db, &key)
+        .await?
+        .ok_or_else(|| ReadError::ChartNotFound(key))?;
+    let data = top_list_data::Entity::find()
+        .select_only()
+        .column(top_list_data::Column::Key)
+        .column(top_list_data::Column::Value)
+        .filter(top_list_data::Column::ChartId.eq(chart_id))
+        .order_by_asc(top_list_data::Column::Rank)
+        .into_model()
+        .all(db)
+        .await?;
+    Ok(data)
+}
+
 #[derive(FromQueryResult)]
 struct MinBlock {
     min_block: i64,
@@ -481,6 +684,33 @@ where
     ChartProps: ChartProperties + ?Sized,
     ChartProps::Resolution: Ord + Clone + Debug,
     Query: QueryBehaviour,
+{
+    last_accurate_point_of_chart(
+        chart_id,
+        min_blockscout_block,
+        db,
+        force_full,
+        approximate_trailing_points,
+        policy,
+    )
+    .await
+}
+
+/// Same as [`last_accurate_point`], but for the chart that is not
+/// defined with a type (e.g. [custom](crate::custom) one).
+///
+/// The last updated timespan is determined in the timezone
+/// the chart is calculated in.
+pub async fn last_accurate_point_of_chart<Resolution>(
+    chart_id: i32,
+    min_blockscout_block: i64,
+    db: &DatabaseConnection,
+    force_full: bool,
+    approximate_trailing_points: u64,
+    policy: MissingDatePolicy,
+) -> Result<Option<TimespanValue<Resolution, String>>, UpdateError>
+where
+    Resolution: Timespan + Ord + Clone + Debug,
 {
     let row = if force_full {
         tracing::info!("running full update due to force override");
@@ -494,21 +724,27 @@ where
             .one(db)
             .await
             .map_err(UpdateError::StatsDB)?;
-        let metadata = get_chart_metadata(db, &ChartProps::key()).await?;
+        let chart = charts::Entity::find_by_id(chart_id)
+            .one(db)
+            .await
+            .map_err(UpdateError::StatsDB)?
+            .ok_or_else(|| UpdateError::Internal(format!("chart with id {chart_id} not found")))?;
 
         match recorded_min_blockscout_block {
             Some(recorded_min_blockscout_block) => {
-                let Some(last_updated_at) = metadata.last_updated_at else {
+                let Some(last_updated_at) = chart.last_updated_at else {
                     // data is present, but `last_updated_at` is not set
                     tracing::info!("running full update due to lack of last_updated_at");
                     return Ok(None);
                 };
-                let last_updated_timespan =
-                    ChartProps::Resolution::from_date(last_updated_at.date_naive());
+                let last_updated_timespan = Resolution::from_timestamp_in(
+                    last_updated_at.to_utc(),
+                    &stored_timezone(&chart),
+                );
 
-                let data = get_line_chart_data::<ChartProps::Resolution>(
+                let data = get_line_chart_data::<Resolution>(
                     db,
-                    &ChartProps::name(),
+                    &chart.name,
                     Some(last_updated_timespan.saturating_sub(
                         TimespanDuration::from_timespan_repeats(approximate_trailing_points),
                     )),
@@ -567,7 +803,7 @@ where
 mod tests {
     use super::*;
     use crate::{
-        charts::ResolutionKind,
+        charts::db_interaction::write::{set_last_update_error, set_last_updated_at},
         counters::TotalBlocks,
         data_source::kinds::local_db::parameters::DefaultQueryVec,
         lines::{ActiveAccounts, TxnsGrowth, TxnsGrowthMonthly},
@@ -578,7 +814,7 @@ mod tests {
         types::timespans::Month,
         Named,
     };
-    use chrono::DateTime;
+    use chrono::{DateTime, NaiveTime};
     use entity::{chart_data, charts, sea_orm_active_enums::ChartType};
     use pretty_assertions::assert_eq;
     use sea_orm::{EntityTrait, Set};
@@ -587,7 +823,7 @@ mod tests {
     fn mock_chart_data(chart_id: i32, date: &str, value: i64) -> chart_data::ActiveModel {
         chart_data::ActiveModel {
             chart_id: Set(chart_id),
-            date: Set(NaiveDate::from_str(date).unwrap()),
+            date: Set(NaiveDate::from_str(date).unwrap().and_time(NaiveTime::MIN)),
             value: Set(value.to_string()),
             min_blockscout_block: Set(Some(1)),
             ..Default::default()
@@ -722,6 +958,73 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn get_counters_at_mock() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let db = init_db("get_counters_at_mock").await;
+        insert_mock_data(&db).await;
+        assert_eq!(
+            get_raw_counters_at(&db, d("2022-11-11")).await.unwrap(),
+            HashMap::from_iter([("totalBlocks".into(), value("2022-11-11", "1150"))]),
+        );
+        assert_eq!(
+            get_raw_counters_at(&db, d("2022-12-01")).await.unwrap(),
+            HashMap::from_iter([("totalBlocks".into(), value("2022-11-12", "1350"))]),
+        );
+        assert_eq!(
+            get_raw_counters_at(&db, d("2022-11-09")).await.unwrap(),
+            HashMap::new()
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_status_is_recorded() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let db = init_db("update_status_is_recorded").await;
+        insert_mock_data(&db).await;
+        let key = TotalBlocks::key();
+        let error_time = DateTime::parse_from_rfc3339("2022-11-13T00:00:00+00:00").unwrap();
+        set_last_update_error(&db, &key, "some error".to_owned(), error_time)
+            .await
+            .unwrap();
+        let statuses = get_charts_update_status(&db, [&key]).await.unwrap();
+        // only requested charts are returned
+        assert_eq!(statuses.len(), 1);
+        let status = statuses[&key].clone();
+        assert_eq!(
+            status,
+            ChartUpdateStatus {
+                last_updated_at: Some(
+                    DateTime::parse_from_rfc3339("2022-11-12T08:08:08+00:00")
+                        .unwrap()
+                        .to_utc()
+                ),
+                last_error: Some("some error".to_owned()),
+                last_error_at: Some(error_time.to_utc()),
+            }
+        );
+
+        // successful update clears the error
+        let chart_id = find_chart(&db, &key).await.unwrap().unwrap();
+        let update_time = DateTime::parse_from_rfc3339("2022-11-14T00:00:00+00:00").unwrap();
+        set_last_updated_at(chart_id, &db, update_time)
+            .await
+            .unwrap();
+        let status = get_charts_update_status(&db, [&key]).await.unwrap()[&key].clone();
+        assert_eq!(
+            status,
+            ChartUpdateStatus {
+                last_updated_at: Some(update_time.to_utc()),
+                last_error: None,
+                last_error_at: None,
+            }
+        );
+    }
+
     #[tokio::test]
     #[ignore = "needs database to run"]
     async fn get_chart_int_mock() {
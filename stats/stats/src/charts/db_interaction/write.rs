@@ -1,8 +1,12 @@
-use chrono::{DateTime, Offset, TimeZone};
-use entity::{chart_data, charts, sea_orm_active_enums::ChartType};
-use sea_orm::{prelude::*, sea_query, ConnectionTrait, Set, Unchanged};
+use chrono::{DateTime, NaiveDateTime, Offset, TimeZone};
+use entity::{
+    chart_data, chart_data_series, charts,
+    sea_orm_active_enums::{ChartResolution, ChartType},
+    top_list_data,
+};
+use sea_orm::{prelude::*, sea_query, ConnectionTrait, Set, TransactionTrait, Unchanged};
 
-use crate::charts::ChartKey;
+use crate::{charts::ChartKey, types::TopListEntry};
 
 use super::read::find_chart;
 
@@ -11,6 +15,22 @@ pub async fn create_chart<Tz: TimeZone>(
     key: ChartKey,
     chart_type: ChartType,
     creation_time: &DateTime<Tz>,
+) -> Result<(), DbErr> {
+    create_chart_in_timezone(db, key, chart_type, creation_time, None).await
+}
+
+/// Same as [`create_chart`], but also records the timezone that the chart data
+/// is calculated in (`None` means UTC).
+///
+/// Charts calculated in different timezones are stored under different names
+/// (see [`crate::custom::timezone_variant_name`]), so the timezone of
+/// an existing chart is never changed.
+pub async fn create_chart_in_timezone<Tz: TimeZone>(
+    db: &DatabaseConnection,
+    key: ChartKey,
+    chart_type: ChartType,
+    creation_time: &DateTime<Tz>,
+    timezone: Option<&chrono_tz::Tz>,
 ) -> Result<(), DbErr> {
     let id = find_chart(db, &key).await?;
     if id.is_some() {
@@ -21,6 +41,7 @@ pub async fn create_chart<Tz: TimeZone>(
         resolution: Set((*key.resolution()).into()),
         chart_type: Set(chart_type),
         created_at: Set(creation_time.with_timezone(&creation_time.offset().fix())),
+        timezone: Set(timezone.map(|tz| tz.name().to_owned())),
         ..Default::default()
     })
     .on_conflict(
@@ -56,6 +77,56 @@ where
     Ok(())
 }
 
+/// Same as [`insert_data_many`] but for values of named series
+pub async fn insert_series_data_many<C, D>(db: &C, data: D) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+    D: IntoIterator<Item = chart_data_series::ActiveModel> + Send + Sync,
+{
+    let mut data = data.into_iter().peekable();
+    if data.peek().is_some() {
+        chart_data_series::Entity::insert_many(data)
+            .on_conflict(
+                sea_query::OnConflict::columns([
+                    chart_data_series::Column::ChartId,
+                    chart_data_series::Column::Series,
+                    chart_data_series::Column::Date,
+                ])
+                .update_column(chart_data_series::Column::Value)
+                .update_column(chart_data_series::Column::MinBlockscoutBlock)
+                .to_owned(),
+            )
+            .exec(db)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Replace the whole top list of the chart with `entries`
+/// (ranked by their order).
+pub async fn replace_top_list(
+    db: &DatabaseConnection,
+    chart_id: i32,
+    entries: &[TopListEntry],
+    min_blockscout_block: Option<i64>,
+) -> Result<(), DbErr> {
+    let tx = db.begin().await?;
+    top_list_data::Entity::delete_many()
+        .filter(top_list_data::Column::ChartId.eq(chart_id))
+        .exec(&tx)
+        .await?;
+    let rows: Vec<_> = entries
+        .iter()
+        .zip(1..)
+        .map(|(entry, rank)| entry.active_model(chart_id, rank, min_blockscout_block))
+        .collect();
+    if !rows.is_empty() {
+        top_list_data::Entity::insert_many(rows).exec(&tx).await?;
+    }
+    tx.commit().await
+}
+
+/// Successful update also clears the last update error
 pub async fn set_last_updated_at<Tz>(
     chart_id: i32,
     db: &DatabaseConnection,
@@ -68,6 +139,8 @@ where
     let model = charts::ActiveModel {
         id: Unchanged(chart_id),
         last_updated_at: Set(Some(last_updated_at)),
+        last_update_error: Set(None),
+        last_update_error_at: Set(None),
         ..Default::default()
     };
     charts::Entity::update(model)
@@ -76,3 +149,90 @@ where
         .await?;
     Ok(())
 }
+
+/// Record error of the latest (failed) update of the chart
+pub async fn set_last_update_error<Tz>(
+    db: &DatabaseConnection,
+    chart: &ChartKey,
+    error: String,
+    at: chrono::DateTime<Tz>,
+) -> Result<(), DbErr>
+where
+    Tz: chrono::TimeZone,
+{
+    charts::Entity::update_many()
+        .col_expr(
+            charts::Column::LastUpdateError,
+            sea_query::Expr::value(error),
+        )
+        .col_expr(
+            charts::Column::LastUpdateErrorAt,
+            sea_query::Expr::value(at.with_timezone(&chrono::Utc.fix())),
+        )
+        .filter(charts::Column::Name.eq(chart.name()))
+        .filter(charts::Column::Resolution.eq(ChartResolution::from(*chart.resolution())))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Remove all points of the chart with timespans starting before `before`.
+///
+/// Returns number of removed points.
+pub async fn delete_data_before<C>(
+    db: &C,
+    chart_id: i32,
+    before: NaiveDateTime,
+) -> Result<u64, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let result = chart_data::Entity::delete_many()
+        .filter(chart_data::Column::ChartId.eq(chart_id))
+        .filter(chart_data::Column::Date.lt(before))
+        .exec(db)
+        .await?;
+    chart_data_series::Entity::delete_many()
+        .filter(chart_data_series::Column::ChartId.eq(chart_id))
+        .filter(chart_data_series::Column::Date.lt(before))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+/// Remove all points of the chart with timespans starting at `from` or later.
+/// Also moves `last_updated_at` back to `from`, so that the next (non-full) update
+/// recalculates the removed points.
+///
+/// Returns number of removed points.
+pub async fn reset_data_from<Tz>(
+    db: &DatabaseConnection,
+    chart_id: i32,
+    from: DateTime<Tz>,
+) -> Result<u64, DbErr>
+where
+    Tz: TimeZone,
+{
+    let tx = db.begin().await?;
+    let result = chart_data::Entity::delete_many()
+        .filter(chart_data::Column::ChartId.eq(chart_id))
+        .filter(chart_data::Column::Date.gte(from.naive_utc()))
+        .exec(&tx)
+        .await?;
+    chart_data_series::Entity::delete_many()
+        .filter(chart_data_series::Column::ChartId.eq(chart_id))
+        .filter(chart_data_series::Column::Date.gte(from.naive_utc()))
+        .exec(&tx)
+        .await?;
+    let model = charts::ActiveModel {
+        id: Unchanged(chart_id),
+        last_updated_at: Set(Some(from.with_timezone(&chrono::Utc.fix()))),
+        ..Default::default()
+    };
+    charts::Entity::update(model)
+        .filter(charts::Column::Id.eq(chart_id))
+        .exec(&tx)
+        .await?;
+    tx.commit().await?;
+    Ok(result.rows_affected)
+}
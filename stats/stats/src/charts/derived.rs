@@ -0,0 +1,393 @@
+//! Line charts calculated from other (already stored) line charts.
+//!
+//! A derived chart is a [custom chart](crate::custom) whose daily values are
+//! obtained with one of [`DerivedTransform`]s from the daily values of the source
+//! charts instead of a query to blockscout DB.
+//!
+//! Source data is read from the stats DB with missing dates filled according to
+//! the sources' [`MissingDatePolicy`]. Derived values are recalculated starting from
+//! the last updated day; the source data is read from an earlier date if the transform
+//! needs it (e.g. moving average window), and cumulative sum continues from the
+//! previously calculated value. Full update (or the first one) recalculates the whole history.
+//!
+//! Note that sources are updated independently (within their own update groups),
+//! so derived chart may lag behind them until its next update. Changes of the older
+//! source data (e.g. after reindex) are only reflected after full update.
+
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate};
+use rust_decimal::Decimal;
+
+use super::custom::{parse_values, values_to_string, LowerResolutionAggregation};
+use crate::{types::TimespanValue, MissingDatePolicy, UpdateError};
+
+/// Daily line chart used as an input of a transform
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivedSource {
+    /// Name of the chart in stats DB (see [`crate::custom::timezone_variant_name`])
+    pub name: String,
+    pub missing_date_policy: MissingDatePolicy,
+}
+
+impl DerivedSource {
+    pub fn new(name: String, missing_date_policy: MissingDatePolicy) -> Self {
+        Self {
+            name,
+            missing_date_policy,
+        }
+    }
+}
+
+/// How the values of derived chart are calculated from the source(-s).
+///
+/// Values of the sources must be numbers. Transforms of two charts
+/// produce values only for the dates present in both of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DerivedTransform {
+    /// Average of the source values over the last `days` days (including the current one).
+    /// Values are present only for the dates with complete window.
+    MovingAverage { source: DerivedSource, days: u32 },
+    /// `numerator / denominator` (e.g. share of failed transactions).
+    /// Dates with zero denominator are skipped.
+    Ratio {
+        numerator: DerivedSource,
+        denominator: DerivedSource,
+    },
+    /// Sum of all the source values up to the date (e.g. growth from "new" chart)
+    Cumulative { source: DerivedSource },
+    /// `minuend - subtrahend`
+    Difference {
+        minuend: DerivedSource,
+        subtrahend: DerivedSource,
+    },
+}
+
+impl DerivedTransform {
+    /// Charts used by the transform, in the order expected by [`Self::apply`]
+    pub fn sources(&self) -> Vec<&DerivedSource> {
+        match self {
+            DerivedTransform::MovingAverage { source, .. }
+            | DerivedTransform::Cumulative { source } => vec![source],
+            DerivedTransform::Ratio {
+                numerator,
+                denominator,
+            } => vec![numerator, denominator],
+            DerivedTransform::Difference {
+                minuend,
+                subtrahend,
+            } => vec![minuend, subtrahend],
+        }
+    }
+
+    /// Aggregation of derived values that makes sense for the transform
+    pub fn default_aggregation(&self) -> LowerResolutionAggregation {
+        match self {
+            DerivedTransform::MovingAverage { .. } | DerivedTransform::Ratio { .. } => {
+                LowerResolutionAggregation::Average
+            }
+            DerivedTransform::Cumulative { .. } => LowerResolutionAggregation::Last,
+            DerivedTransform::Difference { .. } => LowerResolutionAggregation::Sum,
+        }
+    }
+
+    /// Policy for the derived values
+    pub fn missing_date_policy(&self) -> MissingDatePolicy {
+        match self {
+            DerivedTransform::Cumulative { .. } => MissingDatePolicy::FillPrevious,
+            DerivedTransform::MovingAverage { .. }
+            | DerivedTransform::Ratio { .. }
+            | DerivedTransform::Difference { .. } => MissingDatePolicy::FillZero,
+        }
+    }
+
+    /// First date of the source data needed to calculate values
+    /// after `last_accurate_date` (see [`Self::apply_after`])
+    pub fn sources_start_after(&self, last_accurate_date: NaiveDate) -> NaiveDate {
+        let first_date = last_accurate_date.succ_opt().unwrap_or(NaiveDate::MAX);
+        match self {
+            DerivedTransform::MovingAverage { days, .. } => {
+                first_date - Duration::days(i64::from(days.saturating_sub(1)))
+            }
+            DerivedTransform::Ratio { .. }
+            | DerivedTransform::Cumulative { .. }
+            | DerivedTransform::Difference { .. } => first_date,
+        }
+    }
+
+    /// Calculate derived values from the sorted daily data of [`Self::sources`]
+    pub fn apply(
+        &self,
+        sources_data: Vec<Vec<TimespanValue<NaiveDate, String>>>,
+    ) -> Result<Vec<TimespanValue<NaiveDate, String>>, UpdateError> {
+        self.apply_inner(sources_data, None)
+    }
+
+    /// Same as [`Self::apply`], but only the values after `last_accurate_point`
+    /// (previously calculated derived value) are calculated.
+    ///
+    /// The data of the sources is expected to start from [`Self::sources_start_after`].
+    pub fn apply_after(
+        &self,
+        sources_data: Vec<Vec<TimespanValue<NaiveDate, String>>>,
+        last_accurate_point: &TimespanValue<NaiveDate, String>,
+    ) -> Result<Vec<TimespanValue<NaiveDate, String>>, UpdateError> {
+        let mut data = self.apply_inner(sources_data, Some(last_accurate_point))?;
+        data.retain(|p| p.timespan > last_accurate_point.timespan);
+        Ok(data)
+    }
+
+    fn apply_inner(
+        &self,
+        mut sources_data: Vec<Vec<TimespanValue<NaiveDate, String>>>,
+        last_accurate_point: Option<&TimespanValue<NaiveDate, String>>,
+    ) -> Result<Vec<TimespanValue<NaiveDate, String>>, UpdateError> {
+        let expected_sources = self.sources().len();
+        if sources_data.len() != expected_sources {
+            return Err(UpdateError::Internal(format!(
+                "expected data of {expected_sources} source charts, got {}",
+                sources_data.len()
+            )));
+        }
+        let second = sources_data.pop().unwrap_or_default();
+        let first = sources_data.pop().unwrap_or_default();
+        Ok(match self {
+            DerivedTransform::MovingAverage { days, .. } => {
+                values_to_string(moving_average(parse_values(second)?, *days))
+            }
+            DerivedTransform::Cumulative { .. } => {
+                let initial = match last_accurate_point {
+                    Some(point) => parse_values::<Decimal>(vec![point.clone()])?[0].value,
+                    None => Decimal::ZERO,
+                };
+                values_to_string(cumulative(parse_values(second)?, initial))
+            }
+            DerivedTransform::Ratio { .. } => {
+                let joined = join_by_date(parse_values::<f64>(first)?, parse_values(second)?);
+                let data = joined
+                    .into_iter()
+                    .filter(|(_, _, denominator)| *denominator != 0.0)
+                    .map(|(timespan, numerator, denominator)| TimespanValue {
+                        timespan,
+                        value: numerator / denominator,
+                    })
+                    .collect();
+                values_to_string(data)
+            }
+            DerivedTransform::Difference { .. } => {
+                let joined = join_by_date(parse_values::<Decimal>(first)?, parse_values(second)?);
+                let data = joined
+                    .into_iter()
+                    .map(|(timespan, minuend, subtrahend)| TimespanValue {
+                        timespan,
+                        value: minuend - subtrahend,
+                    })
+                    .collect();
+                values_to_string(data)
+            }
+        })
+    }
+}
+
+fn moving_average(
+    data: Vec<TimespanValue<NaiveDate, f64>>,
+    days: u32,
+) -> Vec<TimespanValue<NaiveDate, f64>> {
+    let Some(first_date) = data.first().map(|p| p.timespan) else {
+        return vec![];
+    };
+    let window_offset = Duration::days(i64::from(days.saturating_sub(1)));
+    let mut window_start_idx = 0;
+    let mut result = vec![];
+    for (idx, point) in data.iter().enumerate() {
+        let window_start = point.timespan - window_offset;
+        while data[window_start_idx].timespan < window_start {
+            window_start_idx += 1;
+        }
+        if window_start < first_date {
+            continue;
+        }
+        // missing dates (if any) count as zeros
+        let sum: f64 = data[window_start_idx..=idx].iter().map(|p| p.value).sum();
+        result.push(TimespanValue {
+            timespan: point.timespan,
+            value: sum / f64::from(days),
+        });
+    }
+    result
+}
+
+fn cumulative(
+    data: Vec<TimespanValue<NaiveDate, Decimal>>,
+    initial: Decimal,
+) -> Vec<TimespanValue<NaiveDate, Decimal>> {
+    let mut total = initial;
+    data.into_iter()
+        .map(|p| {
+            total += p.value;
+            TimespanValue {
+                timespan: p.timespan,
+                value: total,
+            }
+        })
+        .collect()
+}
+
+/// `(date, a, b)` for the dates present in both `a` and `b`
+fn join_by_date<A, B>(
+    a: Vec<TimespanValue<NaiveDate, A>>,
+    b: Vec<TimespanValue<NaiveDate, B>>,
+) -> Vec<(NaiveDate, A, B)> {
+    let mut b: BTreeMap<_, _> = b.into_iter().map(|p| (p.timespan, p.value)).collect();
+    a.into_iter()
+        .filter_map(|p| {
+            let b_value = b.remove(&p.timespan)?;
+            Some((p.timespan, p.value, b_value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::point_construction::{d, d_v};
+    use pretty_assertions::assert_eq;
+
+    fn source(name: &str) -> DerivedSource {
+        DerivedSource::new(name.into(), MissingDatePolicy::FillZero)
+    }
+
+    #[test]
+    fn moving_average_works() {
+        let transform = DerivedTransform::MovingAverage {
+            source: source("newTxns"),
+            days: 3,
+        };
+        let data = vec![
+            d_v("2024-07-01", "1"),
+            d_v("2024-07-02", "2"),
+            d_v("2024-07-03", "6"),
+            d_v("2024-07-04", "4"),
+            d_v("2024-07-06", "5"),
+        ];
+        assert_eq!(
+            transform.apply(vec![data]).unwrap(),
+            vec![
+                d_v("2024-07-03", "3"),
+                d_v("2024-07-04", "4"),
+                d_v("2024-07-06", "3"),
+            ]
+        );
+        assert_eq!(transform.apply(vec![vec![]]).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn cumulative_works() {
+        let transform = DerivedTransform::Cumulative {
+            source: source("newAccounts"),
+        };
+        let data = vec![
+            d_v("2024-07-01", "1"),
+            d_v("2024-07-02", "2.5"),
+            d_v("2024-07-03", "0"),
+        ];
+        assert_eq!(
+            transform.apply(vec![data]).unwrap(),
+            vec![
+                d_v("2024-07-01", "1"),
+                d_v("2024-07-02", "3.5"),
+                d_v("2024-07-03", "3.5"),
+            ]
+        );
+    }
+
+    #[test]
+    fn apply_after_works() {
+        let last_accurate_point = d_v("2024-07-03", "10");
+
+        let moving_average = DerivedTransform::MovingAverage {
+            source: source("newTxns"),
+            days: 3,
+        };
+        assert_eq!(
+            moving_average.sources_start_after(last_accurate_point.timespan),
+            d("2024-07-02")
+        );
+        let data = vec![
+            d_v("2024-07-02", "2"),
+            d_v("2024-07-03", "6"),
+            d_v("2024-07-04", "4"),
+            d_v("2024-07-05", "5"),
+        ];
+        assert_eq!(
+            moving_average
+                .apply_after(vec![data], &last_accurate_point)
+                .unwrap(),
+            vec![d_v("2024-07-04", "4"), d_v("2024-07-05", "5")]
+        );
+
+        let cumulative = DerivedTransform::Cumulative {
+            source: source("newAccounts"),
+        };
+        assert_eq!(
+            cumulative.sources_start_after(last_accurate_point.timespan),
+            d("2024-07-04")
+        );
+        let data = vec![d_v("2024-07-04", "1"), d_v("2024-07-05", "2.5")];
+        assert_eq!(
+            cumulative
+                .apply_after(vec![data], &last_accurate_point)
+                .unwrap(),
+            vec![d_v("2024-07-04", "11"), d_v("2024-07-05", "13.5")]
+        );
+    }
+
+    #[test]
+    fn ratio_works() {
+        let transform = DerivedTransform::Ratio {
+            numerator: source("newFailedTxns"),
+            denominator: source("newTxns"),
+        };
+        let numerator = vec![
+            d_v("2024-07-01", "1"),
+            d_v("2024-07-02", "0"),
+            d_v("2024-07-03", "3"),
+            d_v("2024-07-04", "1"),
+        ];
+        let denominator = vec![
+            d_v("2024-07-02", "5"),
+            d_v("2024-07-03", "4"),
+            d_v("2024-07-04", "0"),
+        ];
+        assert_eq!(
+            transform.apply(vec![numerator, denominator]).unwrap(),
+            vec![d_v("2024-07-02", "0"), d_v("2024-07-03", "0.75")]
+        );
+    }
+
+    #[test]
+    fn difference_works() {
+        let transform = DerivedTransform::Difference {
+            minuend: source("newTxns"),
+            subtrahend: source("newFailedTxns"),
+        };
+        let minuend = vec![d_v("2024-07-01", "10"), d_v("2024-07-02", "7")];
+        let subtrahend = vec![d_v("2024-07-02", "2"), d_v("2024-07-03", "1")];
+        assert_eq!(
+            transform.apply(vec![minuend, subtrahend]).unwrap(),
+            vec![d_v("2024-07-02", "5")]
+        );
+    }
+
+    #[test]
+    fn apply_checks_inputs() {
+        let transform = DerivedTransform::Difference {
+            minuend: source("newTxns"),
+            subtrahend: source("newFailedTxns"),
+        };
+        assert!(transform.apply(vec![vec![d_v("2024-07-01", "1")]]).is_err());
+        assert!(transform
+            .apply(vec![vec![d_v("2024-07-01", "abc")], vec![]])
+            .is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use std::ops::Range;
+
+use crate::{
+    data_source::kinds::{
+        data_manipulation::{
+            map::{MapParseTo, MapToString},
+            resolutions::average::AverageLowerResolution,
+        },
+        local_db::{
+            parameters::update::batching::parameters::{
+                Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
+            },
+            DirectVecLocalDbChartSource,
+        },
+        remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
+    },
+    define_and_impl_resolution_properties,
+    types::timespans::{Month, Week, Year},
+    utils::sql_with_range_filter_opt,
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+use super::new_blocks::{NewBlocksInt, NewBlocksMonthlyInt};
+
+const GWEI: i64 = 1_000_000_000;
+
+pub struct AverageBaseFeeStatement;
+
+impl StatementFromRange for AverageBaseFeeStatement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    blocks.timestamp::date as date,
+                    (AVG(blocks.base_fee_per_gas) / $1)::float as value
+                FROM blocks
+                WHERE
+                    blocks.timestamp != to_timestamp(0) AND
+                    blocks.base_fee_per_gas IS NOT NULL AND
+                    blocks.consensus = true {filter}
+                GROUP BY date
+            "#,
+            [GWEI.into()],
+            "blocks.timestamp",
+            range,
+        )
+    }
+}
+
+pub type AverageBaseFeeRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<AverageBaseFeeStatement, NaiveDate, f64>>;
+
+pub type AverageBaseFeeRemoteString = MapToString<AverageBaseFeeRemote>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "averageBaseFee".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+define_and_impl_resolution_properties!(
+    define_and_impl: {
+        WeeklyProperties: Week,
+        MonthlyProperties: Month,
+        YearlyProperties: Year,
+    },
+    base_impl: Properties
+);
+
+pub type AverageBaseFee =
+    DirectVecLocalDbChartSource<AverageBaseFeeRemoteString, Batch30Days, Properties>;
+pub type AverageBaseFeeWeekly = DirectVecLocalDbChartSource<
+    MapToString<AverageLowerResolution<MapParseTo<AverageBaseFee, f64>, NewBlocksInt, Week>>,
+    Batch30Weeks,
+    WeeklyProperties,
+>;
+pub type AverageBaseFeeMonthly = DirectVecLocalDbChartSource<
+    MapToString<AverageLowerResolution<MapParseTo<AverageBaseFee, f64>, NewBlocksInt, Month>>,
+    Batch36Months,
+    MonthlyProperties,
+>;
+pub type AverageBaseFeeYearly = DirectVecLocalDbChartSource<
+    MapToString<
+        AverageLowerResolution<MapParseTo<AverageBaseFeeMonthly, f64>, NewBlocksMonthlyInt, Year>,
+    >,
+    Batch30Years,
+    YearlyProperties,
+>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_average_base_fee() {
+        simple_test_chart::<AverageBaseFee>(
+            "update_average_base_fee",
+            vec![
+                ("2022-11-09", "0"),
+                ("2022-11-10", "0.1"),
+                ("2022-11-11", "0.275"),
+                ("2022-11-12", "0.4"),
+                ("2022-12-01", "0.45"),
+                ("2023-01-01", "0.5"),
+                ("2023-02-01", "0.55"),
+                ("2023-03-01", "0.6"),
+            ],
+        )
+        .await;
+    }
+}
@@ -8,14 +8,14 @@ use crate::{
         },
         local_db::{
             parameters::update::batching::parameters::{
-                Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
+                Batch24Hours, Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
             },
             DirectVecLocalDbChartSource,
         },
         remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
     },
     define_and_impl_resolution_properties,
-    types::timespans::{Month, Week, Year},
+    types::timespans::{Hour, Month, Week, Year},
     utils::sql_with_range_filter_opt,
     ChartProperties, Named,
 };
@@ -24,7 +24,10 @@ use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, Statement};
 
-use super::new_txns::{NewTxnsInt, NewTxnsMonthlyInt};
+use super::{
+    new_txns::{NewTxnsInt, NewTxnsMonthlyInt},
+    HOURLY_CHARTS_RETENTION_HOURS,
+};
 
 const GWEI: i64 = 1_000_000_000;
 
@@ -60,9 +63,44 @@ impl StatementFromRange for AverageGasPriceStatement {
     }
 }
 
+pub struct HourlyAverageGasPriceStatement;
+
+impl StatementFromRange for HourlyAverageGasPriceStatement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    date_trunc('hour', blocks.timestamp) as date,
+                    (AVG(
+                        COALESCE(
+                            transactions.gas_price,
+                            blocks.base_fee_per_gas + LEAST(
+                                transactions.max_priority_fee_per_gas,
+                                transactions.max_fee_per_gas - blocks.base_fee_per_gas
+                            )
+                        )
+                    ) / $1)::float as value
+                FROM transactions
+                JOIN blocks ON transactions.block_hash = blocks.hash
+                WHERE
+                    blocks.timestamp != to_timestamp(0) AND
+                    blocks.consensus = true {filter}
+                GROUP BY date
+            "#,
+            [GWEI.into()],
+            "blocks.timestamp",
+            range,
+        )
+    }
+}
+
 pub type AverageGasPriceRemote =
     RemoteDatabaseSource<PullAllWithAndSort<AverageGasPriceStatement, NaiveDate, f64>>;
 
+pub type HourlyAverageGasPriceRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<HourlyAverageGasPriceStatement, Hour, f64>>;
+
 pub type AverageGasPriceRemoteString = MapToString<AverageGasPriceRemote>;
 
 pub struct Properties;
@@ -81,6 +119,26 @@ impl ChartProperties for Properties {
     }
 }
 
+pub struct HourlyProperties;
+
+impl Named for HourlyProperties {
+    fn name() -> String {
+        Properties::name()
+    }
+}
+
+impl ChartProperties for HourlyProperties {
+    type Resolution = Hour;
+
+    fn chart_type() -> ChartType {
+        Properties::chart_type()
+    }
+
+    fn max_stored_timespans() -> Option<u64> {
+        Some(HOURLY_CHARTS_RETENTION_HOURS)
+    }
+}
+
 define_and_impl_resolution_properties!(
     define_and_impl: {
         WeeklyProperties: Week,
@@ -90,6 +148,11 @@ define_and_impl_resolution_properties!(
     base_impl: Properties
 );
 
+pub type AverageGasPriceHourly = DirectVecLocalDbChartSource<
+    MapToString<HourlyAverageGasPriceRemote>,
+    Batch24Hours,
+    HourlyProperties,
+>;
 pub type AverageGasPrice =
     DirectVecLocalDbChartSource<AverageGasPriceRemoteString, Batch30Days, Properties>;
 pub type AverageGasPriceWeekly = DirectVecLocalDbChartSource<
@@ -0,0 +1,139 @@
+use std::ops::Range;
+
+use crate::{
+    data_source::kinds::{
+        data_manipulation::{
+            map::{MapParseTo, MapToString},
+            resolutions::average::AverageLowerResolution,
+        },
+        local_db::{
+            parameters::update::batching::parameters::{
+                Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
+            },
+            DirectVecLocalDbChartSource,
+        },
+        remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
+    },
+    define_and_impl_resolution_properties,
+    types::timespans::{Month, Week, Year},
+    utils::sql_with_range_filter_opt,
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+use super::new_txns::{NewTxnsInt, NewTxnsMonthlyInt};
+
+const GWEI: i64 = 1_000_000_000;
+
+pub struct AveragePriorityFeeStatement;
+
+impl StatementFromRange for AveragePriorityFeeStatement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>) -> Statement {
+        // for legacy transactions everything above base fee
+        // is a priority fee
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    blocks.timestamp::date as date,
+                    (AVG(
+                        COALESCE(
+                            LEAST(
+                                transactions.max_priority_fee_per_gas,
+                                transactions.max_fee_per_gas - blocks.base_fee_per_gas
+                            ),
+                            transactions.gas_price - blocks.base_fee_per_gas
+                        )
+                    ) / $1)::float as value
+                FROM transactions
+                JOIN blocks ON transactions.block_hash = blocks.hash
+                WHERE
+                    blocks.timestamp != to_timestamp(0) AND
+                    blocks.base_fee_per_gas IS NOT NULL AND
+                    blocks.consensus = true {filter}
+                GROUP BY date
+            "#,
+            [GWEI.into()],
+            "blocks.timestamp",
+            range,
+        )
+    }
+}
+
+pub type AveragePriorityFeeRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<AveragePriorityFeeStatement, NaiveDate, f64>>;
+
+pub type AveragePriorityFeeRemoteString = MapToString<AveragePriorityFeeRemote>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "averagePriorityFee".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+define_and_impl_resolution_properties!(
+    define_and_impl: {
+        WeeklyProperties: Week,
+        MonthlyProperties: Month,
+        YearlyProperties: Year,
+    },
+    base_impl: Properties
+);
+
+pub type AveragePriorityFee =
+    DirectVecLocalDbChartSource<AveragePriorityFeeRemoteString, Batch30Days, Properties>;
+pub type AveragePriorityFeeWeekly = DirectVecLocalDbChartSource<
+    MapToString<AverageLowerResolution<MapParseTo<AveragePriorityFee, f64>, NewTxnsInt, Week>>,
+    Batch30Weeks,
+    WeeklyProperties,
+>;
+pub type AveragePriorityFeeMonthly = DirectVecLocalDbChartSource<
+    MapToString<AverageLowerResolution<MapParseTo<AveragePriorityFee, f64>, NewTxnsInt, Month>>,
+    Batch36Months,
+    MonthlyProperties,
+>;
+pub type AveragePriorityFeeYearly = DirectVecLocalDbChartSource<
+    MapToString<
+        AverageLowerResolution<MapParseTo<AveragePriorityFeeMonthly, f64>, NewTxnsMonthlyInt, Year>,
+    >,
+    Batch30Years,
+    YearlyProperties,
+>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_average_priority_fee() {
+        simple_test_chart::<AveragePriorityFee>(
+            "update_average_priority_fee",
+            vec![
+                ("2022-11-09", "0.4493827156"),
+                ("2022-11-10", "1.85354938075"),
+                ("2022-11-11", "3.0151234535"),
+                ("2022-11-12", "5.4419753028"),
+                ("2022-12-01", "6.0660493762"),
+                ("2023-01-01", "0.623456789"),
+                ("2023-02-01", "8.9993827065"),
+                ("2023-03-01", "0.523456789"),
+            ],
+        )
+        .await;
+    }
+}
@@ -0,0 +1,212 @@
+//! 10th, 50th (median) and 90th percentiles of transaction gas price
+//! (multi-series chart).
+//!
+//! Median is stored as the main chart value.
+
+use std::ops::Range;
+
+use crate::{
+    data_source::kinds::{
+        data_manipulation::resolutions::average::AverageSeriesLowerResolution,
+        local_db::{
+            parameters::update::batching::parameters::{
+                Batch30Days, Batch30Weeks, Batch30Years, Batch36Months, SeriesMainValue,
+            },
+            DirectSeriesVecLocalDbChartSource,
+        },
+        remote_db::{PullAllSeriesWithAndSort, RemoteDatabaseSource, StatementFromRange},
+    },
+    define_and_impl_resolution_properties,
+    types::{
+        timespans::{Month, Week, Year},
+        SeriesValues,
+    },
+    utils::sql_with_range_filter_opt,
+    ChartProperties, Named, UpdateError,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+use super::new_txns::{NewTxnsInt, NewTxnsMonthlyInt};
+
+const GWEI: i64 = 1_000_000_000;
+
+pub struct GasPricePercentilesStatement;
+
+impl StatementFromRange for GasPricePercentilesStatement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    date,
+                    unnest(ARRAY['p10', 'p50', 'p90']) as series,
+                    unnest(ARRAY[p10, p50, p90])::TEXT as value
+                FROM (
+                    SELECT
+                        date,
+                        percentile_cont(0.1) WITHIN GROUP (ORDER BY gas_price) as p10,
+                        percentile_cont(0.5) WITHIN GROUP (ORDER BY gas_price) as p50,
+                        percentile_cont(0.9) WITHIN GROUP (ORDER BY gas_price) as p90
+                    FROM (
+                        SELECT
+                            blocks.timestamp::date as date,
+                            (COALESCE(
+                                transactions.gas_price,
+                                blocks.base_fee_per_gas + LEAST(
+                                    transactions.max_priority_fee_per_gas,
+                                    transactions.max_fee_per_gas - blocks.base_fee_per_gas
+                                )
+                            ) / $1)::float as gas_price
+                        FROM transactions
+                        JOIN blocks ON transactions.block_hash = blocks.hash
+                        WHERE
+                            blocks.timestamp != to_timestamp(0) AND
+                            blocks.consensus = true {filter}
+                    ) gas_prices
+                    WHERE gas_price IS NOT NULL
+                    GROUP BY date
+                ) percentiles
+            "#,
+            [GWEI.into()],
+            "blocks.timestamp",
+            range,
+        )
+    }
+}
+
+pub type GasPricePercentilesRemote =
+    RemoteDatabaseSource<PullAllSeriesWithAndSort<GasPricePercentilesStatement>>;
+
+/// Median is the main value of the chart
+pub struct MedianGasPrice;
+
+impl SeriesMainValue for MedianGasPrice {
+    fn main_value(values: &SeriesValues) -> Result<String, UpdateError> {
+        values
+            .0
+            .get("p50")
+            .cloned()
+            .ok_or_else(|| UpdateError::Internal("median gas price is missing".to_string()))
+    }
+}
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "gasPricePercentiles".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+define_and_impl_resolution_properties!(
+    define_and_impl: {
+        WeeklyProperties: Week,
+        MonthlyProperties: Month,
+        YearlyProperties: Year,
+    },
+    base_impl: Properties
+);
+
+pub type GasPricePercentiles = DirectSeriesVecLocalDbChartSource<
+    GasPricePercentilesRemote,
+    Batch30Days,
+    Properties,
+    MedianGasPrice,
+>;
+
+// Lower resolutions are approximated with averages of daily percentiles
+// weighted by the number of transactions
+pub type GasPricePercentilesWeekly = DirectSeriesVecLocalDbChartSource<
+    AverageSeriesLowerResolution<GasPricePercentiles, NewTxnsInt, Week>,
+    Batch30Weeks,
+    WeeklyProperties,
+    MedianGasPrice,
+>;
+pub type GasPricePercentilesMonthly = DirectSeriesVecLocalDbChartSource<
+    AverageSeriesLowerResolution<GasPricePercentiles, NewTxnsInt, Month>,
+    Batch36Months,
+    MonthlyProperties,
+    MedianGasPrice,
+>;
+pub type GasPricePercentilesYearly = DirectSeriesVecLocalDbChartSource<
+    AverageSeriesLowerResolution<GasPricePercentilesMonthly, NewTxnsMonthlyInt, Year>,
+    Batch30Years,
+    YearlyProperties,
+    MedianGasPrice,
+>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_line_chart_series, tests::simple_test::simple_test_chart};
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_gas_price_percentiles() {
+        // values are medians
+        let (db, _blockscout) = simple_test_chart::<GasPricePercentiles>(
+            "update_gas_price_percentiles",
+            vec![
+                ("2022-11-09", "0"),
+                ("2022-11-10", "1.6851851835"),
+                ("2022-11-11", "1.123456789"),
+                ("2022-11-12", "8.987654312"),
+                ("2022-12-01", "10.111111101"),
+                ("2023-01-01", "1.123456789"),
+                ("2023-02-01", "12.358024679"),
+                ("2023-03-01", "1.123456789"),
+            ],
+        )
+        .await;
+        let series = get_line_chart_series::<NaiveDate>(&db, "gasPricePercentiles", None, None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|point| {
+                (
+                    point.timespan.to_string(),
+                    point.value.0.into_values().collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let expected: Vec<(&str, [&str; 3])> = vec![
+            ("2022-11-09", ["0", "0", "1.123456789"]),
+            ("2022-11-10", ["1.123456789", "1.6851851835", "3.370370367"]),
+            ("2022-11-11", ["1.123456789", "1.123456789", "6.740740734"]),
+            ("2022-11-12", ["1.123456789", "8.987654312", "8.987654312"]),
+            (
+                "2022-12-01",
+                ["1.123456789", "10.111111101", "10.111111101"],
+            ),
+            ("2023-01-01", ["1.123456789", "1.123456789", "1.123456789"]),
+            (
+                "2023-02-01",
+                ["4.493827156", "12.358024679", "12.358024679"],
+            ),
+            ("2023-03-01", ["1.123456789", "1.123456789", "1.123456789"]),
+        ];
+        let expected = expected
+            .into_iter()
+            .map(|(date, values)| {
+                (
+                    date.to_string(),
+                    values.into_iter().map(String::from).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        // series are ordered by name, i.e. p10, p50, p90
+        assert_eq!(series, expected);
+    }
+}
@@ -1,11 +1,14 @@
 mod accounts_growth;
 mod active_accounts;
+mod average_base_fee;
 mod average_block_rewards;
 mod average_block_size;
 mod average_gas_limit;
 mod average_gas_price;
+mod average_priority_fee;
 mod average_txn_fee;
 mod contracts_growth;
+mod gas_price_percentiles;
 mod gas_used_growth;
 mod native_coin_holders_growth;
 mod native_coin_supply;
@@ -16,19 +19,28 @@ mod new_contracts;
 mod new_native_coin_holders;
 mod new_native_coin_transfers;
 mod new_txns;
+mod new_txns_by_status;
 mod new_verified_contracts;
+pub mod rollups;
 mod txns_fee;
 mod txns_growth;
 mod txns_success_rate;
+pub mod user_ops;
 mod verified_contracts_growth;
 
 #[cfg(test)]
 mod mock;
 
+/// How long hourly charts are stored (older points are removed)
+pub const HOURLY_CHARTS_RETENTION_HOURS: u64 = 30 * 24;
+
 pub use accounts_growth::{
     AccountsGrowth, AccountsGrowthMonthly, AccountsGrowthWeekly, AccountsGrowthYearly,
 };
 pub use active_accounts::ActiveAccounts;
+pub use average_base_fee::{
+    AverageBaseFee, AverageBaseFeeMonthly, AverageBaseFeeWeekly, AverageBaseFeeYearly,
+};
 pub use average_block_rewards::{
     AverageBlockRewards, AverageBlockRewardsMonthly, AverageBlockRewardsWeekly,
     AverageBlockRewardsYearly,
@@ -40,7 +52,12 @@ pub use average_gas_limit::{
     AverageGasLimit, AverageGasLimitMonthly, AverageGasLimitWeekly, AverageGasLimitYearly,
 };
 pub use average_gas_price::{
-    AverageGasPrice, AverageGasPriceMonthly, AverageGasPriceWeekly, AverageGasPriceYearly,
+    AverageGasPrice, AverageGasPriceHourly, AverageGasPriceMonthly, AverageGasPriceWeekly,
+    AverageGasPriceYearly,
+};
+pub use average_priority_fee::{
+    AveragePriorityFee, AveragePriorityFeeMonthly, AveragePriorityFeeWeekly,
+    AveragePriorityFeeYearly,
 };
 pub use average_txn_fee::{
     AverageTxnFee, AverageTxnFeeMonthly, AverageTxnFeeWeekly, AverageTxnFeeYearly,
@@ -48,6 +65,10 @@ pub use average_txn_fee::{
 pub use contracts_growth::{
     ContractsGrowth, ContractsGrowthMonthly, ContractsGrowthWeekly, ContractsGrowthYearly,
 };
+pub use gas_price_percentiles::{
+    GasPricePercentiles, GasPricePercentilesMonthly, GasPricePercentilesWeekly,
+    GasPricePercentilesYearly,
+};
 pub use gas_used_growth::{
     GasUsedGrowth, GasUsedGrowthMonthly, GasUsedGrowthWeekly, GasUsedGrowthYearly,
 };
@@ -60,7 +81,10 @@ pub use native_coin_supply::{
 };
 pub use new_accounts::{NewAccounts, NewAccountsMonthly, NewAccountsWeekly, NewAccountsYearly};
 pub use new_block_rewards::{NewBlockRewardsInt, NewBlockRewardsMonthlyInt};
-pub use new_blocks::{NewBlocks, NewBlocksMonthly, NewBlocksWeekly, NewBlocksYearly};
+pub use new_blocks::{
+    NewBlocks, NewBlocksHourly, NewBlocksMonthly, NewBlocksStatement, NewBlocksWeekly,
+    NewBlocksYearly,
+};
 pub use new_contracts::{
     NewContracts, NewContractsMonthly, NewContractsWeekly, NewContractsYearly,
 };
@@ -72,7 +96,11 @@ pub use new_native_coin_transfers::{
     NewNativeCoinTransfers, NewNativeCoinTransfersInt, NewNativeCoinTransfersMonthly,
     NewNativeCoinTransfersWeekly, NewNativeCoinTransfersYearly,
 };
-pub use new_txns::{NewTxns, NewTxnsInt, NewTxnsMonthly, NewTxnsWeekly, NewTxnsYearly};
+pub use new_txns::{
+    NewTxns, NewTxnsHourly, NewTxnsInt, NewTxnsMonthly, NewTxnsStatement, NewTxnsWeekly,
+    NewTxnsYearly,
+};
+pub use new_txns_by_status::NewTxnsByStatus;
 pub use new_verified_contracts::{
     NewVerifiedContracts, NewVerifiedContractsMonthly, NewVerifiedContractsWeekly,
     NewVerifiedContractsYearly,
@@ -139,6 +139,8 @@ mod tests {
         let parameters = UpdateParameters {
             db: &db,
             blockscout: &blockscout,
+            user_ops: None,
+            chain: "",
             update_time_override: Some(current_time),
             force_full: false,
         };
@@ -8,22 +8,25 @@ use crate::{
         },
         local_db::{
             parameters::update::batching::parameters::{
-                Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
+                Batch24Hours, Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
             },
             DirectVecLocalDbChartSource,
         },
         remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
     },
     define_and_impl_resolution_properties,
-    types::timespans::{Month, Week, Year},
+    types::timespans::{Hour, Month, Week, Year},
     utils::sql_with_range_filter_opt,
     ChartProperties, Named,
 };
 
 use chrono::NaiveDate;
+use chrono_tz::Tz;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, Statement};
 
+use super::HOURLY_CHARTS_RETENTION_HOURS;
+
 pub struct NewBlocksStatement;
 
 impl StatementFromRange for NewBlocksStatement {
@@ -47,9 +50,58 @@ impl StatementFromRange for NewBlocksStatement {
     }
 }
 
+impl NewBlocksStatement {
+    /// Same as [`StatementFromRange::get_statement`], but days start at local
+    /// midnight in `timezone` (see [`crate::local_time`])
+    pub fn get_statement_in(range: Option<Range<DateTimeUtc>>, timezone: &Tz) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    date(blocks.timestamp AT TIME ZONE 'UTC' AT TIME ZONE $1) as date,
+                    COUNT(*)::TEXT as value
+                FROM public.blocks
+                WHERE
+                    blocks.timestamp != to_timestamp(0) AND
+                    consensus = true {filter}
+                GROUP BY date;
+            "#,
+            [timezone.name().into()],
+            "blocks.timestamp",
+            range
+        )
+    }
+}
+
+pub struct HourlyNewBlocksStatement;
+
+impl StatementFromRange for HourlyNewBlocksStatement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    date_trunc('hour', blocks.timestamp) as date,
+                    COUNT(*)::TEXT as value
+                FROM public.blocks
+                WHERE
+                    blocks.timestamp != to_timestamp(0) AND
+                    consensus = true {filter}
+                GROUP BY date;
+            "#,
+            [],
+            "blocks.timestamp",
+            range
+        )
+    }
+}
+
 pub type NewBlocksRemote =
     RemoteDatabaseSource<PullAllWithAndSort<NewBlocksStatement, NaiveDate, String>>;
 
+pub type HourlyNewBlocksRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<HourlyNewBlocksStatement, Hour, String>>;
+
 pub struct Properties;
 
 impl Named for Properties {
@@ -66,6 +118,26 @@ impl ChartProperties for Properties {
     }
 }
 
+pub struct HourlyProperties;
+
+impl Named for HourlyProperties {
+    fn name() -> String {
+        Properties::name()
+    }
+}
+
+impl ChartProperties for HourlyProperties {
+    type Resolution = Hour;
+
+    fn chart_type() -> ChartType {
+        Properties::chart_type()
+    }
+
+    fn max_stored_timespans() -> Option<u64> {
+        Some(HOURLY_CHARTS_RETENTION_HOURS)
+    }
+}
+
 define_and_impl_resolution_properties!(
     define_and_impl: {
         WeeklyProperties: Week,
@@ -75,6 +147,8 @@ define_and_impl_resolution_properties!(
     base_impl: Properties
 );
 
+pub type NewBlocksHourly =
+    DirectVecLocalDbChartSource<HourlyNewBlocksRemote, Batch24Hours, HourlyProperties>;
 pub type NewBlocks = DirectVecLocalDbChartSource<NewBlocksRemote, Batch30Days, Properties>;
 pub type NewBlocksInt = MapParseTo<NewBlocks, i64>;
 pub type NewBlocksWeekly = DirectVecLocalDbChartSource<
@@ -132,14 +206,14 @@ mod tests {
         chart_data::Entity::insert_many([
             chart_data::ActiveModel {
                 chart_id: Set(1),
-                date: Set(NaiveDate::from_str("2022-11-10").unwrap()),
+                date: Set(dt("2022-11-10T00:00:00")),
                 value: Set(3.to_string()),
                 min_blockscout_block: Set(Some(min_blockscout_block)),
                 ..Default::default()
             },
             chart_data::ActiveModel {
                 chart_id: Set(1),
-                date: Set(NaiveDate::from_str("2022-11-11").unwrap()),
+                date: Set(dt("2022-11-11T00:00:00")),
                 value: Set(100.to_string()),
                 min_blockscout_block: Set(Some(min_blockscout_block)),
                 ..Default::default()
@@ -163,8 +237,11 @@ mod tests {
         let mut cx = UpdateContext {
             db: &db,
             blockscout: &blockscout,
+            user_ops: None,
+            chain: "",
             time: current_time,
             force_full: false,
+            cache: Default::default(),
         };
         NewBlocks::update_recursively(&cx).await.unwrap();
         let data = get_line_chart_data::<NaiveDate>(
@@ -256,8 +333,11 @@ mod tests {
         let cx = UpdateContext {
             db: &db,
             blockscout: &blockscout,
+            user_ops: None,
+            chain: "",
             time: current_time,
             force_full: true,
+            cache: Default::default(),
         };
         NewBlocks::update_recursively(&cx).await.unwrap();
         let data = get_line_chart_data::<NaiveDate>(
@@ -316,28 +396,28 @@ mod tests {
         chart_data::Entity::insert_many([
             chart_data::ActiveModel {
                 chart_id: Set(1),
-                date: Set(NaiveDate::from_str("2022-11-09").unwrap()),
+                date: Set(dt("2022-11-09T00:00:00")),
                 value: Set(2.to_string()),
                 min_blockscout_block: Set(Some(min_blockscout_block)),
                 ..Default::default()
             },
             chart_data::ActiveModel {
                 chart_id: Set(1),
-                date: Set(NaiveDate::from_str("2022-11-10").unwrap()),
+                date: Set(dt("2022-11-10T00:00:00")),
                 value: Set(4.to_string()),
                 min_blockscout_block: Set(Some(min_blockscout_block)),
                 ..Default::default()
             },
             chart_data::ActiveModel {
                 chart_id: Set(1),
-                date: Set(NaiveDate::from_str("2022-11-11").unwrap()),
+                date: Set(dt("2022-11-11T00:00:00")),
                 value: Set(5.to_string()),
                 min_blockscout_block: Set(Some(min_blockscout_block)),
                 ..Default::default()
             },
             chart_data::ActiveModel {
                 chart_id: Set(1),
-                date: Set(NaiveDate::from_str("2022-11-12").unwrap()),
+                date: Set(dt("2022-11-12T00:00:00")),
                 value: Set(2.to_string()),
                 min_blockscout_block: Set(Some(min_blockscout_block)),
                 ..Default::default()
@@ -359,8 +439,11 @@ mod tests {
         let cx = UpdateContext {
             db: &db,
             blockscout: &blockscout,
+            user_ops: None,
+            chain: "",
             time: current_time,
             force_full: false,
+            cache: Default::default(),
         };
         NewBlocks::update_recursively(&cx).await.unwrap();
         let data = get_line_chart_data::<NaiveDate>(
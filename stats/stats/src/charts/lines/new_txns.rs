@@ -8,22 +8,25 @@ use crate::{
         },
         local_db::{
             parameters::update::batching::parameters::{
-                Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
+                Batch24Hours, Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
             },
             DirectVecLocalDbChartSource,
         },
         remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
     },
     define_and_impl_resolution_properties,
-    types::timespans::{Month, Week, Year},
+    types::timespans::{Hour, Month, Week, Year},
     utils::sql_with_range_filter_opt,
     ChartProperties, Named,
 };
 
 use chrono::NaiveDate;
+use chrono_tz::Tz;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, Statement};
 
+use super::HOURLY_CHARTS_RETENTION_HOURS;
+
 pub struct NewTxnsStatement;
 
 impl StatementFromRange for NewTxnsStatement {
@@ -48,9 +51,60 @@ impl StatementFromRange for NewTxnsStatement {
     }
 }
 
+impl NewTxnsStatement {
+    /// Same as [`StatementFromRange::get_statement`], but days start at local
+    /// midnight in `timezone` (see [`crate::local_time`])
+    pub fn get_statement_in(range: Option<Range<DateTimeUtc>>, timezone: &Tz) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    date(b.timestamp AT TIME ZONE 'UTC' AT TIME ZONE $1) as date,
+                    COUNT(*)::TEXT as value
+                FROM transactions t
+                JOIN blocks       b ON t.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true {filter}
+                GROUP BY date;
+            "#,
+            [timezone.name().into()],
+            "b.timestamp",
+            range
+        )
+    }
+}
+
+pub struct HourlyNewTxnsStatement;
+
+impl StatementFromRange for HourlyNewTxnsStatement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    date_trunc('hour', b.timestamp) as date,
+                    COUNT(*)::TEXT as value
+                FROM transactions t
+                JOIN blocks       b ON t.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true {filter}
+                GROUP BY date;
+            "#,
+            [],
+            "b.timestamp",
+            range
+        )
+    }
+}
+
 pub type NewTxnsRemote =
     RemoteDatabaseSource<PullAllWithAndSort<NewTxnsStatement, NaiveDate, String>>;
 
+pub type HourlyNewTxnsRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<HourlyNewTxnsStatement, Hour, String>>;
+
 pub struct Properties;
 
 impl Named for Properties {
@@ -67,6 +121,26 @@ impl ChartProperties for Properties {
     }
 }
 
+pub struct HourlyProperties;
+
+impl Named for HourlyProperties {
+    fn name() -> String {
+        Properties::name()
+    }
+}
+
+impl ChartProperties for HourlyProperties {
+    type Resolution = Hour;
+
+    fn chart_type() -> ChartType {
+        Properties::chart_type()
+    }
+
+    fn max_stored_timespans() -> Option<u64> {
+        Some(HOURLY_CHARTS_RETENTION_HOURS)
+    }
+}
+
 define_and_impl_resolution_properties!(
     define_and_impl: {
         WeeklyProperties: Week,
@@ -76,6 +150,8 @@ define_and_impl_resolution_properties!(
     base_impl: Properties
 );
 
+pub type NewTxnsHourly =
+    DirectVecLocalDbChartSource<HourlyNewTxnsRemote, Batch24Hours, HourlyProperties>;
 pub type NewTxns = DirectVecLocalDbChartSource<NewTxnsRemote, Batch30Days, Properties>;
 pub type NewTxnsInt = MapParseTo<NewTxns, i64>;
 pub type NewTxnsWeekly = DirectVecLocalDbChartSource<
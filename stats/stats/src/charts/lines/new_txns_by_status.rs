@@ -0,0 +1,122 @@
+//! Number of successful and failed transactions (multi-series chart)
+
+use std::ops::Range;
+
+use crate::{
+    data_source::kinds::{
+        local_db::{
+            parameters::update::batching::parameters::Batch30Days,
+            DirectSeriesVecLocalDbChartSource,
+        },
+        remote_db::{PullAllSeriesWithAndSort, RemoteDatabaseSource, StatementFromRange},
+    },
+    utils::sql_with_range_filter_opt,
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+pub struct NewTxnsByStatusStatement;
+
+impl StatementFromRange for NewTxnsByStatusStatement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    date(b.timestamp) as date,
+                    (CASE WHEN t.status = 1 THEN 'success' ELSE 'failed' END) as series,
+                    COUNT(*)::TEXT as value
+                FROM transactions t
+                JOIN blocks       b ON t.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true {filter}
+                GROUP BY date, series;
+            "#,
+            [],
+            "b.timestamp",
+            range
+        )
+    }
+}
+
+pub type NewTxnsByStatusRemote =
+    RemoteDatabaseSource<PullAllSeriesWithAndSort<NewTxnsByStatusStatement>>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "newTxnsByStatus".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub type NewTxnsByStatus =
+    DirectSeriesVecLocalDbChartSource<NewTxnsByStatusRemote, Batch30Days, Properties>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_line_chart_series, tests::simple_test::simple_test_chart};
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_txns_by_status() {
+        // values are totals of all series
+        let (db, _blockscout) = simple_test_chart::<NewTxnsByStatus>(
+            "update_new_txns_by_status",
+            vec![
+                ("2022-11-09", "5"),
+                ("2022-11-10", "12"),
+                ("2022-11-11", "14"),
+                ("2022-11-12", "5"),
+                ("2022-12-01", "5"),
+                ("2023-01-01", "1"),
+                ("2023-02-01", "4"),
+                ("2023-03-01", "1"),
+            ],
+        )
+        .await;
+        let series = get_line_chart_series::<NaiveDate>(
+            &db,
+            "newTxnsByStatus",
+            Some("2023-02-01".parse().unwrap()),
+            None,
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|point| {
+            (
+                point.timespan.to_string(),
+                point.value.0.into_iter().collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+        assert_eq!(
+            series,
+            vec![
+                (
+                    "2023-02-01".to_string(),
+                    vec![("success".to_string(), "4".to_string())]
+                ),
+                (
+                    "2023-03-01".to_string(),
+                    vec![("failed".to_string(), "1".to_string())]
+                ),
+            ]
+        );
+    }
+}
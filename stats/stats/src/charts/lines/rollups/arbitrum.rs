@@ -0,0 +1,76 @@
+//! Arbitrum: batches are stored in `arbitrum_l1_batches`, the time of posting
+//! is taken from the commitment transaction on L1.
+
+use std::ops::Range;
+
+use sea_orm::{prelude::DateTimeUtc, DbBackend, Statement};
+
+use crate::utils::sql_with_range_filter_opt;
+
+use super::{RollupStatements, ETHER};
+
+pub struct Arbitrum;
+
+impl RollupStatements for Arbitrum {
+    fn new_batches(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    DATE(l1_txn.timestamp) as date,
+                    COUNT(*)::TEXT as value
+                FROM arbitrum_l1_batches batches
+                JOIN arbitrum_lifecycle_l1_transactions l1_txn
+                    ON batches.commitment_id = l1_txn.id
+                WHERE
+                    l1_txn.timestamp != to_timestamp(0) {filter}
+                GROUP BY date
+            "#,
+            [],
+            "l1_txn.timestamp",
+            range
+        )
+    }
+
+    fn average_batch_size(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    DATE(l1_txn.timestamp) as date,
+                    ROUND(AVG(batches.transactions_count))::TEXT as value
+                FROM arbitrum_l1_batches batches
+                JOIN arbitrum_lifecycle_l1_transactions l1_txn
+                    ON batches.commitment_id = l1_txn.id
+                WHERE
+                    l1_txn.timestamp != to_timestamp(0) {filter}
+                GROUP BY date
+            "#,
+            [],
+            "l1_txn.timestamp",
+            range
+        )
+    }
+
+    fn l1_data_fees(range: Option<Range<DateTimeUtc>>) -> Statement {
+        // `gas_price` of arbitrum transactions is the effective one
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    DATE(b.timestamp) as date,
+                    (SUM(t.gas_used_for_l1 * t.gas_price) / $1)::FLOAT as value
+                FROM transactions t
+                JOIN blocks       b ON t.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true AND
+                    t.gas_used_for_l1 IS NOT NULL {filter}
+                GROUP BY DATE(b.timestamp)
+            "#,
+            [ETHER.into()],
+            "b.timestamp",
+            range
+        )
+    }
+}
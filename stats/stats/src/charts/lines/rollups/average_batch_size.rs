@@ -0,0 +1,150 @@
+//! Average number of L2 transactions in a batch
+
+use std::{marker::PhantomData, ops::Range};
+
+use crate::{
+    data_source::kinds::{
+        data_manipulation::{
+            map::{MapParseTo, MapToString},
+            resolutions::average::AverageLowerResolution,
+        },
+        local_db::{
+            parameters::update::batching::parameters::{
+                Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
+            },
+            DirectVecLocalDbChartSource,
+        },
+        remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
+    },
+    define_and_impl_resolution_properties,
+    types::timespans::{Month, Week, Year},
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, Statement};
+
+use super::{
+    new_batches::{NewBatchesInt, NewBatchesMonthlyInt},
+    RollupStatements,
+};
+
+pub struct AverageBatchSizeStatement<C>(PhantomData<C>);
+
+impl<C: RollupStatements> StatementFromRange for AverageBatchSizeStatement<C> {
+    fn get_statement(range: Option<Range<DateTimeUtc>>) -> Statement {
+        C::average_batch_size(range)
+    }
+}
+
+pub type AverageBatchSizeRemote<C> =
+    RemoteDatabaseSource<PullAllWithAndSort<AverageBatchSizeStatement<C>, NaiveDate, String>>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "averageBatchSize".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+define_and_impl_resolution_properties!(
+    define_and_impl: {
+        WeeklyProperties: Week,
+        MonthlyProperties: Month,
+        YearlyProperties: Year,
+    },
+    base_impl: Properties
+);
+
+pub type AverageBatchSize<C> =
+    DirectVecLocalDbChartSource<AverageBatchSizeRemote<C>, Batch30Days, Properties>;
+
+pub type AverageBatchSizeWeekly<C> = DirectVecLocalDbChartSource<
+    MapToString<
+        AverageLowerResolution<MapParseTo<AverageBatchSize<C>, f64>, NewBatchesInt<C>, Week>,
+    >,
+    Batch30Weeks,
+    WeeklyProperties,
+>;
+pub type AverageBatchSizeMonthly<C> = DirectVecLocalDbChartSource<
+    MapToString<
+        AverageLowerResolution<MapParseTo<AverageBatchSize<C>, f64>, NewBatchesInt<C>, Month>,
+    >,
+    Batch36Months,
+    MonthlyProperties,
+>;
+pub type AverageBatchSizeYearly<C> = DirectVecLocalDbChartSource<
+    MapToString<
+        AverageLowerResolution<
+            MapParseTo<AverageBatchSizeMonthly<C>, f64>,
+            NewBatchesMonthlyInt<C>,
+            Year,
+        >,
+    >,
+    Batch30Years,
+    YearlyProperties,
+>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charts::lines::rollups::{
+        tests::{simple_test_arbitrum_chart, simple_test_optimism_chart},
+        Arbitrum, Optimism,
+    };
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_average_batch_size_optimism() {
+        simple_test_optimism_chart::<AverageBatchSize<Optimism>>(
+            "update_average_batch_size_optimism",
+            vec![
+                ("2022-11-10", "9"),
+                ("2022-11-11", "12"),
+                ("2022-11-12", "7"),
+                ("2022-12-01", "5"),
+                ("2023-02-01", "5"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_average_batch_size_weekly_optimism() {
+        // (9 * 2 + 12 + 7) / 4
+        simple_test_optimism_chart::<AverageBatchSizeWeekly<Optimism>>(
+            "update_average_batch_size_weekly_optimism",
+            vec![
+                ("2022-11-07", "9.25"),
+                ("2022-11-28", "5"),
+                ("2023-01-30", "5"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_average_batch_size_arbitrum() {
+        simple_test_arbitrum_chart::<AverageBatchSize<Arbitrum>>(
+            "update_average_batch_size_arbitrum",
+            vec![
+                ("2022-11-10", "15"),
+                ("2022-11-11", "6"),
+                ("2023-01-01", "3"),
+            ],
+        )
+        .await;
+    }
+}
@@ -0,0 +1,128 @@
+//! Fees paid by L2 transactions for posting their data to L1
+
+use std::{marker::PhantomData, ops::Range};
+
+use crate::{
+    data_source::kinds::{
+        data_manipulation::{
+            map::{MapParseTo, MapToString},
+            resolutions::sum::SumLowerResolution,
+        },
+        local_db::{
+            parameters::update::batching::parameters::{
+                Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
+            },
+            DirectVecLocalDbChartSource,
+        },
+        remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
+    },
+    define_and_impl_resolution_properties,
+    types::timespans::{Month, Week, Year},
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, Statement};
+
+use super::RollupStatements;
+
+pub struct L1DataFeesStatement<C>(PhantomData<C>);
+
+impl<C: RollupStatements> StatementFromRange for L1DataFeesStatement<C> {
+    fn get_statement(range: Option<Range<DateTimeUtc>>) -> Statement {
+        C::l1_data_fees(range)
+    }
+}
+
+pub type L1DataFeesRemote<C> =
+    RemoteDatabaseSource<PullAllWithAndSort<L1DataFeesStatement<C>, NaiveDate, f64>>;
+
+pub type L1DataFeesRemoteString<C> = MapToString<L1DataFeesRemote<C>>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "l1DataFees".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+define_and_impl_resolution_properties!(
+    define_and_impl: {
+        WeeklyProperties: Week,
+        MonthlyProperties: Month,
+        YearlyProperties: Year,
+    },
+    base_impl: Properties
+);
+
+pub type L1DataFees<C> =
+    DirectVecLocalDbChartSource<L1DataFeesRemoteString<C>, Batch30Days, Properties>;
+pub type L1DataFeesFloat<C> = MapParseTo<L1DataFees<C>, f64>;
+pub type L1DataFeesWeekly<C> = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<L1DataFeesFloat<C>, Week>>,
+    Batch30Weeks,
+    WeeklyProperties,
+>;
+pub type L1DataFeesMonthly<C> = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<L1DataFeesFloat<C>, Month>>,
+    Batch36Months,
+    MonthlyProperties,
+>;
+pub type L1DataFeesMonthlyFloat<C> = MapParseTo<L1DataFeesMonthly<C>, f64>;
+pub type L1DataFeesYearly<C> = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<L1DataFeesMonthlyFloat<C>, Year>>,
+    Batch30Years,
+    YearlyProperties,
+>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charts::lines::rollups::{
+        tests::{simple_test_arbitrum_chart, simple_test_optimism_chart},
+        Arbitrum, Optimism,
+    };
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_l1_data_fees_optimism() {
+        simple_test_optimism_chart::<L1DataFees<Optimism>>(
+            "update_l1_data_fees_optimism",
+            vec![
+                ("2022-11-09", "0"),
+                ("2022-11-10", "0.027"),
+                ("2022-11-11", "0.077"),
+                ("2022-11-12", "0.04"),
+                ("2022-12-01", "0.045"),
+                ("2023-01-01", "0.01"),
+                ("2023-02-01", "0.044"),
+                ("2023-03-01", "0.012"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_l1_data_fees_arbitrum() {
+        simple_test_arbitrum_chart::<L1DataFees<Arbitrum>>(
+            "update_l1_data_fees_arbitrum",
+            vec![
+                ("2022-11-10", "0.008987654312"),
+                ("2022-12-01", "0.032580246881"),
+                ("2023-02-01", "0.038197530826"),
+            ],
+        )
+        .await;
+    }
+}
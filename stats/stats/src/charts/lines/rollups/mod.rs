@@ -0,0 +1,120 @@
+//! Charts specific to rollups (L2 chains).
+//!
+//! The data comes from the rollup tables that blockscout indexes
+//! for the corresponding chain type, so the charts can only be
+//! enabled on chains of that type.
+//!
+//! Most of the charts are the same for each rollup kind and differ only in
+//! the queries; they are generic over [`RollupStatements`] (implemented by
+//! [`Optimism`] and [`Arbitrum`]).
+
+mod arbitrum;
+mod average_batch_size;
+mod l1_data_fees;
+mod new_batches;
+mod new_blobs;
+mod optimism;
+
+use std::ops::Range;
+
+use sea_orm::{prelude::DateTimeUtc, Statement};
+
+pub use arbitrum::Arbitrum;
+pub use average_batch_size::{
+    AverageBatchSize, AverageBatchSizeMonthly, AverageBatchSizeWeekly, AverageBatchSizeYearly,
+};
+pub use l1_data_fees::{L1DataFees, L1DataFeesMonthly, L1DataFeesWeekly, L1DataFeesYearly};
+pub use new_batches::{
+    NewBatches, NewBatchesInt, NewBatchesMonthly, NewBatchesMonthlyInt, NewBatchesWeekly,
+    NewBatchesYearly,
+};
+pub use new_blobs::{NewBlobs, NewBlobsMonthly, NewBlobsWeekly, NewBlobsYearly};
+pub use optimism::Optimism;
+
+/// Queries for the charts that are present for each rollup kind.
+///
+/// All of them return `date` and `value` columns, daily values are
+/// expected.
+pub trait RollupStatements: Send + Sync + 'static {
+    /// Number of batches posted to L1 (`TEXT` integer values)
+    fn new_batches(range: Option<Range<DateTimeUtc>>) -> Statement;
+    /// Average number of L2 transactions in a batch (`TEXT` values)
+    fn average_batch_size(range: Option<Range<DateTimeUtc>>) -> Statement;
+    /// Fees paid by L2 transactions for posting their data to L1,
+    /// in native coins (`FLOAT` values)
+    fn l1_data_fees(range: Option<Range<DateTimeUtc>>) -> Statement;
+}
+
+const ETHER: i64 = i64::pow(10, 18);
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::{
+        data_source::DataSource,
+        tests::{
+            init_db::init_db_all,
+            mock_blockscout::fill_mock_blockscout_data,
+            mock_rollups::{fill_mock_arbitrum_data, fill_mock_optimism_data},
+            point_construction::d,
+            simple_test::simple_test_chart_with_dbs,
+        },
+        ChartProperties,
+    };
+
+    /// [`simple_test_chart`](crate::tests::simple_test::simple_test_chart)
+    /// for a chain with OP stack mock data
+    pub async fn simple_test_optimism_chart<C>(test_name: &str, expected: Vec<(&str, &str)>)
+    where
+        C: DataSource + ChartProperties,
+        C::Resolution: Ord + Clone + Debug,
+    {
+        let (db, blockscout) = init_db_all(test_name).await;
+        fill_mock_blockscout_data(&blockscout, d("2023-03-01")).await;
+        fill_mock_optimism_data(&blockscout).await;
+        simple_test_chart_with_dbs::<C>(&db, &blockscout, None, expected).await;
+    }
+
+    /// [`simple_test_chart`](crate::tests::simple_test::simple_test_chart)
+    /// for a chain with Arbitrum mock data
+    pub async fn simple_test_arbitrum_chart<C>(test_name: &str, expected: Vec<(&str, &str)>)
+    where
+        C: DataSource + ChartProperties,
+        C::Resolution: Ord + Clone + Debug,
+    {
+        let (db, blockscout) = init_db_all(test_name).await;
+        fill_mock_blockscout_data(&blockscout, d("2023-03-01")).await;
+        fill_mock_arbitrum_data(&blockscout).await;
+        simple_test_chart_with_dbs::<C>(&db, &blockscout, None, expected).await;
+    }
+
+    fn check_statements<C: RollupStatements>() {
+        let range = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap()
+            ..Utc.with_ymd_and_hms(2024, 3, 2, 0, 0, 0).unwrap();
+        let statements: [(fn(_) -> Statement, usize); 3] = [
+            (C::new_batches, 0),
+            (C::average_batch_size, 0),
+            (C::l1_data_fees, 1),
+        ];
+        for (statement, extra_values) in statements {
+            let unfiltered = statement(None);
+            assert_eq!(
+                unfiltered.values.map(|v| v.0.len()).unwrap_or_default(),
+                extra_values
+            );
+            let filtered = statement(Some(range.clone()));
+            assert_eq!(filtered.values.unwrap().0.len(), extra_values + 2);
+            assert!(filtered.sql.contains(&format!("< ${}", extra_values + 2)));
+        }
+    }
+
+    #[test]
+    fn statements_are_filtered_by_range() {
+        check_statements::<Optimism>();
+        check_statements::<Arbitrum>();
+    }
+}
@@ -0,0 +1,136 @@
+//! Number of batches posted to L1
+
+use std::{marker::PhantomData, ops::Range};
+
+use crate::{
+    data_source::kinds::{
+        data_manipulation::{
+            map::{MapParseTo, MapToString},
+            resolutions::sum::SumLowerResolution,
+        },
+        local_db::{
+            parameters::update::batching::parameters::{
+                Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
+            },
+            DirectVecLocalDbChartSource,
+        },
+        remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
+    },
+    define_and_impl_resolution_properties,
+    types::timespans::{Month, Week, Year},
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, Statement};
+
+use super::RollupStatements;
+
+pub struct NewBatchesStatement<C>(PhantomData<C>);
+
+impl<C: RollupStatements> StatementFromRange for NewBatchesStatement<C> {
+    fn get_statement(range: Option<Range<DateTimeUtc>>) -> Statement {
+        C::new_batches(range)
+    }
+}
+
+pub type NewBatchesRemote<C> =
+    RemoteDatabaseSource<PullAllWithAndSort<NewBatchesStatement<C>, NaiveDate, String>>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "newBatches".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+define_and_impl_resolution_properties!(
+    define_and_impl: {
+        WeeklyProperties: Week,
+        MonthlyProperties: Month,
+        YearlyProperties: Year,
+    },
+    base_impl: Properties
+);
+
+pub type NewBatches<C> = DirectVecLocalDbChartSource<NewBatchesRemote<C>, Batch30Days, Properties>;
+pub type NewBatchesInt<C> = MapParseTo<NewBatches<C>, i64>;
+pub type NewBatchesWeekly<C> = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<NewBatchesInt<C>, Week>>,
+    Batch30Weeks,
+    WeeklyProperties,
+>;
+pub type NewBatchesMonthly<C> = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<NewBatchesInt<C>, Month>>,
+    Batch36Months,
+    MonthlyProperties,
+>;
+pub type NewBatchesMonthlyInt<C> = MapParseTo<NewBatchesMonthly<C>, i64>;
+pub type NewBatchesYearly<C> = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<NewBatchesMonthlyInt<C>, Year>>,
+    Batch30Years,
+    YearlyProperties,
+>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charts::lines::rollups::{
+        tests::{simple_test_arbitrum_chart, simple_test_optimism_chart},
+        Arbitrum, Optimism,
+    };
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_batches_optimism() {
+        simple_test_optimism_chart::<NewBatches<Optimism>>(
+            "update_new_batches_optimism",
+            vec![
+                ("2022-11-10", "2"),
+                ("2022-11-11", "1"),
+                ("2022-11-12", "1"),
+                ("2022-12-01", "1"),
+                ("2023-02-01", "1"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_batches_weekly_optimism() {
+        simple_test_optimism_chart::<NewBatchesWeekly<Optimism>>(
+            "update_new_batches_weekly_optimism",
+            vec![
+                ("2022-11-07", "4"),
+                ("2022-11-28", "1"),
+                ("2023-01-30", "1"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_batches_arbitrum() {
+        simple_test_arbitrum_chart::<NewBatches<Arbitrum>>(
+            "update_new_batches_arbitrum",
+            vec![
+                ("2022-11-10", "2"),
+                ("2022-11-11", "2"),
+                ("2023-01-01", "1"),
+            ],
+        )
+        .await;
+    }
+}
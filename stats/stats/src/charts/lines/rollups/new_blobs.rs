@@ -0,0 +1,120 @@
+//! Number of EIP-4844 blobs posted to L1.
+//!
+//! Only OP stack chains are supported: blockscout does not index
+//! blobs of Arbitrum batches (only the fact that a batch was posted in blobs).
+
+use std::ops::Range;
+
+use crate::{
+    data_source::kinds::{
+        data_manipulation::{
+            map::{MapParseTo, MapToString},
+            resolutions::sum::SumLowerResolution,
+        },
+        local_db::{
+            parameters::update::batching::parameters::{
+                Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
+            },
+            DirectVecLocalDbChartSource,
+        },
+        remote_db::{PullAllWithAndSort, RemoteDatabaseSource, StatementFromRange},
+    },
+    define_and_impl_resolution_properties,
+    types::timespans::{Month, Week, Year},
+    utils::sql_with_range_filter_opt,
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+pub struct NewBlobsStatement;
+
+impl StatementFromRange for NewBlobsStatement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    DATE(blobs.l1_timestamp) as date,
+                    COUNT(*)::TEXT as value
+                FROM op_frame_sequence_blobs blobs
+                WHERE
+                    blobs.type = 'eip4844' {filter}
+                GROUP BY date
+            "#,
+            [],
+            "blobs.l1_timestamp",
+            range
+        )
+    }
+}
+
+pub type NewBlobsRemote =
+    RemoteDatabaseSource<PullAllWithAndSort<NewBlobsStatement, NaiveDate, String>>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "newBlobs".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+define_and_impl_resolution_properties!(
+    define_and_impl: {
+        WeeklyProperties: Week,
+        MonthlyProperties: Month,
+        YearlyProperties: Year,
+    },
+    base_impl: Properties
+);
+
+pub type NewBlobs = DirectVecLocalDbChartSource<NewBlobsRemote, Batch30Days, Properties>;
+pub type NewBlobsInt = MapParseTo<NewBlobs, i64>;
+pub type NewBlobsWeekly = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<NewBlobsInt, Week>>,
+    Batch30Weeks,
+    WeeklyProperties,
+>;
+pub type NewBlobsMonthly = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<NewBlobsInt, Month>>,
+    Batch36Months,
+    MonthlyProperties,
+>;
+pub type NewBlobsMonthlyInt = MapParseTo<NewBlobsMonthly, i64>;
+pub type NewBlobsYearly = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<NewBlobsMonthlyInt, Year>>,
+    Batch30Years,
+    YearlyProperties,
+>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charts::lines::rollups::tests::simple_test_optimism_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_blobs() {
+        // celestia blobs are not counted
+        simple_test_optimism_chart::<NewBlobs>(
+            "update_new_blobs",
+            vec![
+                ("2022-11-10", "2"),
+                ("2022-11-11", "1"),
+                ("2023-02-01", "3"),
+            ],
+        )
+        .await;
+    }
+}
@@ -0,0 +1,82 @@
+//! OP stack: batches are frame sequences posted by the batcher
+//! (`op_frame_sequences`), L2 blocks are linked to them in `op_transaction_batches`.
+
+use std::ops::Range;
+
+use sea_orm::{prelude::DateTimeUtc, DbBackend, Statement};
+
+use crate::utils::sql_with_range_filter_opt;
+
+use super::{RollupStatements, ETHER};
+
+pub struct Optimism;
+
+impl RollupStatements for Optimism {
+    fn new_batches(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    DATE(fs.l1_timestamp) as date,
+                    COUNT(*)::TEXT as value
+                FROM op_frame_sequences fs
+                WHERE
+                    fs.l1_timestamp != to_timestamp(0) {filter}
+                GROUP BY date
+            "#,
+            [],
+            "fs.l1_timestamp",
+            range
+        )
+    }
+
+    fn average_batch_size(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    DATE(batches.l1_timestamp) as date,
+                    ROUND(AVG(batches.txns_count))::TEXT as value
+                FROM (
+                    SELECT
+                        fs.l1_timestamp,
+                        COUNT(t.hash) as txns_count
+                    FROM op_frame_sequences fs
+                    JOIN op_transaction_batches tb ON tb.frame_sequence_id = fs.id
+                    LEFT JOIN blocks b ON
+                        b.number = tb.l2_block_number AND
+                        b.consensus = true
+                    LEFT JOIN transactions t ON t.block_hash = b.hash
+                    WHERE
+                        fs.l1_timestamp != to_timestamp(0) {filter}
+                    GROUP BY fs.id
+                ) batches
+                GROUP BY date
+            "#,
+            [],
+            "fs.l1_timestamp",
+            range
+        )
+    }
+
+    fn l1_data_fees(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    DATE(b.timestamp) as date,
+                    (SUM(t.l1_fee) / $1)::FLOAT as value
+                FROM transactions t
+                JOIN blocks       b ON t.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true AND
+                    t.l1_fee IS NOT NULL {filter}
+                GROUP BY DATE(b.timestamp)
+            "#,
+            [ETHER.into()],
+            "b.timestamp",
+            range
+        )
+    }
+}
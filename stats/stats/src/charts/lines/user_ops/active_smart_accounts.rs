@@ -0,0 +1,87 @@
+//! Smart accounts that sent user operations on each day.
+
+use std::ops::Range;
+
+use crate::{
+    data_source::kinds::{
+        local_db::{
+            parameters::update::batching::parameters::Batch30Days, DirectVecLocalDbChartSource,
+        },
+        remote_db::{PullAllWithAndSort, StatementFromRange},
+        user_ops_db::UserOpsDatabaseSource,
+    },
+    utils::sql_with_range_filter_opt,
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+pub struct ActiveSmartAccountsStatement;
+
+impl StatementFromRange for ActiveSmartAccountsStatement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    DATE(b.timestamp) as date,
+                    COUNT(DISTINCT uo.sender)::TEXT as value
+                FROM user_operations uo
+                JOIN blocks          b ON uo.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true {filter}
+                GROUP BY date
+            "#,
+            [],
+            "b.timestamp",
+            range
+        )
+    }
+}
+
+pub type ActiveSmartAccountsRemote =
+    UserOpsDatabaseSource<PullAllWithAndSort<ActiveSmartAccountsStatement, NaiveDate, String>>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "activeSmartAccounts".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+pub type ActiveSmartAccounts =
+    DirectVecLocalDbChartSource<ActiveSmartAccountsRemote, Batch30Days, Properties>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charts::lines::user_ops::tests::simple_test_user_ops_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_active_smart_accounts() {
+        simple_test_user_ops_chart::<ActiveSmartAccounts>(
+            "update_active_smart_accounts",
+            vec![
+                ("2022-11-10", "2"),
+                ("2022-11-11", "1"),
+                ("2022-12-01", "1"),
+                ("2023-02-01", "1"),
+                ("2023-03-01", "1"),
+            ],
+        )
+        .await;
+    }
+}
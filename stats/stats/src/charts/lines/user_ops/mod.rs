@@ -0,0 +1,51 @@
+//! Account abstraction (ERC-4337) charts.
+//!
+//! The data comes from `user-ops-indexer` database
+//! (see [`UserOpsDatabaseSource`](crate::data_source::kinds::user_ops_db::UserOpsDatabaseSource)),
+//! so the charts can only be enabled if it is available.
+
+mod active_smart_accounts;
+mod new_smart_accounts;
+mod new_user_ops;
+mod sponsored_user_ops_share;
+
+pub use active_smart_accounts::ActiveSmartAccounts;
+pub use new_smart_accounts::{
+    NewSmartAccounts, NewSmartAccountsMonthly, NewSmartAccountsWeekly, NewSmartAccountsYearly,
+};
+pub use new_user_ops::{
+    NewUserOps, NewUserOpsInt, NewUserOpsMonthly, NewUserOpsMonthlyInt, NewUserOpsWeekly,
+    NewUserOpsYearly,
+};
+pub use sponsored_user_ops_share::{
+    SponsoredUserOpsShare, SponsoredUserOpsShareMonthly, SponsoredUserOpsShareWeekly,
+    SponsoredUserOpsShareYearly,
+};
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use crate::{
+        data_source::DataSource,
+        tests::{
+            init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data,
+            mock_user_ops::fill_mock_user_ops_data, point_construction::d,
+            simple_test::simple_test_chart_with_dbs,
+        },
+        ChartProperties,
+    };
+
+    /// [`simple_test_chart`](crate::tests::simple_test::simple_test_chart)
+    /// with mock `user-ops-indexer` data
+    pub async fn simple_test_user_ops_chart<C>(test_name: &str, expected: Vec<(&str, &str)>)
+    where
+        C: DataSource + ChartProperties,
+        C::Resolution: Ord + Clone + Debug,
+    {
+        let (db, blockscout) = init_db_all(test_name).await;
+        fill_mock_blockscout_data(&blockscout, d("2023-03-01")).await;
+        fill_mock_user_ops_data(&blockscout).await;
+        simple_test_chart_with_dbs::<C>(&db, &blockscout, Some(&*blockscout), expected).await;
+    }
+}
@@ -0,0 +1,118 @@
+//! Number of smart accounts deployed with a user operation
+//! (i.e. operations with `factory`) on each day
+
+use std::ops::Range;
+
+use crate::{
+    data_source::kinds::{
+        data_manipulation::{
+            map::{MapParseTo, MapToString},
+            resolutions::sum::SumLowerResolution,
+        },
+        local_db::{
+            parameters::update::batching::parameters::{
+                Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
+            },
+            DirectVecLocalDbChartSource,
+        },
+        remote_db::{PullAllWithAndSort, StatementFromRange},
+        user_ops_db::UserOpsDatabaseSource,
+    },
+    define_and_impl_resolution_properties,
+    types::timespans::{Month, Week, Year},
+    utils::sql_with_range_filter_opt,
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+pub struct NewSmartAccountsStatement;
+
+impl StatementFromRange for NewSmartAccountsStatement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    DATE(b.timestamp) as date,
+                    COUNT(*)::TEXT as value
+                FROM user_operations uo
+                JOIN blocks          b ON uo.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true AND
+                    uo.factory IS NOT NULL {filter}
+                GROUP BY date
+            "#,
+            [],
+            "b.timestamp",
+            range
+        )
+    }
+}
+
+pub type NewSmartAccountsRemote =
+    UserOpsDatabaseSource<PullAllWithAndSort<NewSmartAccountsStatement, NaiveDate, String>>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "newSmartAccounts".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+define_and_impl_resolution_properties!(
+    define_and_impl: {
+        WeeklyProperties: Week,
+        MonthlyProperties: Month,
+        YearlyProperties: Year,
+    },
+    base_impl: Properties
+);
+
+pub type NewSmartAccounts =
+    DirectVecLocalDbChartSource<NewSmartAccountsRemote, Batch30Days, Properties>;
+pub type NewSmartAccountsInt = MapParseTo<NewSmartAccounts, i64>;
+pub type NewSmartAccountsWeekly = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<NewSmartAccountsInt, Week>>,
+    Batch30Weeks,
+    WeeklyProperties,
+>;
+pub type NewSmartAccountsMonthly = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<NewSmartAccountsInt, Month>>,
+    Batch36Months,
+    MonthlyProperties,
+>;
+pub type NewSmartAccountsMonthlyInt = MapParseTo<NewSmartAccountsMonthly, i64>;
+pub type NewSmartAccountsYearly = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<NewSmartAccountsMonthlyInt, Year>>,
+    Batch30Years,
+    YearlyProperties,
+>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charts::lines::user_ops::tests::simple_test_user_ops_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_smart_accounts() {
+        simple_test_user_ops_chart::<NewSmartAccounts>(
+            "update_new_smart_accounts",
+            vec![("2022-11-10", "2"), ("2022-12-01", "1")],
+        )
+        .await;
+    }
+}
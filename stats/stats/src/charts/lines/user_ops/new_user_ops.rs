@@ -0,0 +1,136 @@
+//! Number of user operations included on each day
+
+use std::ops::Range;
+
+use crate::{
+    data_source::kinds::{
+        data_manipulation::{
+            map::{MapParseTo, MapToString},
+            resolutions::sum::SumLowerResolution,
+        },
+        local_db::{
+            parameters::update::batching::parameters::{
+                Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
+            },
+            DirectVecLocalDbChartSource,
+        },
+        remote_db::{PullAllWithAndSort, StatementFromRange},
+        user_ops_db::UserOpsDatabaseSource,
+    },
+    define_and_impl_resolution_properties,
+    types::timespans::{Month, Week, Year},
+    utils::sql_with_range_filter_opt,
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+pub struct NewUserOpsStatement;
+
+impl StatementFromRange for NewUserOpsStatement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    DATE(b.timestamp) as date,
+                    COUNT(*)::TEXT as value
+                FROM user_operations uo
+                JOIN blocks          b ON uo.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true {filter}
+                GROUP BY date
+            "#,
+            [],
+            "b.timestamp",
+            range
+        )
+    }
+}
+
+pub type NewUserOpsRemote =
+    UserOpsDatabaseSource<PullAllWithAndSort<NewUserOpsStatement, NaiveDate, String>>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "newUserOps".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+define_and_impl_resolution_properties!(
+    define_and_impl: {
+        WeeklyProperties: Week,
+        MonthlyProperties: Month,
+        YearlyProperties: Year,
+    },
+    base_impl: Properties
+);
+
+pub type NewUserOps = DirectVecLocalDbChartSource<NewUserOpsRemote, Batch30Days, Properties>;
+pub type NewUserOpsInt = MapParseTo<NewUserOps, i64>;
+pub type NewUserOpsWeekly = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<NewUserOpsInt, Week>>,
+    Batch30Weeks,
+    WeeklyProperties,
+>;
+pub type NewUserOpsMonthly = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<NewUserOpsInt, Month>>,
+    Batch36Months,
+    MonthlyProperties,
+>;
+pub type NewUserOpsMonthlyInt = MapParseTo<NewUserOpsMonthly, i64>;
+pub type NewUserOpsYearly = DirectVecLocalDbChartSource<
+    MapToString<SumLowerResolution<NewUserOpsMonthlyInt, Year>>,
+    Batch30Years,
+    YearlyProperties,
+>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charts::lines::user_ops::tests::simple_test_user_ops_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_user_ops() {
+        simple_test_user_ops_chart::<NewUserOps>(
+            "update_new_user_ops",
+            vec![
+                ("2022-11-10", "3"),
+                ("2022-11-11", "1"),
+                ("2022-12-01", "1"),
+                ("2023-02-01", "1"),
+                ("2023-03-01", "1"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_user_ops_monthly() {
+        simple_test_user_ops_chart::<NewUserOpsMonthly>(
+            "update_new_user_ops_monthly",
+            vec![
+                ("2022-11-01", "4"),
+                ("2022-12-01", "1"),
+                ("2023-02-01", "1"),
+                ("2023-03-01", "1"),
+            ],
+        )
+        .await;
+    }
+}
@@ -0,0 +1,151 @@
+//! Share of user operations that were sponsored by a paymaster
+
+use std::ops::Range;
+
+use crate::{
+    data_source::kinds::{
+        data_manipulation::{
+            map::{MapParseTo, MapToString},
+            resolutions::average::AverageLowerResolution,
+        },
+        local_db::{
+            parameters::update::batching::parameters::{
+                Batch30Days, Batch30Weeks, Batch30Years, Batch36Months,
+            },
+            DirectVecLocalDbChartSource,
+        },
+        remote_db::{PullAllWithAndSort, StatementFromRange},
+        user_ops_db::UserOpsDatabaseSource,
+    },
+    define_and_impl_resolution_properties,
+    types::timespans::{Month, Week, Year},
+    utils::sql_with_range_filter_opt,
+    ChartProperties, Named,
+};
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, Statement};
+
+use super::new_user_ops::{NewUserOpsInt, NewUserOpsMonthlyInt};
+
+pub struct SponsoredUserOpsShareStatement;
+
+impl StatementFromRange for SponsoredUserOpsShareStatement {
+    fn get_statement(range: Option<Range<DateTimeUtc>>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    DATE(b.timestamp) as date,
+                    COUNT(CASE WHEN uo.paymaster IS NOT NULL THEN 1 END)::FLOAT
+                        / COUNT(*)::FLOAT as value
+                FROM user_operations uo
+                JOIN blocks          b ON uo.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true {filter}
+                GROUP BY date
+            "#,
+            [],
+            "b.timestamp",
+            range
+        )
+    }
+}
+
+pub type SponsoredUserOpsShareRemote =
+    UserOpsDatabaseSource<PullAllWithAndSort<SponsoredUserOpsShareStatement, NaiveDate, f64>>;
+
+pub type SponsoredUserOpsShareRemoteString = MapToString<SponsoredUserOpsShareRemote>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "sponsoredUserOpsShare".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+}
+
+define_and_impl_resolution_properties!(
+    define_and_impl: {
+        WeeklyProperties: Week,
+        MonthlyProperties: Month,
+        YearlyProperties: Year,
+    },
+    base_impl: Properties
+);
+
+pub type SponsoredUserOpsShare =
+    DirectVecLocalDbChartSource<SponsoredUserOpsShareRemoteString, Batch30Days, Properties>;
+pub type SponsoredUserOpsShareWeekly = DirectVecLocalDbChartSource<
+    MapToString<
+        AverageLowerResolution<MapParseTo<SponsoredUserOpsShare, f64>, NewUserOpsInt, Week>,
+    >,
+    Batch30Weeks,
+    WeeklyProperties,
+>;
+pub type SponsoredUserOpsShareMonthly = DirectVecLocalDbChartSource<
+    MapToString<
+        AverageLowerResolution<MapParseTo<SponsoredUserOpsShare, f64>, NewUserOpsInt, Month>,
+    >,
+    Batch36Months,
+    MonthlyProperties,
+>;
+pub type SponsoredUserOpsShareYearly = DirectVecLocalDbChartSource<
+    MapToString<
+        AverageLowerResolution<
+            MapParseTo<SponsoredUserOpsShareMonthly, f64>,
+            NewUserOpsMonthlyInt,
+            Year,
+        >,
+    >,
+    Batch30Years,
+    YearlyProperties,
+>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charts::lines::user_ops::tests::simple_test_user_ops_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_sponsored_user_ops_share() {
+        simple_test_user_ops_chart::<SponsoredUserOpsShare>(
+            "update_sponsored_user_ops_share",
+            vec![
+                ("2022-11-10", "0.6666666666666666"),
+                ("2022-11-11", "0"),
+                ("2022-12-01", "1"),
+                ("2023-02-01", "1"),
+                ("2023-03-01", "0"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_sponsored_user_ops_share_weekly() {
+        // weighted by the number of user ops: (2/3 * 3 + 0 * 1) / 4
+        simple_test_user_ops_chart::<SponsoredUserOpsShareWeekly>(
+            "update_sponsored_user_ops_share_weekly",
+            vec![
+                ("2022-11-07", "0.5"),
+                ("2022-11-28", "1"),
+                ("2023-01-30", "1"),
+                ("2023-02-27", "0"),
+            ],
+        )
+        .await;
+    }
+}
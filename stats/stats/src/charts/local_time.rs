@@ -0,0 +1,162 @@
+//! Built-in charts calculated in local time of some timezone.
+//!
+//! Built-in charts are calculated in UTC. Some of their statements can group the data
+//! by local date instead ([`LocalChartKind`]); a chart calculated this way is a separate
+//! [`custom`](crate::custom) chart stored under [`timezone_variant_name`], so the UTC
+//! chart (and its dependants) stays unchanged. All local charts are updated within
+//! a single update group ([`local_charts_group`]).
+
+use std::ops::Range;
+
+use chrono_tz::Tz;
+use sea_orm::{prelude::DateTimeUtc, Statement};
+
+use super::custom::{
+    timezone_variant_name, CustomChartDefinition, CustomChartGroup, InvalidCustomChart,
+};
+use crate::{
+    data_source::kinds::remote_db::StatementFromRange,
+    lines::{NewBlocks, NewBlocksStatement, NewTxns, NewTxnsStatement},
+    Named, ResolutionKind,
+};
+
+/// Name of the update group with all local charts
+pub const LOCAL_CHARTS_GROUP_NAME: &str = "LocalChartsGroup";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LocalChartKind {
+    NewTxns,
+    NewBlocks,
+}
+
+impl LocalChartKind {
+    pub fn all() -> [Self; 2] {
+        [Self::NewTxns, Self::NewBlocks]
+    }
+
+    /// Name of the built-in (UTC) chart
+    pub fn chart_name(&self) -> String {
+        match self {
+            Self::NewTxns => NewTxns::name(),
+            Self::NewBlocks => NewBlocks::name(),
+        }
+    }
+
+    pub fn from_chart_name(name: &str) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|kind| kind.chart_name() == name)
+    }
+
+    /// Hourly data does not depend on the timezone (apart from a few
+    /// ones with non-hour offsets), so it's taken from the built-in chart
+    pub fn supported_resolutions(&self) -> &'static [ResolutionKind] {
+        &[
+            ResolutionKind::Day,
+            ResolutionKind::Week,
+            ResolutionKind::Month,
+            ResolutionKind::Year,
+        ]
+    }
+
+    /// Name of the chart calculated in `timezone`
+    pub fn local_chart_name(&self, timezone: &Tz) -> String {
+        timezone_variant_name(&self.chart_name(), Some(timezone))
+    }
+
+    pub(super) fn statement(&self, range: Range<DateTimeUtc>, timezone: &Tz) -> Statement {
+        match (self, timezone) {
+            // keep the exact statement of the built-in chart
+            (Self::NewTxns, Tz::UTC) => NewTxnsStatement::get_statement(Some(range)),
+            (Self::NewBlocks, Tz::UTC) => NewBlocksStatement::get_statement(Some(range)),
+            (Self::NewTxns, _) => NewTxnsStatement::get_statement_in(Some(range), timezone),
+            (Self::NewBlocks, _) => NewBlocksStatement::get_statement_in(Some(range), timezone),
+        }
+    }
+}
+
+/// Chart of `kind` calculated in `timezone`
+pub fn local_chart(
+    kind: LocalChartKind,
+    timezone: Tz,
+    resolutions: impl IntoIterator<Item = ResolutionKind>,
+) -> Result<CustomChartDefinition, InvalidCustomChart> {
+    let resolutions: Vec<_> = resolutions.into_iter().collect();
+    if let Some(r) = resolutions
+        .iter()
+        .find(|r| !kind.supported_resolutions().contains(r))
+    {
+        return Err(InvalidCustomChart::UnsupportedResolution(*r));
+    }
+    CustomChartDefinition::local(kind, resolutions)?.with_timezone(timezone)
+}
+
+/// Update group with the provided local charts
+pub fn local_charts_group(charts: Vec<CustomChartDefinition>) -> CustomChartGroup {
+    CustomChartGroup::with_charts(LOCAL_CHARTS_GROUP_NAME.to_owned(), charts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::point_construction::dt;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn local_charts_are_valid() {
+        for kind in LocalChartKind::all() {
+            assert_eq!(
+                LocalChartKind::from_chart_name(&kind.chart_name()),
+                Some(kind)
+            );
+            let chart = local_chart(
+                kind,
+                chrono_tz::Asia::Tokyo,
+                kind.supported_resolutions().iter().copied(),
+            )
+            .unwrap();
+            assert_eq!(chart.name(), kind.chart_name());
+            assert_eq!(
+                chart.stored_name(),
+                format!("{}_Asia/Tokyo", kind.chart_name())
+            );
+            assert_eq!(
+                chart.stored_name(),
+                kind.local_chart_name(&chrono_tz::Asia::Tokyo)
+            );
+        }
+        assert_eq!(
+            local_chart(
+                LocalChartKind::NewTxns,
+                chrono_tz::Asia::Tokyo,
+                [ResolutionKind::Hour, ResolutionKind::Day]
+            ),
+            Err(InvalidCustomChart::UnsupportedResolution(
+                ResolutionKind::Hour
+            ))
+        );
+    }
+
+    #[test]
+    fn local_statement_binds_timezone() {
+        let range = dt("2024-01-01T00:00:00").and_utc()..dt("2024-01-02T00:00:00").and_utc();
+        for kind in LocalChartKind::all() {
+            let statement = kind.statement(range.clone(), &chrono_tz::Asia::Tokyo);
+            assert!(statement.sql.contains("AT TIME ZONE 'UTC' AT TIME ZONE $1"));
+            assert_eq!(
+                statement.values.unwrap().0,
+                vec!["Asia/Tokyo".into(), range.start.into(), range.end.into()]
+            );
+            assert_eq!(
+                kind.statement(range.clone(), &Tz::UTC).sql,
+                match kind {
+                    LocalChartKind::NewTxns => NewTxnsStatement::get_statement(Some(range.clone())),
+                    LocalChartKind::NewBlocks => {
+                        NewBlocksStatement::get_statement(Some(range.clone()))
+                    }
+                }
+                .sql
+            );
+        }
+    }
+}
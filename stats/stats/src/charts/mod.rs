@@ -1,7 +1,12 @@
 mod chart;
 pub mod counters;
+pub mod custom;
 pub mod db_interaction;
+pub mod derived;
 pub mod lines;
+pub mod local_time;
+pub mod tokens;
+pub mod top_lists;
 pub mod types;
 pub use chart::{
     chart_properties_portrait, ChartKey, ChartProperties, ChartPropertiesObject, MissingDatePolicy,
@@ -0,0 +1,303 @@
+//! Charts calculated for individual (ERC-20) tokens.
+//!
+//! The set of charts is the same for each tracked token ([`TokenChartKind`]);
+//! a chart for a particular token is named `<kind id>_<token address>`
+//! (see [`TokenChartKind::chart_name`]).
+//!
+//! Token charts are defined via [`custom`](crate::custom) charts with the token
+//! address as a query parameter. All charts of all tracked tokens are updated
+//! within a single update group ([`token_charts_group`]).
+
+use std::{fmt::Display, str::FromStr};
+
+use entity::sea_orm_active_enums::ChartType;
+use thiserror::Error;
+
+use super::custom::{
+    CustomChartDefinition, CustomChartGroup, InvalidCustomChart, LowerResolutionAggregation,
+};
+use crate::{MissingDatePolicy, ResolutionKind};
+
+/// Name of the update group with all token charts
+pub const TOKEN_CHARTS_GROUP_NAME: &str = "TokenChartsGroup";
+
+const TOKEN_PARAMETER: &str = "token";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TokenChartKind {
+    /// Number of transfers of the token
+    Transfers,
+    /// Number of unique addresses that sent the token
+    UniqueSenders,
+    /// Transferred amount (adjusted for token decimals)
+    Volume,
+    /// Number of addresses with positive balance
+    Holders,
+}
+
+impl TokenChartKind {
+    pub fn all() -> [Self; 4] {
+        [
+            Self::Transfers,
+            Self::UniqueSenders,
+            Self::Volume,
+            Self::Holders,
+        ]
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Transfers => "tokenTransfers",
+            Self::UniqueSenders => "tokenUniqueSenders",
+            Self::Volume => "tokenVolume",
+            Self::Holders => "tokenHolders",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all().into_iter().find(|kind| kind.id() == id)
+    }
+
+    /// Unique senders can't be summed up, so only daily values are available
+    pub fn supported_resolutions(&self) -> &'static [ResolutionKind] {
+        match self {
+            Self::Transfers | Self::Volume | Self::Holders => &[
+                ResolutionKind::Day,
+                ResolutionKind::Week,
+                ResolutionKind::Month,
+                ResolutionKind::Year,
+            ],
+            Self::UniqueSenders => &[ResolutionKind::Day],
+        }
+    }
+
+    pub fn chart_name(&self, token: &TokenAddress) -> String {
+        format!("{}_{}", self.id(), token)
+    }
+
+    fn missing_date_policy(&self) -> MissingDatePolicy {
+        match self {
+            Self::Transfers | Self::UniqueSenders | Self::Volume => MissingDatePolicy::FillZero,
+            Self::Holders => MissingDatePolicy::FillPrevious,
+        }
+    }
+
+    fn aggregation(&self) -> LowerResolutionAggregation {
+        match self {
+            Self::Transfers | Self::UniqueSenders | Self::Volume => LowerResolutionAggregation::Sum,
+            Self::Holders => LowerResolutionAggregation::Last,
+        }
+    }
+
+    fn query(&self) -> &'static str {
+        match self {
+            Self::Transfers => {
+                r#"
+                    SELECT
+                        date(b.timestamp) as date,
+                        COUNT(*)::TEXT as value
+                    FROM token_transfers tt
+                    JOIN blocks          b ON tt.block_hash = b.hash
+                    WHERE
+                        tt.token_contract_address_hash = decode({token}, 'hex') AND
+                        b.timestamp != to_timestamp(0) AND
+                        b.consensus = true AND
+                        b.timestamp >= {from} AND b.timestamp < {to}
+                    GROUP BY date;
+                "#
+            }
+            Self::UniqueSenders => {
+                r#"
+                    SELECT
+                        date(b.timestamp) as date,
+                        COUNT(DISTINCT tt.from_address_hash)::TEXT as value
+                    FROM token_transfers tt
+                    JOIN blocks          b ON tt.block_hash = b.hash
+                    WHERE
+                        tt.token_contract_address_hash = decode({token}, 'hex') AND
+                        b.timestamp != to_timestamp(0) AND
+                        b.consensus = true AND
+                        b.timestamp >= {from} AND b.timestamp < {to}
+                    GROUP BY date;
+                "#
+            }
+            Self::Volume => {
+                r#"
+                    SELECT
+                        date(b.timestamp) as date,
+                        ROUND(
+                            SUM(tt.amount / power(10, COALESCE(t.decimals, 0))),
+                            6
+                        )::TEXT as value
+                    FROM token_transfers tt
+                    JOIN blocks          b ON tt.block_hash = b.hash
+                    JOIN tokens          t ON tt.token_contract_address_hash = t.contract_address_hash
+                    WHERE
+                        tt.token_contract_address_hash = decode({token}, 'hex') AND
+                        tt.amount IS NOT NULL AND
+                        b.timestamp != to_timestamp(0) AND
+                        b.consensus = true AND
+                        b.timestamp >= {from} AND b.timestamp < {to}
+                    GROUP BY date;
+                "#
+            }
+            // holders count depends on the whole history, so the deltas are
+            // accumulated from the beginning and only the requested range is returned
+            Self::Holders => {
+                r#"
+                    SELECT date, value FROM (
+                        SELECT
+                            date,
+                            (SUM(delta) OVER (ORDER BY date))::TEXT as value
+                        FROM (
+                            SELECT
+                                date(b.timestamp) as date,
+                                SUM(
+                                    (COALESCE(balances.value, 0) > 0)::int -
+                                    (COALESCE(balances.prev_value, 0) > 0)::int
+                                ) as delta
+                            FROM (
+                                SELECT
+                                    atb.block_number,
+                                    atb.value,
+                                    LAG(atb.value) OVER (
+                                        PARTITION BY atb.address_hash
+                                        ORDER BY atb.block_number
+                                    ) as prev_value
+                                FROM address_token_balances atb
+                                WHERE atb.token_contract_address_hash = decode({token}, 'hex')
+                            ) balances
+                            JOIN blocks b ON balances.block_number = b.number
+                            WHERE
+                                b.timestamp != to_timestamp(0) AND
+                                b.consensus = true AND
+                                b.timestamp < {to}
+                            GROUP BY date
+                        ) deltas
+                    ) holders
+                    WHERE date >= {from}::date;
+                "#
+            }
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("invalid token address '{0}': expected 0x-prefixed 20-byte hex string")]
+pub struct InvalidTokenAddress(String);
+
+/// Lowercase `0x`-prefixed token contract address
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TokenAddress(String);
+
+impl TokenAddress {
+    /// Hex without `0x` prefix
+    fn hex(&self) -> &str {
+        &self.0[2..]
+    }
+}
+
+impl FromStr for TokenAddress {
+    type Err = InvalidTokenAddress;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .ok_or_else(|| InvalidTokenAddress(s.to_owned()))?;
+        if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(InvalidTokenAddress(s.to_owned()));
+        }
+        Ok(Self(format!("0x{}", hex.to_ascii_lowercase())))
+    }
+}
+
+impl Display for TokenAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Definition of `kind` chart for `token`.
+///
+/// `resolutions` must be a subset of [`TokenChartKind::supported_resolutions`].
+pub fn token_chart(
+    kind: TokenChartKind,
+    token: &TokenAddress,
+    resolutions: impl IntoIterator<Item = ResolutionKind>,
+) -> Result<CustomChartDefinition, InvalidCustomChart> {
+    let resolutions: Vec<_> = resolutions.into_iter().collect();
+    if let Some(r) = resolutions
+        .iter()
+        .find(|r| !kind.supported_resolutions().contains(r))
+    {
+        return Err(InvalidCustomChart::UnsupportedResolution(*r));
+    }
+    CustomChartDefinition::new_with_parameters(
+        kind.chart_name(token),
+        ChartType::Line,
+        kind.query().to_owned(),
+        kind.missing_date_policy(),
+        kind.aggregation(),
+        resolutions,
+        vec![(TOKEN_PARAMETER.to_owned(), token.hex().to_owned())],
+    )
+}
+
+/// Update group with the provided token charts
+pub fn token_charts_group(charts: Vec<CustomChartDefinition>) -> CustomChartGroup {
+    CustomChartGroup::with_charts(TOKEN_CHARTS_GROUP_NAME.to_owned(), charts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const TOKEN: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+
+    #[test]
+    fn token_address_parsing_works() {
+        let token = TokenAddress::from_str(TOKEN).unwrap();
+        assert_eq!(
+            token.to_string(),
+            "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+        );
+        assert_eq!(token.hex(), "a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48");
+        for invalid in [
+            "",
+            "a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+            "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb4",
+            "0xz0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+        ] {
+            assert_eq!(
+                TokenAddress::from_str(invalid),
+                Err(InvalidTokenAddress(invalid.to_owned()))
+            );
+        }
+    }
+
+    #[test]
+    fn token_charts_are_valid() {
+        let token = TokenAddress::from_str(TOKEN).unwrap();
+        for kind in TokenChartKind::all() {
+            assert_eq!(TokenChartKind::from_id(kind.id()), Some(kind));
+            let chart =
+                token_chart(kind, &token, kind.supported_resolutions().iter().copied()).unwrap();
+            assert_eq!(
+                chart.name(),
+                format!("{}_0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", kind.id())
+            );
+        }
+        assert_eq!(
+            token_chart(
+                TokenChartKind::UniqueSenders,
+                &token,
+                [ResolutionKind::Day, ResolutionKind::Week]
+            ),
+            Err(InvalidCustomChart::UnsupportedResolution(
+                ResolutionKind::Week
+            ))
+        );
+    }
+}
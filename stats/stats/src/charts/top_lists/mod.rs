@@ -0,0 +1,225 @@
+//! Top lists (leaderboards), e.g. top contracts by number of transactions.
+//!
+//! Each list is recalculated as a whole for a sliding window that
+//! ends at the update time (see [`TopListWindow`]). Lists for different
+//! windows are separate charts named `<list name><window suffix>`
+//! (e.g. `topSenders7d`).
+//!
+//! Lists of [`UserOpsTopList`] type are calculated from `user-ops-indexer`
+//! database instead of the blockscout one.
+
+mod top_bundlers_by_fees;
+mod top_contracts_by_txns;
+mod top_gas_consumers;
+mod top_senders;
+
+use std::{marker::PhantomData, ops::Range};
+
+use chrono::{Duration, NaiveDate};
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::DateTimeUtc, FromQueryResult, Statement};
+
+use crate::{
+    data_source::{
+        kinds::{
+            local_db::DirectTopListLocalDbChartSource,
+            remote_db::{RemoteDatabaseSource, RemoteQueryBehaviour},
+            user_ops_db::UserOpsDatabaseSource,
+        },
+        UpdateContext,
+    },
+    types::TopListEntry,
+    ChartProperties, Named, UpdateError,
+};
+
+pub use top_bundlers_by_fees::{TopBundlersByFees24h, TopBundlersByFees30d, TopBundlersByFees7d};
+pub use top_contracts_by_txns::{
+    TopContractsByTxns24h, TopContractsByTxns30d, TopContractsByTxns7d,
+};
+pub use top_gas_consumers::{TopGasConsumers24h, TopGasConsumers30d, TopGasConsumers7d};
+pub use top_senders::{TopSenders24h, TopSenders30d, TopSenders7d};
+
+/// Max number of rows stored for each list
+pub const TOP_LIST_SIZE: i64 = 50;
+
+pub trait TopListKind {
+    /// Name of the list without window suffix
+    fn name() -> String;
+
+    /// Statement must return `key` and `value` (text) columns ordered by rank,
+    /// `$1` is the limit for number of rows and `range` is the window.
+    fn get_statement(limit: i64, range: Range<DateTimeUtc>) -> Statement;
+}
+
+pub trait TopListWindow {
+    fn suffix() -> &'static str;
+    fn duration() -> Duration;
+}
+
+pub struct Last24Hours;
+pub struct Last7Days;
+pub struct Last30Days;
+
+impl TopListWindow for Last24Hours {
+    fn suffix() -> &'static str {
+        "24h"
+    }
+
+    fn duration() -> Duration {
+        Duration::hours(24)
+    }
+}
+
+impl TopListWindow for Last7Days {
+    fn suffix() -> &'static str {
+        "7d"
+    }
+
+    fn duration() -> Duration {
+        Duration::days(7)
+    }
+}
+
+impl TopListWindow for Last30Days {
+    fn suffix() -> &'static str {
+        "30d"
+    }
+
+    fn duration() -> Duration {
+        Duration::days(30)
+    }
+}
+
+/// Pull the list for the window `W` ending at the update time
+pub struct PullTopList<K, W>(PhantomData<(K, W)>);
+
+impl<K, W> RemoteQueryBehaviour for PullTopList<K, W>
+where
+    K: TopListKind + Send,
+    W: TopListWindow + Send,
+{
+    type Output = Vec<TopListEntry>;
+
+    async fn query_data(
+        cx: &UpdateContext<'_>,
+        _range: Option<Range<DateTimeUtc>>,
+    ) -> Result<Self::Output, UpdateError> {
+        let window = cx.time - W::duration()..cx.time;
+        let query = K::get_statement(TOP_LIST_SIZE, window);
+        TopListEntry::find_by_statement(query)
+            .all(cx.blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)
+    }
+}
+
+pub struct TopListProperties<K, W>(PhantomData<(K, W)>);
+
+impl<K: TopListKind, W: TopListWindow> Named for TopListProperties<K, W> {
+    fn name() -> String {
+        format!("{}{}", K::name(), W::suffix())
+    }
+}
+
+impl<K, W> ChartProperties for TopListProperties<K, W>
+where
+    K: TopListKind + Sync,
+    W: TopListWindow + Sync,
+{
+    // top lists don't have resolutions; day is used just to identify the chart
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::TopList
+    }
+}
+
+pub type TopList<K, W> = DirectTopListLocalDbChartSource<
+    RemoteDatabaseSource<PullTopList<K, W>>,
+    TopListProperties<K, W>,
+>;
+
+pub type UserOpsTopList<K, W> = DirectTopListLocalDbChartSource<
+    UserOpsDatabaseSource<PullTopList<K, W>>,
+    TopListProperties<K, W>,
+>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data_source::{DataSource, UpdateParameters},
+        get_top_list_data,
+        tests::{
+            init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data,
+            mock_user_ops::fill_mock_user_ops_data,
+        },
+    };
+    use chrono::DateTime;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    /// `test_name` must be unique to avoid db clashes
+    pub async fn simple_test_top_list<L>(test_name: &str, expected: Vec<(&str, &str)>)
+    where
+        L: DataSource + ChartProperties,
+    {
+        test_top_list::<L>(test_name, expected, false).await
+    }
+
+    /// Same as [`simple_test_top_list`], but with mock `user-ops-indexer` data
+    pub async fn simple_test_user_ops_top_list<L>(test_name: &str, expected: Vec<(&str, &str)>)
+    where
+        L: DataSource + ChartProperties,
+    {
+        test_top_list::<L>(test_name, expected, true).await
+    }
+
+    async fn test_top_list<L>(test_name: &str, expected: Vec<(&str, &str)>, with_user_ops: bool)
+    where
+        L: DataSource + ChartProperties,
+    {
+        let _ = tracing_subscriber::fmt::try_init();
+        let expected: Vec<_> = expected
+            .into_iter()
+            .map(|(key, value)| TopListEntry {
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+            .collect();
+        let (db, blockscout) = init_db_all(test_name).await;
+        let current_time = DateTime::from_str("2023-03-01T12:00:00Z").unwrap();
+        L::init_recursively(&db, &current_time).await.unwrap();
+        fill_mock_blockscout_data(&blockscout, current_time.date_naive()).await;
+        if with_user_ops {
+            fill_mock_user_ops_data(&blockscout).await;
+        }
+
+        let mut parameters = UpdateParameters {
+            db: &db,
+            blockscout: &blockscout,
+            user_ops: with_user_ops.then_some(&*blockscout),
+            chain: "",
+            update_time_override: Some(current_time),
+            force_full: true,
+        };
+        let cx = UpdateContext::from_params_now_or_override(parameters.clone());
+        L::update_recursively(&cx).await.unwrap();
+        assert_eq!(get_top_list_data(&db, &L::name()).await.unwrap(), expected);
+
+        // the list must be replaced, not appended to
+        parameters.force_full = false;
+        parameters.update_time_override = Some(current_time + Duration::seconds(1));
+        let cx = UpdateContext::from_params_now_or_override(parameters);
+        L::update_recursively(&cx).await.unwrap();
+        assert_eq!(get_top_list_data(&db, &L::name()).await.unwrap(), expected);
+    }
+
+    #[test]
+    fn names_are_correct() {
+        assert_eq!(TopSenders24h::name(), "topSenders24h");
+        assert_eq!(TopContractsByTxns7d::name(), "topContractsByTxns7d");
+        assert_eq!(TopGasConsumers30d::name(), "topGasConsumers30d");
+        assert_eq!(TopBundlersByFees24h::name(), "topBundlersByFees24h");
+    }
+}
@@ -0,0 +1,74 @@
+use std::ops::Range;
+
+use sea_orm::{prelude::DateTimeUtc, DbBackend, Statement};
+
+use crate::utils::sql_with_range_filter_opt;
+
+use super::{Last24Hours, Last30Days, Last7Days, TopListKind, UserOpsTopList};
+
+const ETHER: i64 = i64::pow(10, 18);
+
+/// Bundlers that received the most fees (in native coin) for user operations.
+///
+/// Calculated from `user-ops-indexer` database.
+pub struct TopBundlersByFees;
+
+impl TopListKind for TopBundlersByFees {
+    fn name() -> String {
+        "topBundlersByFees".into()
+    }
+
+    fn get_statement(limit: i64, range: Range<DateTimeUtc>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    '0x' || encode(uo.bundler, 'hex') as key,
+                    (SUM(uo.gas_used * uo.gas_price) / $2)::FLOAT::TEXT as value
+                FROM user_operations uo
+                JOIN blocks          b ON uo.block_hash = b.hash
+                WHERE
+                    b.consensus = true {filter}
+                GROUP BY uo.bundler
+                ORDER BY SUM(uo.gas_used * uo.gas_price) DESC, uo.bundler
+                LIMIT $1;
+            "#,
+            [limit.into(), ETHER.into()],
+            "b.timestamp",
+            Some(range),
+        )
+    }
+}
+
+pub type TopBundlersByFees24h = UserOpsTopList<TopBundlersByFees, Last24Hours>;
+pub type TopBundlersByFees7d = UserOpsTopList<TopBundlersByFees, Last7Days>;
+pub type TopBundlersByFees30d = UserOpsTopList<TopBundlersByFees, Last30Days>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::top_lists::tests::simple_test_user_ops_top_list;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_top_bundlers_by_fees_24h() {
+        simple_test_user_ops_top_list::<TopBundlersByFees24h>(
+            "update_top_bundlers_by_fees_24h",
+            vec![("0x1111111111111111111111111111111111111111", "0.0006")],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_top_bundlers_by_fees_30d() {
+        simple_test_user_ops_top_list::<TopBundlersByFees30d>(
+            "update_top_bundlers_by_fees_30d",
+            vec![
+                ("0x1111111111111111111111111111111111111111", "0.0006"),
+                ("0x2222222222222222222222222222222222222222", "0.0002"),
+            ],
+        )
+        .await;
+    }
+}
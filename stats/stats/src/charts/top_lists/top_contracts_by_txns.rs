@@ -0,0 +1,57 @@
+use std::ops::Range;
+
+use sea_orm::{prelude::DateTimeUtc, DbBackend, Statement};
+
+use crate::utils::sql_with_range_filter_opt;
+
+use super::{Last24Hours, Last30Days, Last7Days, TopList, TopListKind};
+
+/// Contracts with the most incoming transactions
+pub struct TopContractsByTxns;
+
+impl TopListKind for TopContractsByTxns {
+    fn name() -> String {
+        "topContractsByTxns".into()
+    }
+
+    fn get_statement(limit: i64, range: Range<DateTimeUtc>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    '0x' || encode(t.to_address_hash, 'hex') as key,
+                    COUNT(*)::TEXT as value
+                FROM transactions t
+                JOIN blocks       b ON t.block_hash = b.hash
+                JOIN addresses    a ON t.to_address_hash = a.hash
+                WHERE
+                    a.contract_code IS NOT NULL AND
+                    b.consensus = true {filter}
+                GROUP BY t.to_address_hash
+                ORDER BY COUNT(*) DESC, t.to_address_hash
+                LIMIT $1;
+            "#,
+            [limit.into()],
+            "b.timestamp",
+            Some(range),
+        )
+    }
+}
+
+pub type TopContractsByTxns24h = TopList<TopContractsByTxns, Last24Hours>;
+pub type TopContractsByTxns7d = TopList<TopContractsByTxns, Last7Days>;
+pub type TopContractsByTxns30d = TopList<TopContractsByTxns, Last30Days>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::top_lists::tests::simple_test_top_list;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_top_contracts_by_txns_30d() {
+        // mock transactions are sent only to non-contract addresses
+        simple_test_top_list::<TopContractsByTxns30d>("update_top_contracts_by_txns_30d", vec![])
+            .await;
+    }
+}
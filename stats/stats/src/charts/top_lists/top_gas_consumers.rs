@@ -0,0 +1,44 @@
+use std::ops::Range;
+
+use sea_orm::{prelude::DateTimeUtc, DbBackend, Statement};
+
+use crate::utils::sql_with_range_filter_opt;
+
+use super::{Last24Hours, Last30Days, Last7Days, TopList, TopListKind};
+
+/// Contracts that consumed the most gas in transactions sent to them
+pub struct TopGasConsumers;
+
+impl TopListKind for TopGasConsumers {
+    fn name() -> String {
+        "topGasConsumers".into()
+    }
+
+    fn get_statement(limit: i64, range: Range<DateTimeUtc>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    '0x' || encode(t.to_address_hash, 'hex') as key,
+                    SUM(t.gas_used)::TEXT as value
+                FROM transactions t
+                JOIN blocks       b ON t.block_hash = b.hash
+                JOIN addresses    a ON t.to_address_hash = a.hash
+                WHERE
+                    a.contract_code IS NOT NULL AND
+                    t.gas_used IS NOT NULL AND
+                    b.consensus = true {filter}
+                GROUP BY t.to_address_hash
+                ORDER BY SUM(t.gas_used) DESC, t.to_address_hash
+                LIMIT $1;
+            "#,
+            [limit.into()],
+            "b.timestamp",
+            Some(range),
+        )
+    }
+}
+
+pub type TopGasConsumers24h = TopList<TopGasConsumers, Last24Hours>;
+pub type TopGasConsumers7d = TopList<TopGasConsumers, Last7Days>;
+pub type TopGasConsumers30d = TopList<TopGasConsumers, Last30Days>;
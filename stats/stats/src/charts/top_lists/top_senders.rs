@@ -0,0 +1,70 @@
+use std::ops::Range;
+
+use sea_orm::{prelude::DateTimeUtc, DbBackend, Statement};
+
+use crate::utils::sql_with_range_filter_opt;
+
+use super::{Last24Hours, Last30Days, Last7Days, TopList, TopListKind};
+
+/// Addresses that sent the most transactions
+pub struct TopSenders;
+
+impl TopListKind for TopSenders {
+    fn name() -> String {
+        "topSenders".into()
+    }
+
+    fn get_statement(limit: i64, range: Range<DateTimeUtc>) -> Statement {
+        sql_with_range_filter_opt!(
+            DbBackend::Postgres,
+            r#"
+                SELECT
+                    '0x' || encode(t.from_address_hash, 'hex') as key,
+                    COUNT(*)::TEXT as value
+                FROM transactions t
+                JOIN blocks       b ON t.block_hash = b.hash
+                WHERE
+                    b.consensus = true {filter}
+                GROUP BY t.from_address_hash
+                ORDER BY COUNT(*) DESC, t.from_address_hash
+                LIMIT $1;
+            "#,
+            [limit.into()],
+            "b.timestamp",
+            Some(range),
+        )
+    }
+}
+
+pub type TopSenders24h = TopList<TopSenders, Last24Hours>;
+pub type TopSenders7d = TopList<TopSenders, Last7Days>;
+pub type TopSenders30d = TopList<TopSenders, Last30Days>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::top_lists::tests::simple_test_top_list;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_top_senders_24h() {
+        // only the failed transaction (without sender) is in the window
+        simple_test_top_list::<TopSenders24h>("update_top_senders_24h", vec![("0x", "1")]).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_top_senders_30d() {
+        simple_test_top_list::<TopSenders30d>(
+            "update_top_senders_30d",
+            vec![
+                (
+                    "0x0400000000000000000000000000000000000000000000000000000000000000",
+                    "4",
+                ),
+                ("0x", "1"),
+            ],
+        )
+        .await;
+    }
+}
@@ -1,9 +1,14 @@
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime};
 use entity::chart_data;
 
 use sea_orm::{DbErr, FromQueryResult, QueryResult, Set, TryGetable};
 
-use super::{timespans::DateValue, TimespanValue};
+use crate::utils::day_start;
+
+use super::{
+    timespans::{DateValue, Hour, HourValue},
+    TimespanValue,
+};
 
 // Separate type instead of `TimespanValue` just to derive `FromQueryResult`
 /// Internal (database) representation of data points.
@@ -34,7 +39,7 @@ impl DbDateValue<String> {
         chart_data::ActiveModel {
             id: Default::default(),
             chart_id: Set(chart_id),
-            date: Set(self.date),
+            date: Set(day_start(&self.date).naive_utc()),
             value: Set(self.value.clone()),
             created_at: Default::default(),
             min_blockscout_block: Set(min_blockscout_block),
@@ -47,3 +52,26 @@ impl<V: TryGetable> FromQueryResult for TimespanValue<NaiveDate, V> {
         DbDateValue::<V>::from_query_result(res, pre).map(|dv| dv.into())
     }
 }
+
+/// Same as [`DbDateValue`] but for timespans shorter than a day,
+/// where `date` column contains timestamp of the timespan start
+#[derive(FromQueryResult, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DbDateTimeValue<V: TryGetable> {
+    pub date: NaiveDateTime,
+    pub value: V,
+}
+
+impl<V: TryGetable> From<DbDateTimeValue<V>> for HourValue<V> {
+    fn from(value: DbDateTimeValue<V>) -> Self {
+        Self {
+            timespan: Hour::new(value.date),
+            value: value.value,
+        }
+    }
+}
+
+impl<V: TryGetable> FromQueryResult for TimespanValue<Hour, V> {
+    fn from_query_result(res: &QueryResult, pre: &str) -> Result<Self, DbErr> {
+        DbDateTimeValue::<V>::from_query_result(res, pre).map(|dv| dv.into())
+    }
+}
@@ -1,7 +1,9 @@
 pub mod db;
 mod duration;
 mod extended;
+mod series;
 pub mod timespans;
+mod top_list;
 mod traits;
 
 use entity::chart_data;
@@ -9,6 +11,8 @@ use sea_orm::Set;
 
 pub use duration::TimespanDuration;
 pub use extended::ExtendedTimespanValue;
+pub use series::{group_series_by_date, DbDateSeriesValue, SeriesValues};
+pub use top_list::TopListEntry;
 pub use traits::{ConsistsOf, Timespan, TimespanValueTrait, ZeroTimespanValue};
 
 /// Some value for some time interval
@@ -37,7 +41,7 @@ impl<T: Ord> TimespanValue<T, String> {
     }
 }
 
-impl<T: Timespan + Clone> TimespanValue<T, String> {
+impl<T: Timespan> TimespanValue<T, String> {
     pub fn active_model(
         &self,
         chart_id: i32,
@@ -46,7 +50,7 @@ impl<T: Timespan + Clone> TimespanValue<T, String> {
         chart_data::ActiveModel {
             id: Default::default(),
             chart_id: Set(chart_id),
-            date: Set(self.timespan.clone().into_date()),
+            date: Set(self.timespan.saturating_start_timestamp().naive_utc()),
             value: Set(self.value.clone()),
             created_at: Default::default(),
             min_blockscout_block: Set(min_blockscout_block),
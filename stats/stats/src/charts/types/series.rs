@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use entity::chart_data_series;
+use itertools::Itertools;
+use rust_decimal::Decimal;
+use sea_orm::{FromQueryResult, Set};
+
+use super::{timespans::DateValue, Timespan, TimespanValue};
+
+/// Values of named series of a multi-series chart for one timespan.
+///
+/// E.g. number of successful and failed transactions in a day.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct SeriesValues(pub BTreeMap<String, String>);
+
+impl SeriesValues {
+    /// Sum of values of all series.
+    ///
+    /// It's stored as a regular chart value, so that the chart can be
+    /// read (and used as a dependency) as a single-series one.
+    pub fn total(&self) -> Result<String, rust_decimal::Error> {
+        let mut total = Decimal::ZERO;
+        for value in self.0.values() {
+            total += value.parse::<Decimal>()?;
+        }
+        Ok(total.normalize().to_string())
+    }
+}
+
+impl<T: Timespan + Clone> TimespanValue<T, SeriesValues> {
+    pub fn total(&self) -> Result<TimespanValue<T, String>, rust_decimal::Error> {
+        Ok(TimespanValue {
+            timespan: self.timespan.clone(),
+            value: self.value.total()?,
+        })
+    }
+
+    pub fn series_active_models(
+        &self,
+        chart_id: i32,
+        min_blockscout_block: Option<i64>,
+    ) -> Vec<chart_data_series::ActiveModel> {
+        let date = self.timespan.saturating_start_timestamp().naive_utc();
+        self.value
+            .0
+            .iter()
+            .map(|(series, value)| chart_data_series::ActiveModel {
+                id: Default::default(),
+                chart_id: Set(chart_id),
+                series: Set(series.clone()),
+                date: Set(date),
+                value: Set(value.clone()),
+                created_at: Default::default(),
+                min_blockscout_block: Set(min_blockscout_block),
+            })
+            .collect()
+    }
+}
+
+/// Value of one series as it's returned by queries in long format
+/// (i.e. one row per date and series)
+#[derive(FromQueryResult, Debug, Clone, PartialEq, Eq)]
+pub struct DbDateSeriesValue {
+    pub date: NaiveDate,
+    pub series: String,
+    pub value: String,
+}
+
+/// Group rows by date. The result is sorted by date.
+pub fn group_series_by_date(
+    rows: impl IntoIterator<Item = DbDateSeriesValue>,
+) -> Vec<DateValue<SeriesValues>> {
+    rows.into_iter()
+        .map(|row| (row.date, (row.series, row.value)))
+        .into_group_map()
+        .into_iter()
+        .map(|(date, series)| TimespanValue {
+            timespan: date,
+            value: SeriesValues(series.into_iter().collect()),
+        })
+        .sorted_unstable_by(|a, b| a.timespan.cmp(&b.timespan))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn d(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    fn row(date: &str, series: &str, value: &str) -> DbDateSeriesValue {
+        DbDateSeriesValue {
+            date: d(date),
+            series: series.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn series(values: &[(&str, &str)]) -> SeriesValues {
+        SeriesValues(
+            values
+                .iter()
+                .map(|(s, v)| (s.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn total_works() {
+        assert_eq!(series(&[]).total().unwrap(), "0");
+        assert_eq!(series(&[("a", "2"), ("b", "3")]).total().unwrap(), "5");
+        assert_eq!(
+            series(&[("a", "0.5"), ("b", "1.25")]).total().unwrap(),
+            "1.75"
+        );
+        assert!(series(&[("a", "abc")]).total().is_err());
+    }
+
+    #[test]
+    fn group_series_by_date_works() {
+        let rows = vec![
+            row("2022-11-10", "success", "3"),
+            row("2022-11-09", "success", "1"),
+            row("2022-11-10", "failed", "2"),
+        ];
+        assert_eq!(
+            group_series_by_date(rows),
+            vec![
+                TimespanValue {
+                    timespan: d("2022-11-09"),
+                    value: series(&[("success", "1")]),
+                },
+                TimespanValue {
+                    timespan: d("2022-11-10"),
+                    value: series(&[("failed", "2"), ("success", "3")]),
+                },
+            ]
+        );
+    }
+}
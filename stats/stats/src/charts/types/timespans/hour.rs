@@ -0,0 +1,186 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Timelike, Utc};
+use rust_decimal::Decimal;
+
+use crate::{
+    impl_into_string_timespan_value,
+    types::{ConsistsOf, Timespan, TimespanDuration, TimespanValue},
+    ResolutionKind,
+};
+
+/// Hour (in UTC). Stored as the timestamp of its start.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hour(NaiveDateTime);
+
+impl std::fmt::Debug for Hour {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Hour")
+            .field(&self.0.date())
+            .field(&self.0.hour())
+            .finish()
+    }
+}
+
+impl Hour {
+    /// Start of the hour containing `time`
+    fn trunc(time: NaiveDateTime) -> NaiveDateTime {
+        let hour_start = NaiveTime::from_hms_opt(time.hour(), 0, 0).expect("correct time");
+        time.date().and_time(hour_start)
+    }
+
+    pub fn new(time: NaiveDateTime) -> Self {
+        Self(Self::trunc(time))
+    }
+
+    /// Start of the hour as naive (UTC) datetime
+    pub fn start(&self) -> NaiveDateTime {
+        self.0
+    }
+
+    fn max() -> Self {
+        Self::new(NaiveDateTime::MAX)
+    }
+
+    fn min() -> Self {
+        Self::new(NaiveDateTime::MIN)
+    }
+
+    fn checked_delta(duration: TimespanDuration<Self>) -> Option<TimeDelta> {
+        let hours = i64::try_from(duration.repeats()).ok()?;
+        TimeDelta::try_hours(hours)
+    }
+}
+
+impl Timespan for Hour {
+    /// First hour of the day
+    fn from_date(date: NaiveDate) -> Self {
+        Self::new(date.and_time(NaiveTime::MIN))
+    }
+
+    /// Note that multiple hours are mapped into a single date,
+    /// so this conversion loses information.
+    fn into_date(self) -> NaiveDate {
+        self.0.date()
+    }
+
+    fn from_timestamp(timestamp: DateTime<Utc>) -> Self {
+        Self::new(timestamp.naive_utc())
+    }
+
+    fn enum_variant() -> ResolutionKind {
+        ResolutionKind::Hour
+    }
+
+    fn saturating_start_timestamp(&self) -> DateTime<Utc> {
+        self.0.and_utc()
+    }
+
+    fn saturating_add(&self, duration: TimespanDuration<Self>) -> Self
+    where
+        Self: Sized,
+    {
+        Self::checked_delta(duration)
+            .and_then(|delta| self.0.checked_add_signed(delta))
+            .map(Self::new)
+            .unwrap_or_else(Self::max)
+    }
+
+    fn saturating_sub(&self, duration: TimespanDuration<Self>) -> Self
+    where
+        Self: Sized,
+    {
+        Self::checked_delta(duration)
+            .and_then(|delta| self.0.checked_sub_signed(delta))
+            .map(Self::new)
+            .unwrap_or_else(Self::min)
+    }
+}
+
+impl ConsistsOf<Hour> for NaiveDate {
+    fn from_smaller(hour: Hour) -> Self {
+        hour.into_date()
+    }
+
+    fn into_smaller(self) -> Hour {
+        Hour::from_date(self)
+    }
+}
+
+pub type HourValue<V> = TimespanValue<Hour, V>;
+
+impl_into_string_timespan_value!(Hour, i64);
+impl_into_string_timespan_value!(Hour, f64);
+impl_into_string_timespan_value!(Hour, Decimal);
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::point_construction::{d, dt};
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn hour_conversions_work() {
+        assert_eq!(
+            Hour::new(dt("2024-07-08T09:42:13")).start(),
+            dt("2024-07-08T09:00:00")
+        );
+        assert_eq!(
+            Hour::from_timestamp(dt("2024-07-08T23:59:59").and_utc()),
+            Hour::new(dt("2024-07-08T23:00:00"))
+        );
+        assert_eq!(
+            Hour::from_date(d("2024-07-08")),
+            Hour::new(dt("2024-07-08T00:00:00"))
+        );
+        assert_eq!(
+            Hour::new(dt("2024-07-08T23:59:59")).into_date(),
+            d("2024-07-08")
+        );
+        assert_eq!(
+            Hour::new(dt("2024-07-08T13:15:00")).saturating_start_timestamp(),
+            dt("2024-07-08T13:00:00").and_utc()
+        );
+    }
+
+    #[test]
+    fn hour_saturating_arithmetics_works() {
+        assert_eq!(
+            Hour::new(dt("2024-07-08T23:00:00")).saturating_next_timespan(),
+            Hour::new(dt("2024-07-09T00:00:00"))
+        );
+        assert_eq!(
+            Hour::new(dt("2024-07-08T00:00:00")).saturating_previous_timespan(),
+            Hour::new(dt("2024-07-07T23:00:00"))
+        );
+        assert_eq!(
+            Hour::new(dt("2024-07-08T10:00:00"))
+                .saturating_add(TimespanDuration::from_timespan_repeats(72)),
+            Hour::new(dt("2024-07-11T10:00:00"))
+        );
+        assert_eq!(
+            Hour::new(dt("2024-07-08T10:00:00"))
+                .saturating_add(TimespanDuration::from_timespan_repeats(u64::MAX)),
+            Hour::max()
+        );
+        assert_eq!(
+            Hour::new(dt("2024-07-08T10:00:00"))
+                .saturating_sub(TimespanDuration::from_timespan_repeats(u64::MAX)),
+            Hour::min()
+        );
+        assert_eq!(Hour::max().saturating_next_timespan(), Hour::max());
+        assert_eq!(Hour::min().saturating_previous_timespan(), Hour::min());
+    }
+
+    #[test]
+    fn day_consists_of_hours() {
+        assert_eq!(
+            NaiveDate::from_smaller(Hour::new(dt("2024-07-08T17:00:00"))),
+            d("2024-07-08")
+        );
+        assert_eq!(
+            d("2024-07-08").into_smaller(),
+            Hour::new(dt("2024-07-08T00:00:00"))
+        );
+    }
+}
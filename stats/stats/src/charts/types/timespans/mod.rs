@@ -1,10 +1,12 @@
 mod day;
+mod hour;
 mod month;
 mod week;
 mod year;
 
 pub use chrono::NaiveDate;
 pub use day::DateValue;
+pub use hour::{Hour, HourValue};
 pub use month::Month;
 pub use week::{Week, WeekValue};
 pub use year::Year;
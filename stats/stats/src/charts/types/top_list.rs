@@ -0,0 +1,31 @@
+use entity::top_list_data;
+use sea_orm::{FromQueryResult, Set};
+
+/// One row of a top list (e.g. contract address and number of its transactions).
+///
+/// Rank is defined by the position in the list.
+#[derive(FromQueryResult, Debug, Clone, PartialEq, Eq)]
+pub struct TopListEntry {
+    pub key: String,
+    pub value: String,
+}
+
+impl TopListEntry {
+    /// `rank` starts from 1
+    pub fn active_model(
+        &self,
+        chart_id: i32,
+        rank: i32,
+        min_blockscout_block: Option<i64>,
+    ) -> top_list_data::ActiveModel {
+        top_list_data::ActiveModel {
+            id: Default::default(),
+            chart_id: Set(chart_id),
+            rank: Set(rank),
+            key: Set(self.key.clone()),
+            value: Set(self.value.clone()),
+            created_at: Default::default(),
+            min_blockscout_block: Set(min_blockscout_block),
+        }
+    }
+}
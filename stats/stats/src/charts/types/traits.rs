@@ -1,9 +1,10 @@
 use std::ops::Range;
 
 use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
 use rust_decimal::Decimal;
 
-use crate::charts::ResolutionKind;
+use crate::{charts::ResolutionKind, utils::local_time_start};
 
 use super::{TimespanDuration, TimespanValue};
 
@@ -16,8 +17,26 @@ pub trait Timespan {
     /// Convert the timespan into a corresponding date
     /// to store in database.
     ///
-    /// Using `from_date` on resulting date will always result in equal timespan.
+    /// Using `from_date` on resulting date will always result in equal timespan
+    /// (for timespans not shorter than a day).
     fn into_date(self) -> NaiveDate;
+    /// Construct the timespan from a timestamp within the timespan.
+    ///
+    /// Has to be overridden for timespans shorter than a day.
+    fn from_timestamp(timestamp: DateTime<Utc>) -> Self
+    where
+        Self: Sized,
+    {
+        Self::from_date(timestamp.date_naive())
+    }
+    /// Same as [`Timespan::from_timestamp`], but timespan borders are
+    /// in local time of `timezone` instead of UTC.
+    fn from_timestamp_in(timestamp: DateTime<Utc>, timezone: &Tz) -> Self
+    where
+        Self: Sized,
+    {
+        Self::from_timestamp(timestamp.with_timezone(timezone).naive_local().and_utc())
+    }
     /// Get the next interval right after the current one (saturating)
     fn saturating_next_timespan(&self) -> Self
     where
@@ -44,6 +63,24 @@ pub trait Timespan {
         self.saturating_start_timestamp()
             ..self.saturating_next_timespan().saturating_start_timestamp()
     }
+    /// Extract the start of given timespan in local time of `timezone`
+    /// as UTC timestamp.
+    ///
+    /// I.e. the timespan itself is treated as local (e.g. local date
+    /// for `NaiveDate`).
+    fn saturating_start_timestamp_in(&self, timezone: &Tz) -> DateTime<Utc> {
+        local_time_start(self.saturating_start_timestamp().naive_utc(), timezone)
+    }
+    /// Represent the (local for `timezone`) timespan as UTC timestamp range
+    fn into_time_range_in(self, timezone: &Tz) -> Range<DateTime<Utc>>
+    where
+        Self: Sized,
+    {
+        self.saturating_start_timestamp_in(timezone)
+            ..self
+                .saturating_next_timespan()
+                .saturating_start_timestamp_in(timezone)
+    }
     fn saturating_add(&self, duration: TimespanDuration<Self>) -> Self
     where
         Self: Sized;
@@ -12,7 +12,6 @@ use crate::{
     charts::ChartProperties,
     data_source::{source::DataSource, UpdateContext},
     types::{Timespan, TimespanValue, ZeroTimespanValue},
-    utils::day_start,
     UpdateError,
 };
 
@@ -52,9 +51,11 @@ where
         _range: Option<Range<DateTimeUtc>>,
         dependency_data_fetch_timer: &mut AggregateTimer,
     ) -> Result<Self::Output, UpdateError> {
+        let current_timespan_start =
+            Resolution::from_timestamp(cx.time).saturating_start_timestamp();
         let data = DS::query_data(
             cx,
-            Some(day_start(&cx.time.date_naive())..cx.time),
+            Some(current_timespan_start..cx.time),
             dependency_data_fetch_timer,
         )
         .await?;
@@ -66,7 +67,7 @@ where
             // in the dependency, which in all (current) cases means that
             // the value is 0
             .unwrap_or(TimespanValue::<Resolution, Value>::with_zero_value(
-                Resolution::from_date(cx.time.date_naive()),
+                Resolution::from_timestamp(cx.time),
             ));
         Ok(last_point)
     }
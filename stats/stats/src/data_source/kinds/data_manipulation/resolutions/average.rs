@@ -1,5 +1,5 @@
 //! Constructors for lower resolutions of average value charts
-use std::{cmp::Ordering, fmt::Debug, marker::PhantomData, ops::Range};
+use std::{cmp::Ordering, collections::BTreeMap, fmt::Debug, marker::PhantomData, ops::Range};
 
 use blockscout_metrics_tools::AggregateTimer;
 use chrono::{DateTime, Utc};
@@ -10,7 +10,7 @@ use crate::{
     data_source::{
         kinds::data_manipulation::resolutions::reduce_each_timespan, DataSource, UpdateContext,
     },
-    types::{ConsistsOf, Timespan, TimespanValue},
+    types::{ConsistsOf, SeriesValues, Timespan, TimespanValue},
     UpdateError,
 };
 
@@ -77,6 +77,62 @@ where
     }
 }
 
+/// Same as [`AverageLowerResolution`], but for multi-series charts.
+/// Each series is averaged separately with the same `Weight`.
+///
+/// Series values missing for some timespan are treated as zero.
+pub struct AverageSeriesLowerResolution<Average, Weight, LowerRes>(
+    PhantomData<(Average, Weight, LowerRes)>,
+);
+
+impl<Average, Weight, LowerRes, HigherRes> DataSource
+    for AverageSeriesLowerResolution<Average, Weight, LowerRes>
+where
+    Average: DataSource<Output = Vec<TimespanValue<HigherRes, SeriesValues>>>,
+    Weight: DataSource<Output = Vec<TimespanValue<HigherRes, i64>>>,
+    LowerRes: Timespan + ConsistsOf<HigherRes> + Ord + Debug + Send,
+    HigherRes: Ord + Clone + Debug + Send,
+{
+    type MainDependencies = Average;
+    type ResolutionDependencies = Weight;
+    type Output = Vec<TimespanValue<LowerRes, SeriesValues>>;
+
+    fn mutex_id() -> Option<String> {
+        // just an adapter
+        None
+    }
+
+    async fn init_itself(
+        _db: &DatabaseConnection,
+        _init_time: &DateTime<Utc>,
+    ) -> Result<(), DbErr> {
+        // just an adapter; inner is handled recursively
+        Ok(())
+    }
+
+    async fn update_itself(_cx: &UpdateContext<'_>) -> Result<(), UpdateError> {
+        // just an adapter; inner is handled recursively
+        Ok(())
+    }
+
+    async fn query_data(
+        cx: &UpdateContext<'_>,
+        range: Option<Range<DateTimeUtc>>,
+        dependency_data_fetch_timer: &mut AggregateTimer,
+    ) -> Result<Self::Output, UpdateError> {
+        let time_range_for_lower_res = range.map(extend_to_timespan_boundaries::<LowerRes>);
+        let high_res_averages = Average::query_data(
+            cx,
+            time_range_for_lower_res.clone(),
+            dependency_data_fetch_timer,
+        )
+        .await?;
+        let weights =
+            Weight::query_data(cx, time_range_for_lower_res, dependency_data_fetch_timer).await?;
+        lower_res_series_average_from(high_res_averages, weights)
+    }
+}
+
 /// "zip" two sorted date/value vectors, combining
 /// values with the same date.
 ///
@@ -130,7 +186,10 @@ where
     result
 }
 
-fn lower_res_average_from<LowerRes, HigherRes>(
+/// Weighted average of `h_res_average` within each `LowerRes` timespan.
+///
+/// Both vectors must be sorted by timespan.
+pub fn lower_res_average_from<LowerRes, HigherRes>(
     h_res_average: Vec<TimespanValue<HigherRes, f64>>,
     h_res_weight: Vec<TimespanValue<HigherRes, i64>>,
 ) -> Vec<TimespanValue<LowerRes, f64>>
@@ -186,6 +245,47 @@ where
     l_res_averages.into_iter().flatten().collect_vec()
 }
 
+/// Weighted average of each series of `h_res_average` within each `LowerRes` timespan.
+///
+/// Both vectors must be sorted by timespan.
+pub fn lower_res_series_average_from<LowerRes, HigherRes>(
+    h_res_average: Vec<TimespanValue<HigherRes, SeriesValues>>,
+    h_res_weight: Vec<TimespanValue<HigherRes, i64>>,
+) -> Result<Vec<TimespanValue<LowerRes, SeriesValues>>, UpdateError>
+where
+    LowerRes: ConsistsOf<HigherRes> + Ord + Debug,
+    HigherRes: Ord + Clone + Debug,
+{
+    let mut per_series: BTreeMap<String, Vec<TimespanValue<HigherRes, f64>>> = BTreeMap::new();
+    for point in h_res_average {
+        for (series, value) in point.value.0 {
+            let value = value.parse::<f64>().map_err(|e| {
+                UpdateError::Internal(format!("failed to parse '{series}' series value: {e}"))
+            })?;
+            per_series.entry(series).or_default().push(TimespanValue {
+                timespan: point.timespan.clone(),
+                value,
+            });
+        }
+    }
+    let mut l_res_series: BTreeMap<LowerRes, SeriesValues> = BTreeMap::new();
+    for (series, h_res_series_average) in per_series {
+        for point in
+            lower_res_average_from::<LowerRes, _>(h_res_series_average, h_res_weight.clone())
+        {
+            l_res_series
+                .entry(point.timespan)
+                .or_default()
+                .0
+                .insert(series.clone(), point.value.to_string());
+        }
+    }
+    Ok(l_res_series
+        .into_iter()
+        .map(|(timespan, value)| TimespanValue { timespan, value })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -299,6 +399,58 @@ mod tests {
         )
     }
 
+    #[test]
+    fn weekly_series_average_from_works() {
+        fn series(values: &[(&str, f64)]) -> SeriesValues {
+            SeriesValues(
+                values
+                    .iter()
+                    .map(|(s, v)| (s.to_string(), v.to_string()))
+                    .collect(),
+            )
+        }
+        let d_s = |date: &str, values: &[(&str, f64)]| TimespanValue {
+            timespan: d(date),
+            value: series(values),
+        };
+        let w_s = |date: &str, values: &[(&str, f64)]| TimespanValue {
+            timespan: week_of(date),
+            value: series(values),
+        };
+
+        let week_1_a = (5.0 * 100.0 + 34.2 * 2.0) / (100.0 + 2.0);
+        // missing `b` value on 2024-07-08 is treated as zero
+        let week_1_b = (1.5 * 2.0) / (100.0 + 2.0);
+        assert_eq!(
+            lower_res_series_average_from(
+                vec![
+                    d_s("2024-07-08", &[("a", 5.0)]),
+                    d_s("2024-07-10", &[("a", 34.2), ("b", 1.5)]),
+                    d_s("2024-07-17", &[("a", 5.0), ("b", 3.0)]),
+                ],
+                vec![
+                    d_v_int("2024-07-08", 100),
+                    d_v_int("2024-07-10", 2),
+                    d_v_int("2024-07-17", 5)
+                ]
+            )
+            .unwrap(),
+            vec![
+                w_s("2024-07-08", &[("a", week_1_a), ("b", week_1_b)]),
+                w_s("2024-07-15", &[("a", 5.0), ("b", 3.0)]),
+            ],
+        );
+        let invalid = TimespanValue {
+            timespan: d("2024-07-08"),
+            value: SeriesValues([("a".to_string(), "abc".to_string())].into()),
+        };
+        assert!(lower_res_series_average_from::<Week, _>(
+            vec![invalid],
+            vec![d_v_int("2024-07-08", 1)]
+        )
+        .is_err());
+    }
+
     #[tokio::test]
     async fn weekly_average_source_queries_correct_range() {
         gettable_const!(Dates: Range<NaiveDate> = d("2024-07-01")..d("2024-07-31"));
@@ -321,8 +473,11 @@ mod tests {
             &UpdateContext {
                 db: &db,
                 blockscout: &db,
+                user_ops: None,
+                chain: "",
                 time: dt("2024-07-15T09:00:00").and_utc(),
                 force_full: false,
+                cache: Default::default(),
             },
             Some(dt("2024-07-08T09:00:00").and_utc()..dt("2024-07-15T00:00:01").and_utc()),
             &mut AggregateTimer::new(),
@@ -368,8 +523,11 @@ mod tests {
         let context = UpdateContext {
             db: &empty_db,
             blockscout: &empty_db,
+            user_ops: None,
+            chain: "",
             time: dt("2024-07-30T09:00:00").and_utc(),
             force_full: false,
+            cache: Default::default(),
         };
         let week_1_average = (5.0 * 100.0 + 34.2 * 2.0 + 10.3 * 12.0) / (100.0 + 2.0 + 12.0);
         assert_eq!(
@@ -413,8 +571,11 @@ mod tests {
         let context = UpdateContext {
             db: &empty_db,
             blockscout: &empty_db,
+            user_ops: None,
+            chain: "",
             time: dt("2023-03-30T09:00:00").and_utc(),
             force_full: false,
+            cache: Default::default(),
         };
         assert_eq!(
             TestedAverageSource::query_data(&context, None, &mut AggregateTimer::new())
@@ -454,8 +615,11 @@ mod tests {
         let context = UpdateContext {
             db: &empty_db,
             blockscout: &empty_db,
+            user_ops: None,
+            chain: "",
             time: dt("2023-03-30T09:00:00").and_utc(),
             force_full: false,
+            cache: Default::default(),
         };
         assert_eq!(
             TestedAverageSource::query_data(&context, None, &mut AggregateTimer::new())
@@ -61,23 +61,36 @@ where
             dependency_data_fetch_timer,
         )
         .await?;
-        Ok(reduce_each_timespan(
-            high_res_data,
-            |t| LowerRes::from_smaller(t.timespan.clone()),
-            |a| {
-                let last = a.into_iter().next_back();
-                last.map(|p| TimespanValue {
-                    timespan: LowerRes::from_smaller(p.timespan),
-                    value: p.value,
-                })
-            },
-        )
-        .into_iter()
-        .flatten()
-        .collect())
+        Ok(last_value_lower_resolution(high_res_data))
     }
 }
 
+/// Take last point within each `LowerRes` timespan.
+///
+/// `data` must be sorted by timespan.
+pub fn last_value_lower_resolution<LowerRes, HigherRes, Value>(
+    data: Vec<TimespanValue<HigherRes, Value>>,
+) -> Vec<TimespanValue<LowerRes, Value>>
+where
+    LowerRes: Timespan + ConsistsOf<HigherRes> + Eq,
+    HigherRes: Clone,
+{
+    reduce_each_timespan(
+        data,
+        |t| LowerRes::from_smaller(t.timespan.clone()),
+        |a| {
+            let last = a.into_iter().next_back();
+            last.map(|p| TimespanValue {
+                timespan: LowerRes::from_smaller(p.timespan),
+                value: p.value,
+            })
+        },
+    )
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use blockscout_metrics_tools::AggregateTimer;
@@ -116,8 +129,11 @@ mod tests {
         let context = UpdateContext {
             db: &empty_db,
             blockscout: &empty_db,
+            user_ops: None,
+            chain: "",
             time: dt("2024-07-30T09:00:00").and_utc(),
             force_full: false,
+            cache: Default::default(),
         };
         assert_eq!(
             MockSource::query_data(&context, None, &mut AggregateTimer::new())
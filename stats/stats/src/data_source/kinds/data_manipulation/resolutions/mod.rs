@@ -15,8 +15,8 @@ pub mod sum;
 // Boundaries of resulting range - timespans that contain boundaries of date range
 fn date_range_to_timespan<T: Timespan>(range: Range<DateTime<Utc>>) -> RangeInclusive<T> {
     let range = exclusive_datetime_range_to_inclusive(range);
-    let start_timespan = T::from_date(range.start().date_naive());
-    let end_timespan = T::from_date(range.end().date_naive());
+    let start_timespan = T::from_timestamp(*range.start());
+    let end_timespan = T::from_timestamp(*range.end());
     start_timespan..=end_timespan
 }
 
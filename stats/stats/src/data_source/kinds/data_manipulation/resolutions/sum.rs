@@ -66,42 +66,56 @@ where
             dependency_data_fetch_timer,
         )
         .await?;
-        Ok(reduce_each_timespan(
-            high_res_data,
-            |t| LowerRes::from_smaller(t.timespan.clone()),
-            |data_for_one_l_res| {
-                let TimespanValue {
-                    timespan: first_h_res,
-                    value: _,
-                } = data_for_one_l_res.first()?;
-                let current_l_res = LowerRes::from_smaller(first_h_res.clone());
-                let mut total = Value::zero();
-                for TimespanValue {
-                    timespan: h_res,
-                    value,
-                } in data_for_one_l_res
-                {
-                    debug_assert_eq!(
-                        current_l_res,
-                        LowerRes::from_smaller(h_res.clone()),
-                        "must've returned only data within current lower res timespan ({:?}); got {:?}",
-                        current_l_res,
-                        h_res
-                    );
-                    total += value;
-                }
-                Some(TimespanValue {
-                    timespan: current_l_res,
-                    value: total,
-                })
-            },
-        )
-        .into_iter()
-        .flatten()
-        .collect())
+        Ok(sum_lower_resolution(high_res_data))
     }
 }
 
+/// Sum points within each `LowerRes` timespan.
+///
+/// `data` must be sorted by timespan.
+pub fn sum_lower_resolution<LowerRes, HigherRes, Value>(
+    data: Vec<TimespanValue<HigherRes, Value>>,
+) -> Vec<TimespanValue<LowerRes, Value>>
+where
+    LowerRes: Timespan + ConsistsOf<HigherRes> + Eq + Debug,
+    HigherRes: Clone + Debug,
+    Value: AddAssign + Zero,
+{
+    reduce_each_timespan(
+        data,
+        |t| LowerRes::from_smaller(t.timespan.clone()),
+        |data_for_one_l_res| {
+            let TimespanValue {
+                timespan: first_h_res,
+                value: _,
+            } = data_for_one_l_res.first()?;
+            let current_l_res = LowerRes::from_smaller(first_h_res.clone());
+            let mut total = Value::zero();
+            for TimespanValue {
+                timespan: h_res,
+                value,
+            } in data_for_one_l_res
+            {
+                debug_assert_eq!(
+                    current_l_res,
+                    LowerRes::from_smaller(h_res.clone()),
+                    "must've returned only data within current lower res timespan ({:?}); got {:?}",
+                    current_l_res,
+                    h_res
+                );
+                total += value;
+            }
+            Some(TimespanValue {
+                timespan: current_l_res,
+                value: total,
+            })
+        },
+    )
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use blockscout_metrics_tools::AggregateTimer;
@@ -140,8 +154,11 @@ mod tests {
         let context = UpdateContext {
             db: &empty_db,
             blockscout: &empty_db,
+            user_ops: None,
+            chain: "",
             time: dt("2024-07-30T09:00:00").and_utc(),
             force_full: false,
+            cache: Default::default(),
         };
         assert_eq!(
             MockSource::query_data(&context, None, &mut AggregateTimer::new())
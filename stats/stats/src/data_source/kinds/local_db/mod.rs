@@ -9,7 +9,7 @@
 //! Charts are intended to be such persisted sources,
 //! because their data is directly retreived from the database (on requests).
 
-use std::{fmt::Debug, marker::PhantomData, ops::Range, time::Duration};
+use std::{fmt::Debug, future::Future, marker::PhantomData, ops::Range, time::Duration};
 
 use blockscout_metrics_tools::AggregateTimer;
 use chrono::{DateTime, SubsecRound, Utc};
@@ -17,23 +17,32 @@ use parameter_traits::{CreateBehaviour, QueryBehaviour, UpdateBehaviour};
 use parameters::{
     update::{
         batching::{
-            parameters::{AddLastValueStep, Batch30Days, PassVecStep},
+            parameters::{
+                AddLastValueStep, Batch30Days, PassSeriesVecStep, PassVecStep, SeriesTotal,
+            },
             BatchUpdate,
         },
         point::PassPoint,
+        top_list::PassTopList,
     },
-    DefaultCreate, DefaultQueryLast, DefaultQueryVec,
+    DefaultCreate, DefaultQueryLast, DefaultQuerySeriesVec, DefaultQueryTopList, DefaultQueryVec,
 };
 use sea_orm::{prelude::DateTimeUtc, DatabaseConnection, DbErr};
 
 use crate::{
     charts::{
+        chart::ChartMetadata,
         chart_properties_portrait,
-        db_interaction::read::{get_chart_metadata, get_min_block_blockscout, last_accurate_point},
-        ChartProperties, Named,
+        db_interaction::{
+            read::{get_chart_metadata, get_min_block_blockscout, last_accurate_point},
+            write::{delete_data_before, set_last_update_error},
+        },
+        ChartKey, ChartProperties, Named,
     },
     data_source::{DataSource, UpdateContext},
-    metrics, UpdateError,
+    metrics,
+    types::{Timespan, TimespanDuration},
+    UpdateError,
 };
 
 use super::auxiliary::PartialCumulative;
@@ -103,6 +112,33 @@ pub type DirectVecLocalDbChartSource<Dependency, BatchSizeUpperBound, C> = Local
     C,
 >;
 
+/// Chart that stores multi-series vector data received from provided dependency
+/// (without any manipulations).
+///
+/// `MainValue` (sum of the series by default) is stored as the chart value,
+/// so the chart can be displayed as a regular (single-series) one.
+/// Dependants receive the series values.
+pub type DirectSeriesVecLocalDbChartSource<
+    Dependency,
+    BatchSizeUpperBound,
+    C,
+    MainValue = SeriesTotal,
+> = LocalDbChartSource<
+    Dependency,
+    (),
+    DefaultCreate<C>,
+    BatchUpdate<
+        Dependency,
+        (),
+        PassSeriesVecStep<MainValue>,
+        BatchSizeUpperBound,
+        DefaultQueryVec<C>,
+        C,
+    >,
+    DefaultQuerySeriesVec<C>,
+    C,
+>;
+
 /// Chart that stores single data point received from provided dependency (without
 /// any manipulations)
 pub type DirectPointLocalDbChartSource<Dependency, C> = LocalDbChartSource<
@@ -114,6 +150,17 @@ pub type DirectPointLocalDbChartSource<Dependency, C> = LocalDbChartSource<
     C,
 >;
 
+/// Top list (ranked rows) that is fully replaced with the dependency output
+/// on each update
+pub type DirectTopListLocalDbChartSource<Dependency, C> = LocalDbChartSource<
+    Dependency,
+    (),
+    DefaultCreate<C>,
+    PassTopList<Dependency>,
+    DefaultQueryTopList<C>,
+    C,
+>;
+
 impl<MainDep, ResolutionDep, Create, Update, Query, ChartProps>
     LocalDbChartSource<MainDep, ResolutionDep, Create, Update, Query, ChartProps>
 where
@@ -131,26 +178,9 @@ where
         cx: &UpdateContext<'_>,
         dependency_data_fetch_timer: &mut AggregateTimer,
     ) -> Result<(), UpdateError> {
-        let metadata = get_chart_metadata(cx.db, &ChartProps::key()).await?;
-        if let Some(last_updated_at) = metadata.last_updated_at {
-            if postgres_timestamps_eq(cx.time, last_updated_at) {
-                // no need to perform update.
-                // mostly catches second call to update e.g. when both
-                // dependency and this source are in one group and enabled.
-                tracing::debug!(
-                    last_updated_at =? last_updated_at,
-                    update_timestamp =? cx.time,
-                    "Not updating the chart because it was already handled within ongoing update"
-                );
-                return Ok(());
-            } else {
-                tracing::debug!(
-                    last_updated_at =? last_updated_at,
-                    update_timestamp =? cx.time,
-                    "Performing an update"
-                );
-            }
-        }
+        let Some(metadata) = chart_metadata_for_update(cx, &ChartProps::key()).await? else {
+            return Ok(());
+        };
         let chart_id = metadata.id;
         let min_blockscout_block = get_min_block_blockscout(cx.blockscout)
             .await
@@ -175,18 +205,103 @@ where
         .await?;
         tracing::info!(chart =% ChartProps::key(), "updating chart metadata");
         Update::update_metadata(cx.db, chart_id, cx.time).await?;
+        Self::apply_retention(cx, chart_id).await?;
         Ok(())
     }
 
-    fn observe_query_time(time: Duration) {
+    /// Remove points that are outside of [`ChartProperties::max_stored_timespans`]
+    async fn apply_retention(cx: &UpdateContext<'_>, chart_id: i32) -> Result<(), UpdateError> {
+        let Some(max_stored_timespans) = ChartProps::max_stored_timespans() else {
+            return Ok(());
+        };
+        let keep_from = ChartProps::Resolution::from_timestamp(cx.time).saturating_sub(
+            TimespanDuration::from_timespan_repeats(max_stored_timespans),
+        );
+        let removed = delete_data_before(
+            cx.db,
+            chart_id,
+            keep_from.saturating_start_timestamp().naive_utc(),
+        )
+        .await
+        .map_err(UpdateError::StatsDB)?;
+        tracing::debug!(
+            removed = removed,
+            chart =% ChartProps::key(),
+            "removed points outside of retention period"
+        );
+        Ok(())
+    }
+
+    fn observe_query_time(cx: &UpdateContext<'_>, time: Duration) {
         if time > Duration::ZERO {
             metrics::CHART_FETCH_NEW_DATA_TIME
-                .with_label_values(&[&ChartProps::key().to_string()])
+                .with_label_values(&[cx.chain, &ChartProps::key().to_string()])
                 .observe(time.as_secs_f64());
         }
     }
 }
 
+/// Metadata of the chart before its update.
+///
+/// `None` if the chart was already updated within the ongoing update
+/// (i.e. no need to update it again).
+pub(crate) async fn chart_metadata_for_update(
+    cx: &UpdateContext<'_>,
+    chart: &ChartKey,
+) -> Result<Option<ChartMetadata>, UpdateError> {
+    let metadata = get_chart_metadata(cx.db, chart).await?;
+    if let Some(last_updated_at) = metadata.last_updated_at {
+        if postgres_timestamps_eq(cx.time, last_updated_at) {
+            // no need to perform update.
+            // mostly catches second call to update e.g. when both
+            // dependency and this source are in one group and enabled.
+            tracing::debug!(
+                last_updated_at =? last_updated_at,
+                update_timestamp =? cx.time,
+                "Not updating the chart because it was already handled within ongoing update"
+            );
+            return Ok(None);
+        } else {
+            tracing::debug!(
+                last_updated_at =? last_updated_at,
+                update_timestamp =? cx.time,
+                "Performing an update"
+            );
+        }
+    }
+    Ok(Some(metadata))
+}
+
+/// Run `update` of the chart, observing its time and errors
+/// (the error is also recorded as the last update error of the chart).
+pub(crate) async fn observe_chart_update(
+    cx: &UpdateContext<'_>,
+    chart: &ChartKey,
+    update: impl Future<Output = Result<(), UpdateError>>,
+) -> Result<(), UpdateError> {
+    let _update_timer = metrics::CHART_UPDATE_TIME
+        .with_label_values(&[cx.chain, &chart.to_string()])
+        .start_timer();
+    tracing::info!(chart =% chart, "started chart update");
+
+    let result = update.await;
+    match &result {
+        Ok(()) => tracing::info!(chart =% chart, "successfully updated chart"),
+        Err(err) => {
+            metrics::UPDATE_ERRORS
+                .with_label_values(&[cx.chain, &chart.to_string()])
+                .inc();
+            tracing::error!(chart =% chart, "error during updating chart: {}", err);
+            if let Err(record_err) =
+                set_last_update_error(cx.db, chart, err.to_string(), cx.time).await
+            {
+                tracing::error!(chart =% chart, "failed to record update error: {}", record_err);
+            }
+        }
+    }
+    result
+}
+
 /// Compare timestamps as they're seen in Postgres (compare up to microseconds)
 fn postgres_timestamps_eq(time_1: DateTime<Utc>, time_2: DateTime<Utc>) -> bool {
     // PostgreSQL stores timestamps with microsecond precision
@@ -219,29 +334,14 @@ where
     }
 
     async fn update_itself(cx: &UpdateContext<'_>) -> Result<(), UpdateError> {
-        // set up metrics + write some logs
-
         let mut dependency_data_fetch_timer = AggregateTimer::new();
-        let _update_timer = metrics::CHART_UPDATE_TIME
-            .with_label_values(&[&ChartProps::key().to_string()])
-            .start_timer();
-        tracing::info!(chart =% ChartProps::key(), "started chart update");
-
-        Self::update_itself_inner(cx, &mut dependency_data_fetch_timer)
-            .await
-            .inspect_err(|err| {
-                metrics::UPDATE_ERRORS
-                    .with_label_values(&[&ChartProps::key().to_string()])
-                    .inc();
-                tracing::error!(
-                    chart =% ChartProps::key(),
-                    "error during updating chart: {}",
-                    err
-                );
-            })?;
-
-        Self::observe_query_time(dependency_data_fetch_timer.total_time());
-        tracing::info!(chart =% ChartProps::key(), "successfully updated chart");
+        observe_chart_update(
+            cx,
+            &ChartProps::key(),
+            Self::update_itself_inner(cx, &mut dependency_data_fetch_timer),
+        )
+        .await?;
+        Self::observe_query_time(cx, dependency_data_fetch_timer.total_time());
         Ok(())
     }
 
@@ -444,6 +544,8 @@ mod tests {
             let parameters = UpdateParameters {
                 db: &db,
                 blockscout: &blockscout,
+                user_ops: None,
+                chain: "",
                 update_time_override: Some(next_time),
                 force_full: true,
             };
@@ -463,6 +565,8 @@ mod tests {
             let parameters = UpdateParameters {
                 db: &db,
                 blockscout: &blockscout,
+                user_ops: None,
+                chain: "",
                 update_time_override: Some(time),
                 force_full: true,
             };
@@ -478,6 +582,8 @@ mod tests {
             let parameters = UpdateParameters {
                 db: &db,
                 blockscout: &blockscout,
+                user_ops: None,
+                chain: "",
                 update_time_override: Some(time),
                 force_full: true,
             };
@@ -491,6 +597,8 @@ mod tests {
             let parameters = UpdateParameters {
                 db: &db,
                 blockscout: &blockscout,
+                user_ops: None,
+                chain: "",
                 update_time_override: Some(time),
                 force_full: true,
             };
@@ -3,4 +3,4 @@ mod query;
 pub mod update;
 
 pub use create::DefaultCreate;
-pub use query::{DefaultQueryLast, DefaultQueryVec};
+pub use query::{DefaultQueryLast, DefaultQuerySeriesVec, DefaultQueryTopList, DefaultQueryVec};
@@ -3,10 +3,10 @@ use std::{fmt::Debug, marker::PhantomData, ops::Range};
 use sea_orm::prelude::DateTimeUtc;
 
 use crate::{
-    charts::db_interaction::read::get_counter_data,
+    charts::db_interaction::read::{get_counter_data, get_line_chart_series, get_top_list_data},
     data_source::{kinds::local_db::parameter_traits::QueryBehaviour, UpdateContext},
     get_line_chart_data,
-    types::{timespans::DateValue, Timespan, TimespanValue},
+    types::{timespans::DateValue, SeriesValues, Timespan, TimespanValue, TopListEntry},
     utils::exclusive_datetime_range_to_inclusive,
     ChartProperties, UpdateError,
 };
@@ -30,9 +30,9 @@ where
         cx: &UpdateContext<'_>,
         range: Option<Range<DateTimeUtc>>,
     ) -> Result<Self::Output, UpdateError> {
-        // In DB we store data with date precision. Also, `get_line_chart_data`
+        // In DB we store data with timespan precision. Also, `get_line_chart_data`
         // works with inclusive range. Therefore, we need to convert the range and
-        // get date without time.
+        // get timespans containing its boundaries.
         let range = range.map(exclusive_datetime_range_to_inclusive);
         let (start, end) = range.map(|r| r.into_inner()).unzip();
 
@@ -44,8 +44,8 @@ where
         // (it's reflected in `update_time` column of `charts`),
         // and `get_line_chart_data` will return the relevant data.
         // same for weeks or other resolutions.
-        let start = start.map(|s| C::Resolution::from_date(s.date_naive()));
-        let end = end.map(|e| C::Resolution::from_date(e.date_naive()));
+        let start = start.map(C::Resolution::from_timestamp);
+        let end = end.map(C::Resolution::from_timestamp);
         let values: Vec<TimespanValue<C::Resolution, String>> =
             get_line_chart_data::<C::Resolution>(
                 cx.db,
@@ -65,6 +65,33 @@ where
     }
 }
 
+/// The choice for multi-series line charts.
+///
+/// Only stored points are returned (no missing date policy is applied),
+/// so dependants should treat missing points as absent data.
+pub struct DefaultQuerySeriesVec<C: ChartProperties>(PhantomData<C>);
+
+impl<C> QueryBehaviour for DefaultQuerySeriesVec<C>
+where
+    C: ChartProperties,
+    C::Resolution: Timespan + Ord + Debug + Clone + Send,
+{
+    type Output = Vec<TimespanValue<C::Resolution, SeriesValues>>;
+
+    async fn query_data(
+        cx: &UpdateContext<'_>,
+        range: Option<Range<DateTimeUtc>>,
+    ) -> Result<Self::Output, UpdateError> {
+        // same range handling as in `DefaultQueryVec`
+        let range = range.map(exclusive_datetime_range_to_inclusive);
+        let (start, end) = range.map(|r| r.into_inner()).unzip();
+        let start = start.map(C::Resolution::from_timestamp);
+        let end = end.map(C::Resolution::from_timestamp);
+        let values = get_line_chart_series::<C::Resolution>(cx.db, &C::name(), start, end).await?;
+        Ok(values)
+    }
+}
+
 /// Usually the choice for line counters
 pub struct DefaultQueryLast<C: ChartProperties>(PhantomData<C>);
 
@@ -89,3 +116,17 @@ impl<C: ChartProperties> QueryBehaviour for DefaultQueryLast<C> {
         Ok(value)
     }
 }
+
+/// The choice for top lists
+pub struct DefaultQueryTopList<C: ChartProperties>(PhantomData<C>);
+
+impl<C: ChartProperties> QueryBehaviour for DefaultQueryTopList<C> {
+    type Output = Vec<TopListEntry>;
+
+    async fn query_data(
+        cx: &UpdateContext<'_>,
+        _range: Option<Range<DateTimeUtc>>,
+    ) -> Result<Self::Output, UpdateError> {
+        Ok(get_top_list_data(cx.db, &C::name()).await?)
+    }
+}
@@ -0,0 +1,236 @@
+//! Adjusting batch size to the observed performance.
+//!
+//! Batch size upper bound set for a chart is the starting point. If steps are
+//! too slow or hit statement timeout, the size is reduced; if they finish quickly,
+//! it grows back (up to the bound). The size is remembered between updates of the
+//! chart (within the process) separately for each chain, since their databases
+//! may perform very differently.
+
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use lazy_static::lazy_static;
+
+use crate::{metrics, types::TimespanDuration, ChartKey, UpdateError};
+
+/// Steps that take longer than this are considered too slow
+pub const TARGET_STEP_TIME: Duration = Duration::from_secs(5 * 60);
+/// Steps that take less than this allow to increase the batch size
+pub const FAST_STEP_TIME: Duration = Duration::from_secs(75);
+
+lazy_static! {
+    /// Latest batch size (in timespans) for each (chain, chart)
+    static ref BATCH_SIZES: Mutex<HashMap<(String, ChartKey), u64>> = Mutex::new(HashMap::new());
+}
+
+/// Batch size (in timespans) of a particular chart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdaptiveBatchSize {
+    chain: String,
+    chart: ChartKey,
+    current: u64,
+    max: u64,
+}
+
+impl AdaptiveBatchSize {
+    /// Restore the latest size of the chart of `chain` (or start from `upper_bound`).
+    ///
+    /// Unlimited size (`u64::MAX`) means that the chart should be calculated in
+    /// a single step, so it is not changed.
+    pub fn load(chain: &str, chart: ChartKey, upper_bound: u64) -> Self {
+        let max = upper_bound.max(1);
+        let chain = chain.to_owned();
+        let current = BATCH_SIZES
+            .lock()
+            .expect("batch sizes mutex is poisoned")
+            .get(&(chain.clone(), chart.clone()))
+            .copied()
+            .unwrap_or(max)
+            .clamp(1, max);
+        let size = Self {
+            chain,
+            chart,
+            current,
+            max,
+        };
+        size.report();
+        size
+    }
+
+    pub fn current<Resolution>(&self) -> TimespanDuration<Resolution> {
+        TimespanDuration::from_timespan_repeats(self.current)
+    }
+
+    pub fn repeats(&self) -> u64 {
+        self.current
+    }
+
+    fn is_fixed(&self) -> bool {
+        self.max == u64::MAX
+    }
+
+    fn set(&mut self, new: u64) {
+        self.current = new.clamp(1, self.max);
+        BATCH_SIZES
+            .lock()
+            .expect("batch sizes mutex is poisoned")
+            .insert((self.chain.clone(), self.chart.clone()), self.current);
+        self.report();
+    }
+
+    fn report(&self) {
+        let size = i64::try_from(self.current).unwrap_or(i64::MAX);
+        metrics::BATCH_SIZE
+            .with_label_values(&[&self.chain, &self.chart.to_string()])
+            .set(size);
+    }
+
+    /// Adjust the size according to the (dependency fetch) time of successful step.
+    ///
+    /// `full_size` - whether the step had the current batch size (e.g. the last step is usually shorter)
+    pub fn observe_step(&mut self, time: Duration, full_size: bool) {
+        metrics::BATCH_STEP_TIME
+            .with_label_values(&[&self.chain, &self.chart.to_string()])
+            .observe(time.as_secs_f64());
+        if self.is_fixed() {
+            return;
+        }
+        if time > TARGET_STEP_TIME {
+            tracing::info!(chain = self.chain, chart =% self.chart, step_time =? time, "batch step is too slow, reducing batch size");
+            self.set(self.current / 2);
+        } else if time < FAST_STEP_TIME && full_size && self.current < self.max {
+            tracing::debug!(chain = self.chain, chart =% self.chart, step_time =? time, "increasing batch size");
+            self.set(self.current.saturating_mul(2));
+        }
+    }
+
+    /// Reduce the size after the step failed with `err`.
+    ///
+    /// Returns `true` if the step should be retried with the reduced size.
+    pub fn shrink_on_timeout(&mut self, err: &UpdateError) -> bool {
+        if !is_statement_timeout(err) {
+            return false;
+        }
+        metrics::BATCH_STEP_TIMEOUTS
+            .with_label_values(&[&self.chain, &self.chart.to_string()])
+            .inc();
+        if self.is_fixed() || self.current <= 1 {
+            return false;
+        }
+        tracing::warn!(chain = self.chain, chart =% self.chart, batch_size = self.current, "batch step hit statement timeout, retrying with smaller batch");
+        self.set(self.current / 2);
+        true
+    }
+}
+
+fn is_statement_timeout(err: &UpdateError) -> bool {
+    match err {
+        // the message is the same for all postgres versions:
+        // "canceling statement due to statement timeout"
+        UpdateError::BlockscoutDB(e) | UpdateError::UserOpsDB(e) => {
+            e.to_string().contains("statement timeout")
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::DbErr;
+
+    use super::*;
+    use crate::ResolutionKind;
+
+    fn timeout_error() -> UpdateError {
+        UpdateError::BlockscoutDB(DbErr::Custom(
+            "canceling statement due to statement timeout".to_owned(),
+        ))
+    }
+
+    fn key(name: &str) -> ChartKey {
+        ChartKey::new(name.to_owned(), ResolutionKind::Day)
+    }
+
+    #[test]
+    fn size_grows_on_fast_steps_within_bounds() {
+        let mut size = AdaptiveBatchSize::load("", key("adaptiveGrows"), 30);
+        assert_eq!(size.repeats(), 30);
+        size.observe_step(Duration::from_secs(1), true);
+        assert_eq!(size.repeats(), 30);
+        for _ in 0..3 {
+            size.observe_step(TARGET_STEP_TIME * 2, true);
+        }
+        assert_eq!(size.repeats(), 3);
+        size.observe_step(Duration::from_secs(1), true);
+        assert_eq!(size.repeats(), 6);
+        // shorter steps don't tell much about performance
+        size.observe_step(Duration::from_secs(1), false);
+        assert_eq!(size.repeats(), 6);
+        // moderate time doesn't change the size
+        size.observe_step(FAST_STEP_TIME * 2, true);
+        assert_eq!(size.repeats(), 6);
+        for _ in 0..10 {
+            size.observe_step(Duration::from_secs(1), true);
+        }
+        assert_eq!(size.repeats(), 30);
+    }
+
+    #[test]
+    fn size_shrinks_on_slow_steps() {
+        let mut size = AdaptiveBatchSize::load("", key("adaptiveShrinks"), 4);
+        size.observe_step(TARGET_STEP_TIME * 2, true);
+        assert_eq!(size.repeats(), 2);
+        size.observe_step(TARGET_STEP_TIME * 2, false);
+        assert_eq!(size.repeats(), 1);
+        size.observe_step(TARGET_STEP_TIME * 2, true);
+        assert_eq!(size.repeats(), 1);
+    }
+
+    #[test]
+    fn size_shrinks_on_timeout() {
+        let mut size = AdaptiveBatchSize::load("", key("adaptiveTimeout"), 2);
+        assert!(!size.shrink_on_timeout(&UpdateError::Internal("other".to_owned())));
+        assert!(!size.shrink_on_timeout(&UpdateError::StatsDB(DbErr::Custom(
+            "canceling statement due to statement timeout".to_owned()
+        ))));
+        assert_eq!(size.repeats(), 2);
+        assert!(size.shrink_on_timeout(&timeout_error()));
+        assert_eq!(size.repeats(), 1);
+        // can't be reduced further
+        assert!(!size.shrink_on_timeout(&timeout_error()));
+        assert_eq!(size.repeats(), 1);
+    }
+
+    #[test]
+    fn size_is_remembered() {
+        let mut size = AdaptiveBatchSize::load("", key("adaptiveRemembered"), 30);
+        size.observe_step(TARGET_STEP_TIME * 2, true);
+        assert_eq!(
+            AdaptiveBatchSize::load("", key("adaptiveRemembered"), 30).repeats(),
+            15
+        );
+        // bound is applied to the remembered size
+        assert_eq!(
+            AdaptiveBatchSize::load("", key("adaptiveRemembered"), 10).repeats(),
+            10
+        );
+        // other charts are not affected
+        assert_eq!(
+            AdaptiveBatchSize::load("", key("adaptiveRememberedOther"), 30).repeats(),
+            30
+        );
+        // as well as the same chart of other chains
+        assert_eq!(
+            AdaptiveBatchSize::load("other-chain", key("adaptiveRemembered"), 30).repeats(),
+            30
+        );
+    }
+
+    #[test]
+    fn unlimited_size_is_fixed() {
+        let mut size = AdaptiveBatchSize::load("", key("adaptiveUnlimited"), u64::MAX);
+        size.observe_step(TARGET_STEP_TIME * 2, true);
+        assert_eq!(size.repeats(), u64::MAX);
+        assert!(!size.shrink_on_timeout(&timeout_error()));
+        assert_eq!(size.repeats(), u64::MAX);
+    }
+}
@@ -2,9 +2,13 @@
 //!
 //! Update for some period P can be done only with dependencies'
 //! data for the same exact period P.
+//!
+//! `BatchSizeUpperBound` is the maximum step size. The actual size is adjusted
+//! according to the time of dependency data fetching (see [`adaptive`]).
 
 use std::{fmt::Debug, marker::PhantomData, ops::Range, time::Instant};
 
+use adaptive::AdaptiveBatchSize;
 use blockscout_metrics_tools::AggregateTimer;
 use chrono::{DateTime, Utc};
 use parameter_traits::BatchStepBehaviour;
@@ -21,6 +25,7 @@ use crate::{
     ChartProperties, UpdateError,
 };
 
+pub mod adaptive;
 pub mod parameter_traits;
 pub mod parameters;
 
@@ -67,18 +72,33 @@ where
             .map(|p| p.timespan.saturating_next_timespan());
         let update_range_start = match update_from {
             Some(d) => d,
-            None => ChartProps::Resolution::from_date(
+            None => ChartProps::Resolution::from_timestamp(
                 get_min_date_blockscout(cx.blockscout)
                     .await
-                    .map(|time| time.date())
+                    .map(|time| time.and_utc())
                     .map_err(UpdateError::BlockscoutDB)?,
             ),
         };
+        // there is no need to calculate values that are going to be removed right away
+        let update_range_start = match ChartProps::max_stored_timespans() {
+            Some(max_stored_timespans) => {
+                let retention_start = ChartProps::Resolution::from_timestamp(now).saturating_sub(
+                    TimespanDuration::from_timespan_repeats(max_stored_timespans),
+                );
+                update_range_start.max(retention_start)
+            }
+            None => update_range_start,
+        };
 
-        let steps = generate_batch_ranges(update_range_start, now, BatchSizeUpperBound::get())?;
-        let n = steps.len();
-
-        for (i, range) in steps.into_iter().enumerate() {
+        let mut batch_size = AdaptiveBatchSize::load(
+            cx.chain,
+            ChartProps::key(),
+            BatchSizeUpperBound::get().repeats(),
+        );
+        let mut step_start = update_range_start;
+        let mut i = 0;
+        while let Some(range) = next_batch_range(step_start.clone(), now, batch_size.current())? {
+            i += 1;
             let previous_step_last_point = get_previous_step_last_point::<
                 Query,
                 ChartProps::Resolution,
@@ -87,11 +107,13 @@ where
             tracing::info!(
                 range =? range.clone().into_date_time_range(),
                 previous_step_last_point =? previous_step_last_point,
+                batch_size = batch_size.repeats(),
                 chart =% ChartProps::key(),
-                "run {}/{} step of batch update", i + 1, n
+                "run {} step of batch update", i
             );
             let now = Instant::now();
-            let found = batch_update_values_step::<
+            let fetch_time_before = dependency_data_fetch_timer.total_time();
+            let step_result = batch_update_values_step::<
                 MainDep,
                 ResolutionDep,
                 BatchStep,
@@ -104,16 +126,37 @@ where
                 range.clone(),
                 dependency_data_fetch_timer,
             )
-            .await?;
+            .await;
+            let fetch_time = dependency_data_fetch_timer
+                .total_time()
+                .saturating_sub(fetch_time_before);
+            let found = match step_result {
+                Ok(found) => found,
+                // retry the same step with smaller batch
+                Err(e) if batch_size.shrink_on_timeout(&e) => continue,
+                Err(e) => return Err(e),
+            };
+            let full_size = match &range {
+                BatchRange::Full(r) => r.start.saturating_add(batch_size.current()) == r.end,
+                BatchRange::Partial { .. } => false,
+            };
+            batch_size.observe_step(fetch_time, full_size);
             // for query in `get_previous_step_last_point` to work correctly
-            Self::update_metadata(cx.db, chart_id, range.into_date_time_range().end).await?;
+            Self::update_metadata(cx.db, chart_id, range.clone().into_date_time_range().end)
+                .await?;
             let elapsed: std::time::Duration = now.elapsed();
             tracing::info!(
                 found =? found,
                 elapsed =? elapsed,
+                fetch_time =? fetch_time,
                 chart =% ChartProps::key(),
-                "{}/{} step of batch done", i + 1, n
+                "{} step of batch done", i
             );
+            match range {
+                BatchRange::Full(Range { start: _, end }) => step_start = end,
+                // the range ends at `now`
+                BatchRange::Partial { .. } => break,
+            }
         }
         Ok(())
     }
@@ -216,13 +259,15 @@ impl<Resolution: Timespan> BatchRange<Resolution> {
     }
 }
 
-/// Split the range [`start`, `end`) into multiple
-/// with maximum length `step`
-fn generate_batch_ranges<Resolution>(
+/// The first range of splitting [`start`, `end`) into multiple
+/// with maximum length `max_step`.
+///
+/// `None` if the range is empty.
+fn next_batch_range<Resolution>(
     start: Resolution,
     end: DateTime<Utc>,
     max_step: TimespanDuration<Resolution>,
-) -> Result<Vec<BatchRange<Resolution>>, UpdateError>
+) -> Result<Option<BatchRange<Resolution>>, UpdateError>
 where
     Resolution: Timespan + Ord + Clone,
 {
@@ -231,37 +276,22 @@ where
             "Zero maximum batch step is not allowed".into(),
         ));
     }
-    let mut date_range = Vec::new();
-    let mut current_start = start;
-
-    loop {
-        let next_start = current_start.clone().saturating_add(max_step.clone()); // finish the ranges right at the end
-        let next_start_timestamp = next_start.saturating_start_timestamp();
-        if next_start_timestamp > end {
-            if end == Resolution::from_date(end.date_naive()).saturating_start_timestamp() {
-                // the last interval can be represented as `Resolution` without
-                // any fractions
-                let end_timespan = Resolution::from_date(end.date_naive());
-                let range = current_start..end_timespan;
-                if !range.is_empty() {
-                    date_range.push(BatchRange::Full(range));
-                }
-            } else {
-                date_range.push(BatchRange::Partial {
-                    start: current_start,
-                    end,
-                });
-            }
-            break;
-        }
-        date_range.push(BatchRange::Full(current_start..next_start.clone()));
-        current_start = next_start;
-        if next_start_timestamp == end {
-            break;
-        }
+    if start.saturating_start_timestamp() >= end {
+        return Ok(None);
     }
-
-    Ok(date_range)
+    let next_start = start.saturating_add(max_step);
+    if next_start.saturating_start_timestamp() <= end {
+        return Ok(Some(BatchRange::Full(start..next_start)));
+    }
+    // finish the ranges right at the end
+    let range = if end == Resolution::from_timestamp(end).saturating_start_timestamp() {
+        // the last interval can be represented as `Resolution` without
+        // any fractions
+        BatchRange::Full(start..Resolution::from_timestamp(end))
+    } else {
+        BatchRange::Partial { start, end }
+    };
+    Ok(Some(range))
 }
 
 #[cfg(test)]
@@ -275,6 +305,28 @@ mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    /// Split the range [`start`, `end`) into multiple
+    /// with maximum length `step`
+    fn generate_batch_ranges<Resolution>(
+        start: Resolution,
+        end: DateTime<Utc>,
+        max_step: TimespanDuration<Resolution>,
+    ) -> Result<Vec<BatchRange<Resolution>>, UpdateError>
+    where
+        Resolution: Timespan + Ord + Clone,
+    {
+        let mut ranges = Vec::new();
+        let mut current_start = start;
+        while let Some(range) = next_batch_range(current_start, end, max_step.clone())? {
+            ranges.push(range.clone());
+            match range {
+                BatchRange::Full(r) => current_start = r.end,
+                BatchRange::Partial { .. } => break,
+            }
+        }
+        Ok(ranges)
+    }
+
     #[test]
     fn test_generate_date_ranges() {
         for ((from, to), expected) in [
@@ -335,6 +387,39 @@ mod tests {
         )
     }
 
+    #[test]
+    fn next_batch_range_works() {
+        assert!(next_batch_range(
+            d("2015-07-20"),
+            day_start(&d("2015-07-21")),
+            TimespanDuration::from_days(0)
+        )
+        .is_err());
+        // start after the end
+        assert_eq!(
+            None,
+            next_batch_range(
+                d("2015-07-22"),
+                dt("2015-07-21T20:20:20").and_utc(),
+                TimespanDuration::from_days(1)
+            )
+            .unwrap()
+        );
+        // unlimited step
+        assert_eq!(
+            Some(BatchRange::Partial {
+                start: d("2015-07-20"),
+                end: dt("2016-07-21T20:20:20").and_utc()
+            }),
+            next_batch_range(
+                d("2015-07-20"),
+                dt("2016-07-21T20:20:20").and_utc(),
+                TimespanDuration::from_days(u64::MAX)
+            )
+            .unwrap()
+        );
+    }
+
     #[test]
     fn batch_range_into_timestamp_range_works() {
         assert_eq!(
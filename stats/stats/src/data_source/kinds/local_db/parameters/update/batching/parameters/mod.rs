@@ -1,12 +1,14 @@
+use std::marker::PhantomData;
+
 use chrono::{DateTime, NaiveDate, Utc};
-use sea_orm::DatabaseConnection;
+use sea_orm::{DatabaseConnection, TransactionTrait};
 
 use crate::{
-    charts::db_interaction::write::insert_data_many,
+    charts::db_interaction::write::{insert_data_many, insert_series_data_many},
     gettable_const,
     types::{
-        timespans::{Month, Week, Year},
-        Timespan, TimespanDuration, TimespanValue,
+        timespans::{Hour, Month, Week, Year},
+        SeriesValues, Timespan, TimespanDuration, TimespanValue,
     },
     UpdateError,
 };
@@ -19,6 +21,7 @@ pub mod mock;
 
 pub use cumulative::*;
 
+gettable_const!(Batch24Hours: TimespanDuration<Hour> = TimespanDuration::from_timespan_repeats(24));
 gettable_const!(Batch30Days: TimespanDuration<NaiveDate> = TimespanDuration::from_days(30));
 gettable_const!(BatchMaxDays: TimespanDuration<NaiveDate> = TimespanDuration::from_days(u64::MAX));
 gettable_const!(Batch30Weeks: TimespanDuration<Week> = TimespanDuration::from_timespan_repeats(30));
@@ -60,3 +63,69 @@ where
         Ok(found)
     }
 }
+
+/// Value of a multi-series chart point that is stored as a regular
+/// chart value.
+pub trait SeriesMainValue {
+    fn main_value(values: &SeriesValues) -> Result<String, UpdateError>;
+}
+
+/// Sum of all series is the main value
+pub struct SeriesTotal;
+
+impl SeriesMainValue for SeriesTotal {
+    fn main_value(values: &SeriesValues) -> Result<String, UpdateError> {
+        values
+            .total()
+            .map_err(|e| UpdateError::Internal(format!("invalid series value: {e}")))
+    }
+}
+
+/// Pass the multi-series data from main dependency right into the database.
+///
+/// Values of each series are stored separately, while the main value
+/// (sum of the series by default) is saved as a regular chart value.
+pub struct PassSeriesVecStep<MainValue = SeriesTotal>(PhantomData<MainValue>);
+
+impl<Resolution, MainValue>
+    BatchStepBehaviour<Resolution, Vec<TimespanValue<Resolution, SeriesValues>>, ()>
+    for PassSeriesVecStep<MainValue>
+where
+    Resolution: Timespan + Clone + Send + Sync,
+    MainValue: SeriesMainValue + Send + Sync,
+{
+    async fn batch_update_values_step_with(
+        db: &DatabaseConnection,
+        chart_id: i32,
+        _update_time: DateTime<Utc>,
+        min_blockscout_block: i64,
+        _last_accurate_point: TimespanValue<Resolution, String>,
+        main_data: Vec<TimespanValue<Resolution, SeriesValues>>,
+        _resolution_data: (),
+    ) -> Result<usize, UpdateError> {
+        let found = main_data.len();
+        let main_values = main_data
+            .iter()
+            .map(|value| {
+                let main_value = TimespanValue {
+                    timespan: value.timespan.clone(),
+                    value: MainValue::main_value(&value.value)?,
+                };
+                Ok(main_value.active_model(chart_id, Some(min_blockscout_block)))
+            })
+            .collect::<Result<Vec<_>, UpdateError>>()?;
+        let series = main_data
+            .iter()
+            .flat_map(|value| value.series_active_models(chart_id, Some(min_blockscout_block)))
+            .collect::<Vec<_>>();
+        let tx = db.begin().await.map_err(UpdateError::StatsDB)?;
+        insert_data_many(&tx, main_values)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        insert_series_data_many(&tx, series)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        tx.commit().await.map_err(UpdateError::StatsDB)?;
+        Ok(found)
+    }
+}
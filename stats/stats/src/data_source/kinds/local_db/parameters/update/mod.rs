@@ -1,4 +1,6 @@
 pub mod batching;
 pub mod point;
+pub mod top_list;
 
 pub use point::PassPoint;
+pub use top_list::PassTopList;
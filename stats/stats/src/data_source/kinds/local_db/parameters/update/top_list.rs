@@ -0,0 +1,34 @@
+use std::marker::PhantomData;
+
+use blockscout_metrics_tools::AggregateTimer;
+
+use crate::{
+    charts::db_interaction::write::replace_top_list,
+    data_source::{kinds::local_db::UpdateBehaviour, DataSource, UpdateContext},
+    types::{TimespanValue, TopListEntry},
+    UpdateError,
+};
+
+/// Replace the stored top list with output of the `MainDep`
+pub struct PassTopList<MainDep>(PhantomData<MainDep>);
+
+impl<MainDep, Resolution> UpdateBehaviour<MainDep, (), Resolution> for PassTopList<MainDep>
+where
+    MainDep: DataSource<Output = Vec<TopListEntry>>,
+    Resolution: Send,
+{
+    async fn update_values(
+        cx: &UpdateContext<'_>,
+        chart_id: i32,
+        _last_accurate_point: Option<TimespanValue<Resolution, String>>,
+        min_blockscout_block: i64,
+        remote_fetch_timer: &mut AggregateTimer,
+    ) -> Result<(), UpdateError> {
+        // the list is always recalculated as a whole, so range is not used
+        let data = MainDep::query_data(cx, None, remote_fetch_timer).await?;
+        replace_top_list(cx.db, chart_id, &data, Some(min_blockscout_block))
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        Ok(())
+    }
+}
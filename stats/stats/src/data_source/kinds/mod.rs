@@ -27,3 +27,4 @@ pub mod auxiliary;
 pub mod data_manipulation;
 pub mod local_db;
 pub mod remote_db;
+pub mod user_ops_db;
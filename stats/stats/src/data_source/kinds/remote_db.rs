@@ -28,7 +28,9 @@ use sea_orm::{prelude::DateTimeUtc, DatabaseConnection, DbErr, FromQueryResult,
 
 use crate::{
     data_source::{source::DataSource, types::UpdateContext},
-    types::TimespanValue,
+    types::{
+        group_series_by_date, timespans::DateValue, DbDateSeriesValue, SeriesValues, TimespanValue,
+    },
     UpdateError,
 };
 
@@ -117,6 +119,35 @@ where
     }
 }
 
+/// Pull multi-series data from remote (blockscout) db according to statement
+/// `S`.
+///
+/// The statement must return `date`, `series` and `value` (text) columns,
+/// one row for each series value. The rows are grouped by date
+/// and sorted.
+pub struct PullAllSeriesWithAndSort<S>(PhantomData<S>)
+where
+    S: StatementFromRange;
+
+impl<S> RemoteQueryBehaviour for PullAllSeriesWithAndSort<S>
+where
+    S: StatementFromRange,
+{
+    type Output = Vec<DateValue<SeriesValues>>;
+
+    async fn query_data(
+        cx: &UpdateContext<'_>,
+        range: Option<Range<DateTimeUtc>>,
+    ) -> Result<Vec<DateValue<SeriesValues>>, UpdateError> {
+        let query = S::get_statement(range);
+        let rows = DbDateSeriesValue::find_by_statement(query)
+            .all(cx.blockscout)
+            .await
+            .map_err(UpdateError::BlockscoutDB)?;
+        Ok(group_series_by_date(rows))
+    }
+}
+
 pub trait StatementForOne {
     fn get_statement() -> Statement;
 }
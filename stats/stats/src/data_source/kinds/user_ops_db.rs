@@ -0,0 +1,143 @@
+//! Remote source bound to the `user-ops-indexer` database.
+//!
+//! Works the same as [`RemoteDatabaseSource`](super::remote_db::RemoteDatabaseSource)
+//! (and uses the same query behaviours, e.g.
+//! [`PullAllWithAndSort`](super::remote_db::PullAllWithAndSort)), but the queries
+//! are performed in [`UpdateContext::user_ops`] database instead of the blockscout one.
+//!
+//! The database is optional, so charts using this source should only be enabled if
+//! it is configured. Otherwise the update fails with an error.
+
+use std::{marker::PhantomData, ops::Range};
+
+use blockscout_metrics_tools::AggregateTimer;
+use chrono::{DateTime, Utc};
+use sea_orm::{prelude::DateTimeUtc, DatabaseConnection, DbErr};
+
+use crate::{
+    data_source::{source::DataSource, types::UpdateContext},
+    UpdateError,
+};
+
+use super::remote_db::RemoteQueryBehaviour;
+
+/// See [module-level documentation](self)
+pub struct UserOpsDatabaseSource<Q: RemoteQueryBehaviour>(PhantomData<Q>);
+
+impl<Q: RemoteQueryBehaviour> DataSource for UserOpsDatabaseSource<Q> {
+    type MainDependencies = ();
+    type ResolutionDependencies = ();
+    type Output = Q::Output;
+    // No local state => no race conditions expected
+    fn mutex_id() -> Option<String> {
+        None
+    }
+
+    async fn init_itself(
+        _db: &DatabaseConnection,
+        _init_time: &DateTime<Utc>,
+    ) -> Result<(), DbErr> {
+        Ok(())
+    }
+
+    async fn query_data(
+        cx: &UpdateContext<'_>,
+        range: Option<Range<DateTimeUtc>>,
+        remote_fetch_timer: &mut AggregateTimer,
+    ) -> Result<<Self as DataSource>::Output, UpdateError> {
+        let user_ops = cx.user_ops.ok_or_else(|| {
+            UpdateError::Internal("user-ops-indexer database is not configured".to_owned())
+        })?;
+        // query behaviours use `blockscout` connection
+        let user_ops_cx = UpdateContext {
+            blockscout: user_ops,
+            ..cx.clone()
+        };
+        let _interval = remote_fetch_timer.start_interval();
+        Q::query_data(&user_ops_cx, range)
+            .await
+            .map_err(|e| match e {
+                UpdateError::BlockscoutDB(e) => UpdateError::UserOpsDB(e),
+                e => e,
+            })
+    }
+
+    async fn update_itself(_cx: &UpdateContext<'_>) -> Result<(), UpdateError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use sea_orm::{ConnectionTrait, Database, DbBackend, Statement};
+
+    use super::*;
+
+    struct CountUserOps;
+
+    impl RemoteQueryBehaviour for CountUserOps {
+        type Output = i64;
+
+        async fn query_data(
+            cx: &UpdateContext<'_>,
+            _range: Option<Range<DateTimeUtc>>,
+        ) -> Result<i64, UpdateError> {
+            let statement = Statement::from_string(
+                DbBackend::Sqlite,
+                "SELECT COUNT(*) as value FROM user_operations",
+            );
+            let row = cx
+                .blockscout
+                .query_one(statement)
+                .await
+                .map_err(UpdateError::BlockscoutDB)?
+                .ok_or_else(|| UpdateError::Internal("query returned nothing".into()))?;
+            row.try_get("", "value").map_err(UpdateError::BlockscoutDB)
+        }
+    }
+
+    type CountUserOpsSource = UserOpsDatabaseSource<CountUserOps>;
+
+    #[tokio::test]
+    async fn queries_user_ops_database() {
+        let db = Database::connect("sqlite::memory:").await.unwrap();
+        let user_ops = Database::connect("sqlite::memory:").await.unwrap();
+        user_ops
+            .execute_unprepared(
+                "CREATE TABLE user_operations (hash TEXT); \
+                INSERT INTO user_operations VALUES ('a'), ('b');",
+            )
+            .await
+            .unwrap();
+        let mut cx = UpdateContext {
+            db: &db,
+            blockscout: &db,
+            user_ops: None,
+            chain: "",
+            time: Utc::now(),
+            force_full: false,
+            cache: Default::default(),
+        };
+
+        let err = CountUserOpsSource::query_data(&cx, None, &mut AggregateTimer::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, UpdateError::Internal(_)), "{err:?}");
+
+        cx.user_ops = Some(&user_ops);
+        assert_eq!(
+            CountUserOpsSource::query_data(&cx, None, &mut AggregateTimer::new())
+                .await
+                .unwrap(),
+            2
+        );
+
+        // errors are attributed to the right database
+        cx.user_ops = Some(&db);
+        let err = CountUserOpsSource::query_data(&cx, None, &mut AggregateTimer::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, UpdateError::UserOpsDB(_)), "{err:?}");
+    }
+}
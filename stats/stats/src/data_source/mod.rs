@@ -68,4 +68,4 @@ pub mod types;
 mod tests;
 
 pub use source::DataSource;
-pub use types::{UpdateContext, UpdateParameters};
+pub use types::{UpdateCache, UpdateContext, UpdateParameters};
@@ -110,6 +110,40 @@ pub trait DataSource {
         ids
     }
 
+    /// MUTEX_ID of itself or, if it does not have one, MUTEX_ID's of
+    /// the closest dependencies that have them
+    fn closest_mutex_ids() -> HashSet<String> {
+        match Self::mutex_id() {
+            Some(self_id) => HashSet::from([self_id]),
+            None => {
+                let mut ids = Self::MainDependencies::closest_mutex_ids();
+                ids.extend(Self::ResolutionDependencies::closest_mutex_ids());
+                ids
+            }
+        }
+    }
+
+    /// Direct dependencies between itself and all of its dependencies as
+    /// `(dependent, dependency)` pairs of MUTEX_ID's.
+    ///
+    /// Sources without MUTEX_ID (e.g. remote or data manipulation ones) are not
+    /// present in the graph; sources that depend on them are connected to
+    /// their dependencies instead.
+    fn dependency_mutex_edges() -> HashSet<(String, String)> {
+        let mut edges = Self::MainDependencies::dependency_mutex_edges();
+        edges.extend(Self::ResolutionDependencies::dependency_mutex_edges());
+        if let Some(self_id) = Self::mutex_id() {
+            let mut dependencies = Self::MainDependencies::closest_mutex_ids();
+            dependencies.extend(Self::ResolutionDependencies::closest_mutex_ids());
+            edges.extend(
+                dependencies
+                    .into_iter()
+                    .map(|dependency| (self_id.clone(), dependency)),
+            );
+        }
+        edges
+    }
+
     /// Update dependencies' and this source's data (values + metadata).
     ///
     /// Should be idempontent with regards to `current_time` (in `cx`).
@@ -199,6 +233,14 @@ impl DataSource for () {
         HashSet::new()
     }
 
+    fn closest_mutex_ids() -> HashSet<String> {
+        HashSet::new()
+    }
+
+    fn dependency_mutex_edges() -> HashSet<(String, String)> {
+        HashSet::new()
+    }
+
     async fn update_recursively(_cx: &UpdateContext<'_>) -> Result<(), UpdateError> {
         // stop recursion
         Ok(())
@@ -290,6 +332,22 @@ macro_rules! impl_data_source_for_tuple {
                 )+
                 ids
             }
+
+            fn closest_mutex_ids() -> HashSet<String> {
+                let mut ids = HashSet::new();
+                $(
+                    ids.extend($element_generic_name::closest_mutex_ids());
+                )+
+                ids
+            }
+
+            fn dependency_mutex_edges() -> HashSet<(String, String)> {
+                let mut edges = HashSet::new();
+                $(
+                    edges.extend($element_generic_name::dependency_mutex_edges());
+                )+
+                edges
+            }
         }
     };
 }
@@ -320,4 +378,21 @@ mod tests {
             HashSet::from([NewContracts::mutex_id().unwrap(),])
         )
     }
+
+    #[test]
+    fn dependency_edges_listed_correctly() {
+        assert_eq!(
+            ContractsGrowth::dependency_mutex_edges(),
+            HashSet::from([(
+                ContractsGrowth::mutex_id().unwrap(),
+                NewContracts::mutex_id().unwrap(),
+            )])
+        );
+        // depends only on remote source
+        assert_eq!(NewContracts::dependency_mutex_edges(), HashSet::new());
+        assert_eq!(
+            NewContracts::closest_mutex_ids(),
+            HashSet::from([NewContracts::mutex_id().unwrap()])
+        );
+    }
 }
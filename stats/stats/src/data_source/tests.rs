@@ -244,6 +244,8 @@ async fn update_examples() {
     let parameters = UpdateParameters {
         db: &db,
         blockscout: &blockscout,
+        user_ops: None,
+        chain: "",
         update_time_override: None,
         force_full: true,
     };
@@ -1,3 +1,9 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
 use chrono::Utc;
 use sea_orm::DatabaseConnection;
 
@@ -5,6 +11,11 @@ use sea_orm::DatabaseConnection;
 pub struct UpdateParameters<'a> {
     pub db: &'a DatabaseConnection,
     pub blockscout: &'a DatabaseConnection,
+    /// `user-ops-indexer` database, if available
+    pub user_ops: Option<&'a DatabaseConnection>,
+    /// Name of the chain the charts belong to (empty for the default one).
+    /// Separates the in-process state and metrics of charts of different chains
+    pub chain: &'a str,
     /// If `None`, it will be measured at the start of update
     /// (i.e. after taking mutexes)
     pub update_time_override: Option<chrono::DateTime<Utc>>,
@@ -16,9 +27,15 @@ pub struct UpdateParameters<'a> {
 pub struct UpdateContext<'a> {
     pub db: &'a DatabaseConnection,
     pub blockscout: &'a DatabaseConnection,
+    /// `user-ops-indexer` database, if available
+    pub user_ops: Option<&'a DatabaseConnection>,
+    /// Name of the chain the charts belong to (see [`UpdateParameters::chain`])
+    pub chain: &'a str,
     /// Update time
     pub time: chrono::DateTime<Utc>,
     pub force_full: bool,
+    /// Shared between all charts updated with this context
+    pub cache: UpdateCache,
 }
 
 impl<'a> UpdateContext<'a> {
@@ -26,12 +43,40 @@ impl<'a> UpdateContext<'a> {
         Self {
             db: value.db,
             blockscout: value.blockscout,
+            user_ops: value.user_ops,
+            chain: value.chain,
             time: value.update_time_override.unwrap_or_else(Utc::now),
             force_full: value.force_full,
+            cache: UpdateCache::default(),
         }
     }
 }
 
+/// Storage for the data that is used by several charts within one update
+/// (e.g. results of a remote query that several charts are computed from).
+///
+/// Values are stored by arbitrary string keys and are dropped together with
+/// the update context.
+#[derive(Clone, Default)]
+pub struct UpdateCache {
+    values: Arc<Mutex<HashMap<String, Arc<dyn Any + Send + Sync>>>>,
+}
+
+impl UpdateCache {
+    /// `None` if there is no value for the key or it has another type
+    pub fn get<V: Clone + 'static>(&self, key: &str) -> Option<V> {
+        let values = self.values.lock().expect("cache mutex is not poisoned");
+        values.get(key)?.downcast_ref::<V>().cloned()
+    }
+
+    pub fn insert<V: Send + Sync + 'static>(&self, key: String, value: V) {
+        self.values
+            .lock()
+            .expect("cache mutex is not poisoned")
+            .insert(key, Arc::new(value));
+    }
+}
+
 pub trait Get {
     type Value;
     fn get() -> Self::Value;
@@ -59,3 +104,36 @@ macro_rules! gettable_const {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn update_cache_works() {
+        let cache = UpdateCache::default();
+        assert_eq!(cache.get::<u64>("key"), None);
+
+        cache.insert("key".to_string(), 123u64);
+        assert_eq!(cache.get::<u64>("key"), Some(123));
+        // value of another type is not returned
+        assert_eq!(cache.get::<String>("key"), None);
+        assert_eq!(cache.get::<u64>("another_key"), None);
+
+        cache.insert("key".to_string(), vec!["value".to_string()]);
+        assert_eq!(
+            cache.get::<Vec<String>>("key"),
+            Some(vec!["value".to_string()])
+        );
+        assert_eq!(cache.get::<u64>("key"), None);
+    }
+
+    #[test]
+    fn update_cache_clones_share_values() {
+        let cache = UpdateCache::default();
+        let cloned = cache.clone();
+        cloned.insert("key".to_string(), 5i64);
+        assert_eq!(cache.get::<i64>("key"), Some(5));
+    }
+}
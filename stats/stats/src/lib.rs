@@ -14,10 +14,13 @@ pub use entity;
 pub use migration;
 
 pub use charts::{
-    counters,
-    db_interaction::read::{get_line_chart_data, get_raw_counters, ReadError},
-    lines, types, ChartKey, ChartProperties, ChartPropertiesObject, MissingDatePolicy, Named,
-    ResolutionKind, UpdateError,
+    counters, custom,
+    db_interaction::read::{
+        find_chart, get_charts_update_status, get_line_chart_data, get_line_chart_series,
+        get_raw_counters, get_raw_counters_at, get_top_list_data, ChartUpdateStatus, ReadError,
+    },
+    derived, lines, local_time, tokens, top_lists, types, ChartKey, ChartProperties,
+    ChartPropertiesObject, MissingDatePolicy, Named, ResolutionKind, UpdateError,
 };
 
 pub use utils::exclusive_datetime_range_to_inclusive;
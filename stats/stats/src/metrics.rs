@@ -1,25 +1,76 @@
 use lazy_static::lazy_static;
-use prometheus::{register_histogram_vec, register_int_counter_vec, HistogramVec, IntCounterVec};
+use prometheus::{
+    register_gauge_vec, register_histogram_vec, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, GaugeVec, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec,
+};
 
 lazy_static! {
     pub static ref UPDATE_ERRORS: IntCounterVec = register_int_counter_vec!(
         "stats_update_errors_total",
         "total update errors",
-        &["chart_id"],
+        &["chain", "chart_id"],
     )
     .unwrap();
     pub static ref CHART_UPDATE_TIME: HistogramVec = register_histogram_vec!(
         "stats_chart_update_time_seconds",
         "single chart update time",
-        &["chart_id"],
+        &["chain", "chart_id"],
         vec![1.0, 2.0, 4.0, 8.0, 16.0, 30.0, 60.0, 120.0, 240.0, 480.0, 960.0, 1920.0, 3840.0],
     )
     .unwrap();
     pub static ref CHART_FETCH_NEW_DATA_TIME: HistogramVec = register_histogram_vec!(
         "stats_fetch_new_data_time_seconds",
         "single chart time for fetching data from blockscout",
-        &["chart_id"],
+        &["chain", "chart_id"],
         vec![1.0, 2.0, 4.0, 8.0, 16.0, 30.0, 60.0, 120.0, 240.0, 480.0, 960.0, 1920.0, 3840.0],
     )
     .unwrap();
+    pub static ref BATCH_STEP_TIME: HistogramVec = register_histogram_vec!(
+        "stats_batch_step_time_seconds",
+        "time of fetching dependency data for a single batch update step",
+        &["chain", "chart_id"],
+        vec![1.0, 2.0, 4.0, 8.0, 16.0, 30.0, 60.0, 120.0, 240.0, 480.0, 960.0, 1920.0, 3840.0],
+    )
+    .unwrap();
+    pub static ref BATCH_SIZE: IntGaugeVec = register_int_gauge_vec!(
+        "stats_batch_size",
+        "current batch size of the chart (in chart timespans)",
+        &["chain", "chart_id"],
+    )
+    .unwrap();
+    pub static ref BATCH_STEP_TIMEOUTS: IntCounterVec = register_int_counter_vec!(
+        "stats_batch_step_timeouts_total",
+        "batch update steps that hit statement timeout",
+        &["chain", "chart_id"],
+    )
+    .unwrap();
+    pub static ref CHART_STALENESS: GaugeVec = register_gauge_vec!(
+        "stats_chart_staleness_seconds",
+        "time passed since the last successful chart update",
+        &["chain", "chart", "resolution"],
+    )
+    .unwrap();
+    pub static ref CHART_LAST_UPDATE_FAILED: IntGaugeVec = register_int_gauge_vec!(
+        "stats_chart_last_update_failed",
+        "1 if the latest chart update failed, 0 otherwise",
+        &["chain", "chart", "resolution"],
+    )
+    .unwrap();
+    pub static ref READ_CACHE_HITS: IntCounterVec = register_int_counter_vec!(
+        "stats_read_cache_hits_total",
+        "line chart requests served from the read cache",
+        &["chain"],
+    )
+    .unwrap();
+    pub static ref READ_CACHE_MISSES: IntCounterVec = register_int_counter_vec!(
+        "stats_read_cache_misses_total",
+        "line chart requests that were read from DB",
+        &["chain"],
+    )
+    .unwrap();
+    pub static ref READ_CACHE_SIZE: IntGauge = register_int_gauge!(
+        "stats_read_cache_size_bytes",
+        "approximate memory occupied by cached responses",
+    )
+    .unwrap();
 }
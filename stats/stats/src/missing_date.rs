@@ -130,7 +130,7 @@ where
     };
 
     if let Some(interval_limit) = interval_limit {
-        if to.clone().into_date() - from.clone().into_date() > interval_limit {
+        if to.saturating_start_timestamp() - from.saturating_start_timestamp() > interval_limit {
             return Err(ReadError::IntervalLimitExceeded(interval_limit));
         }
     }
@@ -262,9 +262,30 @@ pub async fn fill_mock_blockscout_data(blockscout: &DatabaseConnection, max_date
         .unwrap();
 }
 
+/// Adds blocks (without transactions) with the provided timestamps after the mock
+/// blocks from [`fill_mock_blockscout_data`], e.g. to have data within retention
+/// period of hourly charts
+pub async fn fill_mock_blocks_at(blockscout: &DatabaseConnection, timestamps: &[NaiveDateTime]) {
+    // far from indices of the blocks in `fill_mock_blockscout_data`
+    let first_index = 1000;
+    let blocks = timestamps.iter().enumerate().map(|(i, ts)| {
+        mock_block(
+            first_index + i as i64,
+            &ts.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            true,
+        )
+    });
+    blocks::Entity::insert_many(blocks)
+        .exec(blockscout)
+        .await
+        .unwrap();
+}
+
 fn mock_block(index: i64, ts: &str, consensus: bool) -> blocks::ActiveModel {
     let size = 1000 + (index as i32 * 15485863) % 5000;
     let gas_limit = if index <= 3 { 12_500_000 } else { 30_000_000 };
+    // not higher than gas price of any transaction in the block
+    let base_fee_per_gas = index * 50_000_000;
     blocks::ActiveModel {
         number: Set(index),
         hash: Set(index.to_le_bytes().to_vec()),
@@ -278,6 +299,7 @@ fn mock_block(index: i64, ts: &str, consensus: bool) -> blocks::ActiveModel {
         inserted_at: Set(Default::default()),
         updated_at: Set(Default::default()),
         size: Set(Some(size)),
+        base_fee_per_gas: Set(Some(Decimal::new(base_fee_per_gas, 0))),
         ..Default::default()
     }
 }
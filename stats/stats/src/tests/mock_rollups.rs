@@ -0,0 +1,125 @@
+//! Mock data for rollup charts.
+//!
+//! Blockscout creates rollup tables (and columns) only for the corresponding
+//! chain type, so they are not present in the default schema. Only the
+//! columns used by the charts are created here.
+//!
+//! Expected to be called after [`fill_mock_blockscout_data`](super::mock_blockscout::fill_mock_blockscout_data),
+//! as L2 blocks and transactions are taken from there.
+
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+
+/// OP stack batches (frame sequences), their blobs and L1 fees of transactions.
+///
+/// Frame sequences (L1 time -> L2 blocks):
+/// - 2022-11-10T01:00:00 -> 0, 1
+/// - 2022-11-10T13:00:00 -> 2, 3 (2 eip4844 blobs)
+/// - 2022-11-11T10:00:00 -> 4, 5, 6 (1 eip4844 and 1 celestia blob)
+/// - 2022-11-12T06:00:00 -> 7, 8
+/// - 2022-12-01T12:00:00 -> 9
+/// - 2023-02-01T12:00:00 -> 10, 11 (3 eip4844 blobs)
+/// - 1970-01-01T00:00:00 -> 12 (should be ignored)
+///
+/// L1 fee of each transaction is `0.001 * block_number` ETH.
+pub async fn fill_mock_optimism_data(blockscout: &DatabaseConnection) {
+    blockscout
+        .execute_unprepared(
+            r#"
+            CREATE TABLE op_frame_sequences (
+                id BIGINT PRIMARY KEY,
+                l1_timestamp TIMESTAMP WITHOUT TIME ZONE NOT NULL
+            );
+            CREATE TABLE op_transaction_batches (
+                l2_block_number BIGINT PRIMARY KEY,
+                frame_sequence_id BIGINT NOT NULL REFERENCES op_frame_sequences (id)
+            );
+            CREATE TYPE op_frame_sequence_blob_type AS ENUM ('celestia', 'eip4844');
+            CREATE TABLE op_frame_sequence_blobs (
+                id BIGINT PRIMARY KEY,
+                frame_sequence_id BIGINT NOT NULL REFERENCES op_frame_sequences (id),
+                type op_frame_sequence_blob_type NOT NULL,
+                l1_timestamp TIMESTAMP WITHOUT TIME ZONE NOT NULL
+            );
+            ALTER TABLE transactions ADD COLUMN l1_fee NUMERIC(100, 0);
+
+            INSERT INTO op_frame_sequences (id, l1_timestamp) VALUES
+                (1, '2022-11-10T01:00:00'),
+                (2, '2022-11-10T13:00:00'),
+                (3, '2022-11-11T10:00:00'),
+                (4, '2022-11-12T06:00:00'),
+                (5, '2022-12-01T12:00:00'),
+                (6, '2023-02-01T12:00:00'),
+                (7, '1970-01-01T00:00:00');
+            INSERT INTO op_transaction_batches (l2_block_number, frame_sequence_id) VALUES
+                (0, 1), (1, 1),
+                (2, 2), (3, 2),
+                (4, 3), (5, 3), (6, 3),
+                (7, 4), (8, 4),
+                (9, 5),
+                (10, 6), (11, 6),
+                (12, 7);
+            INSERT INTO op_frame_sequence_blobs (id, frame_sequence_id, type, l1_timestamp) VALUES
+                (1, 2, 'eip4844', '2022-11-10T13:00:00'),
+                (2, 2, 'eip4844', '2022-11-10T13:00:00'),
+                (3, 3, 'eip4844', '2022-11-11T10:00:00'),
+                (4, 3, 'celestia', '2022-11-11T10:00:00'),
+                (5, 6, 'eip4844', '2023-02-01T12:00:00'),
+                (6, 6, 'eip4844', '2023-02-01T12:00:00'),
+                (7, 6, 'eip4844', '2023-02-01T12:00:00');
+
+            UPDATE transactions
+            SET l1_fee = block_number * 1000000000000000
+            WHERE block_number IS NOT NULL;
+            "#,
+        )
+        .await
+        .unwrap();
+}
+
+/// Arbitrum batches and L1 gas used by transactions.
+///
+/// Batches (commitment L1 time -> transactions in the batch):
+/// - 2022-11-10T05:00:00 -> 10, 20
+/// - 2022-11-11T05:00:00 -> 5
+/// - 2022-11-11T20:00:00 -> 7
+/// - 2023-01-01T12:00:00 -> 3
+/// - 1970-01-01T00:00:00 -> 100 (should be ignored)
+///
+/// Transactions in blocks 2, 9 and 11 used `1_000_000` L1 gas each.
+pub async fn fill_mock_arbitrum_data(blockscout: &DatabaseConnection) {
+    blockscout
+        .execute_unprepared(
+            r#"
+            CREATE TABLE arbitrum_lifecycle_l1_transactions (
+                id BIGINT PRIMARY KEY,
+                timestamp TIMESTAMP WITHOUT TIME ZONE NOT NULL
+            );
+            CREATE TABLE arbitrum_l1_batches (
+                number INTEGER PRIMARY KEY,
+                transactions_count INTEGER NOT NULL,
+                commitment_id BIGINT NOT NULL REFERENCES arbitrum_lifecycle_l1_transactions (id)
+            );
+            ALTER TABLE transactions ADD COLUMN gas_used_for_l1 NUMERIC(100, 0);
+
+            INSERT INTO arbitrum_lifecycle_l1_transactions (id, timestamp) VALUES
+                (1, '2022-11-10T05:00:00'),
+                (2, '2022-11-11T05:00:00'),
+                (3, '2022-11-11T20:00:00'),
+                (4, '2023-01-01T12:00:00'),
+                (5, '1970-01-01T00:00:00');
+            INSERT INTO arbitrum_l1_batches (number, transactions_count, commitment_id) VALUES
+                (1, 10, 1),
+                (2, 20, 1),
+                (3, 5, 2),
+                (4, 7, 3),
+                (5, 3, 4),
+                (6, 100, 5);
+
+            UPDATE transactions
+            SET gas_used_for_l1 = 1000000
+            WHERE block_number IN (2, 9, 11);
+            "#,
+        )
+        .await
+        .unwrap();
+}
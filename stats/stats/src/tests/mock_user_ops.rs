@@ -0,0 +1,64 @@
+//! Mock data of `user-ops-indexer`.
+//!
+//! The indexer stores user operations next to blockscout tables (the charts
+//! join them with `blocks`), so the data is added to the mock blockscout
+//! database, and the same database is used as `user_ops` one in tests.
+//! Only the columns used by the charts are created.
+//!
+//! Expected to be called after [`fill_mock_blockscout_data`](super::mock_blockscout::fill_mock_blockscout_data),
+//! as blocks are taken from there.
+
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+
+/// User operations (block -> sender, factory, paymaster, bundler, fee):
+/// - 1 (2022-11-10) -> `0xaa..`, with factory, -, `0x11..`, 0.0001 ETH
+/// - 2 (2022-11-10) -> `0xaa..`, -, with paymaster, `0x11..`, 0.0001 ETH
+/// - 2 (2022-11-10) -> `0xbb..`, with factory, with paymaster, `0x22..`, 0.0001 ETH
+/// - 5 (2022-11-11) -> `0xbb..`, -, -, `0x22..`, 0.0001 ETH
+/// - 9 (2022-12-01) -> `0xcc..`, with factory, with paymaster, `0x11..`, 0.0001 ETH
+/// - 11 (2023-02-01) -> `0xaa..`, -, with paymaster, `0x22..`, 0.0002 ETH
+/// - 12 (2023-03-01) -> `0xcc..`, -, -, `0x11..`, 0.0006 ETH
+/// - 15 (non-consensus block) -> `0xdd..`, with factory, with paymaster, `0x33..`, 1 ETH
+///   (should be ignored)
+pub async fn fill_mock_user_ops_data(blockscout: &DatabaseConnection) {
+    blockscout
+        .execute_unprepared(
+            r#"
+            CREATE TABLE user_operations (
+                hash BYTEA PRIMARY KEY,
+                sender BYTEA NOT NULL,
+                factory BYTEA,
+                paymaster BYTEA,
+                bundler BYTEA NOT NULL,
+                block_hash BYTEA NOT NULL,
+                gas_used NUMERIC(78, 0) NOT NULL,
+                gas_price NUMERIC(78, 0) NOT NULL
+            );
+
+            INSERT INTO user_operations
+                (hash, sender, factory, paymaster, bundler, block_hash, gas_used, gas_price)
+            SELECT
+                decode(uo.hash, 'hex'),
+                decode(repeat(uo.sender, 20), 'hex'),
+                decode(repeat(uo.factory, 20), 'hex'),
+                decode(repeat(uo.paymaster, 20), 'hex'),
+                decode(repeat(uo.bundler, 20), 'hex'),
+                b.hash,
+                uo.gas_used,
+                uo.gas_price
+            FROM (VALUES
+                ('01', 1, 'aa', 'ff', NULL, '11', 100000, 1000000000),
+                ('02', 2, 'aa', NULL, 'ee', '11', 100000, 1000000000),
+                ('03', 2, 'bb', 'ff', 'ee', '22', 100000, 1000000000),
+                ('04', 5, 'bb', NULL, NULL, '22', 100000, 1000000000),
+                ('05', 9, 'cc', 'ff', 'ee', '11', 100000, 1000000000),
+                ('06', 11, 'aa', NULL, 'ee', '22', 100000, 2000000000),
+                ('07', 12, 'cc', NULL, NULL, '11', 300000, 2000000000),
+                ('08', 15, 'dd', 'ff', 'ee', '33', 1000000000, 1000000000)
+            ) AS uo (hash, block_number, sender, factory, paymaster, bundler, gas_used, gas_price)
+            JOIN blocks b ON b.number = uo.block_number;
+            "#,
+        )
+        .await
+        .unwrap();
+}
@@ -2,6 +2,8 @@
 
 pub mod init_db;
 pub mod mock_blockscout;
+pub mod mock_rollups;
+pub mod mock_user_ops;
 pub mod point_construction;
 pub mod recorder;
 pub mod simple_test;
@@ -14,6 +14,9 @@ use pretty_assertions::assert_eq;
 use sea_orm::DatabaseConnection;
 use std::{fmt::Debug, str::FromStr};
 
+/// Update time used in [`simple_test_chart`]
+const SIMPLE_TEST_TIME: &str = "2023-03-01T12:00:00Z";
+
 pub fn map_str_tuple_to_owned(l: Vec<(&str, &str)>) -> Vec<(String, String)> {
     l.into_iter()
         .map(|t| (t.0.to_string(), t.1.to_string()))
@@ -31,18 +34,38 @@ where
     C: DataSource + ChartProperties,
     C::Resolution: Ord + Clone + Debug,
 {
-    let _ = tracing_subscriber::fmt::try_init();
-    let expected = map_str_tuple_to_owned(expected);
     let (db, blockscout) = init_db_all(test_name).await;
-    let current_time = DateTime::from_str("2023-03-01T12:00:00Z").unwrap();
-    let current_date = current_time.date_naive();
-    C::init_recursively(&db, &current_time).await.unwrap();
+    let current_date = DateTime::<Utc>::from_str(SIMPLE_TEST_TIME)
+        .unwrap()
+        .date_naive();
     fill_mock_blockscout_data(&blockscout, current_date).await;
+    simple_test_chart_with_dbs::<C>(&db, &blockscout, None, expected).await;
+    (db, blockscout)
+}
+
+/// Same as [`simple_test_chart`], but the databases are initialized and
+/// filled by the caller (e.g. with mock data of specific chain type or
+/// `user_ops` database).
+pub async fn simple_test_chart_with_dbs<C>(
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    user_ops: Option<&DatabaseConnection>,
+    expected: Vec<(&str, &str)>,
+) where
+    C: DataSource + ChartProperties,
+    C::Resolution: Ord + Clone + Debug,
+{
+    let _ = tracing_subscriber::fmt::try_init();
+    let expected = map_str_tuple_to_owned(expected);
+    let current_time = DateTime::from_str(SIMPLE_TEST_TIME).unwrap();
+    C::init_recursively(db, &current_time).await.unwrap();
     let approximate_trailing_points = C::approximate_trailing_points();
 
     let mut parameters = UpdateParameters {
-        db: &db,
-        blockscout: &blockscout,
+        db,
+        blockscout,
+        user_ops,
+        chain: "",
         update_time_override: Some(current_time),
         force_full: true,
     };
@@ -50,7 +73,7 @@ where
     C::update_recursively(&cx).await.unwrap();
     assert_eq!(
         &get_chart::<C>(
-            &db,
+            db,
             None,
             None,
             C::missing_date_policy(),
@@ -66,7 +89,7 @@ where
     C::update_recursively(&cx).await.unwrap();
     assert_eq!(
         &get_chart::<C>(
-            &db,
+            db,
             None,
             None,
             C::missing_date_policy(),
@@ -76,7 +99,6 @@ where
         .await,
         &expected
     );
-    (db, blockscout)
 }
 
 /// Expects to have `test_name` db's initialized (e.g. by [`simple_test_chart`]).
@@ -101,6 +123,8 @@ pub async fn dirty_force_update_and_check<C>(
     let parameters = UpdateParameters {
         db,
         blockscout,
+        user_ops: None,
+        chain: "",
         update_time_override: Some(current_time),
         force_full: true,
     };
@@ -145,6 +169,8 @@ pub async fn ranged_test_chart<C>(
     let mut parameters = UpdateParameters {
         db: &db,
         blockscout: &blockscout,
+        user_ops: None,
+        chain: "",
         update_time_override: Some(current_time),
         force_full: true,
     };
@@ -227,6 +253,8 @@ pub async fn simple_test_counter<C: DataSource + ChartProperties>(
     let mut parameters = UpdateParameters {
         db: &db,
         blockscout: &blockscout,
+        user_ops: None,
+        chain: "",
         update_time_override: Some(current_time),
         force_full: true,
     };
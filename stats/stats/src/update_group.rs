@@ -31,13 +31,23 @@ use std::{
 use async_trait::async_trait;
 use chrono::Utc;
 use itertools::Itertools;
-use sea_orm::{DatabaseConnection, DbErr};
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr, Statement,
+    TransactionTrait,
+};
 use thiserror::Error;
 use tokio::sync::{Mutex, MutexGuard};
 
 use crate::{
-    charts::{chart_properties_portrait::imports::ChartKey, ChartPropertiesObject},
-    data_source::UpdateParameters,
+    charts::{
+        chart_properties_portrait::imports::ChartKey,
+        db_interaction::{
+            read::find_chart,
+            write::{reset_data_from, set_last_update_error},
+        },
+        ChartPropertiesObject,
+    },
+    data_source::{UpdateContext, UpdateParameters},
     UpdateError,
 };
 
@@ -72,6 +82,9 @@ pub trait UpdateGroup: core::fmt::Debug {
     ///
     /// `None` if `chart_name` is not a member.
     fn dependency_mutex_ids_of(&self, chart_id: &ChartKey) -> Option<HashSet<String>>;
+    /// Direct dependencies between group members and their dependencies
+    /// as `(dependent, dependency)` pairs of mutex ids.
+    fn dependency_mutex_edges(&self) -> HashSet<(String, String)>;
     /// Create/init enabled charts with their dependencies (in DB) recursively.
     /// Idempotent, does nothing if the charts were previously initialized.
     ///
@@ -85,7 +98,9 @@ pub trait UpdateGroup: core::fmt::Debug {
     ) -> Result<(), DbErr>;
     /// Update enabled charts and their dependencies in one go.
     ///
-    /// Recursively updates dependencies first.
+    /// Recursively updates dependencies first. Members are not updated after the
+    /// first error; it is recorded as the last update error of the failed member
+    /// and the skipped ones.
     async fn update_charts<'a>(
         &self,
         params: UpdateParameters<'a>,
@@ -280,6 +295,14 @@ macro_rules! construct_update_group {
                 return None;
             }
 
+            fn dependency_mutex_edges(&self) -> ::std::collections::HashSet<(String, String)> {
+                let mut edges = ::std::collections::HashSet::new();
+                $(
+                    edges.extend(<$member as $crate::data_source::DataSource>::dependency_mutex_edges().into_iter());
+                )*
+                edges
+            }
+
             async fn create_charts(
                 &self,
                 #[allow(unused)]
@@ -311,12 +334,23 @@ macro_rules! construct_update_group {
             ) -> Result<(), $crate::UpdateError> {
                 let cx = $crate::data_source::UpdateContext::from_params_now_or_override(params);
                 ::tracing::Span::current().record("update_time", ::std::format!("{}",&cx.time));
+                let mut result = Ok(());
+                // enabled members that were not updated because of the error
+                let mut failed_members = ::std::vec::Vec::new();
                 $(
                     if enabled_charts.contains(&<$member as $crate::ChartProperties>::key()) {
-                        <$member as $crate::data_source::DataSource>::update_recursively(&cx).await?;
+                        if result.is_ok() {
+                            result = <$member as $crate::data_source::DataSource>::update_recursively(&cx).await;
+                        }
+                        if result.is_err() {
+                            failed_members.push(<$member as $crate::ChartProperties>::key());
+                        }
                     }
                 )*
-                Ok(())
+                if let Err(err) = &result {
+                    $crate::update_group::record_members_update_error(&cx, &failed_members, err).await;
+                }
+                result
             }
         }
 
@@ -325,6 +359,29 @@ macro_rules! construct_update_group {
 
 pub type ArcUpdateGroup = Arc<dyn UpdateGroup + Send + Sync + 'static>;
 
+/// Record `error` as the last update error of the members.
+///
+/// The error is only recorded for the chart where it happened, so members that
+/// depend on the chart (or were skipped after it) would look fine otherwise.
+#[doc(hidden)]
+pub async fn record_members_update_error(
+    cx: &UpdateContext<'_>,
+    members: &[ChartKey],
+    error: &UpdateError,
+) {
+    for member in members {
+        if let Err(record_err) =
+            set_last_update_error(cx.db, member, error.to_string(), cx.time).await
+        {
+            tracing::error!(
+                chart =% member,
+                "failed to record update error: {}",
+                record_err
+            );
+        }
+    }
+}
+
 /// Synchronized update group. Wrapper around [`UpdateGroup`] with
 /// synchronization mechanism.
 ///
@@ -338,13 +395,29 @@ pub type ArcUpdateGroup = Arc<dyn UpdateGroup + Send + Sync + 'static>;
 /// For more info see [link, section "lock ordering"](https://www.cs.cornell.edu/courses/cs4410/2017su/lectures/lec09-deadlock.html)
 ///
 /// The order is a lexicographical order of chart (data source) mutex IDs
+///
+/// ## Multiple processes
+/// The mutexes only synchronize groups within one process. If several processes
+/// (e.g. the server and `stats-cli`) operate on the same stats DB, all of them should
+/// [enable DB locks](SyncUpdateGroup::enable_db_locks). Then Postgres advisory locks
+/// (with the same ids and order) are taken in addition to the mutexes.
 #[derive(Debug, Clone)]
 pub struct SyncUpdateGroup {
     /// Mutexes. Acquired in lexicographical order (=order within `BTreeMap`)
     dependencies_mutexes: BTreeMap<String, Arc<Mutex<()>>>,
+    /// Also take advisory locks in stats DB
+    db_locks: bool,
     inner: ArcUpdateGroup,
 }
 
+/// Locks held by the group. Released on drop.
+struct JointGuard<'a> {
+    _mutexes: Vec<MutexGuard<'a, ()>>,
+    /// Transaction-level advisory locks are released once the
+    /// transaction is finished (rolled back on drop)
+    _db_locks: Option<DatabaseTransaction>,
+}
+
 impl SyncUpdateGroup {
     /// `all_chart_mutexes` must contain mutexes for all members of the group + their dependencies
     /// (will return error otherwise).
@@ -376,10 +449,19 @@ impl SyncUpdateGroup {
         }
         Ok(Self {
             dependencies_mutexes,
+            db_locks: false,
             inner,
         })
     }
 
+    /// Synchronize with groups from other processes that use the same stats DB.
+    ///
+    /// Each operation additionally holds a connection with advisory locks
+    /// for its whole duration.
+    pub fn enable_db_locks(&mut self) {
+        self.db_locks = true;
+    }
+
     /// See [`UpdateGroup::name`]
     pub fn name(&self) -> String {
         self.inner.name()
@@ -399,6 +481,11 @@ impl SyncUpdateGroup {
     pub fn dependency_mutex_ids_of(&self, chart_id: &crate::ChartKey) -> Option<HashSet<String>> {
         self.inner.dependency_mutex_ids_of(chart_id)
     }
+
+    /// See [`UpdateGroup::dependency_mutex_edges`]
+    pub fn dependency_mutex_edges(&self) -> HashSet<(String, String)> {
+        self.inner.dependency_mutex_edges()
+    }
 }
 
 impl SyncUpdateGroup {
@@ -418,8 +505,13 @@ impl SyncUpdateGroup {
         result
     }
 
-    async fn lock_in_order(&self, mut to_lock: HashSet<String>) -> Vec<MutexGuard<()>> {
+    async fn lock_in_order(
+        &self,
+        db: &DatabaseConnection,
+        mut to_lock: HashSet<String>,
+    ) -> Result<JointGuard<'_>, DbErr> {
         let mut guards = vec![];
+        let mut locked_names = vec![];
         // .iter() is ordered by key, so order is followed
         for (name, mutex) in self.dependencies_mutexes.iter() {
             if to_lock.remove(name) {
@@ -436,6 +528,7 @@ impl SyncUpdateGroup {
                     }
                 };
                 guards.push(guard);
+                locked_names.push(name);
             }
         }
         if !to_lock.is_empty() {
@@ -446,7 +539,34 @@ impl SyncUpdateGroup {
                 to_lock
             )
         }
-        guards
+        let db_locks = if self.db_locks {
+            Some(self.lock_in_db(db, locked_names).await?)
+        } else {
+            None
+        };
+        Ok(JointGuard {
+            _mutexes: guards,
+            _db_locks: db_locks,
+        })
+    }
+
+    /// `names` must be ordered in the same way as the mutexes
+    async fn lock_in_db(
+        &self,
+        db: &DatabaseConnection,
+        names: Vec<&String>,
+    ) -> Result<DatabaseTransaction, DbErr> {
+        let txn = db.begin().await?;
+        for name in names {
+            tracing::debug!(update_group = self.name(), lock_id = name, "taking db lock");
+            txn.execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "SELECT pg_advisory_xact_lock(hashtextextended($1, 0))",
+                [name.clone().into()],
+            ))
+            .await?;
+        }
+        Ok(txn)
     }
 
     /// Lock only enabled charts and their dependencies
@@ -454,8 +574,9 @@ impl SyncUpdateGroup {
     /// Returns joint mutex guard and enabled group members list
     async fn lock_enabled_dependencies(
         &self,
+        db: &DatabaseConnection,
         enabled_charts: &HashSet<ChartKey>,
-    ) -> (Vec<MutexGuard<()>>, HashSet<ChartKey>) {
+    ) -> Result<(JointGuard<'_>, HashSet<ChartKey>), DbErr> {
         let members: HashSet<ChartKey> = self.list_charts().into_iter().map(|c| c.key).collect();
         // in-place intersection
         let enabled_members: HashSet<ChartKey> = members
@@ -464,8 +585,8 @@ impl SyncUpdateGroup {
             .collect();
         let enabled_members_with_deps = self.joint_dependencies_of(&enabled_members);
         // order is very important to prevent deadlocks
-        let joint_guard = self.lock_in_order(enabled_members_with_deps).await;
-        (joint_guard, enabled_members)
+        let joint_guard = self.lock_in_order(db, enabled_members_with_deps).await?;
+        Ok((joint_guard, enabled_members))
     }
 
     /// Ignores unknown names
@@ -475,7 +596,10 @@ impl SyncUpdateGroup {
         creation_time_override: Option<chrono::DateTime<Utc>>,
         enabled_charts: &HashSet<ChartKey>,
     ) -> Result<(), UpdateError> {
-        let (_joint_guard, enabled_members) = self.lock_enabled_dependencies(enabled_charts).await;
+        let (_joint_guard, enabled_members) = self
+            .lock_enabled_dependencies(db, enabled_charts)
+            .await
+            .map_err(UpdateError::StatsDB)?;
         self.inner
             .create_charts(db, creation_time_override, &enabled_members)
             .await
@@ -488,7 +612,10 @@ impl SyncUpdateGroup {
         params: UpdateParameters<'a>,
         enabled_charts: &HashSet<ChartKey>,
     ) -> Result<(), UpdateError> {
-        let (_joint_guard, enabled_members) = self.lock_enabled_dependencies(enabled_charts).await;
+        let (_joint_guard, enabled_members) = self
+            .lock_enabled_dependencies(params.db, enabled_charts)
+            .await
+            .map_err(UpdateError::StatsDB)?;
         tracing::info!(
             update_group = self.name(),
             "updating group with enabled members {:?}",
@@ -497,26 +624,71 @@ impl SyncUpdateGroup {
         self.inner.update_charts(params, &enabled_members).await?;
         Ok(())
     }
+
+    /// Remove data of the member chart starting from `from`. The data is
+    /// recalculated on the next (non-full) update of the group.
+    ///
+    /// Charts that depend on `chart` are not affected, they need to be reset separately
+    /// (if needed).
+    ///
+    /// Returns number of removed points.
+    pub async fn reset_chart_with_mutexes(
+        &self,
+        db: &DatabaseConnection,
+        chart: &ChartKey,
+        from: chrono::DateTime<Utc>,
+    ) -> Result<u64, UpdateError> {
+        let Some(dependencies) = self.inner.dependency_mutex_ids_of(chart) else {
+            return Err(UpdateError::ChartNotFound(chart.clone()));
+        };
+        let _joint_guard = self
+            .lock_in_order(db, dependencies)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        let chart_id = find_chart(db, chart)
+            .await
+            .map_err(UpdateError::StatsDB)?
+            .ok_or_else(|| UpdateError::ChartNotFound(chart.clone()))?;
+        tracing::info!(
+            update_group = self.name(),
+            chart =% chart,
+            from =% from,
+            "resetting chart data"
+        );
+        reset_data_from(db, chart_id, from)
+            .await
+            .map_err(UpdateError::StatsDB)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::BTreeMap, sync::Arc};
+    use std::{
+        collections::{BTreeMap, HashSet},
+        sync::Arc,
+    };
     use tokio::sync::Mutex;
 
     use crate::{
         counters::TotalVerifiedContracts,
-        data_source::DataSource,
-        lines::{NewVerifiedContracts, VerifiedContractsGrowth},
+        data_source::{DataSource, UpdateParameters},
+        get_charts_update_status,
+        lines::{NewTxns, NewVerifiedContracts, TxnsGrowth, VerifiedContractsGrowth},
+        tests::init_db::init_db,
         update_group::InitializationError,
+        ChartProperties,
     };
 
-    use super::SyncUpdateGroup;
+    use super::{SyncUpdateGroup, UpdateGroup};
 
     construct_update_group!(GroupWithoutDependencies {
         charts: [TotalVerifiedContracts],
     });
 
+    construct_update_group!(TxnsGrowthGroup {
+        charts: [TxnsGrowth],
+    });
+
     #[test]
     fn new_checks_mutexes() {
         let mutexes: BTreeMap<String, Arc<Mutex<()>>> = [(
@@ -546,4 +718,44 @@ mod tests {
             })
         );
     }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_error_is_recorded_for_dependent_members() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let db = init_db("update_error_is_recorded_for_dependent_members").await;
+        let mutexes: BTreeMap<_, _> = TxnsGrowthGroup
+            .list_dependency_mutex_ids()
+            .into_iter()
+            .map(|id| (id, Arc::new(Mutex::new(()))))
+            .collect();
+        let group = SyncUpdateGroup::new(&mutexes, Arc::new(TxnsGrowthGroup)).unwrap();
+        let enabled = HashSet::from([TxnsGrowth::key()]);
+        group
+            .create_charts_with_mutexes(&db, None, &enabled)
+            .await
+            .unwrap();
+
+        // stats DB has no blockscout tables, so the update of `NewTxns` fails
+        let parameters = UpdateParameters {
+            db: &db,
+            blockscout: &db,
+            user_ops: None,
+            chain: "",
+            update_time_override: None,
+            force_full: false,
+        };
+        group
+            .update_charts_with_mutexes(parameters, &enabled)
+            .await
+            .unwrap_err();
+
+        let keys = [NewTxns::key(), TxnsGrowth::key()];
+        let statuses = get_charts_update_status(&db, &keys).await.unwrap();
+        for key in keys {
+            let status = &statuses[&key];
+            assert!(status.last_error.is_some(), "no error recorded for {key}");
+            assert_eq!(status.last_error, statuses[&NewTxns::key()].last_error);
+        }
+    }
 }
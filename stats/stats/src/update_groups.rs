@@ -1,4 +1,9 @@
-use crate::{construct_update_group, counters::*, lines::*};
+use crate::{
+    construct_update_group,
+    counters::*,
+    lines::{user_ops::*, *},
+    top_lists::*,
+};
 
 macro_rules! singleton_groups {
     ($($chart: ident),+ $(,)?) => {
@@ -20,11 +25,17 @@ singleton_groups!(
     ActiveAccounts,
     AverageBlockTime,
     CompletedTxns,
+    // Lower resolutions of transactions by status are not implemented yet
+    NewTxnsByStatus,
     TotalAddresses,
     TotalBlocks,
     TotalTokens,
 );
 
+// Hourly charts are updated much more often than others,
+// so they are kept in separate groups
+singleton_groups!(AverageGasPriceHourly, NewBlocksHourly, NewTxnsHourly);
+
 construct_update_group!(AverageBlockRewardsGroup {
     charts: [
         AverageBlockRewards,
@@ -61,6 +72,33 @@ construct_update_group!(AverageGasPriceGroup {
     ]
 });
 
+construct_update_group!(AverageBaseFeeGroup {
+    charts: [
+        AverageBaseFee,
+        AverageBaseFeeWeekly,
+        AverageBaseFeeMonthly,
+        AverageBaseFeeYearly,
+    ]
+});
+
+construct_update_group!(AveragePriorityFeeGroup {
+    charts: [
+        AveragePriorityFee,
+        AveragePriorityFeeWeekly,
+        AveragePriorityFeeMonthly,
+        AveragePriorityFeeYearly,
+    ]
+});
+
+construct_update_group!(GasPricePercentilesGroup {
+    charts: [
+        GasPricePercentiles,
+        GasPricePercentilesWeekly,
+        GasPricePercentilesMonthly,
+        GasPricePercentilesYearly,
+    ]
+});
+
 construct_update_group!(AverageTxnFeeGroup {
     charts: [
         AverageTxnFee,
@@ -194,3 +232,107 @@ construct_update_group!(NewNativeCoinTransfersGroup {
         TotalNativeCoinTransfers,
     ],
 });
+
+construct_update_group!(TopContractsByTxnsGroup {
+    charts: [
+        TopContractsByTxns24h,
+        TopContractsByTxns7d,
+        TopContractsByTxns30d,
+    ],
+});
+
+construct_update_group!(TopGasConsumersGroup {
+    charts: [TopGasConsumers24h, TopGasConsumers7d, TopGasConsumers30d],
+});
+
+construct_update_group!(TopSendersGroup {
+    charts: [TopSenders24h, TopSenders7d, TopSenders30d],
+});
+
+// Account abstraction charts; they are available only
+// with `user-ops-indexer` database
+singleton_groups!(ActiveSmartAccounts);
+
+construct_update_group!(NewUserOpsGroup {
+    charts: [
+        NewUserOps,
+        NewUserOpsWeekly,
+        NewUserOpsMonthly,
+        NewUserOpsYearly,
+    ]
+});
+
+construct_update_group!(NewSmartAccountsGroup {
+    charts: [
+        NewSmartAccounts,
+        NewSmartAccountsWeekly,
+        NewSmartAccountsMonthly,
+        NewSmartAccountsYearly,
+    ]
+});
+
+construct_update_group!(SponsoredUserOpsShareGroup {
+    charts: [
+        SponsoredUserOpsShare,
+        SponsoredUserOpsShareWeekly,
+        SponsoredUserOpsShareMonthly,
+        SponsoredUserOpsShareYearly,
+    ]
+});
+
+construct_update_group!(TopBundlersByFeesGroup {
+    charts: [
+        TopBundlersByFees24h,
+        TopBundlersByFees7d,
+        TopBundlersByFees30d,
+    ],
+});
+
+/// Groups of the rollup charts (see [`crate::lines::rollups`]).
+///
+/// The groups are named the same for each rollup kind, so
+/// that their update schedules are configured uniformly.
+macro_rules! rollup_groups {
+    ($rollup: ident) => {
+        use $crate::{construct_update_group, lines::rollups::*};
+
+        construct_update_group!(NewBatchesGroup {
+            charts: [
+                NewBatches<$rollup>,
+                NewBatchesWeekly<$rollup>,
+                NewBatchesMonthly<$rollup>,
+                NewBatchesYearly<$rollup>,
+            ]
+        });
+
+        construct_update_group!(AverageBatchSizeGroup {
+            charts: [
+                AverageBatchSize<$rollup>,
+                AverageBatchSizeWeekly<$rollup>,
+                AverageBatchSizeMonthly<$rollup>,
+                AverageBatchSizeYearly<$rollup>,
+            ]
+        });
+
+        construct_update_group!(L1DataFeesGroup {
+            charts: [
+                L1DataFees<$rollup>,
+                L1DataFeesWeekly<$rollup>,
+                L1DataFeesMonthly<$rollup>,
+                L1DataFeesYearly<$rollup>,
+            ]
+        });
+    };
+}
+
+pub mod optimism {
+    rollup_groups!(Optimism);
+
+    construct_update_group!(NewBlobsGroup {
+        charts: [NewBlobs, NewBlobsWeekly, NewBlobsMonthly, NewBlobsYearly]
+    });
+}
+
+pub mod arbitrum {
+    rollup_groups!(Arbitrum);
+}
@@ -2,7 +2,8 @@
 
 use std::ops::{Range, RangeInclusive};
 
-use chrono::{NaiveDate, NaiveTime};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono_tz::Tz;
 use sea_orm::{prelude::DateTimeUtc, Value};
 
 pub fn day_start(date: &NaiveDate) -> DateTimeUtc {
@@ -10,6 +11,24 @@ pub fn day_start(date: &NaiveDate) -> DateTimeUtc {
         .and_utc()
 }
 
+/// Convert local time in `timezone` into UTC timestamp.
+///
+/// Ambiguous local time (e.g. when clocks are turned back) resolves
+/// to the earliest instant. Local time that does not exist (e.g. when clocks
+/// are turned forward) resolves to the first existing time after it, so that
+/// the result still can be used as a start of the local timespan.
+pub fn local_time_start(local: NaiveDateTime, timezone: &Tz) -> DateTimeUtc {
+    // transitions shift clocks by at most a couple of hours
+    const STEP_MINUTES: i64 = 15;
+    const MAX_STEPS: i64 = 24 * 60 / STEP_MINUTES;
+    (0..=MAX_STEPS)
+        .filter_map(|step| local.checked_add_signed(Duration::minutes(step * STEP_MINUTES)))
+        .find_map(|candidate| timezone.from_local_datetime(&candidate).earliest())
+        .map(|t| t.to_utc())
+        // unreachable in practice (or saturated at `NaiveDateTime::MAX`)
+        .unwrap_or_else(|| local.and_utc())
+}
+
 pub fn exclusive_datetime_range_to_inclusive(r: Range<DateTimeUtc>) -> RangeInclusive<DateTimeUtc> {
     // subtract the smallest unit of time to get semantically the same range
     // but inclusive
@@ -206,4 +225,69 @@ mod test {
             ))
         );
     }
+
+    fn dt(s: &str) -> DateTimeUtc {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn local_time_start_works() {
+        let berlin = chrono_tz::Europe::Berlin;
+        let local = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        assert_eq!(
+            local_time_start(local("2024-01-10 00:00"), &berlin),
+            dt("2024-01-09T23:00:00Z")
+        );
+        assert_eq!(
+            local_time_start(local("2024-07-10 00:00"), &berlin),
+            dt("2024-07-09T22:00:00Z")
+        );
+        // clocks are turned forward from 02:00 to 03:00
+        assert_eq!(
+            local_time_start(local("2024-03-31 02:30"), &berlin),
+            dt("2024-03-31T01:00:00Z")
+        );
+        // clocks are turned back from 03:00 to 02:00
+        assert_eq!(
+            local_time_start(local("2024-10-27 02:30"), &berlin),
+            dt("2024-10-27T00:30:00Z")
+        );
+        assert_eq!(
+            local_time_start(local("2024-01-10 00:00"), &Tz::UTC),
+            dt("2024-01-10T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn timespans_in_timezone_work() {
+        use crate::types::{timespans::Week, Timespan};
+
+        let tokyo = chrono_tz::Asia::Tokyo;
+        let new_york = chrono_tz::America::New_York;
+        let date = NaiveDate::from_ymd_opt(2024, 5, 2).unwrap();
+
+        assert_eq!(
+            date.into_time_range_in(&tokyo),
+            dt("2024-05-01T15:00:00Z")..dt("2024-05-02T15:00:00Z")
+        );
+        assert_eq!(date.into_time_range_in(&Tz::UTC), date.into_time_range());
+        assert_eq!(
+            NaiveDate::from_timestamp_in(dt("2024-05-01T20:00:00Z"), &tokyo),
+            date
+        );
+        assert_eq!(
+            NaiveDate::from_timestamp_in(dt("2024-05-03T03:00:00Z"), &new_york),
+            date
+        );
+        assert_eq!(
+            Week::from_timestamp_in(dt("2024-05-05T16:00:00Z"), &tokyo),
+            Week::from_date(NaiveDate::from_ymd_opt(2024, 5, 6).unwrap())
+        );
+        // the day when clocks are turned forward is 23 hours long
+        let dst_start = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        assert_eq!(
+            dst_start.into_time_range_in(&new_york),
+            dt("2024-03-10T05:00:00Z")..dt("2024-03-11T04:00:00Z")
+        );
+    }
 }